@@ -1,8 +1,48 @@
+use crate::drag::DragObject;
+use crate::edit_text::TextSelection;
 use crate::prelude::*;
+use crate::tag_utils::SwfSlice;
+use gc_arena::GcCell;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
-use std::collections::HashMap;
 use std::io::Cursor;
 use swf::avm1::read::Reader;
+use swf::avm1::types::CatchVar;
+use swf::read::SwfRead;
+
+mod activation;
+mod display_properties;
+mod function;
+mod globals;
+mod object;
+mod scope;
+mod text_field_binding;
+mod timer;
+mod value;
+
+use activation::Activation;
+use function::{Avm1Function, Executable};
+use scope::Scope;
+use text_field_binding::{TextFieldBinding, TextFieldBindings};
+use timer::{TimerCallback, Timers};
+
+use globals::KeyState;
+
+pub use globals::SystemPrototypes;
+pub use object::Object;
+pub use value::Value;
+use value::{number_to_i32, number_to_u32, PrimitiveHint};
+
+/// Returns the text that a text field shows for the value of its variable.
+/// Undefined variables show no text.
+fn text_field_value(value: Value<'_>) -> String {
+    match value {
+        Value::Undefined => String::new(),
+        value => value.into_string(),
+    }
+}
+
+/// The maximum depth of nested function calls before the AVM aborts the script.
+const MAX_CALL_DEPTH: usize = 256;
 
 pub struct ActionContext<'a, 'gc, 'gc_context> {
     pub gc_context: gc_arena::MutationContext<'gc, 'gc_context>,
@@ -11,33 +51,651 @@ pub struct ActionContext<'a, 'gc, 'gc_context> {
     pub start_clip: DisplayNode<'gc>,
     pub active_clip: DisplayNode<'gc>,
     pub audio: &'a mut dyn crate::backend::audio::AudioBackend,
+    pub library: &'a mut crate::library::Library<'gc>,
+
+    /// The position of the mouse on the stage.
+    pub mouse_position: (Twips, Twips),
 }
 
-pub struct Avm1 {
+pub struct Avm1<'gc> {
     swf_version: u8,
-    stack: Vec<Value>,
+    stack: Vec<Value<'gc>>,
     rng: SmallRng,
     constant_pool: Vec<String>,
-    locals: HashMap<String, Value>,
+    registers: [Value<'gc>; 4],
+    stack_frames: Vec<Activation<'gc>>,
+
+    /// The `_global` object, which is the last link of every scope chain.
+    globals: GcCell<'gc, Object<'gc>>,
+
+    /// The prototypes of the built-in classes.
+    prototypes: SystemPrototypes<'gc>,
+
+    /// The URL the movie was loaded from, as reported by `_url`.
+    movie_url: String,
+
+    /// The rendering quality, as reported by `_quality`: `LOW`, `MEDIUM`, `HIGH` or `BEST`.
+    /// TODO: The render backends don't yet change their anti-aliasing to match.
+    quality: String,
+
+    /// Whether buttons show a yellow rectangle when focused with the keyboard (`_focusrect`).
+    focus_rect: bool,
+
+    /// The number of seconds of streaming sound to buffer (`_soundbuftime`).
+    sound_buffer_time: f64,
+
+    /// The value thrown by `Throw` while it unwinds the stack towards a `catch` block.
+    exception: Option<Value<'gc>>,
+
+    /// The messages of exceptions that no `catch` block handled, waiting to be reported
+    /// to the host.
+    uncaught_errors: Vec<String>,
+
+    /// The timers created by `setInterval` and `setTimeout`.
+    timers: Timers<'gc>,
+
+    /// The keys held down, as reported by the `Key` object.
+    key_state: KeyState,
+
+    /// The text fields that show the values of variables.
+    text_field_bindings: TextFieldBindings<'gc>,
+
+    /// The text field that has the keyboard focus, as reported by `Selection.getFocus`.
+    focus: Option<DisplayNode<'gc>>,
+
+    /// The clip being dragged with `startDrag`, if any.
+    drag_object: Option<DragObject<'gc>>,
+
+    /// Whether the mouse cursor is shown, as set by `Mouse.show` and `Mouse.hide`.
+    mouse_visible: bool,
+
+    /// The clips created by scripts that haven't run their first frame yet.
+    new_clips: Vec<DisplayNode<'gc>>,
+}
+
+unsafe impl<'gc> gc_arena::Collect for Avm1<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for value in &self.stack {
+            value.trace(cc);
+        }
+        for value in &self.registers {
+            value.trace(cc);
+        }
+        for frame in &self.stack_frames {
+            frame.trace(cc);
+        }
+        self.globals.trace(cc);
+        self.prototypes.trace(cc);
+        self.exception.trace(cc);
+        self.timers.trace(cc);
+        self.text_field_bindings.trace(cc);
+        self.focus.trace(cc);
+        self.drag_object.trace(cc);
+        self.new_clips.trace(cc);
+    }
 }
 
 type Error = Box<dyn std::error::Error>;
 
-impl Avm1 {
-    pub fn new(swf_version: u8) -> Self {
+/// The error that unwinds the stack after a `Throw` action, until a `catch` block handles it.
+/// The thrown value itself is kept in `Avm1::exception`, where the garbage collector can see it.
+#[derive(Debug)]
+struct ThrownError;
+
+impl std::fmt::Display for ThrownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Uncaught exception")
+    }
+}
+
+impl std::error::Error for ThrownError {}
+
+impl<'gc> Avm1<'gc> {
+    pub fn new(gc_context: gc_arena::MutationContext<'gc, '_>, swf_version: u8) -> Self {
+        let (globals, prototypes) = globals::create_globals(gc_context);
         Self {
             swf_version,
             stack: vec![],
             rng: SmallRng::from_seed([0u8; 16]), // TODO(Herschel): Get a proper seed on all platforms.
             constant_pool: vec![],
-            locals: HashMap::new(),
+            registers: [
+                Value::Undefined,
+                Value::Undefined,
+                Value::Undefined,
+                Value::Undefined,
+            ],
+            stack_frames: vec![],
+            globals,
+            prototypes,
+            movie_url: String::new(),
+            quality: "HIGH".to_string(),
+            focus_rect: true,
+            sound_buffer_time: 5.0,
+            exception: None,
+            uncaught_errors: vec![],
+            timers: Timers::new(),
+            key_state: Default::default(),
+            text_field_bindings: TextFieldBindings::new(),
+            focus: None,
+            drag_object: None,
+            mouse_visible: true,
+            new_clips: vec![],
+        }
+    }
+
+    /// Runs a block of top-level actions, such as the contents of a `DoAction` tag.
+    pub fn do_action(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        code: SwfSlice,
+    ) -> Result<(), Error> {
+        let this = context.active_clip.read().object();
+        let activation = Activation::from_action(this);
+        if let Err(e) = self.run_activation(context, activation, code) {
+            self.report_error(context, &e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Calls the event handler method `name`, such as `onEnterFrame`, on the object of the active clip.
+    /// Does nothing if the clip has no such method.
+    pub fn call_event_handler(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        name: &str,
+    ) -> Result<(), Error> {
+        let this = context.active_clip.read().object();
+        self.call_method(context, this, name, &[])
+    }
+
+    /// Calls the method `name` of `this` with `args`, if `this` is an object with such a method.
+    fn call_method(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        this: Value<'gc>,
+        name: &str,
+        args: &[Value<'gc>],
+    ) -> Result<(), Error> {
+        if let Value::Object(object) = &this {
+            let object = *object;
+            let handler = Object::get(object, name, self, context)?;
+            if let Value::Object(_) = handler {
+                if let Err(e) = self.call_function(context, handler, this, args) {
+                    log::error!("AVM1 error in {}", name);
+                    self.report_error(context, &e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls the timers that are due at `context.global_time`.
+    /// Returns `true` if any timer fired.
+    pub fn run_timers(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> bool {
+        let time = context.global_time;
+        let due_timers = self.timers.due_timers(time);
+        let mut fired = false;
+        for id in due_timers {
+            // An earlier callback may have cleared this timer.
+            let (callback, args, target) = match self.timers.fire(id, time) {
+                Some(call) => call,
+                None => continue,
+            };
+            fired = true;
+            context.start_clip = target;
+            context.active_clip = target;
+            let result = match callback {
+                TimerCallback::Function(function) => {
+                    self.call_function(context, function, Value::Undefined, &args)
+                }
+                TimerCallback::Method { this, method_name } => {
+                    Object::get(this, &method_name, self, context)
+                        .and_then(|method| self.call_function(context, method, this.into(), &args))
+                }
+            };
+            if let Err(e) = result {
+                self.report_error(context, &e);
+            }
+        }
+        fired
+    }
+
+    /// Queues `display_object` to be bound to its variable the next time that actions run,
+    /// if it is a text field with a variable.
+    pub fn add_text_field(&mut self, display_object: DisplayNode<'gc>) {
+        let has_variable = display_object
+            .read()
+            .as_edit_text()
+            .map_or(false, |edit_text| !edit_text.variable_name().is_empty());
+        if has_variable {
+            self.text_field_bindings.add_unbound(display_object);
+        }
+    }
+
+    /// Binds the text fields queued by `add_text_field` to their variables.
+    ///
+    /// A text field shows the value of its variable if the variable exists, and otherwise
+    /// sets the variable to its own text. Text fields whose variable path doesn't lead to
+    /// a clip yet are tried again the next time.
+    pub fn bind_text_fields(&mut self, context: &mut ActionContext<'_, 'gc, '_>) {
+        self.text_field_bindings.remove_orphans();
+        for text_field in self.text_field_bindings.take_unbound() {
+            let (parent, path) = {
+                let display_object = text_field.read();
+                match (display_object.parent(), display_object.as_edit_text()) {
+                    (Some(parent), Some(edit_text)) => {
+                        (parent, edit_text.variable_name().to_string())
+                    }
+                    // Text fields removed before they were bound are forgotten.
+                    _ => continue,
+                }
+            };
+            let target = Self::resolve_slash_path_variable(parent, context.root, &path);
+            let object = target.and_then(|(target, _)| match target.read().object() {
+                Value::Object(object) => Some(object),
+                _ => None,
+            });
+            let (target, variable_name, object) = match (target, object) {
+                (Some((target, variable_name)), Some(object)) => (target, variable_name, object),
+                _ => {
+                    self.text_field_bindings.add_unbound(text_field);
+                    continue;
+                }
+            };
+
+            self.text_field_bindings.bind(TextFieldBinding {
+                text_field,
+                target,
+                variable_name: variable_name.to_string(),
+            });
+            let result = if object.read().has_property(variable_name) {
+                Object::get(object, variable_name, self, context).map(|value| {
+                    if let Some(edit_text) = text_field.write(context.gc_context).as_edit_text_mut()
+                    {
+                        edit_text.set_variable_value(text_field_value(value), context.library);
+                    }
+                })
+            } else {
+                let value = text_field
+                    .read()
+                    .as_edit_text()
+                    .map_or_else(String::new, |edit_text| edit_text.variable_value());
+                Object::set(object, variable_name, value.into(), self, context)
+            };
+            if let Err(e) = result {
+                self.report_error(context, &e);
+            }
+        }
+    }
+
+    /// Shows the new value of the variable `variable_name` of `target` in the text fields
+    /// bound to it.
+    pub fn update_bound_text_fields(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        target: DisplayNode<'gc>,
+        variable_name: &str,
+        value: &Value<'gc>,
+    ) {
+        for text_field in self
+            .text_field_bindings
+            .text_fields_of(target, variable_name)
+        {
+            if let Some(edit_text) = text_field.write(context.gc_context).as_edit_text_mut() {
+                edit_text.set_variable_value(text_field_value(value.clone()), context.library);
+            }
+        }
+    }
+
+    /// Copies the text of `text_field` into the variable that it is bound to,
+    /// after its text was changed.
+    pub fn update_text_field_variable(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        text_field: DisplayNode<'gc>,
+    ) -> Result<(), Error> {
+        let (target, variable_name) = match self.text_field_bindings.binding_of(text_field) {
+            Some(binding) => (binding.target, binding.variable_name.clone()),
+            None => return Ok(()),
+        };
+        let value = match text_field.read().as_edit_text() {
+            Some(edit_text) => edit_text.variable_value(),
+            None => return Ok(()),
+        };
+        if let Value::Object(object) = target.read().object() {
+            Object::set(object, &variable_name, value.into(), self, context)?;
+        }
+        Ok(())
+    }
+
+    /// Binds `text_field` to the variable `variable_name` instead of its previous variable,
+    /// as when a script sets its `variable` property.
+    pub fn rebind_text_field(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        text_field: DisplayNode<'gc>,
+        variable_name: String,
+    ) {
+        self.text_field_bindings.unbind(text_field);
+        if let Some(edit_text) = text_field.write(context.gc_context).as_edit_text_mut() {
+            edit_text.set_variable_name(variable_name);
+        }
+        self.add_text_field(text_field);
+        self.bind_text_fields(context);
+    }
+
+    /// Updates the variable of `text_field` after the user edited its text,
+    /// and calls its `onChanged` handler.
+    pub fn text_field_changed(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        text_field: DisplayNode<'gc>,
+    ) {
+        if let Err(e) = self.update_text_field_variable(context, text_field) {
+            self.report_error(context, &e);
+        }
+        let object = text_field.read().object();
+        let _ = self.call_method(context, object.clone(), "onChanged", &[object]);
+    }
+
+    /// Follows the link `url` of `text_field` after it was clicked, opening it in the
+    /// window `target`. A link of the form `asfunction:name,argument` instead calls the
+    /// method `name` of the clip holding the text field, with the argument as a string.
+    pub fn follow_link(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        text_field: DisplayNode<'gc>,
+        url: &str,
+        target: &str,
+    ) {
+        const AS_FUNCTION: &str = "asfunction:";
+        let is_as_function = url
+            .get(..AS_FUNCTION.len())
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case(AS_FUNCTION));
+        if !is_as_function {
+            if let Err(e) = self.action_get_url(context, url, target) {
+                self.report_error(context, &e);
+            }
+            return;
+        }
+        let mut parts = url[AS_FUNCTION.len()..].splitn(2, ',');
+        let name = parts.next().unwrap_or_default().trim();
+        let args: Vec<Value<'gc>> = parts.next().map(Value::from).into_iter().collect();
+        let this = text_field
+            .read()
+            .parent()
+            .map_or(Value::Undefined, |parent| parent.read().object());
+        let _ = self.call_method(context, this, name, &args);
+    }
+
+    /// The text field that has the keyboard focus, unless it has been removed
+    /// from the display list since it was focused.
+    pub fn focus(&self) -> Option<DisplayNode<'gc>> {
+        self.focus.filter(|node| node.read().parent().is_some())
+    }
+
+    /// Moves the keyboard focus to the text field `focus`, selecting all of its text,
+    /// or removes the focus if `focus` is `None`.
+    ///
+    /// The `onKillFocus` handler of the text field losing the focus is called with the
+    /// text field gaining it, its `onSetFocus` handler with the text field losing it,
+    /// and the `onSetFocus` handlers of `Selection` listeners with both.
+    pub fn set_focus(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        focus: Option<DisplayNode<'gc>>,
+    ) {
+        let old_focus = self.focus();
+        if old_focus.map(GcCell::as_ptr) == focus.map(GcCell::as_ptr) {
+            return;
+        }
+        self.focus = focus;
+        if let Some(old_focus) = old_focus {
+            if let Some(edit_text) = old_focus.write(context.gc_context).as_edit_text_mut() {
+                edit_text.set_selection(None);
+            }
+        }
+        if let Some(focus) = focus {
+            if let Some(edit_text) = focus.write(context.gc_context).as_edit_text_mut() {
+                let len = edit_text.text().chars().count();
+                edit_text.set_selection(Some(TextSelection::new(0, len)));
+            }
+        }
+
+        let old_object = old_focus.map_or(Value::Null, |node| node.read().object());
+        let new_object = focus.map_or(Value::Null, |node| node.read().object());
+        if old_focus.is_some() {
+            let _ = self.call_method(
+                context,
+                old_object.clone(),
+                "onKillFocus",
+                &[new_object.clone()],
+            );
+        }
+        if focus.is_some() {
+            let _ = self.call_method(
+                context,
+                new_object.clone(),
+                "onSetFocus",
+                &[old_object.clone()],
+            );
+        }
+        let _ = self.notify_system_listeners(
+            context,
+            "Selection",
+            "onSetFocus",
+            &[old_object, new_object],
+        );
+    }
+
+    /// Whether the key `key_code` is held down.
+    pub fn is_key_down(&self, key_code: crate::events::KeyCode) -> bool {
+        self.key_state.is_down(key_code)
+    }
+
+    /// Records that a key was pressed, for the `Key` object.
+    pub fn key_down(&mut self, key_code: crate::events::KeyCode, ascii: u8) {
+        self.key_state.key_down(key_code, ascii);
+    }
+
+    /// Records that a key was released, for the `Key` object.
+    pub fn key_up(&mut self, key_code: crate::events::KeyCode, ascii: u8) {
+        self.key_state.key_up(key_code, ascii);
+    }
+
+    /// Records that a mouse button, such as `key_code::LEFT_MOUSE`, was pressed or released.
+    pub fn set_mouse_button(&mut self, key_code: crate::events::KeyCode, is_down: bool) {
+        self.key_state.set_mouse_button(key_code, is_down);
+    }
+
+    /// Starts dragging `display_object` with the mouse, replacing any other drag.
+    /// The constraint rectangle is in the coordinate space of the parent of the display object.
+    pub fn start_drag(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        display_object: DisplayNode<'gc>,
+        lock_center: bool,
+        constraint: Option<BoundingBox>,
+    ) {
+        let drag_object = DragObject::new(
+            display_object,
+            context.mouse_position,
+            lock_center,
+            constraint,
+        );
+        drag_object.update(context.gc_context, context.root, context.mouse_position);
+        self.drag_object = Some(drag_object);
+    }
+
+    pub fn stop_drag(&mut self) {
+        self.drag_object = None;
+    }
+
+    /// Moves the dragged clip, if any, to follow the mouse at `mouse_position`.
+    /// Returns `true` if a clip is being dragged.
+    pub fn update_drag(
+        &self,
+        gc_context: gc_arena::MutationContext<'gc, '_>,
+        root: DisplayNode<'gc>,
+        mouse_position: (Twips, Twips),
+    ) -> bool {
+        if let Some(drag_object) = &self.drag_object {
+            drag_object.update(gc_context, root, mouse_position);
+            true
+        } else {
+            false
         }
     }
 
-    pub fn do_action(&mut self, context: &mut ActionContext, code: &[u8]) -> Result<(), Error> {
-        let mut reader = Reader::new(Cursor::new(code), self.swf_version);
+    pub fn mouse_visible(&self) -> bool {
+        self.mouse_visible
+    }
+
+    /// Calls the method `method` on the listeners of the built-in object `broadcaster`,
+    /// such as the `onKeyDown` methods of the listeners added with `Key.addListener`.
+    pub fn notify_system_listeners(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        broadcaster: &str,
+        method: &str,
+        args: &[Value<'gc>],
+    ) -> Result<(), Error> {
+        let globals = self.globals;
+        let broadcaster = Object::get(globals, broadcaster, self, context)?;
+        let listeners = match broadcaster {
+            Value::Object(broadcaster) => globals::listeners(self, context, broadcaster)?,
+            _ => return Ok(()),
+        };
+        for listener in listeners {
+            if let Value::Object(object) = listener {
+                let handler = Object::get(object, method, self, context)?;
+                if let Value::Object(_) = handler {
+                    if let Err(e) = self.call_function(context, handler, listener, args) {
+                        log::error!("AVM1 error in {}", method);
+                        self.report_error(context, &e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_movie_url(&mut self, url: &str) {
+        self.movie_url = url.to_string();
+    }
+
+    /// Queues a clip created by a script, such as with `attachMovie`, to run its first
+    /// frame as soon as the current actions have finished.
+    pub fn add_new_clip(&mut self, clip: DisplayNode<'gc>) {
+        self.new_clips.push(clip);
+    }
+
+    /// Returns the clips queued by `add_new_clip` since the last call.
+    pub fn take_new_clips(&mut self) -> Vec<DisplayNode<'gc>> {
+        std::mem::replace(&mut self.new_clips, vec![])
+    }
+
+    /// Returns the messages of the exceptions that were thrown and never caught
+    /// since the last call.
+    pub fn take_uncaught_errors(&mut self) -> Vec<String> {
+        std::mem::replace(&mut self.uncaught_errors, vec![])
+    }
+
+    /// Handles an error that aborted a script.
+    /// Uncaught exceptions are queued up for the host, while other errors are only logged.
+    fn report_error(&mut self, context: &mut ActionContext<'_, 'gc, '_>, error: &Error) {
+        if !error.is::<ThrownError>() {
+            log::error!("AVM1 error: {}", error);
+            return;
+        }
+        let exception = self.exception.take().unwrap_or(Value::Undefined);
+        let message = self.exception_message(context, exception);
+        self.uncaught_errors.push(message);
+    }
+
+    /// Describes a thrown value, using its `toString` method if it is an object.
+    fn exception_message(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        exception: Value<'gc>,
+    ) -> String {
+        if let Value::Object(object) = &exception {
+            let object = *object;
+            let to_string = Object::get(object, "toString", self, context);
+            if let Ok(to_string @ Value::Object(_)) = to_string {
+                if let Ok(message) = self.call_function(context, to_string, exception.clone(), &[])
+                {
+                    return message.into_string();
+                }
+            }
+        }
+        exception.into_string()
+    }
+
+    /// The prototypes of the built-in classes, used when creating objects for display objects.
+    pub fn prototypes(&self) -> &SystemPrototypes<'gc> {
+        &self.prototypes
+    }
+
+    /// Runs `code` in a new stack frame, returning the value handed back by `Return`.
+    fn run_activation(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        activation: Activation<'gc>,
+        code: SwfSlice,
+    ) -> Result<Value<'gc>, Error> {
+        if self.stack_frames.len() >= MAX_CALL_DEPTH {
+            return Err("Maximum function call depth exceeded".into());
+        }
+
+        let stack_len = self.stack.len();
+        self.stack_frames.push(activation);
+        let result = self.run_actions(context, code);
+        let mut activation = self.stack_frames.pop().unwrap();
+        self.stack.truncate(stack_len);
+
+        result.map(|_| activation.take_return_value())
+    }
+
+    fn run_actions(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        code: SwfSlice,
+    ) -> Result<(), Error> {
+        let len = code.as_ref().len();
+        self.run_action_range(context, &code, 0, len)?;
+        Ok(())
+    }
+
+    /// Runs the actions of `code` from `start` up to `end`, such as the body of a `try` block.
+    /// If a jump leaves the range, such as a `break` out of a loop, the range stops running
+    /// and the position that was jumped to is returned.
+    fn run_action_range(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        code: &SwfSlice,
+        start: usize,
+        end: usize,
+    ) -> Result<Option<usize>, Error> {
+        let mut reader = Reader::new(Cursor::new(code.as_ref()), self.swf_version);
+        reader.get_inner().set_position(start as u64);
+
+        loop {
+            let action_pos = reader.get_inner().position() as usize;
+            if action_pos == end {
+                break;
+            } else if action_pos < start || action_pos > end {
+                return Ok(Some(action_pos));
+            }
+            let action = if let Some(action) = reader.read_action()? {
+                action
+            } else {
+                break;
+            };
 
-        while let Some(action) = reader.read_action()? {
             use swf::avm1::types::Action;
             let result = match action {
                 Action::Add => self.action_add(context),
@@ -53,16 +711,24 @@ impl Avm1 {
                 Action::Call => self.action_call(context),
                 Action::CallFunction => self.action_call_function(context),
                 Action::CallMethod => self.action_call_method(context),
+                Action::CastOp => self.action_cast_op(context),
                 Action::CharToAscii => self.action_char_to_ascii(context),
+                Action::CloneSprite => self.action_clone_sprite(context),
                 Action::ConstantPool(constant_pool) => {
                     self.action_constant_pool(context, &constant_pool[..])
                 }
                 Action::Decrement => self.action_decrement(context),
-                Action::DefineFunction {
-                    name,
-                    params,
-                    actions,
-                } => self.action_define_function(context, &name, &params[..], &actions[..]),
+                Action::DefineFunction { name, params, .. } => self.action_define_function(
+                    context,
+                    &name,
+                    &params[..],
+                    code,
+                    &mut reader,
+                    action_pos,
+                ),
+                Action::DefineFunction2(function) => {
+                    self.action_define_function_2(context, &function, code, &mut reader, action_pos)
+                }
                 Action::DefineLocal => self.action_define_local(context),
                 Action::DefineLocal2 => self.action_define_local_2(context),
                 Action::Delete => self.action_delete(context),
@@ -70,8 +736,10 @@ impl Avm1 {
                 Action::Divide => self.action_divide(context),
                 Action::EndDrag => self.action_end_drag(context),
                 Action::Enumerate => self.action_enumerate(context),
+                Action::Enumerate2 => self.action_enumerate_2(context),
                 Action::Equals => self.action_equals(context),
                 Action::Equals2 => self.action_equals_2(context),
+                Action::Extends => self.action_extends(context),
                 Action::GetMember => self.action_get_member(context),
                 Action::GetProperty => self.action_get_property(context),
                 Action::GetTime => self.action_get_time(context),
@@ -91,12 +759,15 @@ impl Avm1 {
                 } => self.action_goto_frame_2(context, set_playing, scene_offset),
                 Action::GotoLabel(label) => self.action_goto_label(context, &label),
                 Action::If { offset } => self.action_if(context, offset, &mut reader),
+                Action::ImplementsOp => self.action_implements_op(context),
                 Action::Increment => self.action_increment(context),
                 Action::InitArray => self.action_init_array(context),
                 Action::InitObject => self.action_init_object(context),
+                Action::InstanceOf => self.action_instance_of(context),
                 Action::Jump { offset } => self.action_jump(context, offset, &mut reader),
                 Action::Less => self.action_less(context),
                 Action::Less2 => self.action_less_2(context),
+                Action::Greater => self.action_greater(context),
                 Action::MBAsciiToChar => self.action_mb_ascii_to_char(context),
                 Action::MBCharToAscii => self.action_mb_char_to_ascii(context),
                 Action::MBStringLength => self.action_mb_string_length(context),
@@ -119,24 +790,31 @@ impl Avm1 {
                 Action::SetMember => self.action_set_member(context),
                 Action::SetProperty => self.action_set_property(context),
                 Action::SetTarget(target) => self.action_set_target(context, &target),
+                Action::SetTarget2 => self.action_set_target_2(context),
                 Action::SetVariable => self.action_set_variable(context),
                 Action::StackSwap => self.action_stack_swap(context),
                 Action::StartDrag => self.action_start_drag(context),
                 Action::Stop => self.action_stop(context),
                 Action::StopSounds => self.action_stop_sounds(context),
                 Action::StoreRegister(register) => self.action_store_register(context, register),
+                Action::StrictEquals => self.action_strict_equals(context),
                 Action::StringAdd => self.action_string_add(context),
                 Action::StringEquals => self.action_string_equals(context),
                 Action::StringExtract => self.action_string_extract(context),
+                Action::StringGreater => self.action_string_greater(context),
                 Action::StringLength => self.action_string_length(context),
                 Action::StringLess => self.action_string_less(context),
                 Action::Subtract => self.action_subtract(context),
                 Action::TargetPath => self.action_target_path(context),
+                Action::Throw => self.action_throw(context),
                 Action::ToggleQuality => self.toggle_quality(context),
                 Action::ToInteger => self.action_to_integer(context),
                 Action::ToNumber => self.action_to_number(context),
                 Action::ToString => self.action_to_string(context),
                 Action::Trace => self.action_trace(context),
+                Action::Try(try_block) => {
+                    self.action_try(context, &try_block, code, &mut reader, action_pos)
+                }
                 Action::TypeOf => self.action_type_of(context),
                 Action::WaitForFrame {
                     frame,
@@ -145,351 +823,824 @@ impl Avm1 {
                 Action::WaitForFrame2 {
                     num_actions_to_skip,
                 } => self.action_wait_for_frame_2(context, num_actions_to_skip, &mut reader),
-                Action::With { .. } => self.action_with(context),
+                Action::With { .. } => self.action_with(context, code, &mut reader, action_pos),
                 _ => self.unknown_op(context, action),
             };
-            if let Err(ref e) = result {
-                log::error!("AVM1 error: {}", e);
-                return result;
+            result?;
+
+            if self.current_frame().map_or(false, Activation::has_returned) {
+                break;
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// Returns the bytecode block that directly follows the action at `action_pos`,
+    /// such as the body of a `DefineFunction`, and moves the reader past it.
+    /// The size of the block is stored in the last two bytes of the action.
+    fn read_action_block(
+        code: &SwfSlice,
+        reader: &mut Reader<Cursor<&[u8]>>,
+        action_pos: usize,
+    ) -> Result<SwfSlice, Error> {
+        let data = code.as_ref();
+        let action_len = Self::read_u16_at(data, action_pos + 1)?;
+        if action_len < 2 {
+            return Err("Action is missing its block size".into());
+        }
+        let header_end = action_pos + 3 + action_len;
+        let block_len = Self::read_u16_at(data, header_end - 2)?;
+        let block = code
+            .to_subslice(header_end, block_len)
+            .ok_or("Action block out of bounds")?;
+        reader
+            .get_inner()
+            .set_position((header_end + block_len) as u64);
+        Ok(block)
+    }
+
+    fn read_u16_at(data: &[u8], pos: usize) -> Result<usize, Error> {
+        match (data.get(pos), data.get(pos + 1)) {
+            (Some(&lo), Some(&hi)) => Ok(usize::from(u16::from_le_bytes([lo, hi]))),
+            _ => Err("Action block out of bounds".into()),
+        }
+    }
+
+    fn current_frame(&self) -> Option<&Activation<'gc>> {
+        self.stack_frames.last()
+    }
+
+    fn current_frame_mut(&mut self) -> Option<&mut Activation<'gc>> {
+        self.stack_frames.last_mut()
+    }
+
+    /// The innermost scope of the running code, including any `with` blocks,
+    /// or `None` when running top-level code outside of a `with` block.
+    fn current_scope(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.current_frame().and_then(Activation::scope)
     }
 
-    pub fn resolve_slash_path<'gc>(
+    /// The local scope of the running function, or `None` when running top-level code.
+    fn local_scope(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.current_scope().and_then(Scope::local_scope)
+    }
+
+    fn current_this(&self) -> Value<'gc> {
+        self.current_frame()
+            .map_or(Value::Undefined, |frame| frame.this().clone())
+    }
+
+    /// Calls `function` with the given `this` and arguments, returning its result.
+    pub fn call_function(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        function: Value<'gc>,
+        this: Value<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        self.call_function_with_base(context, function, this, None, args)
+    }
+
+    /// Calls `function` as a method that was found on the prototype `base_proto`.
+    /// Any use of `super` inside the method is resolved relative to `base_proto`.
+    fn call_function_with_base(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        function: Value<'gc>,
+        this: Value<'gc>,
+        base_proto: Option<GcCell<'gc, Object<'gc>>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        if let Value::Object(function) = function {
+            let (executable, super_this) = {
+                let function = function.read();
+                (function.as_executable(), function.super_this().cloned())
+            };
+            if let Some(executable) = executable {
+                return executable.exec(self, context, function, this, base_proto, args);
+            }
+            if let Some(super_this) = super_this {
+                // Calling `super` runs the superclass constructor on the current object.
+                let constructor = Object::get(function, "__constructor__", self, context)?;
+                let base_proto = function.read().prototype();
+                return self.call_function_with_base(
+                    context,
+                    constructor,
+                    super_this,
+                    base_proto,
+                    args,
+                );
+            }
+        }
+        log::warn!("Attempted to call a non-function");
+        Ok(Value::Undefined)
+    }
+
+    /// Creates a new object using `constructor`, as the `new` operator does.
+    fn construct(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        constructor: GcCell<'gc, Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let prototype = Object::get(constructor, "prototype", self, context)?
+            .as_object()
+            .ok();
+        // Instances of `Array` and its subclasses are real arrays.
+        let this = if self.is_array_prototype(prototype) {
+            Object::array(context.gc_context, prototype, vec![])
+        } else {
+            Object::object(context.gc_context, prototype)
+        };
+        this.write(context.gc_context)
+            .force_set("__constructor__", constructor, object::DONT_ENUM);
+        self.call_function_with_base(context, constructor.into(), this.into(), prototype, args)?;
+        Ok(this.into())
+    }
+
+    /// Returns whether `prototype` is `Array.prototype` or inherits from it.
+    fn is_array_prototype(&self, mut prototype: Option<GcCell<'gc, Object<'gc>>>) -> bool {
+        while let Some(p) = prototype {
+            if GcCell::as_ptr(p) == GcCell::as_ptr(self.prototypes.array) {
+                return true;
+            }
+            prototype = p.read().prototype();
+        }
+        false
+    }
+
+    /// Returns the prototype that holds the methods of a primitive value,
+    /// such as `String.prototype` for strings.
+    fn primitive_prototype(&self, value: &Value<'gc>) -> Option<GcCell<'gc, Object<'gc>>> {
+        match value {
+            Value::String(_) => Some(self.prototypes.string),
+            Value::Number(_) => Some(self.prototypes.number),
+            Value::Bool(_) => Some(self.prototypes.boolean),
+            _ => None,
+        }
+    }
+
+    /// Returns whether `constructor.prototype` is in the prototype chain of `object`,
+    /// or is implemented as an interface by one of the prototypes in that chain.
+    fn is_instance_of(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        object: Value<'gc>,
+        constructor: Value<'gc>,
+    ) -> Result<bool, Error> {
+        let (object, constructor) = match (object, constructor) {
+            (Value::Object(object), Value::Object(constructor)) => (object, constructor),
+            _ => return Ok(false),
+        };
+        let prototype = match Object::get(constructor, "prototype", self, context)? {
+            Value::Object(prototype) => prototype,
+            _ => return Ok(false),
+        };
+
+        let is_in_chain = |mut proto: Option<GcCell<'gc, Object<'gc>>>| {
+            while let Some(p) = proto {
+                if GcCell::as_ptr(p) == GcCell::as_ptr(prototype) {
+                    return true;
+                }
+                proto = p.read().prototype();
+            }
+            false
+        };
+
+        let mut proto = object.read().prototype();
+        while let Some(p) = proto {
+            if GcCell::as_ptr(p) == GcCell::as_ptr(prototype) {
+                return Ok(true);
+            }
+            for interface in p.read().interfaces() {
+                let interface_proto = interface
+                    .read()
+                    .get_local("prototype")
+                    .and_then(|value| value.as_object().ok());
+                if is_in_chain(interface_proto) {
+                    return Ok(true);
+                }
+            }
+            proto = p.read().prototype();
+        }
+        Ok(false)
+    }
+
+    fn get_register(&self, id: u8) -> Value<'gc> {
+        let value = match self.current_frame() {
+            Some(frame) if frame.has_local_registers() => frame.local_register(id),
+            _ => self.registers.get(usize::from(id)),
+        };
+        value.cloned().unwrap_or(Value::Undefined)
+    }
+
+    fn set_register(&mut self, id: u8, value: Value<'gc>) {
+        match self.current_frame_mut() {
+            Some(frame) if frame.has_local_registers() => frame.set_local_register(id, value),
+            _ => {
+                if let Some(register) = self.registers.get_mut(usize::from(id)) {
+                    *register = value;
+                }
+            }
+        }
+    }
+
+    /// Pops a function argument count followed by that many arguments off the stack.
+    fn pop_args(&mut self) -> Result<Vec<Value<'gc>>, Error> {
+        let num_args = self.pop()?.as_i64()?; // TODO(Herschel): max arg count?
+        let mut args = Vec::with_capacity(num_args.max(0) as usize);
+        for _ in 0..num_args {
+            args.push(self.pop()?);
+        }
+        Ok(args)
+    }
+
+    /// Resolves a target path relative to `start`.
+    ///
+    /// Paths use either slash syntax, such as `/clip/child` or `../sibling`, or dot syntax,
+    /// such as `_root.clip.child` or `_parent.sibling`.
+    pub fn resolve_slash_path(
         start: DisplayNode<'gc>,
         root: DisplayNode<'gc>,
         mut path: &str,
     ) -> Option<DisplayNode<'gc>> {
-        let mut cur_clip = if path.bytes().nth(0).unwrap_or(0) == b'/' {
+        let mut cur_clip = if path.starts_with('/') {
             path = &path[1..];
             root
         } else {
             start
         };
-        if !path.is_empty() {
-            for name in path.split('/') {
-                let next_clip = if let Some(clip) = cur_clip.read().as_movie_clip() {
-                    if let Some(child) = clip.get_child_by_name(name) {
-                        *child
+        let separator = if path.contains('/') || path == ".." {
+            '/'
+        } else {
+            '.'
+        };
+        for name in path.split(separator).filter(|name| !name.is_empty()) {
+            cur_clip = match name {
+                ".." | "_parent" => cur_clip.read().parent()?,
+                "." | "this" => cur_clip,
+                "_root" => root,
+                _ if name.starts_with("_level") => {
+                    // TODO: Other levels are only used by movies loaded with `loadMovieNum`.
+                    if name["_level".len()..].parse() == Ok(0) {
+                        root
                     } else {
                         return None;
                     }
-                } else {
-                    return None;
-                };
-                cur_clip = next_clip;
-            }
+                }
+                _ => {
+                    let display_object = cur_clip.read();
+                    *display_object.as_movie_clip()?.get_child_by_name(name)?
+                }
+            };
         }
         Some(cur_clip)
     }
 
-    pub fn resolve_slash_path_variable<'gc, 's>(
+    /// Splits a variable path, such as `/clip:var`, `../clip:var` or `_root.clip.var`,
+    /// into the display object that holds the variable and the name of the variable.
+    /// A plain variable name refers to a variable of `start`.
+    pub fn resolve_slash_path_variable<'s>(
         start: DisplayNode<'gc>,
         root: DisplayNode<'gc>,
         path: &'s str,
     ) -> Option<(DisplayNode<'gc>, &'s str)> {
-        if !path.is_empty() {
-            let mut var_iter = path.splitn(2, ':');
-            match (var_iter.next(), var_iter.next()) {
-                (Some(var_name), None) => return Some((start, var_name)),
-                (Some(path), Some(var_name)) => {
-                    if let Some(node) = Self::resolve_slash_path(start, root, path) {
-                        return Some((node, var_name));
-                    }
-                }
-                _ => (),
-            }
+        if path.is_empty() {
+            return None;
+        }
+        let split_pos = if path.contains(':') {
+            path.rfind(':')
+        } else if path.contains('/') {
+            None
+        } else {
+            path.rfind('.')
+        };
+        match split_pos {
+            Some(pos) => Self::resolve_slash_path(start, root, &path[..pos])
+                .map(|node| (node, &path[pos + 1..])),
+            None => Some((start, path)),
         }
-
-        None
     }
 
-    fn push(&mut self, value: impl Into<Value>) {
+    fn push(&mut self, value: impl Into<Value<'gc>>) {
         self.stack.push(value.into());
     }
 
-    fn pop(&mut self) -> Result<Value, Error> {
+    fn pop(&mut self) -> Result<Value<'gc>, Error> {
         self.stack.pop().ok_or_else(|| "Stack underflow".into())
     }
 
+    /// Pops a value and converts it to a number.
+    /// SWF 4 actions convert without calling `valueOf`, as SWF 4 has no objects.
+    fn pop_number(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<f64, Error> {
+        let value = self.pop()?;
+        if self.swf_version < 5 {
+            Ok(value.into_number_v1())
+        } else {
+            value.coerce_to_number(self, context)
+        }
+    }
+
+    /// Pops a value and converts it to a string, calling `toString` on objects.
+    fn pop_string(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<String, Error> {
+        let value = self.pop()?;
+        value.coerce_to_string(self, context)
+    }
+
     fn unknown_op(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         action: swf::avm1::types::Action,
     ) -> Result<(), Error> {
         log::error!("Unknown AVM1 opcode: {:?}", action);
         Err("Unknown op".into())
     }
 
-    fn action_add(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?;
-        let b = self.pop()?;
-        self.push(Value::Number(b.into_number_v1() + a.into_number_v1()));
+    fn action_add(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        self.push(Value::Number(b + a));
         Ok(())
     }
 
-    fn action_add_2(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_add_2(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // ECMA-262 s. 11.6.1
-        let a = self.pop()?;
-        let b = self.pop()?;
-        // TODO(Herschel):
-        if let Value::String(a) = a {
-            let mut s = b.into_string();
-            s.push_str(&a);
+        let a = self
+            .pop()?
+            .to_primitive(self, context, PrimitiveHint::Number)?;
+        let b = self
+            .pop()?
+            .to_primitive(self, context, PrimitiveHint::Number)?;
+        let is_string = match (&a, &b) {
+            (Value::String(_), _) | (_, Value::String(_)) => true,
+            _ => false,
+        };
+        if is_string {
+            let mut s = b.coerce_to_string(self, context)?;
+            s.push_str(&a.coerce_to_string(self, context)?);
             self.push(Value::String(s));
-        } else if let Value::String(mut b) = b {
-            b.push_str(&a.into_string());
-            self.push(Value::String(b));
         } else {
-            self.push(Value::Number(b.into_number() + a.into_number()));
+            let a = a.coerce_to_number(self, context)?;
+            let b = b.coerce_to_number(self, context)?;
+            self.push(Value::Number(b + a));
         }
         Ok(())
     }
 
-    fn action_and(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_and(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 logical and
         let a = self.pop()?;
         let b = self.pop()?;
-        let result = b.into_number_v1() != 0.0 && a.into_number_v1() != 0.0;
+        let result = b.as_bool(self.swf_version) && a.as_bool(self.swf_version);
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn action_ascii_to_char(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_ascii_to_char(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Results on incorrect operands?
-        let val = (self.pop()?.as_f64()? as u8) as char;
+        let val = (number_to_u32(self.pop_number(context)?) as u8) as char;
         self.push(Value::String(val.to_string()));
         Ok(())
     }
 
-    fn action_char_to_ascii(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_clone_sprite(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        // The depth has already been moved into the range used by scripts.
+        let depth = number_to_i32(self.pop_number(context)?);
+        let target = self.pop_string(context)?;
+        let source = self.pop_string(context)?;
+        if let Some(node) = Avm1::resolve_slash_path(context.active_clip, context.root, &source) {
+            if globals::duplicate_movie_clip(self, context, node, &target, depth).is_none() {
+                log::warn!("CloneSprite: Unable to duplicate {}", source);
+            }
+        } else {
+            log::warn!("CloneSprite: Invalid source {}", source);
+        }
+        Ok(())
+    }
+
+    fn action_char_to_ascii(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Results on incorrect operands?
-        let s = self.pop()?.into_string();
+        let s = self.pop_string(context)?;
         let result = s.bytes().nth(0).unwrap_or(0);
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_and(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_u32()?;
-        let b = self.pop()?.as_u32()?;
+    fn action_bit_and(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = number_to_i32(self.pop_number(context)?);
+        let b = number_to_i32(self.pop_number(context)?);
         let result = a & b;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_lshift(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_i32()? & 0b11111; // Only 5 bits used for shift count
-        let b = self.pop()?.as_i32()?;
+    fn action_bit_lshift(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = number_to_u32(self.pop_number(context)?) & 0b11111; // Only 5 bits used for shift count
+        let b = number_to_i32(self.pop_number(context)?);
         let result = b << a;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_or(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_u32()?;
-        let b = self.pop()?.as_u32()?;
+    fn action_bit_or(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = number_to_i32(self.pop_number(context)?);
+        let b = number_to_i32(self.pop_number(context)?);
         let result = a | b;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_rshift(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_i32()? & 0b11111; // Only 5 bits used for shift count
-        let b = self.pop()?.as_i32()?;
+    fn action_bit_rshift(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = number_to_u32(self.pop_number(context)?) & 0b11111; // Only 5 bits used for shift count
+        let b = number_to_i32(self.pop_number(context)?);
         let result = b >> a;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_urshift(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_u32()? & 0b11111; // Only 5 bits used for shift count
-        let b = self.pop()?.as_u32()?;
+    fn action_bit_urshift(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let a = number_to_u32(self.pop_number(context)?) & 0b11111; // Only 5 bits used for shift count
+        let b = number_to_u32(self.pop_number(context)?);
         let result = b >> a;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_bit_xor(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.as_u32()?;
-        let b = self.pop()?.as_u32()?;
+    fn action_bit_xor(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = number_to_i32(self.pop_number(context)?);
+        let b = number_to_i32(self.pop_number(context)?);
         let result = b ^ a;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_call(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_call(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let _val = self.pop()?;
         // TODO(Herschel)
         Err("Unimplemented action: Call".into())
     }
 
-    fn action_call_function(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _fn_name = self.pop()?.as_string()?;
-        let num_args = self.pop()?.as_i64()?; // TODO(Herschel): max arg count?
-        for _ in 0..num_args {
-            self.pop()?;
-        }
+    fn action_call_function(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let fn_name = self.pop()?.into_string();
+        let args = self.pop_args()?;
+        let function = self.get_variable(context, &fn_name)?;
+        let this = self.current_this();
+        let result = self.call_function(context, function, this, &args)?;
+        self.push(result);
+        Ok(())
+    }
 
-        self.stack.push(Value::Undefined);
-        // TODO(Herschel)
-        Err("Unimplemented action: CallFunction".into())
+    fn action_call_method(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let method_name = self.pop()?;
+        let object = self.pop()?;
+        let args = self.pop_args()?;
+
+        let result = match (&method_name, &object) {
+            // A blank method name means that the object itself is the function to call.
+            (Value::Undefined, _) => {
+                self.call_function(context, object, Value::Undefined, &args)?
+            }
+            (Value::String(name), _) if name.is_empty() => {
+                self.call_function(context, object, Value::Undefined, &args)?
+            }
+            (_, Value::Object(target)) => {
+                let name = method_name.into_string();
+                let method = Object::get(*target, &name, self, context)?;
+                // Methods called through `super` still run on the original object.
+                let this = target
+                    .read()
+                    .super_this()
+                    .cloned()
+                    .unwrap_or(object.clone());
+                let base_proto = Object::property_owner(*target, &name);
+                self.call_function_with_base(context, method, this, base_proto, &args)?
+            }
+            (_, primitive) if self.primitive_prototype(primitive).is_some() => {
+                // Methods of primitives are found on the prototype of their class,
+                // and are called with the primitive itself as `this`.
+                let prototype = self.primitive_prototype(primitive).unwrap();
+                let name = method_name.into_string();
+                let method = Object::get(prototype, &name, self, context)?;
+                let base_proto = Object::property_owner(prototype, &name);
+                self.call_function_with_base(context, method, object.clone(), base_proto, &args)?
+            }
+            _ => {
+                log::warn!(
+                    "CallMethod: Cannot call method {} of non-object",
+                    method_name.into_string()
+                );
+                Value::Undefined
+            }
+        };
+        self.push(result);
+        Ok(())
     }
 
-    fn action_call_method(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _method_name = self.pop()?.as_string()?;
-        let _object = self.pop()?.as_object()?;
-        let num_args = self.pop()?.as_i64()?; // TODO(Herschel): max arg count?
-        for _ in 0..num_args {
-            self.pop()?;
+    fn action_cast_op(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let object = self.pop()?;
+        let constructor = self.pop()?;
+        if self.is_instance_of(context, object.clone(), constructor)? {
+            self.push(object);
+        } else {
+            self.push(Value::Null);
         }
-
-        self.stack.push(Value::Undefined);
-        // TODO(Herschel)
-        Err("Unimplemented action: CallMethod".into())
+        Ok(())
     }
 
     fn action_constant_pool(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         constant_pool: &[String],
     ) -> Result<(), Error> {
         self.constant_pool = constant_pool.to_vec();
         Ok(())
     }
 
-    fn action_decrement(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.into_number();
+    fn action_decrement(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = self.pop_number(context)?;
         self.push(Value::Number(a - 1.0));
         Ok(())
     }
 
     fn action_define_function(
         &mut self,
-        _context: &mut ActionContext,
-        _name: &str,
-        _params: &[String],
-        _actions: &[swf::avm1::types::Action],
+        context: &mut ActionContext<'_, 'gc, '_>,
+        name: &str,
+        params: &[String],
+        code: &SwfSlice,
+        reader: &mut Reader<Cursor<&[u8]>>,
+        action_pos: usize,
     ) -> Result<(), Error> {
-        // TODO(Herschel)
-        Err("Unimplemented action: DefineFunction".into())
+        let body = Self::read_action_block(code, reader, action_pos)?;
+        let function = Avm1Function::from_df1(
+            body,
+            name,
+            params,
+            self.current_scope(),
+            self.constant_pool.clone(),
+            context.active_clip,
+        );
+        self.define_function(context, function);
+        Ok(())
     }
 
-    fn action_define_local(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_define_function_2(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        function: &swf::avm1::types::Function,
+        code: &SwfSlice,
+        reader: &mut Reader<Cursor<&[u8]>>,
+        action_pos: usize,
+    ) -> Result<(), Error> {
+        let body = Self::read_action_block(code, reader, action_pos)?;
+        let function = Avm1Function::from_df2(
+            body,
+            function,
+            self.current_scope(),
+            self.constant_pool.clone(),
+            context.active_clip,
+        );
+        self.define_function(context, function);
+        Ok(())
+    }
+
+    /// Creates a function object, and either stores it under its name or pushes it
+    /// on to the stack if it is anonymous.
+    fn define_function(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        function: Avm1Function<'gc>,
+    ) {
+        let name = function.name().to_string();
+        let function = Object::function(
+            context.gc_context,
+            Executable::Action(function),
+            Some(self.prototypes.function),
+        );
+        let prototype = Object::object(context.gc_context, Some(self.prototypes.object));
+        prototype
+            .write(context.gc_context)
+            .force_set("constructor", function, object::DONT_ENUM);
+        function
+            .write(context.gc_context)
+            .force_set("prototype", prototype, object::DONT_ENUM);
+        if name.is_empty() {
+            self.push(function);
+        } else {
+            self.define_local(context, &name, function.into());
+        }
+    }
+
+    fn action_define_local(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         let value = self.pop()?;
-        let name = self.pop()?;
-        self.locals.insert(name.as_string()?.clone(), value);
+        let name = self.pop()?.into_string();
+        self.define_local(context, &name, value);
         Ok(())
     }
 
-    fn action_define_local_2(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let name = self.pop()?;
-        self.locals
-            .insert(name.as_string()?.clone(), Value::Undefined);
+    fn action_define_local_2(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let name = self.pop()?.into_string();
+        let is_defined = if let Some(scope) = self.local_scope() {
+            scope.read().locals().read().has_own_property(&name)
+        } else if let Value::Object(object) = context.active_clip.read().object() {
+            object.read().has_own_property(&name)
+        } else {
+            false
+        };
+        if !is_defined {
+            self.define_local(context, &name, Value::Undefined);
+        }
         Ok(())
     }
 
-    fn action_delete(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _name = self.pop()?.as_string()?;
-        let _object = self.pop()?.as_object()?;
-        Err("Unimplemented action: Delete".into())
-        // TODO(Herschel)
+    /// Declares a variable in the current local scope.
+    /// Top-level code has no local scope, so its variables are stored on the active clip.
+    fn define_local(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        name: &str,
+        value: Value<'gc>,
+    ) {
+        if let Some(scope) = self.local_scope() {
+            scope
+                .read()
+                .locals()
+                .write(context.gc_context)
+                .force_set(name, value, 0);
+        } else if let Value::Object(object) = context.active_clip.read().object() {
+            object.write(context.gc_context).force_set(name, value, 0);
+        }
     }
 
-    fn action_delete_2(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_delete(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let name = self.pop()?.into_string();
+        let object = self.pop()?;
+        let success = if let Value::Object(object) = object {
+            object.write(context.gc_context).delete(&name)
+        } else {
+            log::warn!("Delete: Cannot delete property {} of non-object", name);
+            false
+        };
+        self.push(Value::Bool(success));
+        Ok(())
+    }
+
+    fn action_delete_2(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let _name = self.pop()?.as_string()?;
         Err("Unimplemented action: Delete2".into())
         // TODO(Herschel)
     }
 
-    fn action_divide(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_divide(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 divide
-        let a = self.pop()?;
-        let b = self.pop()?;
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
 
         // TODO(Herschel): SWF19: "If A is zero, the result NaN, Infinity, or -Infinity is pushed to the in SWF 5 and later.
         // In SWF 4, the result is the string #ERROR#.""
         // Seems to be unture for SWF v4, I get 1.#INF.
 
-        self.push(Value::Number(b.into_number_v1() / a.into_number_v1()));
+        self.push(Value::Number(b / a));
         Ok(())
     }
 
-    fn action_end_drag(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // TODO(Herschel)
-        log::error!("Unimplemented action: EndDrag");
+    fn action_end_drag(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        self.stop_drag();
         Ok(())
     }
 
-    fn action_enumerate(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _name = self.pop()?.as_string()?;
+    fn action_enumerate(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let var_path = self.pop()?.into_string();
+        let object = self.get_variable(context, &var_path)?;
+        self.enumerate(object);
+        Ok(())
+    }
+
+    fn action_enumerate_2(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let object = self.pop()?;
+        self.enumerate(object);
+        Ok(())
+    }
+
+    /// Pushes the names of all enumerable properties of `object` for a `for..in` loop.
+    fn enumerate(&mut self, object: Value<'gc>) {
         self.push(Value::Null); // Sentinel that indicates end of enumeration
-                                // TODO(Herschel): Push each property name onto the stack
-        Err("Unimplemented action: Enumerate".into())
+        if let Value::Object(object) = object {
+            // The loop pops the names, so they are pushed in reverse.
+            for name in object.read().get_keys().into_iter().rev() {
+                self.push(Value::String(name));
+            }
+        } else {
+            log::warn!("Enumerate: Cannot enumerate properties of non-object");
+        }
     }
 
     #[allow(clippy::float_cmp)]
-    fn action_equals(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_equals(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 equality
-        let a = self.pop()?;
-        let b = self.pop()?;
-        let result = b.into_number_v1() == a.into_number_v1();
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        let result = b == a;
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    #[allow(clippy::float_cmp)]
-    fn action_equals_2(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // Version >=5 equality
+    fn action_equals_2(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // ECMA-262 s. 11.9.3
         let a = self.pop()?;
         let b = self.pop()?;
-        let result = match (b, a) {
-            (Value::Undefined, Value::Undefined) => true,
-            (Value::Null, Value::Null) => true,
-            (Value::Null, Value::Undefined) => true,
-            (Value::Undefined, Value::Null) => true,
-            (Value::Number(a), Value::Number(b)) => a == b,
-            (Value::String(a), Value::String(b)) => a == b,
-            (Value::Object(_a), Value::Object(_b)) => false, // TODO(Herschel)
-            (Value::String(a), Value::Number(b)) => a.parse().unwrap_or(std::f64::NAN) == b,
-            (Value::Number(a), Value::String(b)) => a == b.parse().unwrap_or(std::f64::NAN),
-            _ => false,
-        };
+        let result = b.abstract_eq(a, self, context)?;
         self.push(Value::Bool(result));
         Ok(())
     }
 
-    fn action_get_member(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _name = self.pop()?.as_string()?;
-        let _object = self.pop()?.as_object()?;
-        // TODO(Herschel)
-        Err("Unimplemented action: GetMember".into())
+    fn action_extends(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let superclass = self.pop()?;
+        let subclass = self.pop()?;
+        let (superclass, subclass) = match (superclass, subclass) {
+            (Value::Object(superclass), Value::Object(subclass)) => (superclass, subclass),
+            _ => {
+                log::warn!("Extends: Superclass and subclass must be objects");
+                return Ok(());
+            }
+        };
+
+        let super_prototype = Object::get(superclass, "prototype", self, context)?
+            .as_object()
+            .ok();
+        let prototype = Object::object(context.gc_context, super_prototype);
+        prototype.write(context.gc_context).force_set(
+            "__constructor__",
+            superclass,
+            object::DONT_ENUM,
+        );
+        Object::set(subclass, "prototype", prototype.into(), self, context)?;
+        Ok(())
+    }
+
+    fn action_get_member(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let name = self.pop()?.into_string();
+        let object = self.pop()?;
+        let value = match object {
+            Value::Object(object) => Object::get(object, &name, self, context)?,
+            Value::String(ref s) if name == "length" => Value::Number(globals::utf16_len(s) as f64),
+            ref primitive if self.primitive_prototype(primitive).is_some() => {
+                let prototype = self.primitive_prototype(primitive).unwrap();
+                let value = Object::get(prototype, &name, self, context)?;
+                value
+            }
+            _ => {
+                log::warn!("GetMember: Cannot get property {} of non-object", name);
+                Value::Undefined
+            }
+        };
+        self.push(value);
+        Ok(())
     }
 
-    fn action_get_property(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_get_property(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         let prop_index = self.pop()?.as_u32()? as usize;
         let clip_path = self.pop()?;
         let ret = if let Some(clip) =
             Avm1::resolve_slash_path(context.active_clip, context.root, clip_path.as_string()?)
         {
-            if let Some(clip) = clip.read().as_movie_clip() {
-                match prop_index {
-                    0 => Value::Number(f64::from(clip.x())),
-                    1 => Value::Number(f64::from(clip.y())),
-                    2 => Value::Number(f64::from(clip.x_scale())),
-                    3 => Value::Number(f64::from(clip.y_scale())),
-                    4 => Value::Number(f64::from(clip.current_frame())),
-                    5 => Value::Number(f64::from(clip.total_frames())),
-                    10 => Value::Number(f64::from(clip.rotation())),
-                    12 => Value::Number(f64::from(clip.frames_loaded())),
-                    _ => {
-                        log::error!("GetProperty: Unimplemented property index {}", prop_index);
-                        Value::Undefined
-                    }
-                }
+            let value = display_properties::name_from_index(prop_index)
+                .and_then(|name| display_properties::get(self, context, clip, name));
+            if let Some(value) = value {
+                value
             } else {
+                log::error!("GetProperty: Invalid property index {}", prop_index);
                 Value::Undefined
             }
         } else {
@@ -499,31 +1650,91 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_get_time(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_get_time(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         self.stack.push(Value::Number(context.global_time as f64));
         Ok(())
     }
 
-    fn action_get_variable(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_get_variable(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // Flash 4-style variable
         let var_path = self.pop()?;
-        if let Some((node, var_name)) = Self::resolve_slash_path_variable(
-            context.active_clip,
-            context.root,
-            var_path.as_string()?,
-        ) {
-            if let Some(clip) = node.read().as_movie_clip() {
-                self.push(clip.get_variable(var_name));
+        let value = self.get_variable(context, var_path.as_string()?)?;
+        self.push(value);
+        Ok(())
+    }
+
+    /// Looks up a variable in the local scope chain and any `with` objects, then on the
+    /// active clip, and finally on `_global`.
+    fn get_variable(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        var_path: &str,
+    ) -> Result<Value<'gc>, Error> {
+        let locals = self
+            .current_scope()
+            .and_then(|scope| scope.read().find(var_path));
+        if let Some(locals) = locals {
+            return Object::get(locals, var_path, self, context);
+        }
+
+        match var_path {
+            "this" => return Ok(self.current_this()),
+            "_global" => return Ok(self.globals.into()),
+            "_root" | "_level0" => return Ok(context.root.read().object()),
+            _ => (),
+        }
+
+        if let Some((node, var_name)) =
+            Self::resolve_slash_path_variable(context.active_clip, context.root, var_path)
+        {
+            let object = node.read().object();
+            if let Value::Object(object) = object {
+                if object.read().has_property(var_name) {
+                    return Object::get(object, var_name, self, context);
+                }
+            }
+        }
+
+        // Anything not found on the timeline falls back to `_global`.
+        let globals = self.globals;
+        if globals.read().has_property(var_path) {
+            return Object::get(globals, var_path, self, context);
+        }
+        Ok(Value::Undefined)
+    }
+
+    /// Assigns to a variable in the innermost scope or `with` object that defines it.
+    /// Undeclared variables are stored on the active clip.
+    fn set_variable(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        var_path: &str,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        let locals = self
+            .current_scope()
+            .and_then(|scope| scope.read().find(var_path));
+        if let Some(locals) = locals {
+            return Object::set(locals, var_path, value, self, context);
+        }
+
+        if let Some((node, var_name)) =
+            Self::resolve_slash_path_variable(context.active_clip, context.root, var_path)
+        {
+            let object = node.read().object();
+            if let Value::Object(object) = object {
+                Object::set(object, var_name, value, self, context)?;
             }
-        } else {
-            self.push(Value::Undefined);
         }
         Ok(())
     }
 
     fn action_get_url(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         _url: &str,
         _target: &str,
     ) -> Result<(), Error> {
@@ -534,7 +1745,7 @@ impl Avm1 {
 
     fn action_get_url_2(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         _method: swf::avm1::types::SendVarsMethod,
         _is_target_sprite: bool,
         _is_load_vars: bool,
@@ -546,30 +1757,44 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_goto_frame(&mut self, context: &mut ActionContext, frame: u16) -> Result<(), Error> {
+    fn action_goto_frame(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        frame: u16,
+    ) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
-        let clip = display_object.as_movie_clip_mut().unwrap();
-        clip.goto_frame(frame + 1, true);
+        if let Some(clip) = display_object.as_movie_clip_mut() {
+            clip.goto_frame(frame.saturating_add(1), true);
+        } else {
+            log::warn!("GotoFrame failed: Not a MovieClip");
+        }
         Ok(())
     }
 
     fn action_goto_frame_2(
         &mut self,
-        context: &mut ActionContext,
+        context: &mut ActionContext<'_, 'gc, '_>,
         set_playing: bool,
         scene_offset: u16,
     ) -> Result<(), Error> {
         // Version 4+ gotoAndPlay/gotoAndStop
         // Param can either be a frame number or a frame label.
+        let frame = self.pop()?;
         let mut display_object = context.active_clip.write(context.gc_context);
-        let clip = display_object.as_movie_clip_mut().unwrap();
-        match self.pop()? {
+        let clip = if let Some(clip) = display_object.as_movie_clip_mut() {
+            clip
+        } else {
+            log::warn!("GotoFrame2 failed: Not a MovieClip");
+            return Ok(());
+        };
+        match frame {
             Value::Number(frame) => {
-                clip.goto_frame(scene_offset + (frame as u16) + 1, !set_playing)
+                let frame = (frame as u16).saturating_add(1);
+                clip.goto_frame(scene_offset.saturating_add(frame), !set_playing)
             }
             Value::String(frame_label) => {
                 if let Some(frame) = clip.frame_label_to_number(&frame_label) {
-                    clip.goto_frame(scene_offset + frame, !set_playing)
+                    clip.goto_frame(scene_offset.saturating_add(frame), !set_playing)
                 } else {
                     log::warn!(
                         "ActionGotoFrame2 failed: Movie clip {} does not contain frame label '{}'",
@@ -583,7 +1808,11 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_goto_label(&mut self, context: &mut ActionContext, label: &str) -> Result<(), Error> {
+    fn action_goto_label(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        label: &str,
+    ) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
         if let Some(clip) = display_object.as_movie_clip_mut() {
             if let Some(frame) = clip.frame_label_to_number(label) {
@@ -599,13 +1828,12 @@ impl Avm1 {
 
     fn action_if(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         jump_offset: i16,
         reader: &mut Reader<Cursor<&[u8]>>,
     ) -> Result<(), Error> {
         let val = self.pop()?;
-        if val.as_bool() {
-            use swf::read::SwfRead;
+        if val.as_bool(self.swf_version) {
             let pos = reader.get_inner().position();
             let new_pos = ((pos as i64) + i64::from(jump_offset)) as u64;
             reader.get_inner().set_position(new_pos);
@@ -613,164 +1841,263 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_increment(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let a = self.pop()?.into_number();
+    fn action_implements_op(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let constructor = self.pop()?;
+        let num_interfaces = self.pop()?.as_i64()?;
+        let mut interfaces = vec![];
+        for _ in 0..num_interfaces {
+            if let Value::Object(interface) = self.pop()? {
+                interfaces.push(interface);
+            }
+        }
+
+        let prototype = if let Value::Object(constructor) = constructor {
+            Object::get(constructor, "prototype", self, context)?
+        } else {
+            Value::Undefined
+        };
+        if let Value::Object(prototype) = prototype {
+            prototype
+                .write(context.gc_context)
+                .set_interfaces(interfaces);
+        } else {
+            log::warn!("ImplementsOp: Constructor has no prototype");
+        }
+        Ok(())
+    }
+
+    fn action_increment(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = self.pop_number(context)?;
         self.push(Value::Number(a + 1.0));
         Ok(())
     }
 
-    fn action_init_array(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_init_array(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let num_elements = self.pop()?.as_i64()?;
+        let mut elements = vec![];
         for _ in 0..num_elements {
-            let _value = self.pop()?;
+            elements.push(self.pop()?);
         }
-
-        // TODO(Herschel)
-        Err("Unimplemented action: InitArray".into())
+        self.push(Object::array(
+            context.gc_context,
+            Some(self.prototypes.array),
+            elements,
+        ));
+        Ok(())
     }
 
-    fn action_init_object(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_init_object(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         let num_props = self.pop()?.as_i64()?;
+        let mut object = Object::bare_object();
+        object.set_prototype(Some(self.prototypes.object));
+        let mut properties = vec![];
         for _ in 0..num_props {
-            let _value = self.pop()?;
-            let _name = self.pop()?;
+            let value = self.pop()?;
+            let name = self.pop()?.into_string();
+            properties.push((name, value));
+        }
+        // The last property is on top of the stack, so they are defined in reverse to
+        // keep the order they were written in.
+        for (name, value) in properties.into_iter().rev() {
+            object.force_set(&name, value, 0);
         }
+        self.push(GcCell::allocate(context.gc_context, object));
+        Ok(())
+    }
 
-        // TODO(Herschel)
-        Err("Unimplemented action: InitObject".into())
+    fn action_instance_of(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let constructor = self.pop()?;
+        let object = self.pop()?;
+        let result = self.is_instance_of(context, object, constructor)?;
+        self.push(result);
+        Ok(())
     }
 
     fn action_jump(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         jump_offset: i16,
         reader: &mut Reader<Cursor<&[u8]>>,
     ) -> Result<(), Error> {
         // TODO(Herschel): Handle out-of-bounds.
-        use swf::read::SwfRead;
         let pos = reader.get_inner().position();
         let new_pos = ((pos as i64) + i64::from(jump_offset)) as u64;
         reader.get_inner().set_position(new_pos);
         Ok(())
     }
 
-    fn action_less(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_less(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 less than
-        let a = self.pop()?;
-        let b = self.pop()?;
-        let result = b.into_number_v1() < a.into_number_v1();
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        let result = b < a;
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn action_less_2(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // ECMA-262 s. 11.8.5
+    fn action_less_2(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // ECMA-262 s. 11.8.1
         let a = self.pop()?;
         let b = self.pop()?;
+        let result = b.abstract_lt(a, self, context)?;
+        self.push(result);
+        Ok(())
+    }
 
-        let result = match (a, b) {
-            (Value::String(a), Value::String(b)) => b.to_string().bytes().lt(a.to_string().bytes()),
-            (a, b) => b.into_number() < a.into_number(),
-        };
-
-        self.push(Value::Bool(result));
+    fn action_greater(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // ECMA-262 s. 11.8.2
+        let a = self.pop()?;
+        let b = self.pop()?;
+        let result = a.abstract_lt(b, self, context)?;
+        self.push(result);
         Ok(())
     }
 
-    fn action_mb_ascii_to_char(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_mb_ascii_to_char(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Results on incorrect operands?
         use std::convert::TryFrom;
-        let val = char::try_from(self.pop()?.as_f64()? as u32)?;
+        let val = char::try_from(number_to_u32(self.pop_number(context)?))?;
         self.push(Value::String(val.to_string()));
         Ok(())
     }
 
-    fn action_mb_char_to_ascii(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_mb_char_to_ascii(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Results on incorrect operands?
-        let s = self.pop()?.into_string();
+        let s = self.pop_string(context)?;
         let result = s.chars().nth(0).unwrap_or('\0') as u32;
         self.push(Value::Number(result.into()));
         Ok(())
     }
 
-    fn action_mb_string_extract(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_mb_string_extract(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Result with incorrect operands?
-        let len = self.pop()?.as_f64()? as usize;
-        let start = self.pop()?.as_f64()? as usize;
-        let s = self.pop()?.into_string();
+        let len = self.pop_number(context)? as usize;
+        let start = self.pop_number(context)? as usize;
+        let s = self.pop_string(context)?;
         let result = s[len..len + start].to_string(); // TODO(Herschel): Flash uses UTF-16 internally.
         self.push(Value::String(result));
         Ok(())
     }
 
-    fn action_mb_string_length(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_mb_string_length(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // TODO(Herschel): Result with non-string operands?
-        let val = self.pop()?.into_string().len();
+        let val = self.pop_string(context)?.len();
         self.push(Value::Number(val as f64));
         Ok(())
     }
 
-    fn action_multiply(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_multiply(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 multiply
-        let a = self.pop()?;
-        let b = self.pop()?;
-        self.push(Value::Number(a.into_number_v1() * b.into_number_v1()));
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        self.push(Value::Number(b * a));
         Ok(())
     }
 
-    fn action_modulo(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // TODO: Wrong operands?
-        let a = self.pop()?.as_f64()?;
-        let b = self.pop()?.as_f64()?;
-        self.push(Value::Number(a % b));
+    fn action_modulo(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        self.push(Value::Number(b % a));
         Ok(())
     }
 
-    fn action_not(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_not(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 logical not
         let val = self.pop()?;
-        let result = val.into_number_v1() == 0.0;
+        let result = !val.as_bool(self.swf_version);
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn action_next_frame(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_next_frame(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
-        let clip = display_object.as_movie_clip_mut().unwrap();
-        clip.next_frame();
+        if let Some(clip) = display_object.as_movie_clip_mut() {
+            clip.next_frame();
+        } else {
+            log::warn!("NextFrame failed: Not a MovieClip");
+        }
         Ok(())
     }
 
-    fn action_new_method(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _name = self.pop()?.as_string()?;
-        let _object = self.pop()?.as_object()?;
-        let _num_args = self.pop()?.as_i64()?;
-        self.push(Value::Undefined);
-        // TODO(Herschel)
-        Err("Unimplemented action: NewMethod".into())
+    fn action_new_method(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let method_name = self.pop()?;
+        let object = self.pop()?;
+        let args = self.pop_args()?;
+
+        // A blank method name means that the object itself is the constructor.
+        let constructor = match (method_name, object) {
+            (Value::Undefined, constructor) => constructor,
+            (Value::String(ref name), constructor) if name.is_empty() => constructor,
+            (method_name, Value::Object(object)) => {
+                Object::get(object, &method_name.into_string(), self, context)?
+            }
+            (method_name, _) => {
+                log::warn!(
+                    "NewMethod: Cannot get constructor {} of non-object",
+                    method_name.into_string()
+                );
+                Value::Undefined
+            }
+        };
+
+        let result = if let Value::Object(constructor) = constructor {
+            self.construct(context, constructor, &args)?
+        } else {
+            log::warn!("NewMethod: Constructor is not a function");
+            Value::Undefined
+        };
+        self.push(result);
+        Ok(())
     }
 
-    fn action_new_object(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _object = self.pop()?.as_string()?;
-        let num_args = self.pop()?.as_i64()?;
-        for _ in 0..num_args {
-            let _arg = self.pop()?;
-        }
-        self.push(Value::Undefined);
-        // TODO(Herschel)
-        Err("Unimplemented action: NewObject".into())
+    fn action_new_object(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let class_name = self.pop()?.into_string();
+        let args = self.pop_args()?;
+        let constructor = self.get_variable(context, &class_name)?;
+        let result = match constructor {
+            Value::Object(constructor) if constructor.read().as_executable().is_some() => {
+                self.construct(context, constructor, &args)?
+            }
+            _ => {
+                log::warn!("NewObject: {} is not a constructor", class_name);
+                Object::object(context.gc_context, Some(self.prototypes.object)).into()
+            }
+        };
+        self.push(result);
+        Ok(())
     }
 
-    fn action_or(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_or(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // AS1 logical or
         let a = self.pop()?;
         let b = self.pop()?;
-        let result = b.into_number_v1() != 0.0 || a.into_number_v1() != 0.0;
+        let result = b.as_bool(self.swf_version) || a.as_bool(self.swf_version);
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn play(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn play(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
         if let Some(clip) = display_object.as_movie_clip_mut() {
             clip.play()
@@ -780,21 +2107,24 @@ impl Avm1 {
         Ok(())
     }
 
-    fn prev_frame(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn prev_frame(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
-        let clip = display_object.as_movie_clip_mut().unwrap();
-        clip.prev_frame();
+        if let Some(clip) = display_object.as_movie_clip_mut() {
+            clip.prev_frame();
+        } else {
+            log::warn!("PreviousFrame failed: Not a MovieClip");
+        }
         Ok(())
     }
 
-    fn action_pop(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_pop(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         self.pop()?;
         Ok(())
     }
 
     fn action_push(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         values: &[swf::avm1::types::Value],
     ) -> Result<(), Error> {
         for value in values {
@@ -807,10 +2137,7 @@ impl Avm1 {
                 SwfValue::Float(v) => Value::Number(f64::from(*v)),
                 SwfValue::Double(v) => Value::Number(*v),
                 SwfValue::Str(v) => Value::String(v.clone()),
-                SwfValue::Register(_v) => {
-                    log::error!("Register push unimplemented");
-                    Value::Undefined
-                }
+                SwfValue::Register(v) => self.get_register(*v),
                 SwfValue::ConstantPool(i) => {
                     if let Some(value) = self.constant_pool.get(*i as usize) {
                         Value::String(value.clone())
@@ -829,57 +2156,77 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_push_duplicate(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_push_duplicate(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         let val = self.stack.last().ok_or("Stack underflow")?.clone();
         self.push(val);
         Ok(())
     }
 
-    fn action_random_number(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let max = self.pop()?.as_f64()? as u32;
-        let val = self.rng.gen_range(0, max);
+    fn action_random_number(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let max = number_to_u32(self.pop_number(context)?);
+        let val = if max > 0 {
+            self.rng.gen_range(0, max)
+        } else {
+            0
+        };
         self.push(Value::Number(val.into()));
         Ok(())
     }
 
-    fn action_remove_sprite(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _target = self.pop()?.into_string();
-        // TODO(Herschel)
-        Err("Unimplemented action: RemoveSprite".into())
+    fn action_remove_sprite(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let target = self.pop()?.into_string();
+        if let Some(node) = Avm1::resolve_slash_path(context.active_clip, context.root, &target) {
+            globals::remove_movie_clip(context, node);
+        } else {
+            log::warn!("RemoveSprite: Invalid target {}", target);
+        }
+        Ok(())
     }
 
-    fn action_return(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _result = self.pop()?;
-        // TODO(Herschel)
-        Err("Unimplemented action: Return".into())
+    fn action_return(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let result = self.pop()?;
+        if let Some(frame) = self.current_frame_mut() {
+            frame.set_return_value(result);
+        }
+        Ok(())
     }
 
-    fn action_set_member(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _value = self.pop()?;
-        let _name = self.pop()?;
-        let _object = self.pop()?;
-        // TODO(Herschel)
-        Err("Unimplemented action: SetMember".into())
+    fn action_set_member(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let value = self.pop()?;
+        let name = self.pop()?.into_string();
+        let object = self.pop()?;
+        if let Value::Object(object) = object {
+            Object::set(object, &name, value, self, context)?;
+        } else {
+            log::warn!("SetMember: Cannot set property {} of non-object", name);
+        }
+        Ok(())
     }
 
-    fn action_set_property(&mut self, context: &mut ActionContext) -> Result<(), Error> {
-        let value = self.pop()?.into_number_v1() as f32;
+    fn action_set_property(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let value = self.pop()?;
         let prop_index = self.pop()?.as_u32()? as usize;
         let clip_path = self.pop()?;
         let path = clip_path.as_string()?;
         if let Some(clip) = Avm1::resolve_slash_path(context.active_clip, context.root, path) {
-            if let Some(clip) = clip.write(context.gc_context).as_movie_clip_mut() {
-                match prop_index {
-                    0 => clip.set_x(value),
-                    1 => clip.set_y(value),
-                    2 => clip.set_x_scale(value),
-                    3 => clip.set_y_scale(value),
-                    10 => clip.set_rotation(value),
-                    _ => log::error!(
-                        "ActionSetProperty: Unimplemented property index {}",
-                        prop_index
-                    ),
-                }
+            let is_set = match display_properties::name_from_index(prop_index) {
+                Some(name) => display_properties::set(self, context, clip, name, value)?,
+                None => false,
+            };
+            if !is_set {
+                log::error!("ActionSetProperty: Invalid property index {}", prop_index);
             }
         } else {
             log::warn!("ActionSetProperty: Invalid path {}", path);
@@ -887,25 +2234,19 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_set_variable(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_set_variable(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // Flash 4-style variable
         let value = self.pop()?;
         let var_path = self.pop()?;
-        if let Some((node, var_name)) = Self::resolve_slash_path_variable(
-            context.active_clip,
-            context.root,
-            var_path.as_string()?,
-        ) {
-            if let Some(clip) = node.write(context.gc_context).as_movie_clip_mut() {
-                clip.set_variable(var_name, value);
-            }
-        }
-        Ok(())
+        self.set_variable(context, var_path.as_string()?, value)
     }
 
     fn action_set_target(
         &mut self,
-        context: &mut ActionContext,
+        context: &mut ActionContext<'_, 'gc, '_>,
         target: &str,
     ) -> Result<(), Error> {
         if target.is_empty() {
@@ -921,7 +2262,29 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_stack_swap(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_set_target_2(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        // The target is either a path or a movie clip.
+        let target = self.pop()?;
+        let node = match &target {
+            Value::Object(object) => object.read().display_node(),
+            _ => None,
+        };
+        match node {
+            Some(node) => {
+                context.active_clip = node;
+                Ok(())
+            }
+            None => self.action_set_target(context, &target.into_string()),
+        }
+    }
+
+    fn action_stack_swap(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         let a = self.pop()?;
         let b = self.pop()?;
         self.push(a);
@@ -929,21 +2292,39 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_start_drag(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _target = self.pop()?;
-        let _lock_center = self.pop()?.as_bool();
-        let constrain = self.pop()?.as_bool();
-        if constrain {
-            let _y2 = self.pop()?;
-            let _x2 = self.pop()?;
-            let _y1 = self.pop()?;
-            let _x1 = self.pop()?;
+    fn action_start_drag(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // The target is either a path or a movie clip.
+        let target = self.pop()?;
+        let lock_center = self.pop()?.as_bool(self.swf_version);
+        let constrain = self.pop()?.as_bool(self.swf_version);
+        let constraint = if constrain {
+            let y2 = self.pop_number(context)?;
+            let x2 = self.pop_number(context)?;
+            let y1 = self.pop_number(context)?;
+            let x1 = self.pop_number(context)?;
+            Some(BoundingBox {
+                x_min: Twips::from_pixels(x1),
+                y_min: Twips::from_pixels(y1),
+                x_max: Twips::from_pixels(x2),
+                y_max: Twips::from_pixels(y2),
+                valid: true,
+            })
+        } else {
+            None
+        };
+        let node = match &target {
+            Value::Object(object) => object.read().display_node(),
+            _ => Avm1::resolve_slash_path(context.active_clip, context.root, &target.into_string()),
+        };
+        if let Some(node) = node {
+            self.start_drag(context, node, lock_center, constraint);
+        } else {
+            log::warn!("StartDrag: Invalid target");
         }
-        log::error!("Unimplemented action: StartDrag");
         Ok(())
     }
 
-    fn action_stop(&mut self, context: &mut ActionContext) -> Result<(), Error> {
+    fn action_stop(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         let mut display_object = context.active_clip.write(context.gc_context);
         if let Some(clip) = display_object.as_movie_clip_mut() {
             clip.stop();
@@ -953,45 +2334,54 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_stop_sounds(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_stop_sounds(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         Err("Unimplemented action: StopSounds".into())
     }
 
     fn action_store_register(
         &mut self,
-        _context: &mut ActionContext,
-        _register: u8,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+        register: u8,
     ) -> Result<(), Error> {
         // Does NOT pop the value from the stack.
-        let _val = self.stack.last().ok_or("Stack underflow")?;
-        Err("Unimplemented action: StoreRegister".into())
+        let val = self.stack.last().ok_or("Stack underflow")?.clone();
+        self.set_register(register, val);
+        Ok(())
     }
 
-    fn action_string_add(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_string_add(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
         // SWFv4 string concatenation
-        // TODO(Herschel): Result with non-string operands?
-        let a = self.pop()?.into_string();
-        let mut b = self.pop()?.into_string();
+        let a = self.pop_string(context)?;
+        let mut b = self.pop_string(context)?;
         b.push_str(&a);
         self.push(Value::String(b));
         Ok(())
     }
 
-    fn action_string_equals(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_string_equals(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // AS1 strcmp
-        let a = self.pop()?;
-        let b = self.pop()?;
-        let result = b.into_string() == a.into_string();
+        let a = self.pop_string(context)?;
+        let b = self.pop_string(context)?;
+        let result = b == a;
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn action_string_extract(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_string_extract(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // SWFv4 substring
         // TODO(Herschel): Result with incorrect operands?
-        let len = self.pop()?.as_f64()? as usize;
-        let start = self.pop()?.as_f64()? as usize;
-        let s = self.pop()?.into_string();
+        let len = self.pop_number(context)? as usize;
+        let start = self.pop_number(context)? as usize;
+        let s = self.pop_string(context)?;
         // This is specifically a non-UTF8 aware substring.
         // SWFv4 only used ANSI strings.
         let result = s
@@ -1004,84 +2394,192 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_string_length(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_string_length(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // AS1 strlen
         // Only returns byte length.
-        // TODO(Herschel): Result with non-string operands?
-        let val = self.pop()?.into_string().bytes().len() as f64;
+        let val = self.pop_string(context)?.bytes().len() as f64;
         self.push(Value::Number(val));
         Ok(())
     }
 
-    fn action_string_less(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_string_less(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
         // AS1 strcmp
-        let a = self.pop()?;
-        let b = self.pop()?;
+        let a = self.pop_string(context)?;
+        let b = self.pop_string(context)?;
+        // This is specifically a non-UTF8 aware comparison.
+        let result = b.bytes().lt(a.bytes());
+        self.push(Value::from_bool_v1(result, self.swf_version));
+        Ok(())
+    }
+
+    fn action_string_greater(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let a = self.pop_string(context)?;
+        let b = self.pop_string(context)?;
         // This is specifically a non-UTF8 aware comparison.
-        let result = b.into_string().bytes().lt(a.into_string().bytes());
+        let result = b.bytes().gt(a.bytes());
         self.push(Value::from_bool_v1(result, self.swf_version));
         Ok(())
     }
 
-    fn action_subtract(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
+    fn action_strict_equals(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        // ECMA-262 s. 11.9.6
         let a = self.pop()?;
         let b = self.pop()?;
-        self.push(Value::Number(a.into_number_v1() + b.into_number_v1()));
+        self.push(Value::Bool(b.strict_eq(&a)));
         Ok(())
     }
 
-    fn action_target_path(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // TODO(Herschel)
-        let _clip = self.pop()?.as_object()?;
-        self.push(Value::Undefined);
-        Err("Unimplemented action: TargetPath".into())
+    fn action_subtract(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let a = self.pop_number(context)?;
+        let b = self.pop_number(context)?;
+        self.push(Value::Number(b - a));
+        Ok(())
     }
 
-    fn toggle_quality(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        // TODO(Herschel): Noop for now? Could chang anti-aliasing on render backend.
+    fn action_target_path(
+        &mut self,
+        _context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let clip = self.pop()?;
+        let node = match clip {
+            Value::Object(object) => object.read().display_node(),
+            _ => None,
+        };
+        if let Some(node) = node {
+            self.push(display_properties::target_path(node));
+        } else {
+            self.push(Value::Undefined);
+        }
         Ok(())
     }
 
-    fn action_to_integer(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let val = self.pop()?;
-        self.push(Value::Number(val.into_number_v1().trunc()));
+    fn toggle_quality(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // Toggles between high and low quality.
+        self.quality = if self.quality == "LOW" { "HIGH" } else { "LOW" }.to_string();
         Ok(())
     }
 
-    fn action_to_number(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let val = self.pop()?;
-        self.push(Value::Number(val.into_number()));
+    fn action_to_integer(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let val = self.pop_number(context)?;
+        let val = if val.is_nan() { 0.0 } else { val.trunc() };
+        self.push(Value::Number(val));
         Ok(())
     }
 
-    fn action_to_string(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let val = self.pop()?;
-        self.push(Value::String(val.into_string()));
+    fn action_to_number(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let val = self.pop()?.coerce_to_number(self, context)?;
+        self.push(Value::Number(val));
         Ok(())
     }
 
-    fn action_trace(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let val = self.pop()?;
-        log::info!(target: "avm_trace", "{}", val.into_string());
+    fn action_to_string(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let val = self.pop_string(context)?;
+        self.push(Value::String(val));
         Ok(())
     }
 
-    fn action_type_of(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _type_str = match self.pop()? {
-            Value::Undefined => "undefined",
-            Value::Null => "null",
-            Value::Number(_) => "number",
-            Value::Bool(_) => "boolean",
-            Value::String(_) => "string",
-            Value::Object(_) => "object",
+    fn action_trace(&mut self, context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let val = self.pop()?;
+        let message = match val {
+            // `trace` prints "undefined" in every SWF version.
+            Value::Undefined => "undefined".to_string(),
+            val => val.coerce_to_string(self, context)?,
         };
-        // TODO(Herschel): function, movieclip
+        log::info!(target: "avm_trace", "{}", message);
+        Ok(())
+    }
+
+    fn action_type_of(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let type_of = self.pop()?.type_of();
+        self.push(Value::String(type_of.to_string()));
+        Ok(())
+    }
+
+    fn action_throw(&mut self, _context: &mut ActionContext<'_, 'gc, '_>) -> Result<(), Error> {
+        let value = self.pop()?;
+        self.exception = Some(value);
+        Err(ThrownError.into())
+    }
+
+    /// Runs a `try` statement. The try, catch and finally blocks directly follow the action,
+    /// and the sizes of each are stored in its header.
+    fn action_try(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        try_block: &swf::avm1::types::TryBlock,
+        code: &SwfSlice,
+        reader: &mut Reader<Cursor<&[u8]>>,
+        action_pos: usize,
+    ) -> Result<(), Error> {
+        let data = code.as_ref();
+        let try_start = action_pos + 3 + Self::read_u16_at(data, action_pos + 1)?;
+        let catch_start = try_start + Self::read_u16_at(data, action_pos + 4)?;
+        let finally_start = catch_start + Self::read_u16_at(data, action_pos + 6)?;
+        let finally_end = finally_start + Self::read_u16_at(data, action_pos + 8)?;
+        if finally_end > data.len() {
+            return Err("Try block out of bounds".into());
+        }
+
+        let stack_len = self.stack.len();
+        let mut result = self.run_action_range(context, code, try_start, catch_start);
+
+        if let Some((catch_var, _)) = &try_block.catch {
+            let is_thrown = match &result {
+                Err(e) => e.is::<ThrownError>(),
+                _ => false,
+            };
+            if is_thrown {
+                self.stack.truncate(stack_len);
+                let exception = self.exception.take().unwrap_or(Value::Undefined);
+                match catch_var {
+                    CatchVar::Var(name) => self.define_local(context, name, exception),
+                    CatchVar::Register(register) => self.set_register(*register, exception),
+                }
+                result = self.run_action_range(context, code, catch_start, finally_start);
+            }
+        }
+
+        if try_block.finally.is_some() {
+            // A return or exception from the try or catch block waits until the finally
+            // block has run, and is abandoned if the finally block returns, throws or
+            // jumps away itself.
+            let pending_return = self
+                .current_frame_mut()
+                .and_then(Activation::take_pending_return);
+            let pending_exception = self.exception.take();
+            let finally_result = self.run_action_range(context, code, finally_start, finally_end);
+            let finally_returned = self.current_frame().map_or(false, Activation::has_returned);
+            match finally_result {
+                Ok(None) if !finally_returned => {
+                    if let (Some(frame), Some(value)) = (self.current_frame_mut(), pending_return) {
+                        frame.set_return_value(value);
+                    }
+                    self.exception = pending_exception;
+                }
+                finally_result => result = finally_result,
+            }
+        }
+
+        let next_pos = result?.unwrap_or(finally_end);
+        reader.get_inner().set_position(next_pos as u64);
         Ok(())
     }
 
     fn action_wait_for_frame(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         _frame: u16,
         num_actions_to_skip: u8,
         reader: &mut Reader<Cursor<&[u8]>>,
@@ -1100,7 +2598,7 @@ impl Avm1 {
 
     fn action_wait_for_frame_2(
         &mut self,
-        _context: &mut ActionContext,
+        _context: &mut ActionContext<'_, 'gc, '_>,
         num_actions_to_skip: u8,
         reader: &mut Reader<Cursor<&[u8]>>,
     ) -> Result<(), Error> {
@@ -1117,114 +2615,42 @@ impl Avm1 {
         Ok(())
     }
 
-    fn action_with(&mut self, _context: &mut ActionContext) -> Result<(), Error> {
-        let _object = self.pop()?.as_object()?;
-        Err("Unimplemented action: With".into())
-    }
-}
-
-type ObjectPtr = std::marker::PhantomData<()>;
-
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub enum Value {
-    Undefined,
-    Null,
-    Bool(bool),
-    Number(f64),
-    String(String),
-    Object(ObjectPtr),
-}
-
-impl Value {
-    fn into_number_v1(self) -> f64 {
-        match self {
-            Value::Bool(true) => 1.0,
-            Value::Number(v) => v,
-            Value::String(v) => v.parse().unwrap_or(0.0),
-            _ => 0.0,
-        }
-    }
-
-    fn into_number(self) -> f64 {
-        // ECMA-262 2nd edtion s. 9.3 ToNumber
-        use std::f64::NAN;
-        match self {
-            Value::Undefined => NAN,
-            Value::Null => NAN,
-            Value::Bool(false) => 0.0,
-            Value::Bool(true) => 1.0,
-            Value::Number(v) => v,
-            Value::String(v) => v.parse().unwrap_or(NAN), // TODO(Herschel): Handle Infinity/etc.?
-            Value::Object(_object) => {
-                log::error!("Unimplemented: Object ToNumber");
-                0.0
-            }
-        }
-    }
-
-    fn from_bool_v1(value: bool, swf_version: u8) -> Value {
-        // SWF version 4 did not have true bools and will push bools as 0 or 1.
-        // e.g. SWF19 p. 72:
-        // "If the numbers are equal, true is pushed to the stack for SWF 5 and later. For SWF 4, 1 is pushed to the stack."
-        if swf_version >= 5 {
-            Value::Bool(value)
-        } else {
-            Value::Number(if value { 1.0 } else { 0.0 })
-        }
-    }
-
-    fn into_string(self) -> String {
-        match self {
-            Value::Undefined => "undefined".to_string(),
-            Value::Null => "null".to_string(),
-            Value::Bool(v) => v.to_string(),
-            Value::Number(v) => v.to_string(), // TODO(Herschel): Rounding for int?
-            Value::String(v) => v,
-            Value::Object(_) => "[Object object]".to_string(), // TODO(Herschel):
-        }
-    }
-
-    fn as_bool(&self) -> bool {
-        match *self {
-            Value::Bool(v) => v,
-            Value::Number(v) => v != 0.0,
-            // TODO(Herschel): Value::String(v) => ??
-            _ => false,
+    /// Runs the block that follows the action with the popped object in front of the scope chain.
+    fn action_with(
+        &mut self,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        code: &SwfSlice,
+        reader: &mut Reader<Cursor<&[u8]>>,
+        action_pos: usize,
+    ) -> Result<(), Error> {
+        let data = code.as_ref();
+        let block_start = action_pos + 3 + Self::read_u16_at(data, action_pos + 1)?;
+        let block_end = block_start + Self::read_u16_at(data, action_pos + 3)?;
+        if block_end > data.len() {
+            return Err("With block out of bounds".into());
         }
-    }
-
-    fn as_i32(&self) -> Result<i32, Error> {
-        self.as_f64().map(|n| n as i32)
-    }
-
-    fn as_u32(&self) -> Result<u32, Error> {
-        self.as_f64().map(|n| n as u32)
-    }
 
-    fn as_i64(&self) -> Result<i64, Error> {
-        self.as_f64().map(|n| n as i64)
-    }
+        let object = match self.pop()? {
+            Value::Object(object) => object,
+            value => {
+                log::warn!("With: {} is not an object", value.type_of());
+                reader.get_inner().set_position(block_end as u64);
+                return Ok(());
+            }
+        };
 
-    fn as_f64(&self) -> Result<f64, Error> {
-        match *self {
-            Value::Number(v) => Ok(v),
-            _ => Err("Expected Number".into()),
+        let scope = self.current_scope();
+        let with_scope = Scope::new_with_scope(context.gc_context, scope, object);
+        if let Some(frame) = self.current_frame_mut() {
+            frame.set_scope(Some(with_scope));
         }
-    }
-
-    fn as_string(&self) -> Result<&String, Error> {
-        match self {
-            Value::String(s) => Ok(s),
-            _ => Err("Expected Number".into()),
+        let result = self.run_action_range(context, code, block_start, block_end);
+        if let Some(frame) = self.current_frame_mut() {
+            frame.set_scope(scope);
         }
-    }
 
-    fn as_object(&self) -> Result<&ObjectPtr, Error> {
-        if let Value::Object(object) = self {
-            Ok(object)
-        } else {
-            Err("Expected Object".into())
-        }
+        let next_pos = result?.unwrap_or(block_end);
+        reader.get_inner().set_position(next_pos as u64);
+        Ok(())
     }
 }
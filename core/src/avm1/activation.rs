@@ -0,0 +1,108 @@
+use crate::avm1::scope::Scope;
+use crate::avm1::Value;
+use gc_arena::GcCell;
+
+/// A single frame of the AVM1 call stack.
+///
+/// An activation is created for every block of top-level code executed on a timeline,
+/// and for every call of an ActionScript function.
+pub struct Activation<'gc> {
+    /// The innermost scope of this frame, or `None` for top-level timeline code outside
+    /// of any `with` block, whose variables live directly on the movie clip.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The value of `this` within this frame.
+    this: Value<'gc>,
+
+    /// The register file allocated by `DefineFunction2`.
+    /// Frames without local registers use the global registers of the AVM.
+    local_registers: Option<Vec<Value<'gc>>>,
+
+    /// The value handed back to the caller by `Return`.
+    return_value: Option<Value<'gc>>,
+}
+
+impl<'gc> Activation<'gc> {
+    pub fn from_action(this: Value<'gc>) -> Self {
+        Self {
+            scope: None,
+            this,
+            local_registers: None,
+            return_value: None,
+        }
+    }
+
+    pub fn from_function(
+        scope: GcCell<'gc, Scope<'gc>>,
+        this: Value<'gc>,
+        local_registers: Option<Vec<Value<'gc>>>,
+    ) -> Self {
+        Self {
+            scope: Some(scope),
+            this,
+            local_registers,
+            return_value: None,
+        }
+    }
+
+    pub fn scope(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.scope
+    }
+
+    /// Replaces the scope of this frame, such as when entering or leaving a `with` block.
+    pub fn set_scope(&mut self, scope: Option<GcCell<'gc, Scope<'gc>>>) {
+        self.scope = scope;
+    }
+
+    pub fn this(&self) -> &Value<'gc> {
+        &self.this
+    }
+
+    pub fn has_local_registers(&self) -> bool {
+        self.local_registers.is_some()
+    }
+
+    pub fn local_register(&self, id: u8) -> Option<&Value<'gc>> {
+        self.local_registers
+            .as_ref()
+            .and_then(|registers| registers.get(usize::from(id)))
+    }
+
+    pub fn set_local_register(&mut self, id: u8, value: Value<'gc>) {
+        if let Some(register) = self
+            .local_registers
+            .as_mut()
+            .and_then(|registers| registers.get_mut(usize::from(id)))
+        {
+            *register = value;
+        }
+    }
+
+    pub fn has_returned(&self) -> bool {
+        self.return_value.is_some()
+    }
+
+    pub fn set_return_value(&mut self, value: Value<'gc>) {
+        self.return_value = Some(value);
+    }
+
+    /// Removes the value handed back by `Return`, so that a `finally` block can run
+    /// before the frame returns.
+    pub fn take_pending_return(&mut self) -> Option<Value<'gc>> {
+        self.return_value.take()
+    }
+
+    pub fn take_return_value(&mut self) -> Value<'gc> {
+        self.return_value.take().unwrap_or(Value::Undefined)
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Activation<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.scope.trace(cc);
+        self.this.trace(cc);
+        self.local_registers.trace(cc);
+        self.return_value.trace(cc);
+    }
+}
@@ -0,0 +1,516 @@
+//! The built-in properties of display objects, such as `_x` and `_currentframe`.
+//!
+//! These are reachable both through dot syntax (`clip._x`) and through the numeric
+//! property indices of `GetProperty` and `SetProperty`.
+
+use crate::avm1::globals::{filter_from_avm, filter_to_avm, utf16_len};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::backend::render::BlendMode;
+use crate::edit_text::EditText;
+use crate::prelude::*;
+
+/// The property names, in the order of the indices used by `GetProperty` and `SetProperty`.
+const PROPERTY_NAMES: [&str; 22] = [
+    "_x",
+    "_y",
+    "_xscale",
+    "_yscale",
+    "_currentframe",
+    "_totalframes",
+    "_alpha",
+    "_visible",
+    "_width",
+    "_height",
+    "_rotation",
+    "_target",
+    "_framesloaded",
+    "_name",
+    "_droptarget",
+    "_url",
+    "_highquality",
+    "_focusrect",
+    "_soundbuftime",
+    "_quality",
+    "_xmouse",
+    "_ymouse",
+];
+
+/// Returns the name of the property with the given `GetProperty` index.
+pub fn name_from_index(index: usize) -> Option<&'static str> {
+    PROPERTY_NAMES.get(index).copied()
+}
+
+/// The names of the blend modes used by `blendMode`.
+/// Scripts may also give a blend mode by its index in this list, starting from 1.
+const BLEND_MODE_NAMES: [(BlendMode, &str); 14] = [
+    (BlendMode::Normal, "normal"),
+    (BlendMode::Layer, "layer"),
+    (BlendMode::Multiply, "multiply"),
+    (BlendMode::Screen, "screen"),
+    (BlendMode::Lighten, "lighten"),
+    (BlendMode::Darken, "darken"),
+    (BlendMode::Difference, "difference"),
+    (BlendMode::Add, "add"),
+    (BlendMode::Subtract, "subtract"),
+    (BlendMode::Invert, "invert"),
+    (BlendMode::Alpha, "alpha"),
+    (BlendMode::Erase, "erase"),
+    (BlendMode::Overlay, "overlay"),
+    (BlendMode::HardLight, "hardlight"),
+];
+
+/// The properties that only text fields have.
+const TEXT_FIELD_PROPERTY_NAMES: [&str; 22] = [
+    "background",
+    "backgroundColor",
+    "border",
+    "borderColor",
+    "bottomScroll",
+    "html",
+    "htmlText",
+    "length",
+    "maxChars",
+    "maxscroll",
+    "multiline",
+    "password",
+    "restrict",
+    "scroll",
+    "selectable",
+    "text",
+    "textColor",
+    "textHeight",
+    "textWidth",
+    "type",
+    "variable",
+    "wordWrap",
+];
+
+pub fn is_display_property(name: &str) -> bool {
+    match name {
+        "_parent" | "blendMode" | "cacheAsBitmap" | "filters" => true,
+        _ => PROPERTY_NAMES.contains(&name),
+    }
+}
+
+/// Gets the value of the property `name` of `node`.
+/// Returns `None` if `name` is not a display object property.
+pub fn get<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    node: DisplayNode<'gc>,
+    name: &str,
+) -> Option<Value<'gc>> {
+    let display_object = node.read();
+    if let Some(edit_text) = display_object.as_edit_text() {
+        if let Some(value) = get_text_field_property(edit_text, name) {
+            return Some(value);
+        }
+    }
+    let value = match name {
+        "_x" => f64::from(display_object.x()).into(),
+        "_y" => f64::from(display_object.y()).into(),
+        "_xscale" => f64::from(display_object.x_scale()).into(),
+        "_yscale" => f64::from(display_object.y_scale()).into(),
+        "_rotation" => f64::from(display_object.rotation()).into(),
+        "_alpha" => f64::from(display_object.color_transform().a_mult * 100.0).into(),
+        "_visible" => Value::from_bool_v1(display_object.visible(), avm.swf_version),
+        "_width" => {
+            let bounds = display_object.bounds_in_parent();
+            pixel_extent(&bounds, bounds.x_min, bounds.x_max).into()
+        }
+        "_height" => {
+            let bounds = display_object.bounds_in_parent();
+            pixel_extent(&bounds, bounds.y_min, bounds.y_max).into()
+        }
+        "_name" => display_object.name().into(),
+        "_parent" => display_object
+            .parent()
+            .map_or(Value::Undefined, |parent| parent.read().object()),
+        "_target" => target_path(node).into(),
+        "_droptarget" => display_object
+            .as_movie_clip()
+            .and_then(|clip| clip.drop_target())
+            .map_or_else(|| "".into(), |target| target_path(target).into()),
+        "_url" => avm.movie_url.clone().into(),
+        "blendMode" => BLEND_MODE_NAMES
+            .iter()
+            .find(|(blend_mode, _)| *blend_mode == display_object.blend_mode())
+            .map_or("normal", |(_, name)| name)
+            .into(),
+        "cacheAsBitmap" => Value::Bool(display_object.is_bitmap_cached()),
+        "filters" => {
+            let filters = display_object
+                .filters()
+                .iter()
+                .map(|filter| filter_to_avm(avm, context.gc_context, filter).into())
+                .collect();
+            Object::array(context.gc_context, Some(avm.prototypes().array), filters).into()
+        }
+        "_highquality" => match avm.quality.as_str() {
+            "LOW" => 0.0,
+            "BEST" => 2.0,
+            _ => 1.0,
+        }
+        .into(),
+        "_focusrect" => Value::from_bool_v1(avm.focus_rect, avm.swf_version),
+        "_soundbuftime" => avm.sound_buffer_time.into(),
+        "_quality" => avm.quality.clone().into(),
+        "_xmouse" => local_mouse_position(context, node).0.into(),
+        "_ymouse" => local_mouse_position(context, node).1.into(),
+        "_currentframe" | "_totalframes" | "_framesloaded" => {
+            match display_object.as_movie_clip() {
+                Some(clip) => match name {
+                    "_currentframe" => f64::from(clip.current_frame()).into(),
+                    "_totalframes" => f64::from(clip.total_frames()).into(),
+                    _ => f64::from(clip.frames_loaded()).into(),
+                },
+                None => Value::Undefined,
+            }
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Sets the property `name` of `node`.
+/// Returns `false` if `name` is not a display object property.
+/// Read-only properties silently ignore the new value.
+pub fn set<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    node: DisplayNode<'gc>,
+    name: &str,
+    value: Value<'gc>,
+) -> Result<bool, Error> {
+    let is_text_field = node.read().as_edit_text().is_some();
+    if is_text_field && TEXT_FIELD_PROPERTY_NAMES.contains(&name) {
+        set_text_field_property(avm, context, node, name, value)?;
+        return Ok(true);
+    }
+    if !is_display_property(name) {
+        return Ok(false);
+    }
+
+    // Reading the filter objects may run scripts, so this is done before borrowing the node.
+    if name == "filters" {
+        match filters_from_avm(avm, context, value) {
+            Ok(filters) => node.write(context.gc_context).set_filters(filters),
+            Err(e) => log::warn!("filters: Couldn't read filters: {}", e),
+        }
+        return Ok(true);
+    }
+
+    // Converting the value to a number may call `valueOf`, so this is also done
+    // before borrowing the node.
+    let number = match name {
+        "_name" | "_visible" | "blendMode" | "cacheAsBitmap" | "_focusrect" | "_quality" => None,
+        _ => Some(value.clone().coerce_to_number(avm, context)?),
+    };
+
+    let mut display_object = node.write(context.gc_context);
+    match name {
+        "_name" => {
+            display_object.set_name(&value.into_string());
+            return Ok(true);
+        }
+        "_visible" => {
+            display_object.set_visible(value.as_bool(avm.swf_version));
+            return Ok(true);
+        }
+        "blendMode" => {
+            let blend_mode = match value {
+                Value::Number(index) if index >= 1.0 => BLEND_MODE_NAMES.get(index as usize - 1),
+                value => {
+                    let name = value.into_string();
+                    BLEND_MODE_NAMES.iter().find(|(_, n)| *n == name)
+                }
+            };
+            match blend_mode {
+                Some((blend_mode, _)) => display_object.set_blend_mode(*blend_mode),
+                None => log::warn!("blendMode: Invalid blend mode"),
+            }
+            return Ok(true);
+        }
+        "cacheAsBitmap" => {
+            display_object.set_is_bitmap_cached(value.as_bool(avm.swf_version));
+            return Ok(true);
+        }
+        "_focusrect" => {
+            avm.focus_rect = value.as_bool(avm.swf_version);
+            return Ok(true);
+        }
+        "_quality" => {
+            let quality = value.into_string().to_uppercase();
+            match quality.as_str() {
+                "LOW" | "MEDIUM" | "HIGH" | "BEST" => avm.quality = quality,
+                _ => log::warn!("_quality: Invalid quality {}", quality),
+            }
+            return Ok(true);
+        }
+        _ => (),
+    }
+
+    // Invalid numbers leave the property unchanged.
+    let number = match number {
+        Some(number) if number.is_finite() => number,
+        _ => return Ok(true),
+    };
+    match name {
+        "_x" => display_object.set_x(number as f32),
+        "_y" => display_object.set_y(number as f32),
+        "_xscale" => display_object.set_x_scale(number as f32),
+        "_yscale" => display_object.set_y_scale(number as f32),
+        "_rotation" => display_object.set_rotation(number as f32),
+        "_alpha" => {
+            let mut color_transform = *display_object.color_transform();
+            color_transform.a_mult = number as f32 / 100.0;
+            display_object.set_color_transform(&color_transform);
+        }
+        "_width" => {
+            let bounds = display_object.bounds_in_parent();
+            let width = pixel_extent(&bounds, bounds.x_min, bounds.x_max);
+            if width != 0.0 {
+                let x_scale = display_object.x_scale();
+                display_object.set_x_scale(x_scale * (number / width) as f32);
+            }
+        }
+        "_height" => {
+            let bounds = display_object.bounds_in_parent();
+            let height = pixel_extent(&bounds, bounds.y_min, bounds.y_max);
+            if height != 0.0 {
+                let y_scale = display_object.y_scale();
+                display_object.set_y_scale(y_scale * (number / height) as f32);
+            }
+        }
+        "_highquality" => {
+            avm.quality = if number >= 2.0 {
+                "BEST"
+            } else if number >= 1.0 {
+                "HIGH"
+            } else {
+                "LOW"
+            }
+            .to_string();
+        }
+        "_soundbuftime" => avm.sound_buffer_time = number,
+        _ => (),
+    }
+    Ok(true)
+}
+
+/// Gets the value of the property `name` of a text field.
+/// Returns `None` if `name` is not a text field property.
+fn get_text_field_property<'gc>(edit_text: &EditText<'gc>, name: &str) -> Option<Value<'gc>> {
+    let value = match name {
+        "background" => Value::Bool(edit_text.has_background()),
+        "backgroundColor" => color_to_avm(&edit_text.background_color()),
+        "border" => Value::Bool(edit_text.has_border()),
+        "borderColor" => color_to_avm(&edit_text.border_color()),
+        "bottomScroll" => (edit_text.bottom_scroll() as f64).into(),
+        "html" => Value::Bool(edit_text.is_html()),
+        "htmlText" => edit_text.html_text().into(),
+        "length" => (utf16_len(edit_text.text()) as f64).into(),
+        "maxChars" => edit_text
+            .max_chars()
+            .map_or(Value::Null, |max_chars| (max_chars as f64).into()),
+        "maxscroll" => (edit_text.max_scroll() as f64).into(),
+        "multiline" => Value::Bool(edit_text.is_multiline()),
+        "password" => Value::Bool(edit_text.is_password()),
+        "restrict" => edit_text.restrict().map_or(Value::Null, Value::from),
+        "scroll" => (edit_text.scroll() as f64).into(),
+        "selectable" => Value::Bool(edit_text.is_selectable()),
+        "text" => edit_text.text().into(),
+        "textColor" => color_to_avm(&edit_text.text_color()),
+        "textHeight" => edit_text.text_height().to_pixels().into(),
+        "textWidth" => edit_text.text_width().to_pixels().into(),
+        "type" => if edit_text.is_editable() {
+            "input"
+        } else {
+            "dynamic"
+        }
+        .into(),
+        "variable" => match edit_text.variable_name() {
+            "" => Value::Null,
+            variable_name => variable_name.into(),
+        },
+        "wordWrap" => Value::Bool(edit_text.is_word_wrap()),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Sets the property `name` of the text field `node`.
+/// Read-only properties silently ignore the new value.
+fn set_text_field_property<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    node: DisplayNode<'gc>,
+    name: &str,
+    value: Value<'gc>,
+) -> Result<(), Error> {
+    // Changing the text or the variable of a text field updates the variable,
+    // which may in turn update other text fields, so the node must not stay borrowed.
+    match name {
+        "text" | "htmlText" => {
+            // Setting `htmlText` of a text field that isn't HTML sets the text as it is.
+            if let Some(edit_text) = node.write(context.gc_context).as_edit_text_mut() {
+                if name == "htmlText" && edit_text.is_html() {
+                    edit_text.set_html_text(value.into_string(), context.library);
+                } else {
+                    edit_text.set_text(value.into_string(), context.library);
+                }
+            }
+            if let Err(e) = avm.update_text_field_variable(context, node) {
+                log::warn!(
+                    "{}: Couldn't set the variable of the text field: {}",
+                    name,
+                    e
+                );
+            }
+            return Ok(());
+        }
+        "variable" => {
+            let variable_name = match value {
+                Value::Undefined | Value::Null => String::new(),
+                value => value.into_string(),
+            };
+            avm.rebind_text_field(context, node, variable_name);
+            return Ok(());
+        }
+        _ => (),
+    }
+
+    let number = match name {
+        "maxChars" | "scroll" | "backgroundColor" | "borderColor" | "textColor" => {
+            Some(value.clone().coerce_to_number(avm, context)?)
+        }
+        _ => None,
+    };
+
+    let mut display_object = node.write(context.gc_context);
+    let edit_text = match display_object.as_edit_text_mut() {
+        Some(edit_text) => edit_text,
+        None => return Ok(()),
+    };
+    match name {
+        "background" => edit_text.set_has_background(value.as_bool(avm.swf_version)),
+        "border" => edit_text.set_has_border(value.as_bool(avm.swf_version)),
+        "html" => edit_text.set_is_html(value.as_bool(avm.swf_version)),
+        "multiline" => edit_text.set_is_multiline(value.as_bool(avm.swf_version), context.library),
+        "password" => edit_text.set_is_password(value.as_bool(avm.swf_version), context.library),
+        "selectable" => edit_text.set_is_selectable(value.as_bool(avm.swf_version)),
+        "maxChars" => {
+            let max_chars = number.filter(|number| number.is_finite() && *number >= 1.0);
+            edit_text.set_max_chars(max_chars.map(|max_chars| max_chars as usize));
+        }
+        "restrict" => match value {
+            Value::Undefined | Value::Null => edit_text.set_restrict(None),
+            value => edit_text.set_restrict(Some(&value.into_string())),
+        },
+        "type" => match value.into_string().as_str() {
+            "input" => edit_text.set_is_editable(true),
+            "dynamic" => edit_text.set_is_editable(false),
+            _ => (),
+        },
+        "wordWrap" => edit_text.set_is_word_wrap(value.as_bool(avm.swf_version), context.library),
+        "backgroundColor" | "borderColor" | "textColor" => {
+            let color = match number.and_then(color_from_avm) {
+                Some(color) => color,
+                None => return Ok(()),
+            };
+            match name {
+                "backgroundColor" => edit_text.set_background_color(color),
+                "borderColor" => edit_text.set_border_color(color),
+                _ => edit_text.set_text_color(color, context.library),
+            }
+        }
+        "scroll" => {
+            if let Some(scroll) = number.filter(|number| number.is_finite()) {
+                edit_text.set_scroll(scroll.max(1.0) as usize);
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Converts a color into an RGB number such as `0xff0000`.
+pub fn color_to_avm<'gc>(color: &Color) -> Value<'gc> {
+    let rgb = (u32::from(color.r) << 16) | (u32::from(color.g) << 8) | u32::from(color.b);
+    f64::from(rgb).into()
+}
+
+/// Converts an RGB number into an opaque color. Returns `None` for invalid numbers.
+pub fn color_from_avm(rgb: f64) -> Option<Color> {
+    if !rgb.is_finite() {
+        return None;
+    }
+    let rgb = rgb as i64 as u32;
+    Some(Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: 255,
+    })
+}
+
+/// Converts an array of filter objects, skipping any elements that are not filters.
+fn filters_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Vec<swf::Filter>, crate::avm1::Error> {
+    let elements = match value {
+        Value::Object(object) if object.read().is_array() => object.read().array_elements(),
+        _ => return Ok(vec![]),
+    };
+    let mut filters = vec![];
+    for element in elements {
+        if let Value::Object(object) = element {
+            if let Some(filter) = filter_from_avm(avm, context, object)? {
+                filters.push(filter);
+            }
+        }
+    }
+    Ok(filters)
+}
+
+/// Returns the slash path of `node`, such as `/clip/child`.
+pub fn target_path(node: DisplayNode<'_>) -> String {
+    let display_object = node.read();
+    match display_object.parent() {
+        Some(parent) => {
+            let parent_path = target_path(parent);
+            if parent_path == "/" {
+                format!("/{}", display_object.name())
+            } else {
+                format!("{}/{}", parent_path, display_object.name())
+            }
+        }
+        None => "/".to_string(),
+    }
+}
+
+/// Returns the dot path of `node`, such as `_level0.clip.child`.
+pub fn dot_path(node: DisplayNode<'_>) -> String {
+    let display_object = node.read();
+    match display_object.parent() {
+        Some(parent) => format!("{}.{}", dot_path(parent), display_object.name()),
+        None => "_level0".to_string(),
+    }
+}
+
+/// Returns the distance between `min` and `max` in pixels, or 0 for an empty bounding box.
+fn pixel_extent(bounds: &BoundingBox, min: Twips, max: Twips) -> f64 {
+    if bounds.valid {
+        (max - min).to_pixels()
+    } else {
+        0.0
+    }
+}
+
+/// Returns the mouse position in the local coordinate space of `node`, in pixels.
+fn local_mouse_position(context: &ActionContext<'_, '_, '_>, node: DisplayNode<'_>) -> (f64, f64) {
+    let (x, y) = node.read().global_to_local(context.mouse_position);
+    (x.to_pixels(), y.to_pixels())
+}
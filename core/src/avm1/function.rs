@@ -0,0 +1,261 @@
+use crate::avm1::activation::Activation;
+use crate::avm1::object::{self, Object};
+use crate::avm1::scope::Scope;
+use crate::avm1::{ActionContext, Avm1, Error, Value};
+use crate::display_object::DisplayNode;
+use crate::tag_utils::SwfSlice;
+use gc_arena::GcCell;
+
+/// A function implemented in Rust that can be called from ActionScript.
+/// Native functions are also used as the getters and setters of virtual properties.
+pub type NativeFunction<'gc> = fn(
+    &mut Avm1<'gc>,
+    &mut ActionContext<'_, 'gc, '_>,
+    Value<'gc>,
+    &[Value<'gc>],
+) -> Result<Value<'gc>, Error>;
+
+/// A function defined in bytecode by `DefineFunction` or `DefineFunction2`.
+#[derive(Clone)]
+pub struct Avm1Function<'gc> {
+    /// The body of the function.
+    data: SwfSlice,
+
+    name: String,
+
+    /// The parameters of the function, along with the register each one is stored in.
+    /// Parameters without a register are stored as local variables.
+    params: Vec<(Option<u8>, String)>,
+
+    /// `DefineFunction2` functions get their own register file, while functions from
+    /// `DefineFunction` share the global registers.
+    register_count: Option<u8>,
+
+    suppress_this: bool,
+    preload_this: bool,
+    suppress_arguments: bool,
+    preload_arguments: bool,
+    suppress_super: bool,
+    preload_super: bool,
+    preload_root: bool,
+    preload_parent: bool,
+    preload_global: bool,
+
+    /// The scope the function was defined in.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The constant pool that was active when the function was defined.
+    constant_pool: Vec<String>,
+
+    /// The movie clip that the function was defined on.
+    /// The function body runs with this clip as its target.
+    base_clip: DisplayNode<'gc>,
+}
+
+impl<'gc> Avm1Function<'gc> {
+    /// Creates a function from a `DefineFunction` action.
+    pub fn from_df1(
+        data: SwfSlice,
+        name: &str,
+        params: &[String],
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        constant_pool: Vec<String>,
+        base_clip: DisplayNode<'gc>,
+    ) -> Self {
+        Self {
+            data,
+            name: name.to_string(),
+            params: params.iter().map(|name| (None, name.clone())).collect(),
+            register_count: None,
+            suppress_this: false,
+            preload_this: false,
+            suppress_arguments: false,
+            preload_arguments: false,
+            suppress_super: false,
+            preload_super: false,
+            preload_root: false,
+            preload_parent: false,
+            preload_global: false,
+            scope,
+            constant_pool,
+            base_clip,
+        }
+    }
+
+    /// Creates a function from a `DefineFunction2` action.
+    pub fn from_df2(
+        data: SwfSlice,
+        function: &swf::avm1::types::Function,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        constant_pool: Vec<String>,
+        base_clip: DisplayNode<'gc>,
+    ) -> Self {
+        Self {
+            data,
+            name: function.name.clone(),
+            params: function
+                .params
+                .iter()
+                .map(|param| (param.register_index.map(u8::from), param.name.clone()))
+                .collect(),
+            register_count: Some(function.register_count),
+            suppress_this: function.suppress_this,
+            preload_this: function.preload_this,
+            suppress_arguments: function.suppress_arguments,
+            preload_arguments: function.preload_arguments,
+            suppress_super: function.suppress_super,
+            preload_super: function.preload_super,
+            preload_root: function.preload_root,
+            preload_parent: function.preload_parent,
+            preload_global: function.preload_global,
+            scope,
+            constant_pool,
+            base_clip,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Calls this function, returning the value handed back by `Return`.
+    ///
+    /// `base_proto` is the prototype object that the function was found on when called
+    /// as a method or constructor, and determines what `super` refers to.
+    pub fn exec(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        callee: GcCell<'gc, Object<'gc>>,
+        this: Value<'gc>,
+        base_proto: Option<GcCell<'gc, Object<'gc>>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let scope = Scope::new_local_scope(context.gc_context, self.scope);
+        let locals = scope.read().locals();
+
+        let arguments = Object::array(
+            context.gc_context,
+            Some(avm.prototypes.array),
+            args.to_vec(),
+        );
+        arguments
+            .write(context.gc_context)
+            .force_set("callee", callee, object::DONT_ENUM);
+
+        let super_object = base_proto
+            .map(|base_proto| Object::super_object(context.gc_context, this.clone(), base_proto));
+
+        {
+            let mut locals = locals.write(context.gc_context);
+            if !self.suppress_this {
+                locals.force_set("this", this.clone(), object::DONT_ENUM);
+            }
+            if !self.suppress_arguments {
+                locals.force_set("arguments", arguments, object::DONT_ENUM);
+            }
+            if let (false, Some(super_object)) = (self.suppress_super, super_object) {
+                locals.force_set("super", super_object, object::DONT_ENUM);
+            }
+        }
+
+        let mut registers = self
+            .register_count
+            .map(|count| vec![Value::Undefined; usize::from(count)]);
+        if let Some(registers) = &mut registers {
+            // Preloaded values are placed in consecutive registers starting at 1,
+            // in a fixed order.
+            let mut preloaded = vec![];
+            if self.preload_this {
+                preloaded.push(this.clone());
+            }
+            if self.preload_arguments {
+                preloaded.push(arguments.into());
+            }
+            if self.preload_super {
+                preloaded.push(super_object.map_or(Value::Undefined, Value::Object));
+            }
+            if self.preload_root {
+                preloaded.push(context.root.read().object());
+            }
+            if self.preload_parent {
+                let parent = self.base_clip.read().parent();
+                preloaded.push(parent.map_or(Value::Undefined, |parent| parent.read().object()));
+            }
+            if self.preload_global {
+                preloaded.push(avm.globals.into());
+            }
+            for (register, value) in registers.iter_mut().skip(1).zip(preloaded) {
+                *register = value;
+            }
+        }
+
+        for (i, (register, name)) in self.params.iter().enumerate() {
+            let arg = args.get(i).cloned().unwrap_or(Value::Undefined);
+            match (register, &mut registers) {
+                (Some(register), Some(registers)) if usize::from(*register) < registers.len() => {
+                    registers[usize::from(*register)] = arg;
+                }
+                _ => locals.write(context.gc_context).force_set(name, arg, 0),
+            }
+        }
+
+        let activation = Activation::from_function(scope, this, registers);
+
+        let constant_pool = std::mem::replace(&mut avm.constant_pool, self.constant_pool.clone());
+        let (start_clip, active_clip) = (context.start_clip, context.active_clip);
+        context.start_clip = self.base_clip;
+        context.active_clip = self.base_clip;
+
+        let result = avm.run_activation(context, activation, self.data.clone());
+
+        context.start_clip = start_clip;
+        context.active_clip = active_clip;
+        avm.constant_pool = constant_pool;
+
+        result
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Avm1Function<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.scope.trace(cc);
+        self.base_clip.trace(cc);
+    }
+}
+
+/// The code that runs when an object is called as a function.
+#[derive(Clone)]
+pub enum Executable<'gc> {
+    Native(NativeFunction<'gc>),
+    Action(Avm1Function<'gc>),
+}
+
+impl<'gc> Executable<'gc> {
+    pub fn exec(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        callee: GcCell<'gc, Object<'gc>>,
+        this: Value<'gc>,
+        base_proto: Option<GcCell<'gc, Object<'gc>>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        match self {
+            Executable::Native(function) => function(avm, context, this, args),
+            Executable::Action(function) => {
+                function.exec(avm, context, callee, this, base_proto, args)
+            }
+        }
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Executable<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        if let Executable::Action(function) = self {
+            function.trace(cc);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::avm1::function::{Executable, NativeFunction};
+use crate::avm1::object::DONT_ENUM;
+use crate::avm1::{Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+mod array;
+mod boolean;
+mod broadcaster;
+mod error;
+mod filters;
+mod function;
+mod key;
+mod math;
+mod mouse;
+mod movie_clip;
+mod number;
+mod object;
+mod selection;
+mod string;
+mod text_field;
+mod text_format;
+mod timer;
+
+pub use broadcaster::listeners;
+pub use filters::{filter_from_avm, filter_to_avm, FilterPrototypes};
+pub use key::KeyState;
+pub use movie_clip::{duplicate_movie_clip, remove_movie_clip};
+pub use string::utf16_len;
+
+/// The prototypes of the built-in classes.
+///
+/// The AVM keeps its own references to these, so that literals such as `[]` and `{}`
+/// get the original prototypes even if a script replaces the classes in `_global`.
+#[derive(Clone)]
+pub struct SystemPrototypes<'gc> {
+    pub object: GcCell<'gc, Object<'gc>>,
+    pub function: GcCell<'gc, Object<'gc>>,
+    pub array: GcCell<'gc, Object<'gc>>,
+    pub string: GcCell<'gc, Object<'gc>>,
+    pub number: GcCell<'gc, Object<'gc>>,
+    pub boolean: GcCell<'gc, Object<'gc>>,
+    pub movie_clip: GcCell<'gc, Object<'gc>>,
+    pub text_field: GcCell<'gc, Object<'gc>>,
+    pub text_format: GcCell<'gc, Object<'gc>>,
+    pub filters: FilterPrototypes<'gc>,
+}
+
+unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.object.trace(cc);
+        self.function.trace(cc);
+        self.array.trace(cc);
+        self.string.trace(cc);
+        self.number.trace(cc);
+        self.boolean.trace(cc);
+        self.movie_clip.trace(cc);
+        self.text_field.trace(cc);
+        self.text_format.trace(cc);
+        self.filters.trace(cc);
+    }
+}
+
+/// Creates the `_global` object, populated with the built-in classes.
+pub fn create_globals<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+) -> (GcCell<'gc, Object<'gc>>, SystemPrototypes<'gc>) {
+    let object_proto = Object::object(gc_context, None);
+    let function_proto = Object::object(gc_context, Some(object_proto));
+    let (filters, filter_prototypes) = filters::create(gc_context, object_proto, function_proto);
+    let prototypes = SystemPrototypes {
+        object: object_proto,
+        function: function_proto,
+        array: Object::object(gc_context, Some(object_proto)),
+        string: Object::object(gc_context, Some(object_proto)),
+        number: Object::object(gc_context, Some(object_proto)),
+        boolean: Object::object(gc_context, Some(object_proto)),
+        movie_clip: Object::object(gc_context, Some(object_proto)),
+        text_field: Object::object(gc_context, Some(object_proto)),
+        text_format: Object::object(gc_context, Some(object_proto)),
+        filters: filter_prototypes,
+    };
+
+    object::fill_proto(gc_context, prototypes.object, function_proto);
+    function::fill_proto(gc_context, prototypes.function, function_proto);
+    array::fill_proto(gc_context, prototypes.array, function_proto);
+    string::fill_proto(gc_context, prototypes.string, function_proto);
+    number::fill_proto(gc_context, prototypes.number, function_proto);
+    boolean::fill_proto(gc_context, prototypes.boolean, function_proto);
+    movie_clip::fill_proto(gc_context, prototypes.movie_clip, function_proto);
+    text_field::fill_proto(gc_context, prototypes.text_field, function_proto);
+
+    let object = define_class(
+        gc_context,
+        object::constructor,
+        prototypes.object,
+        function_proto,
+    );
+    let function = define_class(
+        gc_context,
+        function::constructor,
+        prototypes.function,
+        function_proto,
+    );
+    let array = define_class(
+        gc_context,
+        array::constructor,
+        prototypes.array,
+        function_proto,
+    );
+    array::fill_constructor(gc_context, array);
+    let string = define_class(
+        gc_context,
+        string::constructor,
+        prototypes.string,
+        function_proto,
+    );
+    string::fill_constructor(gc_context, string, function_proto);
+    let number = define_class(
+        gc_context,
+        number::constructor,
+        prototypes.number,
+        function_proto,
+    );
+    number::fill_constructor(gc_context, number);
+    let boolean = define_class(
+        gc_context,
+        boolean::constructor,
+        prototypes.boolean,
+        function_proto,
+    );
+    let error_proto = Object::object(gc_context, Some(object_proto));
+    error::fill_proto(gc_context, error_proto, function_proto);
+    let error = define_class(gc_context, error::constructor, error_proto, function_proto);
+    let movie_clip = define_class(
+        gc_context,
+        movie_clip::constructor,
+        prototypes.movie_clip,
+        function_proto,
+    );
+    let text_field = define_class(
+        gc_context,
+        text_field::constructor,
+        prototypes.text_field,
+        function_proto,
+    );
+    let text_format = define_class(
+        gc_context,
+        text_format::constructor,
+        prototypes.text_format,
+        function_proto,
+    );
+    let math = math::create(gc_context, object_proto, function_proto);
+    let key = key::create(gc_context, object_proto, prototypes.array, function_proto);
+    let mouse = mouse::create(gc_context, object_proto, prototypes.array, function_proto);
+    let selection = selection::create(gc_context, object_proto, prototypes.array, function_proto);
+    let flash = Object::object(gc_context, Some(object_proto));
+    flash
+        .write(gc_context)
+        .force_set("filters", filters, DONT_ENUM);
+
+    let globals = Object::object(gc_context, Some(object_proto));
+    {
+        let mut globals = globals.write(gc_context);
+        globals.force_set("Object", object, DONT_ENUM);
+        globals.force_set("Function", function, DONT_ENUM);
+        globals.force_set("Array", array, DONT_ENUM);
+        globals.force_set("String", string, DONT_ENUM);
+        globals.force_set("Number", number, DONT_ENUM);
+        globals.force_set("Boolean", boolean, DONT_ENUM);
+        globals.force_set("Error", error, DONT_ENUM);
+        globals.force_set("MovieClip", movie_clip, DONT_ENUM);
+        globals.force_set("TextField", text_field, DONT_ENUM);
+        globals.force_set("TextFormat", text_format, DONT_ENUM);
+        globals.force_set("Math", math, DONT_ENUM);
+        globals.force_set("Key", key, DONT_ENUM);
+        globals.force_set("Mouse", mouse, DONT_ENUM);
+        globals.force_set("Selection", selection, DONT_ENUM);
+        globals.force_set("flash", flash, DONT_ENUM);
+    }
+    define_method(
+        gc_context,
+        globals,
+        "setInterval",
+        timer::set_interval,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        globals,
+        "setTimeout",
+        timer::set_timeout,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        globals,
+        "clearInterval",
+        timer::clear_interval,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        globals,
+        "clearTimeout",
+        timer::clear_interval,
+        function_proto,
+    );
+
+    (globals, prototypes)
+}
+
+/// Creates a constructor function for a built-in class, and links it with `prototype`.
+fn define_class<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constructor: NativeFunction<'gc>,
+    prototype: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> GcCell<'gc, Object<'gc>> {
+    let constructor = Object::function(
+        gc_context,
+        Executable::Native(constructor),
+        Some(function_proto),
+    );
+    constructor
+        .write(gc_context)
+        .force_set("prototype", prototype, DONT_ENUM);
+    prototype
+        .write(gc_context)
+        .force_set("constructor", constructor, DONT_ENUM);
+    constructor
+}
+
+/// Defines a native method called `name` on `object`.
+fn define_method<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+    name: &str,
+    function: NativeFunction<'gc>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let function = Object::function(
+        gc_context,
+        Executable::Native(function),
+        Some(function_proto),
+    );
+    object
+        .write(gc_context)
+        .force_set(name, function, DONT_ENUM);
+}
+
+/// Returns the argument at `index`, or `undefined` if it was not passed.
+fn arg<'gc>(args: &[Value<'gc>], index: usize) -> Value<'gc> {
+    args.get(index).cloned().unwrap_or(Value::Undefined)
+}
+
+/// Unwraps the primitive value of a `String`, `Number` or `Boolean` object.
+/// Methods of these classes may be called on either a primitive or a wrapper object.
+fn primitive_of<'gc>(this: &Value<'gc>) -> Value<'gc> {
+    if let Value::Object(object) = this {
+        if let Some(primitive) = object.read().primitive() {
+            return primitive.clone();
+        }
+    }
+    this.clone()
+}
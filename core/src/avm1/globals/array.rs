@@ -0,0 +1,475 @@
+//! `Array` class and `Array.prototype`
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::object::{DONT_DELETE, DONT_ENUM, READ_ONLY};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+use std::cmp::Ordering;
+
+/// Flags accepted by `sort` and `sortOn`.
+const CASE_INSENSITIVE: i32 = 1;
+const DESCENDING: i32 = 2;
+const UNIQUE_SORT: i32 = 4;
+const RETURN_INDEXED_ARRAY: i32 = 8;
+const NUMERIC: i32 = 16;
+
+/// A comparison between two elements used to sort an array.
+type CompareFn<'a, 'gc> = dyn FnMut(
+        &mut Avm1<'gc>,
+        &mut ActionContext<'_, 'gc, '_>,
+        &Value<'gc>,
+        &Value<'gc>,
+    ) -> Result<Ordering, Error>
+    + 'a;
+
+/// Implements `Array(...)` and `new Array(...)`.
+///
+/// A single numeric argument creates an array of that length, with no elements.
+/// Otherwise, the arguments become the elements of the array.
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let array = match this {
+        Value::Object(this) if this.read().is_array() => this,
+        _ => Object::array(context.gc_context, Some(avm.prototypes.array), vec![]),
+    };
+    let mut array_object = array.write(context.gc_context);
+    match args {
+        [Value::Number(length)] if *length >= 0.0 => {
+            array_object.set_array_elements(vec![]);
+            array_object.set_array_length(*length as usize);
+        }
+        _ => array_object.set_array_elements(args.to_vec()),
+    }
+    Ok(array.into())
+}
+
+pub fn fill_constructor<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constructor: GcCell<'gc, Object<'gc>>,
+) {
+    let attributes = DONT_ENUM | DONT_DELETE | READ_ONLY;
+    let mut constructor = constructor.write(gc_context);
+    constructor.force_set("CASEINSENSITIVE", f64::from(CASE_INSENSITIVE), attributes);
+    constructor.force_set("DESCENDING", f64::from(DESCENDING), attributes);
+    constructor.force_set("UNIQUESORT", f64::from(UNIQUE_SORT), attributes);
+    constructor.force_set(
+        "RETURNINDEXEDARRAY",
+        f64::from(RETURN_INDEXED_ARRAY),
+        attributes,
+    );
+    constructor.force_set("NUMERIC", f64::from(NUMERIC), attributes);
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("concat", concat),
+        ("join", join),
+        ("pop", pop),
+        ("push", push),
+        ("reverse", reverse),
+        ("shift", shift),
+        ("slice", slice),
+        ("sort", sort),
+        ("sortOn", sort_on),
+        ("splice", splice),
+        ("toString", to_string),
+        ("unshift", unshift),
+    ];
+    for (name, method) in methods {
+        define_method(gc_context, proto, name, *method, function_proto);
+    }
+}
+
+/// Resolves a possibly negative index relative to an array of length `len`.
+fn relative_index(index: f64, len: usize) -> usize {
+    if index.is_nan() {
+        0
+    } else if index < 0.0 {
+        (len as f64 + index).max(0.0) as usize
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+fn concat<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut elements = match this {
+        Value::Object(this) => this.read().array_elements(),
+        _ => vec![],
+    };
+    for arg in args {
+        // Arrays are flattened into the result, but only by one level.
+        match arg {
+            Value::Object(array) if array.read().is_array() => {
+                elements.extend(array.read().array_elements())
+            }
+            _ => elements.push(arg.clone()),
+        }
+    }
+    Ok(Object::array(context.gc_context, Some(avm.prototypes.array), elements).into())
+}
+
+fn join<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let separator = match arg(args, 0) {
+        Value::Undefined => ",".to_string(),
+        separator => separator.coerce_to_string(avm, context)?,
+    };
+    let elements = match this {
+        Value::Object(this) => this.read().array_elements(),
+        _ => vec![],
+    };
+    let mut strings = Vec::with_capacity(elements.len());
+    for element in elements {
+        strings.push(element.coerce_to_string(avm, context)?);
+    }
+    Ok(strings.join(&separator).into())
+}
+
+fn to_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    join(avm, context, this, &[])
+}
+
+fn pop<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        let mut elements = this.array_elements();
+        let result = elements.pop();
+        this.set_array_elements(elements);
+        return Ok(result.unwrap_or(Value::Undefined));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Appends the arguments to the array, returning its new length.
+fn push<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        let mut elements = this.array_elements();
+        elements.extend_from_slice(args);
+        let length = elements.len();
+        this.set_array_elements(elements);
+        return Ok((length as f64).into());
+    }
+    Ok(Value::Undefined)
+}
+
+fn reverse<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(object) = this {
+        let mut object = object.write(context.gc_context);
+        let mut elements = object.array_elements();
+        elements.reverse();
+        object.set_array_elements(elements);
+    }
+    Ok(this)
+}
+
+fn shift<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        let mut elements = this.array_elements();
+        if elements.is_empty() {
+            return Ok(Value::Undefined);
+        }
+        let result = elements.remove(0);
+        this.set_array_elements(elements);
+        return Ok(result);
+    }
+    Ok(Value::Undefined)
+}
+
+fn slice<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let elements = match this {
+        Value::Object(this) => this.read().array_elements(),
+        _ => vec![],
+    };
+    let start = relative_index(arg(args, 0).coerce_to_number(avm, context)?, elements.len());
+    let end = match arg(args, 1) {
+        Value::Undefined => elements.len(),
+        end => relative_index(end.coerce_to_number(avm, context)?, elements.len()),
+    };
+    let result = if start < end {
+        elements[start..end].to_vec()
+    } else {
+        vec![]
+    };
+    Ok(Object::array(context.gc_context, Some(avm.prototypes.array), result).into())
+}
+
+/// Implements `splice(start, deleteCount, ...items)`.
+/// Returns an array of the removed elements.
+fn splice<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let this = match this {
+        Value::Object(this) => this,
+        _ => return Ok(Value::Undefined),
+    };
+    let mut elements = this.read().array_elements();
+    let start = relative_index(arg(args, 0).coerce_to_number(avm, context)?, elements.len());
+    let delete_count = match arg(args, 1) {
+        Value::Undefined => elements.len() - start,
+        count => {
+            let count = count.coerce_to_number(avm, context)?;
+            if count > 0.0 {
+                (count as usize).min(elements.len() - start)
+            } else {
+                0
+            }
+        }
+    };
+    let items = args.get(2..).unwrap_or(&[]).iter().cloned();
+    let removed = elements
+        .splice(start..start + delete_count, items)
+        .collect();
+    this.write(context.gc_context).set_array_elements(elements);
+    Ok(Object::array(context.gc_context, Some(avm.prototypes.array), removed).into())
+}
+
+/// Prepends the arguments to the array, returning its new length.
+fn unshift<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        let mut elements = args.to_vec();
+        elements.extend(this.array_elements());
+        let length = elements.len();
+        this.set_array_elements(elements);
+        return Ok((length as f64).into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `sort(compareFunction, flags)`. Both arguments are optional.
+fn sort<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (compare_function, flags) = match arg(args, 0) {
+        function @ Value::Object(_) => (Some(function), arg(args, 1)),
+        flags => (None, flags),
+    };
+    let flags = match flags {
+        Value::Undefined => 0,
+        flags => flags.coerce_to_number(avm, context)? as i32,
+    };
+
+    if let Some(compare_function) = compare_function {
+        sort_elements(avm, context, this, flags, &mut |avm, context, a, b| {
+            let result = avm
+                .call_function(
+                    context,
+                    compare_function.clone(),
+                    Value::Undefined,
+                    &[a.clone(), b.clone()],
+                )?
+                .coerce_to_number(avm, context)?;
+            Ok(result.partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+        })
+    } else {
+        sort_elements(avm, context, this, flags, &mut |avm, context, a, b| {
+            compare_values(avm, context, a, b, flags)
+        })
+    }
+}
+
+/// Implements `sortOn(fieldName, flags)`, which sorts an array of objects by one of their properties.
+fn sort_on<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let field_name = arg(args, 0).into_string();
+    let flags = match arg(args, 1) {
+        Value::Undefined => 0,
+        flags => flags.coerce_to_number(avm, context)? as i32,
+    };
+    sort_elements(avm, context, this, flags, &mut |avm, context, a, b| {
+        let a = get_field(avm, context, a, &field_name)?;
+        let b = get_field(avm, context, b, &field_name)?;
+        compare_values(avm, context, &a, &b, flags)
+    })
+}
+
+fn get_field<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    value: &Value<'gc>,
+    name: &str,
+) -> Result<Value<'gc>, Error> {
+    match value {
+        Value::Object(object) => Object::get(*object, name, avm, context),
+        _ => Ok(Value::Undefined),
+    }
+}
+
+/// The default ordering of `sort`, which compares elements as strings, by their UTF-16
+/// code units, unless `NUMERIC` is set. NaN sorts after all other numbers.
+fn compare_values<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    a: &Value<'gc>,
+    b: &Value<'gc>,
+    flags: i32,
+) -> Result<Ordering, Error> {
+    if flags & NUMERIC != 0 {
+        let a = a.clone().coerce_to_number(avm, context)?;
+        let b = b.clone().coerce_to_number(avm, context)?;
+        return Ok(a
+            .partial_cmp(&b)
+            .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan())));
+    }
+    let mut a = a.clone().coerce_to_string(avm, context)?;
+    let mut b = b.clone().coerce_to_string(avm, context)?;
+    if flags & CASE_INSENSITIVE != 0 {
+        a = a.to_lowercase();
+        b = b.to_lowercase();
+    }
+    Ok(a.encode_utf16().cmp(b.encode_utf16()))
+}
+
+/// Sorts the elements of `this` with the given comparison function, applying the `flags`
+/// shared by `sort` and `sortOn`.
+fn sort_elements<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    flags: i32,
+    compare: &mut CompareFn<'_, 'gc>,
+) -> Result<Value<'gc>, Error> {
+    let object = match this {
+        Value::Object(object) => object,
+        _ => return Ok(Value::Undefined),
+    };
+
+    let mut elements: Vec<(usize, Value<'gc>)> = object
+        .read()
+        .array_elements()
+        .into_iter()
+        .enumerate()
+        .collect();
+    let mut is_unique = true;
+    merge_sort(&mut elements, &mut |(_, a), (_, b)| {
+        let ordering = compare(avm, context, a, b)?;
+        if ordering == Ordering::Equal {
+            is_unique = false;
+        }
+        if flags & DESCENDING != 0 {
+            Ok(ordering.reverse())
+        } else {
+            Ok(ordering)
+        }
+    })?;
+
+    // A unique sort fails and leaves the array untouched if any elements are equal.
+    if flags & UNIQUE_SORT != 0 && !is_unique {
+        return Ok(0.0.into());
+    }
+
+    if flags & RETURN_INDEXED_ARRAY != 0 {
+        let indices = elements
+            .into_iter()
+            .map(|(i, _)| Value::Number(i as f64))
+            .collect();
+        Ok(Object::array(context.gc_context, Some(avm.prototypes.array), indices).into())
+    } else {
+        let elements = elements.into_iter().map(|(_, value)| value).collect();
+        object
+            .write(context.gc_context)
+            .set_array_elements(elements);
+        Ok(this)
+    }
+}
+
+/// Sorts `elements` stably with `compare`, stopping at the first error it returns.
+///
+/// Script comparators aren't guaranteed to be a total order, which `slice::sort_by`
+/// may panic on, so the elements are merge sorted instead, which works with any
+/// comparison.
+fn merge_sort<T: Clone>(
+    elements: &mut Vec<T>,
+    compare: &mut dyn FnMut(&T, &T) -> Result<Ordering, Error>,
+) -> Result<(), Error> {
+    let len = elements.len();
+    let mut width = 1;
+    while width < len {
+        let mut merged = Vec::with_capacity(len);
+        for start in (0..len).step_by(width * 2) {
+            let middle = (start + width).min(len);
+            let end = (start + width * 2).min(len);
+            let (mut left, mut right) = (start, middle);
+            while left < middle && right < end {
+                // Equal elements keep their order, as the left one is taken first.
+                if compare(&elements[right], &elements[left])? == Ordering::Less {
+                    merged.push(elements[right].clone());
+                    right += 1;
+                } else {
+                    merged.push(elements[left].clone());
+                    left += 1;
+                }
+            }
+            merged.extend_from_slice(&elements[left..middle]);
+            merged.extend_from_slice(&elements[right..end]);
+        }
+        *elements = merged;
+        width *= 2;
+    }
+    Ok(())
+}
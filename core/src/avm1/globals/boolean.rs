@@ -0,0 +1,48 @@
+//! `Boolean` class and `Boolean.prototype`
+
+use crate::avm1::globals::{arg, define_method, primitive_of};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = Value::Bool(arg(args, 0).as_bool(avm.swf_version));
+    if let Value::Object(this) = this {
+        this.write(context.gc_context).set_primitive(value.clone());
+    }
+    Ok(value)
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    define_method(gc_context, proto, "toString", to_string, function_proto);
+    define_method(gc_context, proto, "valueOf", value_of, function_proto);
+}
+
+fn to_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this)
+        .as_bool(avm.swf_version)
+        .to_string()
+        .into())
+}
+
+fn value_of<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this).as_bool(avm.swf_version).into())
+}
@@ -0,0 +1,108 @@
+//! `addListener` and `removeListener` for the built-in objects that broadcast events,
+//! such as `Key` and `Mouse`.
+//!
+//! As in Flash, the listeners are kept in the `_listeners` array of the broadcaster.
+
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::object::DONT_ENUM;
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+/// Gives `broadcaster` an empty `_listeners` array, and the methods that manage it.
+pub fn initialize<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    broadcaster: GcCell<'gc, Object<'gc>>,
+    array_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let listeners = Object::array(gc_context, Some(array_proto), vec![]);
+    broadcaster
+        .write(gc_context)
+        .force_set("_listeners", listeners, DONT_ENUM);
+    define_method(
+        gc_context,
+        broadcaster,
+        "addListener",
+        add_listener,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        broadcaster,
+        "removeListener",
+        remove_listener,
+        function_proto,
+    );
+}
+
+/// Returns the listeners registered with `broadcaster`.
+pub fn listeners<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    broadcaster: GcCell<'gc, Object<'gc>>,
+) -> Result<Vec<Value<'gc>>, Error> {
+    let listeners = Object::get(broadcaster, "_listeners", avm, context)?;
+    match listeners {
+        Value::Object(listeners) => Ok(listeners.read().array_elements()),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Adds a listener, unless it is already registered. Always returns `true`.
+fn add_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let listener = arg(args, 0);
+    if let Some(listeners) = listeners_array(avm, context, this)? {
+        let mut elements = listeners.read().array_elements();
+        if !elements.iter().any(|element| element.strict_eq(&listener)) {
+            elements.push(listener);
+            listeners
+                .write(context.gc_context)
+                .set_array_elements(elements);
+        }
+    }
+    Ok(true.into())
+}
+
+/// Removes a listener. Returns `false` if it was not registered.
+fn remove_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let listener = arg(args, 0);
+    if let Some(listeners) = listeners_array(avm, context, this)? {
+        let mut elements = listeners.read().array_elements();
+        if let Some(index) = elements
+            .iter()
+            .position(|element| element.strict_eq(&listener))
+        {
+            elements.remove(index);
+            listeners
+                .write(context.gc_context)
+                .set_array_elements(elements);
+            return Ok(true.into());
+        }
+    }
+    Ok(false.into())
+}
+
+fn listeners_array<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+) -> Result<Option<GcCell<'gc, Object<'gc>>>, Error> {
+    if let Value::Object(this) = this {
+        if let Value::Object(listeners) = Object::get(this, "_listeners", avm, context)? {
+            if listeners.read().is_array() {
+                return Ok(Some(listeners));
+            }
+        }
+    }
+    Ok(None)
+}
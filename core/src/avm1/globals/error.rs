@@ -0,0 +1,51 @@
+//! `Error` class and `Error.prototype`
+
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::object::DONT_ENUM;
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match (this, arg(args, 0)) {
+        (_, Value::Undefined) => (),
+        (Value::Object(this), message) => {
+            this.write(context.gc_context)
+                .force_set("message", message.into_string(), 0);
+        }
+        _ => (),
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    {
+        let mut proto = proto.write(gc_context);
+        proto.force_set("name", "Error", DONT_ENUM);
+        proto.force_set("message", "Error", DONT_ENUM);
+    }
+    define_method(gc_context, proto, "toString", to_string, function_proto);
+}
+
+fn to_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match this {
+        Value::Object(this) => {
+            let message = Object::get(this, "message", avm, context)?;
+            Ok(message.into_string().into())
+        }
+        _ => Ok("Error".into()),
+    }
+}
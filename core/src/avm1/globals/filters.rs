@@ -0,0 +1,767 @@
+//! The `flash.filters` package
+//!
+//! Filter objects are plain objects whose properties are read when they are assigned to
+//! the `filters` of a display object. Reading `filters` gives new copies of the objects.
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, define_class, define_method};
+use crate::avm1::object::DONT_ENUM;
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+use swf::{
+    BevelFilter, BlurFilter, Color, ColorMatrixFilter, ConvolutionFilter, DropShadowFilter, Filter,
+    GlowFilter, GradientBevelFilter, GradientGlowFilter, GradientRecord,
+};
+
+/// The classes of the `flash.filters` package, other than `BitmapFilter`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum FilterClass {
+    Bevel,
+    Blur,
+    ColorMatrix,
+    Convolution,
+    DropShadow,
+    Glow,
+    GradientBevel,
+    GradientGlow,
+}
+
+impl FilterClass {
+    /// The filter that the constructor creates when it is given no arguments.
+    fn default_filter(self) -> Filter {
+        let black = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        let angle = 45f64.to_radians();
+        match self {
+            FilterClass::Bevel => Filter::BevelFilter(Box::new(BevelFilter {
+                shadow_color: black,
+                highlight_color: Color {
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                    a: 255,
+                },
+                blur_x: 4.0,
+                blur_y: 4.0,
+                angle,
+                distance: 4.0,
+                strength: 1.0,
+                is_inner: true,
+                is_knockout: false,
+                is_on_top: false,
+                num_passes: 1,
+            })),
+            FilterClass::Blur => Filter::BlurFilter(Box::new(BlurFilter {
+                blur_x: 4.0,
+                blur_y: 4.0,
+                num_passes: 1,
+            })),
+            FilterClass::ColorMatrix => {
+                let mut matrix = [0.0; 20];
+                for i in 0..4 {
+                    matrix[i * 6] = 1.0;
+                }
+                Filter::ColorMatrixFilter(Box::new(ColorMatrixFilter { matrix }))
+            }
+            FilterClass::Convolution => Filter::ConvolutionFilter(Box::new(ConvolutionFilter {
+                num_matrix_rows: 0,
+                num_matrix_cols: 0,
+                matrix: vec![],
+                divisor: 1.0,
+                bias: 0.0,
+                default_color: Color {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 0,
+                },
+                is_clamped: true,
+                is_preserve_alpha: true,
+            })),
+            FilterClass::DropShadow => Filter::DropShadowFilter(Box::new(DropShadowFilter {
+                color: black,
+                blur_x: 4.0,
+                blur_y: 4.0,
+                angle,
+                distance: 4.0,
+                strength: 1.0,
+                is_inner: false,
+                is_knockout: false,
+                num_passes: 1,
+            })),
+            FilterClass::Glow => Filter::GlowFilter(Box::new(GlowFilter {
+                color: Color {
+                    r: 255,
+                    g: 0,
+                    b: 0,
+                    a: 255,
+                },
+                blur_x: 6.0,
+                blur_y: 6.0,
+                strength: 2.0,
+                is_inner: false,
+                is_knockout: false,
+                num_passes: 1,
+            })),
+            FilterClass::GradientBevel => {
+                Filter::GradientBevelFilter(Box::new(GradientBevelFilter {
+                    colors: vec![],
+                    blur_x: 4.0,
+                    blur_y: 4.0,
+                    angle,
+                    distance: 4.0,
+                    strength: 1.0,
+                    is_inner: true,
+                    is_knockout: false,
+                    is_on_top: false,
+                    num_passes: 1,
+                }))
+            }
+            FilterClass::GradientGlow => Filter::GradientGlowFilter(Box::new(GradientGlowFilter {
+                colors: vec![],
+                blur_x: 4.0,
+                blur_y: 4.0,
+                angle,
+                distance: 4.0,
+                strength: 1.0,
+                is_inner: true,
+                is_knockout: false,
+                is_on_top: false,
+                num_passes: 1,
+            })),
+        }
+    }
+}
+
+/// The prototypes of the filter classes, which tell what kind of filter an object is.
+#[derive(Clone)]
+pub struct FilterPrototypes<'gc> {
+    classes: Vec<(FilterClass, GcCell<'gc, Object<'gc>>)>,
+}
+
+unsafe impl<'gc> gc_arena::Collect for FilterPrototypes<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for (_, prototype) in &self.classes {
+            prototype.trace(cc);
+        }
+    }
+}
+
+impl<'gc> FilterPrototypes<'gc> {
+    /// Returns the filter class that `object` is an instance of.
+    fn class_of(&self, object: GcCell<'gc, Object<'gc>>) -> Option<FilterClass> {
+        let mut prototype = object.read().prototype();
+        while let Some(p) = prototype {
+            let class = self
+                .classes
+                .iter()
+                .find(|(_, class_proto)| GcCell::as_ptr(*class_proto) == GcCell::as_ptr(p));
+            if let Some((class, _)) = class {
+                return Some(*class);
+            }
+            prototype = p.read().prototype();
+        }
+        None
+    }
+
+    fn prototype(&self, class: FilterClass) -> Option<GcCell<'gc, Object<'gc>>> {
+        self.classes
+            .iter()
+            .find(|(c, _)| *c == class)
+            .map(|(_, prototype)| *prototype)
+    }
+}
+
+/// Creates the `flash.filters` package, and the prototypes of its classes.
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> (GcCell<'gc, Object<'gc>>, FilterPrototypes<'gc>) {
+    let package = Object::object(gc_context, Some(object_proto));
+
+    let bitmap_filter_proto = Object::object(gc_context, Some(object_proto));
+    define_method(
+        gc_context,
+        bitmap_filter_proto,
+        "clone",
+        clone,
+        function_proto,
+    );
+    let bitmap_filter = define_class(
+        gc_context,
+        bitmap_filter,
+        bitmap_filter_proto,
+        function_proto,
+    );
+    package
+        .write(gc_context)
+        .force_set("BitmapFilter", bitmap_filter, DONT_ENUM);
+
+    let constructors: &[(FilterClass, &str, NativeFunction<'gc>)] = &[
+        (FilterClass::Bevel, "BevelFilter", bevel_filter),
+        (FilterClass::Blur, "BlurFilter", blur_filter),
+        (
+            FilterClass::ColorMatrix,
+            "ColorMatrixFilter",
+            color_matrix_filter,
+        ),
+        (
+            FilterClass::Convolution,
+            "ConvolutionFilter",
+            convolution_filter,
+        ),
+        (
+            FilterClass::DropShadow,
+            "DropShadowFilter",
+            drop_shadow_filter,
+        ),
+        (FilterClass::Glow, "GlowFilter", glow_filter),
+        (
+            FilterClass::GradientBevel,
+            "GradientBevelFilter",
+            gradient_bevel_filter,
+        ),
+        (
+            FilterClass::GradientGlow,
+            "GradientGlowFilter",
+            gradient_glow_filter,
+        ),
+    ];
+    let mut classes = vec![];
+    for (class, name, constructor) in constructors {
+        let prototype = Object::object(gc_context, Some(bitmap_filter_proto));
+        let constructor = define_class(gc_context, *constructor, prototype, function_proto);
+        package
+            .write(gc_context)
+            .force_set(name, constructor, DONT_ENUM);
+        classes.push((*class, prototype));
+    }
+
+    (package, FilterPrototypes { classes })
+}
+
+/// Converts a filter object into a filter, or returns `None` if it is not a filter object.
+pub fn filter_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+) -> Result<Option<Filter>, Error> {
+    let class = match avm.prototypes().filters.class_of(object) {
+        Some(class) => class,
+        None => return Ok(None),
+    };
+    let mut get = FilterReader {
+        avm,
+        context,
+        object,
+    };
+    let filter = match class {
+        FilterClass::Bevel => {
+            let (is_inner, is_on_top) = bevel_type_from_avm(&get.string("type")?);
+            Filter::BevelFilter(Box::new(BevelFilter {
+                shadow_color: get.color("shadowColor", "shadowAlpha")?,
+                highlight_color: get.color("highlightColor", "highlightAlpha")?,
+                blur_x: blur_from_avm(get.number("blurX")?),
+                blur_y: blur_from_avm(get.number("blurY")?),
+                angle: get.number("angle")?.to_radians(),
+                distance: get.number("distance")?,
+                strength: get.number("strength")? as f32,
+                is_inner,
+                is_knockout: get.boolean("knockout")?,
+                is_on_top,
+                num_passes: quality_from_avm(get.number("quality")?),
+            }))
+        }
+        FilterClass::Blur => Filter::BlurFilter(Box::new(BlurFilter {
+            blur_x: blur_from_avm(get.number("blurX")?),
+            blur_y: blur_from_avm(get.number("blurY")?),
+            num_passes: quality_from_avm(get.number("quality")?),
+        })),
+        FilterClass::ColorMatrix => {
+            let mut matrix = [0.0; 20];
+            for (m, value) in matrix.iter_mut().zip(get.numbers("matrix")?) {
+                *m = value;
+            }
+            Filter::ColorMatrixFilter(Box::new(ColorMatrixFilter { matrix }))
+        }
+        FilterClass::Convolution => {
+            let dimension = |number: f64| number.max(0.0).min(15.0) as u8;
+            Filter::ConvolutionFilter(Box::new(ConvolutionFilter {
+                num_matrix_rows: dimension(get.number("matrixY")?),
+                num_matrix_cols: dimension(get.number("matrixX")?),
+                matrix: get.numbers("matrix")?,
+                divisor: get.number("divisor")?,
+                bias: get.number("bias")?,
+                default_color: get.color("color", "alpha")?,
+                is_clamped: get.boolean("clamp")?,
+                is_preserve_alpha: get.boolean("preserveAlpha")?,
+            }))
+        }
+        FilterClass::DropShadow => Filter::DropShadowFilter(Box::new(DropShadowFilter {
+            color: get.color("color", "alpha")?,
+            blur_x: blur_from_avm(get.number("blurX")?),
+            blur_y: blur_from_avm(get.number("blurY")?),
+            angle: get.number("angle")?.to_radians(),
+            distance: get.number("distance")?,
+            strength: get.number("strength")? as f32,
+            is_inner: get.boolean("inner")?,
+            is_knockout: get.boolean("knockout")?,
+            num_passes: quality_from_avm(get.number("quality")?),
+        })),
+        FilterClass::Glow => Filter::GlowFilter(Box::new(GlowFilter {
+            color: get.color("color", "alpha")?,
+            blur_x: blur_from_avm(get.number("blurX")?),
+            blur_y: blur_from_avm(get.number("blurY")?),
+            strength: get.number("strength")? as f32,
+            is_inner: get.boolean("inner")?,
+            is_knockout: get.boolean("knockout")?,
+            num_passes: quality_from_avm(get.number("quality")?),
+        })),
+        FilterClass::GradientBevel | FilterClass::GradientGlow => {
+            let (is_inner, is_on_top) = bevel_type_from_avm(&get.string("type")?);
+            let colors = gradient_from_avm(
+                get.numbers("colors")?,
+                get.numbers("alphas")?,
+                get.numbers("ratios")?,
+            );
+            let blur_x = blur_from_avm(get.number("blurX")?);
+            let blur_y = blur_from_avm(get.number("blurY")?);
+            let angle = get.number("angle")?.to_radians();
+            let distance = get.number("distance")?;
+            let strength = get.number("strength")? as f32;
+            let is_knockout = get.boolean("knockout")?;
+            let num_passes = quality_from_avm(get.number("quality")?);
+            if class == FilterClass::GradientBevel {
+                Filter::GradientBevelFilter(Box::new(GradientBevelFilter {
+                    colors,
+                    blur_x,
+                    blur_y,
+                    angle,
+                    distance,
+                    strength,
+                    is_inner,
+                    is_knockout,
+                    is_on_top,
+                    num_passes,
+                }))
+            } else {
+                Filter::GradientGlowFilter(Box::new(GradientGlowFilter {
+                    colors,
+                    blur_x,
+                    blur_y,
+                    angle,
+                    distance,
+                    strength,
+                    is_inner,
+                    is_knockout,
+                    is_on_top,
+                    num_passes,
+                }))
+            }
+        }
+    };
+    Ok(Some(filter))
+}
+
+/// Creates a new filter object for `filter`.
+pub fn filter_to_avm<'gc>(
+    avm: &Avm1<'gc>,
+    gc_context: MutationContext<'gc, '_>,
+    filter: &Filter,
+) -> GcCell<'gc, Object<'gc>> {
+    let (class, properties) = filter_properties(avm, gc_context, filter);
+    let prototype = avm.prototypes().filters.prototype(class);
+    let object = Object::object(gc_context, prototype);
+    {
+        let mut object = object.write(gc_context);
+        for (name, value) in properties {
+            object.force_set(name, value, 0);
+        }
+    }
+    object
+}
+
+/// Returns the class of `filter`, and its properties in the order of the arguments
+/// of the constructor of the class.
+fn filter_properties<'gc>(
+    avm: &Avm1<'gc>,
+    gc_context: MutationContext<'gc, '_>,
+    filter: &Filter,
+) -> (FilterClass, Vec<(&'static str, Value<'gc>)>) {
+    let array_proto = avm.prototypes().array;
+    let array = |elements: Vec<Value<'gc>>| -> Value<'gc> {
+        Object::array(gc_context, Some(array_proto), elements).into()
+    };
+    match filter {
+        Filter::BevelFilter(filter) => (
+            FilterClass::Bevel,
+            vec![
+                ("distance", filter.distance.into()),
+                ("angle", filter.angle.to_degrees().into()),
+                ("highlightColor", rgb_to_avm(&filter.highlight_color)),
+                ("highlightAlpha", alpha_to_avm(&filter.highlight_color)),
+                ("shadowColor", rgb_to_avm(&filter.shadow_color)),
+                ("shadowAlpha", alpha_to_avm(&filter.shadow_color)),
+                ("blurX", filter.blur_x.into()),
+                ("blurY", filter.blur_y.into()),
+                ("strength", f64::from(filter.strength).into()),
+                ("quality", f64::from(filter.num_passes).into()),
+                ("type", bevel_type_to_avm(filter.is_inner, filter.is_on_top)),
+                ("knockout", Value::Bool(filter.is_knockout)),
+            ],
+        ),
+        Filter::BlurFilter(filter) => (
+            FilterClass::Blur,
+            vec![
+                ("blurX", filter.blur_x.into()),
+                ("blurY", filter.blur_y.into()),
+                ("quality", f64::from(filter.num_passes).into()),
+            ],
+        ),
+        Filter::ColorMatrixFilter(filter) => (
+            FilterClass::ColorMatrix,
+            vec![(
+                "matrix",
+                array(filter.matrix.iter().map(|m| (*m).into()).collect()),
+            )],
+        ),
+        Filter::ConvolutionFilter(filter) => (
+            FilterClass::Convolution,
+            vec![
+                ("matrixX", f64::from(filter.num_matrix_cols).into()),
+                ("matrixY", f64::from(filter.num_matrix_rows).into()),
+                (
+                    "matrix",
+                    array(filter.matrix.iter().map(|m| (*m).into()).collect()),
+                ),
+                ("divisor", filter.divisor.into()),
+                ("bias", filter.bias.into()),
+                ("preserveAlpha", Value::Bool(filter.is_preserve_alpha)),
+                ("clamp", Value::Bool(filter.is_clamped)),
+                ("color", rgb_to_avm(&filter.default_color)),
+                ("alpha", alpha_to_avm(&filter.default_color)),
+            ],
+        ),
+        Filter::DropShadowFilter(filter) => (
+            FilterClass::DropShadow,
+            vec![
+                ("distance", filter.distance.into()),
+                ("angle", filter.angle.to_degrees().into()),
+                ("color", rgb_to_avm(&filter.color)),
+                ("alpha", alpha_to_avm(&filter.color)),
+                ("blurX", filter.blur_x.into()),
+                ("blurY", filter.blur_y.into()),
+                ("strength", f64::from(filter.strength).into()),
+                ("quality", f64::from(filter.num_passes).into()),
+                ("inner", Value::Bool(filter.is_inner)),
+                ("knockout", Value::Bool(filter.is_knockout)),
+                ("hideObject", Value::Bool(false)),
+            ],
+        ),
+        Filter::GlowFilter(filter) => (
+            FilterClass::Glow,
+            vec![
+                ("color", rgb_to_avm(&filter.color)),
+                ("alpha", alpha_to_avm(&filter.color)),
+                ("blurX", filter.blur_x.into()),
+                ("blurY", filter.blur_y.into()),
+                ("strength", f64::from(filter.strength).into()),
+                ("quality", f64::from(filter.num_passes).into()),
+                ("inner", Value::Bool(filter.is_inner)),
+                ("knockout", Value::Bool(filter.is_knockout)),
+            ],
+        ),
+        Filter::GradientBevelFilter(filter) => (
+            FilterClass::GradientBevel,
+            gradient_filter_properties(
+                &filter.colors,
+                array,
+                [filter.distance, filter.angle, filter.blur_x, filter.blur_y],
+                filter.strength,
+                filter.num_passes,
+                (filter.is_inner, filter.is_on_top, filter.is_knockout),
+            ),
+        ),
+        Filter::GradientGlowFilter(filter) => (
+            FilterClass::GradientGlow,
+            gradient_filter_properties(
+                &filter.colors,
+                array,
+                [filter.distance, filter.angle, filter.blur_x, filter.blur_y],
+                filter.strength,
+                filter.num_passes,
+                (filter.is_inner, filter.is_on_top, filter.is_knockout),
+            ),
+        ),
+    }
+}
+
+/// The properties shared by `GradientBevelFilter` and `GradientGlowFilter`.
+fn gradient_filter_properties<'gc>(
+    colors: &[GradientRecord],
+    array: impl Fn(Vec<Value<'gc>>) -> Value<'gc>,
+    [distance, angle, blur_x, blur_y]: [f64; 4],
+    strength: f32,
+    num_passes: u8,
+    (is_inner, is_on_top, is_knockout): (bool, bool, bool),
+) -> Vec<(&'static str, Value<'gc>)> {
+    vec![
+        ("distance", distance.into()),
+        ("angle", angle.to_degrees().into()),
+        (
+            "colors",
+            array(colors.iter().map(|r| rgb_to_avm(&r.color)).collect()),
+        ),
+        (
+            "alphas",
+            array(colors.iter().map(|r| alpha_to_avm(&r.color)).collect()),
+        ),
+        (
+            "ratios",
+            array(colors.iter().map(|r| f64::from(r.ratio).into()).collect()),
+        ),
+        ("blurX", blur_x.into()),
+        ("blurY", blur_y.into()),
+        ("strength", f64::from(strength).into()),
+        ("quality", f64::from(num_passes).into()),
+        ("type", bevel_type_to_avm(is_inner, is_on_top)),
+        ("knockout", Value::Bool(is_knockout)),
+    ]
+}
+
+/// Sets the properties of a new filter object from the arguments of its constructor,
+/// with the properties of the default filter for any missing arguments.
+fn construct<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+    class: FilterClass,
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let (_, properties) = filter_properties(avm, context.gc_context, &class.default_filter());
+        let mut this = this.write(context.gc_context);
+        for (i, (name, default)) in properties.into_iter().enumerate() {
+            let value = match arg(args, i) {
+                Value::Undefined => default,
+                value => value,
+            };
+            this.force_set(name, value, 0);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+fn bitmap_filter<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+fn bevel_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::Bevel)
+}
+
+fn blur_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::Blur)
+}
+
+fn color_matrix_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::ColorMatrix)
+}
+
+fn convolution_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::Convolution)
+}
+
+fn drop_shadow_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::DropShadow)
+}
+
+fn glow_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::Glow)
+}
+
+fn gradient_bevel_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::GradientBevel)
+}
+
+fn gradient_glow_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    construct(avm, context, this, args, FilterClass::GradientGlow)
+}
+
+/// `BitmapFilter.clone()`
+fn clone<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        if let Some(filter) = filter_from_avm(avm, context, this)? {
+            return Ok(filter_to_avm(avm, context.gc_context, &filter).into());
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Reads the properties of a filter object, converting them as they are read.
+struct FilterReader<'a, 'b, 'gc, 'gc_context> {
+    avm: &'a mut Avm1<'gc>,
+    context: &'a mut ActionContext<'b, 'gc, 'gc_context>,
+    object: GcCell<'gc, Object<'gc>>,
+}
+
+impl<'a, 'b, 'gc, 'gc_context> FilterReader<'a, 'b, 'gc, 'gc_context> {
+    fn value(&mut self, name: &str) -> Result<Value<'gc>, Error> {
+        Object::get(self.object, name, self.avm, self.context)
+    }
+
+    fn boolean(&mut self, name: &str) -> Result<bool, Error> {
+        let swf_version = self.avm.swf_version;
+        Ok(self.value(name)?.as_bool(swf_version))
+    }
+
+    fn string(&mut self, name: &str) -> Result<String, Error> {
+        self.value(name)?.coerce_to_string(self.avm, self.context)
+    }
+
+    /// Reads a number. Numbers that aren't finite are read as 0.
+    fn number(&mut self, name: &str) -> Result<f64, Error> {
+        let value = self.value(name)?;
+        self.number_from_value(value)
+    }
+
+    /// Reads an array of numbers. Values other than arrays are read as empty arrays.
+    fn numbers(&mut self, name: &str) -> Result<Vec<f64>, Error> {
+        let elements = match self.value(name)? {
+            Value::Object(object) if object.read().is_array() => object.read().array_elements(),
+            _ => vec![],
+        };
+        elements
+            .into_iter()
+            .map(|value| self.number_from_value(value))
+            .collect()
+    }
+
+    fn color(&mut self, rgb_name: &str, alpha_name: &str) -> Result<Color, Error> {
+        Ok(color_from_avm(
+            self.number(rgb_name)?,
+            self.number(alpha_name)?,
+        ))
+    }
+
+    fn number_from_value(&mut self, value: Value<'gc>) -> Result<f64, Error> {
+        let number = value.coerce_to_number(self.avm, self.context)?;
+        Ok(if number.is_finite() { number } else { 0.0 })
+    }
+}
+
+fn blur_from_avm(number: f64) -> f64 {
+    number.max(0.0).min(255.0)
+}
+
+fn quality_from_avm(number: f64) -> u8 {
+    number.max(0.0).min(15.0) as u8
+}
+
+/// Converts an RGB color and an alpha from 0 to 1.
+fn color_from_avm(rgb: f64, alpha: f64) -> Color {
+    let rgb = rgb as i64 as u32;
+    let alpha = alpha.max(0.0).min(1.0);
+    Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: (alpha * 255.0) as u8,
+    }
+}
+
+fn rgb_to_avm<'gc>(color: &Color) -> Value<'gc> {
+    f64::from(u32::from(color.r) << 16 | u32::from(color.g) << 8 | u32::from(color.b)).into()
+}
+
+fn alpha_to_avm<'gc>(color: &Color) -> Value<'gc> {
+    (f64::from(color.a) / 255.0).into()
+}
+
+/// Converts the `type` of a bevel filter into whether it is inner, and whether it is on top.
+fn bevel_type_from_avm(bevel_type: &str) -> (bool, bool) {
+    match bevel_type {
+        "outer" => (false, false),
+        "full" => (false, true),
+        _ => (true, false),
+    }
+}
+
+fn bevel_type_to_avm<'gc>(is_inner: bool, is_on_top: bool) -> Value<'gc> {
+    if is_on_top {
+        "full".into()
+    } else if is_inner {
+        "inner".into()
+    } else {
+        "outer".into()
+    }
+}
+
+/// Converts the `colors`, `alphas` and `ratios` arrays of a gradient filter.
+fn gradient_from_avm(colors: Vec<f64>, alphas: Vec<f64>, ratios: Vec<f64>) -> Vec<GradientRecord> {
+    colors
+        .into_iter()
+        .zip(alphas)
+        .zip(ratios)
+        .map(|((color, alpha), ratio)| GradientRecord {
+            ratio: ratio.max(0.0).min(255.0) as u8,
+            color: color_from_avm(color, alpha),
+        })
+        .collect()
+}
@@ -0,0 +1,60 @@
+//! `Function` class and `Function.prototype`
+
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this)
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    define_method(gc_context, proto, "call", call, function_proto);
+    define_method(gc_context, proto, "apply", apply, function_proto);
+    define_method(gc_context, proto, "toString", to_string, function_proto);
+}
+
+/// Implements `Function.prototype.call(thisObject, ...args)`.
+fn call<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let this_object = arg(args, 0);
+    let args = args.get(1..).unwrap_or(&[]);
+    avm.call_function(context, this, this_object, args)
+}
+
+/// Implements `Function.prototype.apply(thisObject, argumentsArray)`.
+fn apply<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let this_object = arg(args, 0);
+    let args = match arg(args, 1) {
+        Value::Object(array) => array.read().array_elements(),
+        _ => vec![],
+    };
+    avm.call_function(context, this, this_object, &args)
+}
+
+fn to_string<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok("[type Function]".into())
+}
@@ -0,0 +1,171 @@
+//! `Key` object
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, broadcaster, define_method};
+use crate::avm1::object::{DONT_DELETE, DONT_ENUM, READ_ONLY};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::events::{key_code, KeyCode};
+use gc_arena::{GcCell, MutationContext};
+use std::collections::HashSet;
+
+/// The state of the keyboard, as reported by the `Key` object.
+#[derive(Default)]
+pub struct KeyState {
+    pressed: HashSet<KeyCode>,
+
+    /// The lock keys that are currently toggled on, such as Caps Lock.
+    toggled: HashSet<KeyCode>,
+
+    /// The keycode of the last key pressed or released.
+    last_key_code: KeyCode,
+
+    /// The ASCII code of the last key pressed or released, or 0 if it has none.
+    last_ascii: u8,
+}
+
+impl KeyState {
+    pub fn key_down(&mut self, key_code: KeyCode, ascii: u8) {
+        let is_lock_key = key_code == key_code::CAPS_LOCK
+            || key_code == key_code::NUM_LOCK
+            || key_code == key_code::SCROLL_LOCK;
+        // Flip the lock on the first press only, and not on the repeats while it is held.
+        if is_lock_key && !self.pressed.contains(&key_code) {
+            if self.toggled.contains(&key_code) {
+                self.toggled.remove(&key_code);
+            } else {
+                self.toggled.insert(key_code);
+            }
+        }
+        self.pressed.insert(key_code);
+        self.last_key_code = key_code;
+        self.last_ascii = ascii;
+    }
+
+    pub fn key_up(&mut self, key_code: KeyCode, ascii: u8) {
+        self.pressed.remove(&key_code);
+        self.last_key_code = key_code;
+        self.last_ascii = ascii;
+    }
+
+    pub fn is_down(&self, key_code: KeyCode) -> bool {
+        self.pressed.contains(&key_code)
+    }
+
+    /// Records a mouse button press or release, which `Key.isDown` reports
+    /// without changing the last key.
+    pub fn set_mouse_button(&mut self, key_code: KeyCode, is_down: bool) {
+        if is_down {
+            self.pressed.insert(key_code);
+        } else {
+            self.pressed.remove(&key_code);
+        }
+    }
+}
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object_proto: GcCell<'gc, Object<'gc>>,
+    array_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> GcCell<'gc, Object<'gc>> {
+    let key = Object::object(gc_context, Some(object_proto));
+
+    {
+        let attributes = DONT_ENUM | DONT_DELETE | READ_ONLY;
+        let constants: &[(&str, KeyCode)] = &[
+            ("BACKSPACE", key_code::BACKSPACE),
+            ("CAPSLOCK", key_code::CAPS_LOCK),
+            ("CONTROL", key_code::CONTROL),
+            ("DELETEKEY", key_code::DELETE),
+            ("DOWN", key_code::DOWN),
+            ("END", key_code::END),
+            ("ENTER", key_code::ENTER),
+            ("ESCAPE", key_code::ESCAPE),
+            ("HOME", key_code::HOME),
+            ("INSERT", key_code::INSERT),
+            ("LEFT", key_code::LEFT),
+            ("PGDN", key_code::PG_DOWN),
+            ("PGUP", key_code::PG_UP),
+            ("RIGHT", key_code::RIGHT),
+            ("SHIFT", key_code::SHIFT),
+            ("SPACE", key_code::SPACE),
+            ("TAB", key_code::TAB),
+            ("UP", key_code::UP),
+        ];
+        let mut key = key.write(gc_context);
+        for (name, code) in constants {
+            key.force_set(name, f64::from(*code), attributes);
+        }
+    }
+
+    let functions: &[(&str, NativeFunction<'gc>)] = &[
+        ("getAscii", get_ascii),
+        ("getCode", get_code),
+        ("isDown", is_down),
+        ("isToggled", is_toggled),
+    ];
+    for (name, function) in functions {
+        define_method(gc_context, key, name, *function, function_proto);
+    }
+    broadcaster::initialize(gc_context, key, array_proto, function_proto);
+
+    key
+}
+
+fn get_ascii<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(f64::from(avm.key_state.last_ascii).into())
+}
+
+fn get_code<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(f64::from(avm.key_state.last_key_code).into())
+}
+
+fn is_down<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let result = match key_code_arg(avm, context, args)? {
+        Some(key_code) => avm.key_state.is_down(key_code),
+        None => false,
+    };
+    Ok(result.into())
+}
+
+fn is_toggled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let result = match key_code_arg(avm, context, args)? {
+        Some(key_code) => avm.key_state.toggled.contains(&key_code),
+        None => false,
+    };
+    Ok(result.into())
+}
+
+/// Reads a keycode from the first argument, or `None` if it is not a valid keycode.
+fn key_code_arg<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<KeyCode>, Error> {
+    let key_code = arg(args, 0).coerce_to_number(avm, context)?;
+    if (0.0..256.0).contains(&key_code) {
+        Ok(Some(key_code as KeyCode))
+    } else {
+        Ok(None)
+    }
+}
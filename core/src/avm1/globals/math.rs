@@ -0,0 +1,145 @@
+//! `Math` object
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::object::{DONT_DELETE, DONT_ENUM, READ_ONLY};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+use rand::Rng;
+
+/// Defines a native function that applies `$std` to its numeric arguments.
+macro_rules! wrap_std {
+    ( $name: ident, $std: expr, $x: ident ) => {
+        fn $name<'gc>(
+            avm: &mut Avm1<'gc>,
+            context: &mut ActionContext<'_, 'gc, '_>,
+            _this: Value<'gc>,
+            args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            let $x = arg(args, 0).coerce_to_number(avm, context)?;
+            Ok($std.into())
+        }
+    };
+    ( $name: ident, $std: expr, $x: ident, $y: ident ) => {
+        fn $name<'gc>(
+            avm: &mut Avm1<'gc>,
+            context: &mut ActionContext<'_, 'gc, '_>,
+            _this: Value<'gc>,
+            args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            let $x = arg(args, 0).coerce_to_number(avm, context)?;
+            let $y = arg(args, 1).coerce_to_number(avm, context)?;
+            Ok($std.into())
+        }
+    };
+}
+
+wrap_std!(abs, x.abs(), x);
+wrap_std!(acos, x.acos(), x);
+wrap_std!(asin, x.asin(), x);
+wrap_std!(atan, x.atan(), x);
+wrap_std!(atan2, y.atan2(x), y, x);
+wrap_std!(ceil, x.ceil(), x);
+wrap_std!(cos, x.cos(), x);
+wrap_std!(exp, x.exp(), x);
+wrap_std!(floor, x.floor(), x);
+wrap_std!(log, x.ln(), x);
+wrap_std!(pow, x.powf(y), x, y);
+// Halfway values always round up, even when negative.
+wrap_std!(round, (x + 0.5).floor(), x);
+wrap_std!(sin, x.sin(), x);
+wrap_std!(sqrt, x.sqrt(), x);
+wrap_std!(tan, x.tan(), x);
+
+fn max<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut result = std::f64::NEG_INFINITY;
+    for arg in args {
+        let n = arg.clone().coerce_to_number(avm, context)?;
+        if n.is_nan() {
+            return Ok(n.into());
+        }
+        result = result.max(n);
+    }
+    Ok(result.into())
+}
+
+fn min<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut result = std::f64::INFINITY;
+    for arg in args {
+        let n = arg.clone().coerce_to_number(avm, context)?;
+        if n.is_nan() {
+            return Ok(n.into());
+        }
+        result = result.min(n);
+    }
+    Ok(result.into())
+}
+
+/// Returns a number in the range [0, 1).
+/// This shares the seeded random number generator of the AVM, so that it is deterministic.
+fn random<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(avm.rng.gen_range(0.0f64, 1.0).into())
+}
+
+/// Creates the `Math` object.
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> GcCell<'gc, Object<'gc>> {
+    let math = Object::object(gc_context, Some(object_proto));
+
+    {
+        let attributes = DONT_ENUM | DONT_DELETE | READ_ONLY;
+        let mut math = math.write(gc_context);
+        math.force_set("E", std::f64::consts::E, attributes);
+        math.force_set("LN10", std::f64::consts::LN_10, attributes);
+        math.force_set("LN2", std::f64::consts::LN_2, attributes);
+        math.force_set("LOG10E", std::f64::consts::LOG10_E, attributes);
+        math.force_set("LOG2E", std::f64::consts::LOG2_E, attributes);
+        math.force_set("PI", std::f64::consts::PI, attributes);
+        math.force_set("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2, attributes);
+        math.force_set("SQRT2", std::f64::consts::SQRT_2, attributes);
+    }
+
+    let functions: &[(&str, NativeFunction<'gc>)] = &[
+        ("abs", abs),
+        ("acos", acos),
+        ("asin", asin),
+        ("atan", atan),
+        ("atan2", atan2),
+        ("ceil", ceil),
+        ("cos", cos),
+        ("exp", exp),
+        ("floor", floor),
+        ("log", log),
+        ("max", max),
+        ("min", min),
+        ("pow", pow),
+        ("random", random),
+        ("round", round),
+        ("sin", sin),
+        ("sqrt", sqrt),
+        ("tan", tan),
+    ];
+    for (name, function) in functions {
+        define_method(gc_context, math, name, *function, function_proto);
+    }
+
+    math
+}
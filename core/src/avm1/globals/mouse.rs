@@ -0,0 +1,42 @@
+//! `Mouse` object
+
+use crate::avm1::globals::{broadcaster, define_method};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object_proto: GcCell<'gc, Object<'gc>>,
+    array_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> GcCell<'gc, Object<'gc>> {
+    let mouse = Object::object(gc_context, Some(object_proto));
+    define_method(gc_context, mouse, "hide", hide, function_proto);
+    define_method(gc_context, mouse, "show", show, function_proto);
+    broadcaster::initialize(gc_context, mouse, array_proto, function_proto);
+    mouse
+}
+
+/// Hides the cursor. Returns 1 if it was visible, or 0 if it was already hidden.
+fn hide<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let was_visible = avm.mouse_visible;
+    avm.mouse_visible = false;
+    Ok(if was_visible { 1.0 } else { 0.0 }.into())
+}
+
+/// Shows the cursor. Returns 1 if it was already visible, or 0 if it was hidden.
+fn show<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let was_visible = avm.mouse_visible;
+    avm.mouse_visible = true;
+    Ok(if was_visible { 1.0 } else { 0.0 }.into())
+}
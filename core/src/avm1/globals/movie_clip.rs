@@ -0,0 +1,843 @@
+//! `MovieClip` class and `MovieClip.prototype`
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, define_method};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::display_object::DisplayObject;
+use crate::drawing::Drawing;
+use crate::movie_clip::{MovieClip, AVM_DEPTH_BIAS, AVM_MAX_DEPTH};
+use crate::prelude::*;
+use gc_arena::{GcCell, MutationContext};
+use swf::{FillStyle, Gradient, GradientInterpolation, GradientRecord, GradientSpread, LineStyle};
+
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("attachMovie", attach_movie),
+        ("beginFill", begin_fill),
+        ("beginGradientFill", begin_gradient_fill),
+        ("clear", clear),
+        ("createEmptyMovieClip", create_empty_movie_clip),
+        ("curveTo", curve_to),
+        ("duplicateMovieClip", duplicate_movie_clip_method),
+        ("endFill", end_fill),
+        ("getDepth", get_depth),
+        ("getNextHighestDepth", get_next_highest_depth),
+        ("gotoAndPlay", goto_and_play),
+        ("gotoAndStop", goto_and_stop),
+        ("hitTest", hit_test),
+        ("lineStyle", line_style),
+        ("lineTo", line_to),
+        ("moveTo", move_to),
+        ("nextFrame", next_frame),
+        ("play", play),
+        ("prevFrame", prev_frame),
+        ("removeMovieClip", remove_movie_clip_method),
+        ("setMask", set_mask),
+        ("startDrag", start_drag),
+        ("stop", stop),
+        ("stopDrag", stop_drag),
+        ("swapDepths", swap_depths),
+    ];
+    for (name, method) in methods {
+        define_method(gc_context, proto, name, *method, function_proto);
+    }
+}
+
+/// Returns the node of the movie clip controlled by `this`.
+fn movie_clip_node<'gc>(this: &Value<'gc>) -> Option<DisplayNode<'gc>> {
+    let node = match this {
+        Value::Object(object) => object.read().display_node(),
+        _ => None,
+    }?;
+    if node.read().as_movie_clip().is_some() {
+        Some(node)
+    } else {
+        None
+    }
+}
+
+/// Converts a depth given by a script into a depth in the display list.
+/// Returns `None` if the depth is outside of the range that scripts can use.
+fn depth_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    depth: Value<'gc>,
+) -> Result<Option<i32>, Error> {
+    let depth = depth.coerce_to_number(avm, context)?;
+    if (-f64::from(AVM_DEPTH_BIAS)..=f64::from(AVM_MAX_DEPTH)).contains(&depth) {
+        Ok(Some(depth as i32 + AVM_DEPTH_BIAS))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Adds `child` to the clip `parent` at `depth`, and returns the object of the child.
+fn add_child<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    parent: DisplayNode<'gc>,
+    child: DisplayNode<'gc>,
+    name: &str,
+    depth: i32,
+) -> Value<'gc> {
+    if let Some(clip) = parent.write(context.gc_context).as_movie_clip_mut() {
+        clip.add_child_from_avm(context.gc_context, parent, child, name, depth);
+    }
+    avm.add_new_clip(child);
+    let object = child.read().object();
+    object
+}
+
+/// Creates a copy of the movie clip `node` called `new_name` next to it at `depth`.
+/// The copy starts from its first frame with the transform, clip events and drawing of the
+/// original, but with none of its variables.
+pub fn duplicate_movie_clip<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    node: DisplayNode<'gc>,
+    new_name: &str,
+    depth: i32,
+) -> Option<DisplayNode<'gc>> {
+    let source = node.read();
+    let parent = source.parent()?;
+    let clip: Box<dyn DisplayObject<'gc>> = Box::new(source.as_movie_clip()?.duplicate());
+    let copy = GcCell::allocate(context.gc_context, clip);
+    {
+        let mut display_object = copy.write(context.gc_context);
+        display_object.post_instantiation(context.gc_context, copy, &avm.prototypes);
+        display_object.set_matrix(source.matrix());
+        display_object.set_color_transform(source.color_transform());
+        display_object.set_visible(source.visible());
+    }
+    drop(source);
+    add_child(avm, context, parent, copy, new_name, depth);
+    Some(copy)
+}
+
+/// Removes the movie clip `node` from its parent.
+/// Only clips at the depths used by scripts can be removed.
+pub fn remove_movie_clip<'gc>(context: &mut ActionContext<'_, 'gc, '_>, node: DisplayNode<'gc>) {
+    let parent = match node.read().parent() {
+        Some(parent) => parent,
+        None => return,
+    };
+    let mut parent = parent.write(context.gc_context);
+    if let Some(clip) = parent.as_movie_clip_mut() {
+        let depth = clip.child_depth(node).unwrap_or(0);
+        if (AVM_DEPTH_BIAS..=AVM_DEPTH_BIAS + AVM_MAX_DEPTH).contains(&depth) {
+            clip.remove_child(context.gc_context, node);
+        } else {
+            log::warn!("removeMovieClip: Only clips created by scripts can be removed");
+        }
+    }
+}
+
+/// Runs `f` on the movie clip controlled by `this`.
+/// Calls on anything other than a movie clip object are ignored.
+fn with_movie_clip_mut<'gc>(
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: &Value<'gc>,
+    f: impl FnOnce(&mut MovieClip<'gc>),
+) {
+    let node = match this {
+        Value::Object(object) => object.read().display_node(),
+        _ => None,
+    };
+    if let Some(node) = node {
+        with_movie_clip_node_mut(context, node, f);
+    }
+}
+
+/// Runs `f` on the movie clip `node`. Nodes other than movie clips are ignored.
+fn with_movie_clip_node_mut<'gc>(
+    context: &mut ActionContext<'_, 'gc, '_>,
+    node: DisplayNode<'gc>,
+    f: impl FnOnce(&mut MovieClip<'gc>),
+) {
+    if let Some(clip) = node.write(context.gc_context).as_movie_clip_mut() {
+        f(clip);
+    }
+}
+
+/// Jumps to the frame given by a frame number or label.
+fn goto_frame<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: &Value<'gc>,
+    frame: Value<'gc>,
+    stop: bool,
+) -> Result<(), Error> {
+    // Converting the frame number may call `valueOf`, so this is done before
+    // borrowing the clip.
+    let number = match &frame {
+        Value::String(_) => None,
+        frame => Some(frame.clone().coerce_to_number(avm, context)?),
+    };
+    with_movie_clip_mut(context, this, |clip| {
+        let frame = match (frame, number) {
+            (Value::String(label), _) => clip.frame_label_to_number(&label),
+            (_, Some(number)) if number >= 1.0 => Some(number as u16),
+            _ => None,
+        };
+        if let Some(frame) = frame {
+            clip.goto_frame(frame, stop);
+        } else {
+            log::warn!("MovieClip.goto: Invalid frame");
+        }
+    });
+    Ok(())
+}
+
+/// `attachMovie(linkageName, newName, depth, initObject)`
+///
+/// Creates an instance of the library symbol exported as `linkageName` and adds it as a child.
+fn attach_movie<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let node = match movie_clip_node(&this) {
+        Some(node) => node,
+        None => return Ok(Value::Undefined),
+    };
+
+    let export_name = arg(args, 0).into_string();
+    let new_name = arg(args, 1).into_string();
+    let depth = match depth_from_avm(avm, context, arg(args, 2))? {
+        Some(depth) => depth,
+        None => return Ok(Value::Undefined),
+    };
+    let id = match context.library.character_id_by_export_name(&export_name) {
+        Some(id) => id,
+        None => {
+            log::warn!("attachMovie: No symbol is exported as {}", export_name);
+            return Ok(Value::Undefined);
+        }
+    };
+    let child =
+        match context
+            .library
+            .instantiate_display_object(id, context.gc_context, &avm.prototypes)
+        {
+            Ok(child) => child,
+            Err(e) => {
+                log::warn!("attachMovie: Unable to instantiate {}: {}", export_name, e);
+                return Ok(Value::Undefined);
+            }
+        };
+    let child_object = add_child(avm, context, node, child, &new_name, depth);
+    copy_init_object(avm, context, &child_object, arg(args, 3))?;
+    Ok(child_object)
+}
+
+/// Copies the properties of `init_object`, as passed to `attachMovie`, onto a new instance.
+fn copy_init_object<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: &Value<'gc>,
+    init_object: Value<'gc>,
+) -> Result<(), Error> {
+    if let (Value::Object(init_object), Value::Object(object)) = (init_object, object) {
+        for key in init_object.read().get_keys() {
+            let value = Object::get(init_object, &key, avm, context)?;
+            Object::set(*object, &key, value, avm, context)?;
+        }
+    }
+    Ok(())
+}
+
+/// `createEmptyMovieClip(name, depth)`
+fn create_empty_movie_clip<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let depth = depth_from_avm(avm, context, arg(args, 1))?;
+    let (node, depth) = match (movie_clip_node(&this), depth) {
+        (Some(node), Some(depth)) => (node, depth),
+        _ => return Ok(Value::Undefined),
+    };
+    let name = arg(args, 0).into_string();
+    let clip: Box<dyn DisplayObject<'gc>> = Box::new(MovieClip::new(context.gc_context));
+    let child = GcCell::allocate(context.gc_context, clip);
+    child
+        .write(context.gc_context)
+        .post_instantiation(context.gc_context, child, &avm.prototypes);
+    Ok(add_child(avm, context, node, child, &name, depth))
+}
+
+/// `duplicateMovieClip(newName, depth, initObject)`
+fn duplicate_movie_clip_method<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let depth = depth_from_avm(avm, context, arg(args, 1))?;
+    let (node, depth) = match (movie_clip_node(&this), depth) {
+        (Some(node), Some(depth)) => (node, depth),
+        _ => return Ok(Value::Undefined),
+    };
+    let new_name = arg(args, 0).into_string();
+    match duplicate_movie_clip(avm, context, node, &new_name, depth) {
+        Some(copy) => {
+            let object = copy.read().object();
+            copy_init_object(avm, context, &object, arg(args, 2))?;
+            Ok(object)
+        }
+        None => Ok(Value::Undefined),
+    }
+}
+
+fn get_depth<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let depth = movie_clip_node(&this).and_then(|node| {
+        let parent = node.read().parent()?;
+        let parent = parent.read();
+        parent.as_movie_clip()?.child_depth(node)
+    });
+    Ok(depth.map_or(Value::Undefined, |depth| {
+        f64::from(depth - AVM_DEPTH_BIAS).into()
+    }))
+}
+
+fn get_next_highest_depth<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let depth = movie_clip_node(&this).and_then(|node| {
+        let display_object = node.read();
+        Some(display_object.as_movie_clip()?.next_highest_avm_depth())
+    });
+    Ok(depth.map_or(Value::Undefined, |depth| f64::from(depth).into()))
+}
+
+fn remove_movie_clip_method<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = movie_clip_node(&this) {
+        remove_movie_clip(context, node);
+    }
+    Ok(Value::Undefined)
+}
+
+/// `hitTest(x, y, shapeFlag)` or `hitTest(target)`
+///
+/// A point is given in stage coordinates. With `shapeFlag`, the point must lie on the shapes
+/// of the clip, rather than anywhere within its bounds. A target clip, or the path to one,
+/// hits if its bounds overlap the bounds of this clip.
+fn hit_test<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let node = match movie_clip_node(&this) {
+        Some(node) => node,
+        None => return Ok(Value::Undefined),
+    };
+    if args.len() >= 2 {
+        let x = arg(args, 0).coerce_to_number(avm, context)?;
+        let y = arg(args, 1).coerce_to_number(avm, context)?;
+        let point = (Twips::from_pixels(x), Twips::from_pixels(y));
+        let shape_flag = arg(args, 2).as_bool(avm.swf_version);
+        let display_object = node.read();
+        let result = if shape_flag {
+            display_object.hit_test(point)
+        } else {
+            display_object.world_bounds().contains(point)
+        };
+        return Ok(result.into());
+    }
+
+    let target = match arg(args, 0) {
+        Value::Object(object) => object.read().display_node(),
+        Value::Undefined => None,
+        path => Avm1::resolve_slash_path(node, context.root, &path.into_string()),
+    };
+    let result = match target {
+        Some(target) => {
+            let bounds = node.read().world_bounds();
+            let target_bounds = target.read().world_bounds();
+            bounds.intersects(&target_bounds)
+        }
+        None => false,
+    };
+    Ok(result.into())
+}
+
+/// `setMask(mask)`
+///
+/// Clips this clip to `mask`, a clip or the path to one, which is no longer drawn itself.
+/// `null` removes the mask. A clip can't be masked by one of its ancestors.
+fn set_mask<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let node = match movie_clip_node(&this) {
+        Some(node) => node,
+        None => return Ok(Value::Undefined),
+    };
+    let mask = match arg(args, 0) {
+        Value::Undefined | Value::Null => None,
+        Value::Object(object) => object.read().display_node(),
+        path => Avm1::resolve_slash_path(node, context.root, &path.into_string()),
+    }
+    .filter(|mask| mask.read().as_movie_clip().is_some());
+
+    // Drawing a mask that contains the clip would draw the clip again, without end.
+    if let Some(mask) = mask {
+        let mut ancestor = node.read().parent();
+        while let Some(parent) = ancestor {
+            if parent.as_ptr() == mask.as_ptr() {
+                log::warn!("setMask: A clip can't be masked by one of its ancestors");
+                return Ok(Value::Undefined);
+            }
+            ancestor = parent.read().parent();
+        }
+    }
+
+    // A clip has at most one mask, and a mask masks at most one clip.
+    let old_mask = node.read().as_movie_clip().and_then(MovieClip::masker);
+    if let Some(old_mask) = old_mask {
+        with_movie_clip_node_mut(context, old_mask, |clip| clip.set_maskee(None));
+    }
+    if let Some(mask) = mask {
+        let old_maskee = mask.read().as_movie_clip().and_then(MovieClip::maskee);
+        if let Some(old_maskee) = old_maskee {
+            with_movie_clip_node_mut(context, old_maskee, |clip| clip.set_masker(None));
+        }
+        with_movie_clip_node_mut(context, mask, |clip| clip.set_maskee(Some(node)));
+    }
+    with_movie_clip_node_mut(context, node, |clip| clip.set_masker(mask));
+    Ok(Value::Undefined)
+}
+
+/// `startDrag(lockCenter, left, top, right, bottom)`
+///
+/// The clip is only constrained to the rectangle if all four of its sides are given.
+fn start_drag<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = movie_clip_node(&this) {
+        let lock_center = arg(args, 0).as_bool(avm.swf_version);
+        let constraint = if args.len() >= 5 {
+            let mut sides = [Twips::new(0); 4];
+            for (side, value) in sides.iter_mut().zip(&args[1..5]) {
+                *side = Twips::from_pixels(value.clone().coerce_to_number(avm, context)?);
+            }
+            Some(BoundingBox {
+                x_min: sides[0],
+                y_min: sides[1],
+                x_max: sides[2],
+                y_max: sides[3],
+                valid: true,
+            })
+        } else {
+            None
+        };
+        avm.start_drag(context, node, lock_center, constraint);
+    }
+    Ok(Value::Undefined)
+}
+
+fn stop_drag<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    avm.stop_drag();
+    Ok(Value::Undefined)
+}
+
+/// `swapDepths(target)`
+///
+/// `target` is either a depth or a clip with the same parent.
+fn swap_depths<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let node = match movie_clip_node(&this) {
+        Some(node) => node,
+        None => return Ok(Value::Undefined),
+    };
+    let parent = match node.read().parent() {
+        Some(parent) => parent,
+        None => return Ok(Value::Undefined),
+    };
+    let target = arg(args, 0);
+    let depth = match &target {
+        Value::Object(_) => None,
+        depth => depth_from_avm(avm, context, depth.clone())?,
+    };
+    let mut parent = parent.write(context.gc_context);
+    if let Some(clip) = parent.as_movie_clip_mut() {
+        let depth = match target {
+            Value::Object(object) => object
+                .read()
+                .display_node()
+                .and_then(|other| clip.child_depth(other)),
+            _ => depth,
+        };
+        if let Some(depth) = depth {
+            clip.swap_child_to_depth(node, depth);
+        } else {
+            log::warn!("swapDepths: Invalid target");
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+fn goto_and_play<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    goto_frame(avm, context, &this, arg(args, 0), false)?;
+    Ok(Value::Undefined)
+}
+
+fn goto_and_stop<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    goto_frame(avm, context, &this, arg(args, 0), true)?;
+    Ok(Value::Undefined)
+}
+
+fn next_frame<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_movie_clip_mut(context, &this, MovieClip::next_frame);
+    Ok(Value::Undefined)
+}
+
+fn play<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_movie_clip_mut(context, &this, MovieClip::play);
+    Ok(Value::Undefined)
+}
+
+fn prev_frame<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_movie_clip_mut(context, &this, MovieClip::prev_frame);
+    Ok(Value::Undefined)
+}
+
+fn stop<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_movie_clip_mut(context, &this, MovieClip::stop);
+    Ok(Value::Undefined)
+}
+
+/// Runs `f` on the drawing of the movie clip controlled by `this`.
+fn with_drawing<'gc>(
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: &Value<'gc>,
+    f: impl FnOnce(&mut Drawing),
+) {
+    with_movie_clip_mut(context, this, |clip| f(clip.drawing_mut()));
+}
+
+/// Converts a point given in pixels by a script.
+fn point_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    x: Value<'gc>,
+    y: Value<'gc>,
+) -> Result<(Twips, Twips), Error> {
+    Ok((
+        Twips::from_pixels(x.coerce_to_number(avm, context)?),
+        Twips::from_pixels(y.coerce_to_number(avm, context)?),
+    ))
+}
+
+/// Converts an RGB color and an alpha from 0 to 100, which defaults to 100.
+fn color_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    rgb: Value<'gc>,
+    alpha: Value<'gc>,
+) -> Result<Color, Error> {
+    let rgb = rgb.coerce_to_number(avm, context)?;
+    let rgb = if rgb.is_finite() {
+        rgb as i64 as u32
+    } else {
+        0
+    };
+    let alpha = match alpha {
+        Value::Undefined => 100.0,
+        alpha => alpha.coerce_to_number(avm, context)?,
+    };
+    let alpha = if alpha.is_finite() {
+        alpha.max(0.0).min(100.0)
+    } else {
+        0.0
+    };
+    Ok(Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: (alpha * 255.0 / 100.0) as u8,
+    })
+}
+
+/// `beginFill(rgb, alpha)`
+///
+/// Without a color, the following edges are not filled.
+fn begin_fill<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let fill_style = match arg(args, 0) {
+        Value::Undefined => None,
+        rgb => Some(FillStyle::Color(color_from_avm(
+            avm,
+            context,
+            rgb,
+            arg(args, 1),
+        )?)),
+    };
+    with_drawing(context, &this, |drawing| drawing.begin_fill(fill_style));
+    Ok(Value::Undefined)
+}
+
+/// `beginGradientFill(fillType, colors, alphas, ratios, matrix)`
+///
+/// `fillType` is `"linear"` or `"radial"`, and `colors`, `alphas` and `ratios` are arrays
+/// with one element for each color of the gradient.
+fn begin_gradient_fill<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let array_elements = |value: Value<'gc>| match value {
+        Value::Object(object) if object.read().is_array() => Some(object.read().array_elements()),
+        _ => None,
+    };
+    let (colors, alphas, ratios) = match (
+        array_elements(arg(args, 1)),
+        array_elements(arg(args, 2)),
+        array_elements(arg(args, 3)),
+    ) {
+        (Some(colors), Some(alphas), Some(ratios))
+            if colors.len() == alphas.len() && colors.len() == ratios.len() =>
+        {
+            (colors, alphas, ratios)
+        }
+        _ => {
+            log::warn!("beginGradientFill: Invalid colors, alphas or ratios");
+            return Ok(Value::Undefined);
+        }
+    };
+    let mut records = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.into_iter().zip(alphas).zip(ratios) {
+        records.push(GradientRecord {
+            ratio: ratio.coerce_to_number(avm, context)?.max(0.0).min(255.0) as u8,
+            color: color_from_avm(avm, context, color, alpha)?,
+        });
+    }
+    let gradient = Gradient {
+        matrix: gradient_matrix(avm, context, arg(args, 4))?,
+        spread: GradientSpread::Pad,
+        interpolation: GradientInterpolation::RGB,
+        records,
+    };
+    let fill_style = match arg(args, 0).into_string().as_str() {
+        "linear" => FillStyle::LinearGradient(gradient),
+        "radial" => FillStyle::RadialGradient(gradient),
+        fill_type => {
+            log::warn!("beginGradientFill: Invalid fill type {}", fill_type);
+            return Ok(Value::Undefined);
+        }
+    };
+    with_drawing(context, &this, |drawing| {
+        drawing.begin_fill(Some(fill_style))
+    });
+    Ok(Value::Undefined)
+}
+
+/// The width and height, in pixels, of the square that gradients are defined in.
+const GRADIENT_SIZE: f64 = 32768.0 / Twips::TWIPS_PER_PIXEL;
+
+/// Converts the matrix passed to `beginGradientFill` into the matrix of a gradient fill.
+///
+/// The matrix is either a box, `{matrixType: "box", x, y, w, h, r}`, that the gradient
+/// is stretched over and rotated by `r` radians, or a 3x3 matrix, `{a, b, ..., i}`, that
+/// transforms a gradient of size 1 centered on the origin.
+fn gradient_matrix<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    matrix: Value<'gc>,
+) -> Result<swf::Matrix, Error> {
+    let object = match matrix {
+        Value::Object(object) => object,
+        _ => return Ok(Default::default()),
+    };
+    let matrix_type = Object::get(object, "matrixType", avm, context)?;
+    let is_box = matrix_type.coerce_to_string(avm, context)? == "box";
+    let mut get = |name: &str| -> Result<f64, Error> {
+        Object::get(object, name, avm, context)?.coerce_to_number(avm, context)
+    };
+    let matrix = if is_box {
+        let (x, y) = (get("x")?, get("y")?);
+        let (width, height) = (get("w")?, get("h")?);
+        let rotation = get("r")?;
+        let (sin, cos) = (rotation.sin(), rotation.cos());
+        swf::Matrix {
+            translate_x: Twips::from_pixels(x + width / 2.0),
+            translate_y: Twips::from_pixels(y + height / 2.0),
+            scale_x: (cos * width / GRADIENT_SIZE) as f32,
+            scale_y: (cos * height / GRADIENT_SIZE) as f32,
+            rotate_skew_0: (sin * width / GRADIENT_SIZE) as f32,
+            rotate_skew_1: (-sin * height / GRADIENT_SIZE) as f32,
+        }
+    } else {
+        swf::Matrix {
+            translate_x: Twips::from_pixels(get("g")?),
+            translate_y: Twips::from_pixels(get("h")?),
+            scale_x: (get("a")? / GRADIENT_SIZE) as f32,
+            scale_y: (get("e")? / GRADIENT_SIZE) as f32,
+            rotate_skew_0: (get("b")? / GRADIENT_SIZE) as f32,
+            rotate_skew_1: (get("d")? / GRADIENT_SIZE) as f32,
+        }
+    };
+    Ok(matrix)
+}
+
+fn clear<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_drawing(context, &this, Drawing::clear);
+    Ok(Value::Undefined)
+}
+
+/// `curveTo(controlX, controlY, anchorX, anchorY)`
+fn curve_to<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let control = point_from_avm(avm, context, arg(args, 0), arg(args, 1))?;
+    let anchor = point_from_avm(avm, context, arg(args, 2), arg(args, 3))?;
+    with_drawing(context, &this, |drawing| drawing.curve_to(control, anchor));
+    Ok(Value::Undefined)
+}
+
+fn end_fill<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_drawing(context, &this, Drawing::end_fill);
+    Ok(Value::Undefined)
+}
+
+/// `lineStyle(thickness, rgb, alpha)`
+///
+/// Without a thickness, the following edges are not stroked.
+/// Thicknesses below 1 are drawn 1 pixel wide, so a thickness of 0 draws hairlines.
+fn line_style<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let line_style = match arg(args, 0) {
+        Value::Undefined => None,
+        thickness => {
+            let thickness = thickness.coerce_to_number(avm, context)?;
+            let thickness = if thickness.is_finite() {
+                thickness.max(1.0).min(255.0)
+            } else {
+                1.0
+            };
+            let color = color_from_avm(avm, context, arg(args, 1), arg(args, 2))?;
+            Some(LineStyle::new_v1(Twips::from_pixels(thickness), color))
+        }
+    };
+    with_drawing(context, &this, |drawing| drawing.set_line_style(line_style));
+    Ok(Value::Undefined)
+}
+
+/// `lineTo(x, y)`
+fn line_to<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let point = point_from_avm(avm, context, arg(args, 0), arg(args, 1))?;
+    with_drawing(context, &this, |drawing| drawing.line_to(point));
+    Ok(Value::Undefined)
+}
+
+/// `moveTo(x, y)`
+fn move_to<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let point = point_from_avm(avm, context, arg(args, 0), arg(args, 1))?;
+    with_drawing(context, &this, |drawing| drawing.move_to(point));
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,85 @@
+//! `Number` class and `Number.prototype`
+
+use crate::avm1::globals::{arg, define_method, primitive_of};
+use crate::avm1::object::{DONT_DELETE, DONT_ENUM, READ_ONLY};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = match args.get(0) {
+        Some(value) => Value::Number(value.clone().coerce_to_number(avm, context)?),
+        None => Value::Number(0.0),
+    };
+    if let Value::Object(this) = this {
+        this.write(context.gc_context).set_primitive(value.clone());
+    }
+    Ok(value)
+}
+
+pub fn fill_constructor<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constructor: GcCell<'gc, Object<'gc>>,
+) {
+    let attributes = DONT_ENUM | DONT_DELETE | READ_ONLY;
+    let mut constructor = constructor.write(gc_context);
+    constructor.force_set("MAX_VALUE", std::f64::MAX, attributes);
+    constructor.force_set("MIN_VALUE", f64::from_bits(1), attributes);
+    constructor.force_set("NaN", std::f64::NAN, attributes);
+    constructor.force_set("NEGATIVE_INFINITY", std::f64::NEG_INFINITY, attributes);
+    constructor.force_set("POSITIVE_INFINITY", std::f64::INFINITY, attributes);
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    define_method(gc_context, proto, "toString", to_string, function_proto);
+    define_method(gc_context, proto, "valueOf", value_of, function_proto);
+}
+
+/// Implements `Number.prototype.toString(radix)`.
+fn to_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = primitive_of(&this).into_number();
+    let radix = match arg(args, 0) {
+        Value::Undefined => 10,
+        radix => radix.coerce_to_number(avm, context)? as u32,
+    };
+    if radix == 10 || !(2..=36).contains(&radix) || !value.is_finite() {
+        return Ok(Value::Number(value).into_string().into());
+    }
+
+    // Other radixes only print the integer part of the number.
+    let mut n = value.trunc().abs() as u64;
+    let mut digits = vec![];
+    loop {
+        digits.push(std::char::from_digit((n % u64::from(radix)) as u32, radix).unwrap());
+        n /= u64::from(radix);
+        if n == 0 {
+            break;
+        }
+    }
+    if value < 0.0 {
+        digits.push('-');
+    }
+    Ok(digits.into_iter().rev().collect::<String>().into())
+}
+
+fn value_of<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this).into_number().into())
+}
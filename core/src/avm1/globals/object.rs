@@ -0,0 +1,116 @@
+//! `Object` class and `Object.prototype`
+
+use crate::avm1::globals::{arg, define_method, primitive_of};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match arg(args, 0) {
+        value @ Value::Object(_) => Ok(value),
+        _ => match this {
+            Value::Object(_) => Ok(this),
+            _ => Ok(Object::object(context.gc_context, Some(avm.prototypes.object)).into()),
+        },
+    }
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    define_method(
+        gc_context,
+        proto,
+        "hasOwnProperty",
+        has_own_property,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        proto,
+        "isPropertyEnumerable",
+        is_property_enumerable,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        proto,
+        "isPrototypeOf",
+        is_prototype_of,
+        function_proto,
+    );
+    define_method(gc_context, proto, "toString", to_string, function_proto);
+    define_method(gc_context, proto, "valueOf", value_of, function_proto);
+}
+
+fn has_own_property<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let result = match this {
+        Value::Object(this) => this.read().has_own_property(&arg(args, 0).into_string()),
+        _ => false,
+    };
+    Ok(result.into())
+}
+
+fn is_property_enumerable<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let result = match this {
+        Value::Object(this) => {
+            let name = arg(args, 0).into_string();
+            let this = this.read();
+            this.has_own_property(&name) && this.get_keys().contains(&name)
+        }
+        _ => false,
+    };
+    Ok(result.into())
+}
+
+fn is_prototype_of<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let (Value::Object(this), Value::Object(object)) = (this, arg(args, 0)) {
+        let mut proto = object.read().prototype();
+        while let Some(p) = proto {
+            if GcCell::as_ptr(p) == GcCell::as_ptr(this) {
+                return Ok(true.into());
+            }
+            proto = p.read().prototype();
+        }
+    }
+    Ok(false.into())
+}
+
+fn to_string<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok("[object Object]".into())
+}
+
+fn value_of<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this))
+}
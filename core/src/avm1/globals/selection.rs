@@ -0,0 +1,156 @@
+//! `Selection` object
+
+use crate::avm1::display_properties::dot_path;
+use crate::avm1::globals::{arg, broadcaster, define_method};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::edit_text::TextSelection;
+use gc_arena::{GcCell, MutationContext};
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    object_proto: GcCell<'gc, Object<'gc>>,
+    array_proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) -> GcCell<'gc, Object<'gc>> {
+    let selection = Object::object(gc_context, Some(object_proto));
+    define_method(
+        gc_context,
+        selection,
+        "getBeginIndex",
+        get_begin_index,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        selection,
+        "getCaretIndex",
+        get_caret_index,
+        function_proto,
+    );
+    define_method(
+        gc_context,
+        selection,
+        "getEndIndex",
+        get_end_index,
+        function_proto,
+    );
+    define_method(gc_context, selection, "getFocus", get_focus, function_proto);
+    define_method(gc_context, selection, "setFocus", set_focus, function_proto);
+    define_method(
+        gc_context,
+        selection,
+        "setSelection",
+        set_selection,
+        function_proto,
+    );
+    broadcaster::initialize(gc_context, selection, array_proto, function_proto);
+    selection
+}
+
+/// Returns the selection of the focused text field in UTF-16 code units, if any.
+fn focused_selection(avm: &Avm1<'_>) -> Option<TextSelection> {
+    let focus = avm.focus()?;
+    let display_object = focus.read();
+    display_object.as_edit_text()?.utf16_selection()
+}
+
+/// Returns the index of the first selected character, or -1 if no text field has the focus.
+fn get_begin_index<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(focused_selection(avm)
+        .map_or(-1.0, |selection| selection.start() as f64)
+        .into())
+}
+
+/// Returns the index of the caret, or -1 if no text field has the focus.
+fn get_caret_index<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(focused_selection(avm)
+        .map_or(-1.0, |selection| selection.caret() as f64)
+        .into())
+}
+
+/// Returns the index after the last selected character, or -1 if no text field has the focus.
+fn get_end_index<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(focused_selection(avm)
+        .map_or(-1.0, |selection| selection.end() as f64)
+        .into())
+}
+
+/// Returns the path of the focused text field, such as `_level0.name`, or null.
+fn get_focus<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(avm
+        .focus()
+        .map_or(Value::Null, |focus| dot_path(focus).into()))
+}
+
+/// Focuses the text field given either as an object or as a path, or removes the focus
+/// when given null. Returns whether the focus was set.
+fn set_focus<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let focus = match arg(args, 0) {
+        Value::Undefined | Value::Null => {
+            avm.set_focus(context, None);
+            return Ok(true.into());
+        }
+        Value::Object(object) => object.read().display_node(),
+        value => {
+            let path = value.coerce_to_string(avm, context)?;
+            Avm1::resolve_slash_path(context.active_clip, context.root, &path)
+        }
+    };
+    // TODO: Buttons and movie clips can take the focus too, for keyboard navigation.
+    let focus = focus.filter(|node| node.read().as_edit_text().is_some());
+    if focus.is_some() {
+        avm.set_focus(context, focus);
+    }
+    Ok(focus.is_some().into())
+}
+
+/// Selects the characters from the first index up to the second in the focused text field.
+fn set_selection<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let focus = match avm.focus() {
+        Some(focus) => focus,
+        None => return Ok(Value::Undefined),
+    };
+    let begin = arg(args, 0).coerce_to_number(avm, context)?;
+    let end = arg(args, 1).coerce_to_number(avm, context)?;
+    let to_index = |index: f64| {
+        if index.is_nan() {
+            0
+        } else {
+            index.max(0.0) as usize
+        }
+    };
+    if let Some(edit_text) = focus.write(context.gc_context).as_edit_text_mut() {
+        edit_text.set_utf16_selection(TextSelection::new(to_index(begin), to_index(end)));
+    }
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,331 @@
+//! `String` class and `String.prototype`
+//!
+//! Flash strings are UTF-16, so all indices and lengths are measured in UTF-16 code units.
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::{arg, define_method, primitive_of};
+use crate::avm1::object::{DONT_DELETE, DONT_ENUM, READ_ONLY};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use gc_arena::{GcCell, MutationContext};
+
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = match args.get(0) {
+        Some(value) => value.clone().into_string(),
+        None => String::new(),
+    };
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        this.force_set(
+            "length",
+            utf16_len(&value) as f64,
+            DONT_ENUM | DONT_DELETE | READ_ONLY,
+        );
+        this.set_primitive(value.clone().into());
+    }
+    Ok(value.into())
+}
+
+pub fn fill_constructor<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constructor: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    define_method(
+        gc_context,
+        constructor,
+        "fromCharCode",
+        from_char_code,
+        function_proto,
+    );
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("charAt", char_at),
+        ("charCodeAt", char_code_at),
+        ("concat", concat),
+        ("indexOf", index_of),
+        ("lastIndexOf", last_index_of),
+        ("slice", slice),
+        ("split", split),
+        ("substr", substr),
+        ("substring", substring),
+        ("toLowerCase", to_lower_case),
+        ("toString", to_string),
+        ("toUpperCase", to_upper_case),
+        ("valueOf", to_string),
+    ];
+    for (name, method) in methods {
+        define_method(gc_context, proto, name, *method, function_proto);
+    }
+}
+
+/// Returns the length of `s` in UTF-16 code units.
+pub fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+/// Returns the string value of `this` as UTF-16 code units.
+fn this_units(this: &Value<'_>) -> Vec<u16> {
+    primitive_of(this).into_string().encode_utf16().collect()
+}
+
+fn from_units<'gc>(units: &[u16]) -> Value<'gc> {
+    String::from_utf16_lossy(units).into()
+}
+
+/// Converts an argument to an integer as `ToInteger` does, treating NaN as 0.
+fn to_integer<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<i64, Error> {
+    let n = value.coerce_to_number(avm, context)?;
+    if n.is_nan() {
+        Ok(0)
+    } else {
+        Ok(n as i64)
+    }
+}
+
+/// Resolves a possibly negative `slice` index relative to a string of length `len`.
+fn relative_index(index: i64, len: usize) -> usize {
+    if index < 0 {
+        (len as i64 + index).max(0) as usize
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` at or after `start`.
+fn find(haystack: &[u16], needle: &[u16], start: usize) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    (start..=haystack.len() - needle.len()).find(|&i| haystack[i..].starts_with(needle))
+}
+
+fn from_char_code<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut units = Vec::with_capacity(args.len());
+    for arg in args {
+        units.push(to_integer(avm, context, arg.clone())? as u16);
+    }
+    Ok(from_units(&units))
+}
+
+fn char_at<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let index = to_integer(avm, context, arg(args, 0))?;
+    if index >= 0 && (index as usize) < units.len() {
+        let index = index as usize;
+        Ok(from_units(&units[index..=index]))
+    } else {
+        Ok("".into())
+    }
+}
+
+fn char_code_at<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let index = to_integer(avm, context, arg(args, 0))?;
+    if index >= 0 && (index as usize) < units.len() {
+        Ok(f64::from(units[index as usize]).into())
+    } else {
+        Ok(std::f64::NAN.into())
+    }
+}
+
+fn concat<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut result = primitive_of(&this).into_string();
+    for arg in args {
+        result.push_str(&arg.clone().into_string());
+    }
+    Ok(result.into())
+}
+
+fn index_of<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let needle: Vec<u16> = arg(args, 0).into_string().encode_utf16().collect();
+    let start = relative_index(to_integer(avm, context, arg(args, 1))?.max(0), units.len());
+    let result = find(&units, &needle, start).map_or(-1.0, |i| i as f64);
+    Ok(result.into())
+}
+
+fn last_index_of<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let needle: Vec<u16> = arg(args, 0).into_string().encode_utf16().collect();
+    if needle.len() > units.len() {
+        return Ok((-1.0).into());
+    }
+    let last_start = units.len() - needle.len();
+    let start = match arg(args, 1) {
+        Value::Undefined => last_start,
+        start => {
+            let start = to_integer(avm, context, start)?;
+            if start < 0 {
+                return Ok((-1.0).into());
+            }
+            (start as usize).min(last_start)
+        }
+    };
+    let result = (0..=start)
+        .rev()
+        .find(|&i| units[i..].starts_with(&needle))
+        .map_or(-1.0, |i| i as f64);
+    Ok(result.into())
+}
+
+fn slice<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let start = relative_index(to_integer(avm, context, arg(args, 0))?, units.len());
+    let end = match arg(args, 1) {
+        Value::Undefined => units.len(),
+        end => relative_index(to_integer(avm, context, end)?, units.len()),
+    };
+    if start < end {
+        Ok(from_units(&units[start..end]))
+    } else {
+        Ok("".into())
+    }
+}
+
+fn split<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let string = primitive_of(&this).into_string();
+    let limit = match arg(args, 1) {
+        Value::Undefined => std::usize::MAX,
+        limit => to_integer(avm, context, limit)?.max(0) as usize,
+    };
+    let elements: Vec<Value<'gc>> = match arg(args, 0) {
+        Value::Undefined => vec![string.into()],
+        delimiter => {
+            let delimiter = delimiter.into_string();
+            if delimiter.is_empty() {
+                // An empty delimiter splits the string into individual characters.
+                string
+                    .encode_utf16()
+                    .take(limit)
+                    .map(|unit| from_units(&[unit]))
+                    .collect()
+            } else {
+                string
+                    .split(delimiter.as_str())
+                    .take(limit)
+                    .map(Value::from)
+                    .collect()
+            }
+        }
+    };
+    Ok(Object::array(context.gc_context, Some(avm.prototypes.array), elements).into())
+}
+
+fn substr<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let start = relative_index(to_integer(avm, context, arg(args, 0))?, units.len());
+    let len = match arg(args, 1) {
+        Value::Undefined => units.len(),
+        len => to_integer(avm, context, len)?.max(0) as usize,
+    };
+    let end = start.saturating_add(len).min(units.len());
+    Ok(from_units(&units[start..end]))
+}
+
+fn substring<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let units = this_units(&this);
+    let start = relative_index(to_integer(avm, context, arg(args, 0))?.max(0), units.len());
+    let end = match arg(args, 1) {
+        Value::Undefined => units.len(),
+        end => relative_index(to_integer(avm, context, end)?.max(0), units.len()),
+    };
+    // The arguments are swapped if they are given in reverse order.
+    let (start, end) = if start <= end {
+        (start, end)
+    } else {
+        (end, start)
+    };
+    Ok(from_units(&units[start..end]))
+}
+
+fn to_lower_case<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this).into_string().to_lowercase().into())
+}
+
+fn to_string<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this).into_string().into())
+}
+
+fn to_upper_case<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(primitive_of(&this).into_string().to_uppercase().into())
+}
@@ -0,0 +1,151 @@
+//! `TextField` class and `TextField.prototype`
+
+use crate::avm1::function::NativeFunction;
+use crate::avm1::globals::define_method;
+use crate::avm1::globals::text_format::{text_format_from_avm, text_format_to_avm};
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::edit_text::char_index;
+use crate::prelude::*;
+use gc_arena::{GcCell, MutationContext};
+
+/// Text fields are only created by the movie, so the constructor does nothing.
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+pub fn fill_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: GcCell<'gc, Object<'gc>>,
+    function_proto: GcCell<'gc, Object<'gc>>,
+) {
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("getNewTextFormat", get_new_text_format),
+        ("getTextFormat", get_text_format),
+        ("setNewTextFormat", set_new_text_format),
+        ("setTextFormat", set_text_format),
+    ];
+    for (name, method) in methods {
+        define_method(gc_context, proto, name, *method, function_proto);
+    }
+}
+
+/// Returns the node of the text field controlled by `this`.
+fn text_field_node<'gc>(this: &Value<'gc>) -> Option<DisplayNode<'gc>> {
+    let node = match this {
+        Value::Object(object) => object.read().display_node(),
+        _ => None,
+    }?;
+    if node.read().as_edit_text().is_some() {
+        Some(node)
+    } else {
+        None
+    }
+}
+
+/// Converts the `beginIndex` and `endIndex` arguments. Invalid indices are `None`.
+///
+/// This may call `valueOf`, so it is done before borrowing the text field.
+fn indices_from_args<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(Option<usize>, Option<usize>), Error> {
+    let mut indices = [None, None];
+    for (index, value) in indices.iter_mut().zip(args) {
+        let number = value.clone().coerce_to_number(avm, context)?;
+        if number.is_finite() && number >= 0.0 {
+            *index = Some(number as usize);
+        }
+    }
+    Ok((indices[0], indices[1]))
+}
+
+/// Converts the `beginIndex` and `endIndex` arguments, in UTF-16 code units, into a range
+/// of characters in `text`. With only a begin index, the range holds the character at that
+/// index, and without a valid begin index, the range holds all of the text.
+fn range_from_indices(indices: (Option<usize>, Option<usize>), text: &str) -> (usize, usize) {
+    match indices {
+        (Some(start), Some(end)) => (char_index(text, start), char_index(text, end)),
+        (Some(start), None) => (char_index(text, start), char_index(text, start + 1)),
+        (None, _) => (0, text.chars().count()),
+    }
+}
+
+/// `TextField.getTextFormat([beginIndex, [endIndex]])` returns the format shared by
+/// a range of the text, with null for the properties that differ.
+fn get_text_format<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let indices = indices_from_args(avm, context, args)?;
+    let format = match text_field_node(&this) {
+        Some(node) => {
+            let display_object = node.read();
+            let edit_text = display_object.as_edit_text().unwrap();
+            let (start, end) = range_from_indices(indices, edit_text.text());
+            edit_text.text_format(start, end)
+        }
+        None => return Ok(Value::Undefined),
+    };
+    Ok(text_format_to_avm(avm, context.gc_context, &format).into())
+}
+
+/// `TextField.setTextFormat([beginIndex, [endIndex,]] textFormat)` applies the properties
+/// of `textFormat` that aren't null to a range of the text.
+fn set_text_format<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (node, format, indices) = match (text_field_node(&this), args.split_last()) {
+        (Some(node), Some((Value::Object(format), indices))) => (node, *format, indices),
+        _ => return Ok(Value::Undefined),
+    };
+    // Reading the format may run scripts, so the text field is only borrowed afterwards.
+    let format = text_format_from_avm(avm, context, format)?;
+    let indices = indices_from_args(avm, context, indices)?;
+    let mut display_object = node.write(context.gc_context);
+    if let Some(edit_text) = display_object.as_edit_text_mut() {
+        let (start, end) = range_from_indices(indices, edit_text.text());
+        edit_text.set_text_format(start, end, &format, context.library);
+    }
+    Ok(Value::Undefined)
+}
+
+/// `TextField.getNewTextFormat()` returns the format given to new text.
+fn get_new_text_format<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let format = match text_field_node(&this) {
+        Some(node) => node.read().as_edit_text().unwrap().new_text_format(),
+        None => return Ok(Value::Undefined),
+    };
+    Ok(text_format_to_avm(avm, context.gc_context, &format).into())
+}
+
+/// `TextField.setNewTextFormat(textFormat)` sets the format given to new text.
+fn set_new_text_format<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let (Some(node), Some(Value::Object(format))) = (text_field_node(&this), args.get(0)) {
+        let format = text_format_from_avm(avm, context, *format)?;
+        if let Some(edit_text) = node.write(context.gc_context).as_edit_text_mut() {
+            edit_text.set_new_text_format(&format);
+        }
+    }
+    Ok(Value::Undefined)
+}
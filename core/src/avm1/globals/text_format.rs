@@ -0,0 +1,207 @@
+//! `TextFormat` class
+//!
+//! Text formats are plain objects whose properties are read when they are given to a
+//! text field. Properties that are null are left unchanged when a format is applied.
+
+use crate::avm1::display_properties::{color_from_avm, color_to_avm};
+use crate::avm1::globals::arg;
+use crate::avm1::{ActionContext, Avm1, Error, Object, Value};
+use crate::prelude::*;
+use crate::text_format::TextFormat;
+use gc_arena::{GcCell, MutationContext};
+use swf::TextAlign;
+
+/// The properties of a text format, starting with those set by the arguments
+/// of the constructor, in order.
+const PROPERTY_NAMES: [&str; 16] = [
+    "font",
+    "size",
+    "color",
+    "bold",
+    "italic",
+    "underline",
+    "url",
+    "target",
+    "align",
+    "leftMargin",
+    "rightMargin",
+    "indent",
+    "leading",
+    "blockIndent",
+    "bullet",
+    "tabStops",
+];
+
+/// The number of properties that the constructor takes as arguments.
+const NUM_CONSTRUCTOR_ARGS: usize = 13;
+
+/// `new TextFormat(font, size, color, bold, italic, underline, url, target, align,
+/// leftMargin, rightMargin, indent, leading)`
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Value::Object(this) = this {
+        let mut this = this.write(context.gc_context);
+        for (i, name) in PROPERTY_NAMES.iter().enumerate() {
+            let value = match arg(args, i) {
+                _ if i >= NUM_CONSTRUCTOR_ARGS => Value::Null,
+                Value::Undefined => Value::Null,
+                value => value,
+            };
+            this.force_set(name, value, 0);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Creates a `TextFormat` object with the fields of `format`, and null for any unset fields.
+pub fn text_format_to_avm<'gc>(
+    avm: &Avm1<'gc>,
+    gc_context: MutationContext<'gc, '_>,
+    format: &TextFormat,
+) -> GcCell<'gc, Object<'gc>> {
+    let array_proto = avm.prototypes().array;
+    let string = |value: &Option<String>| value.as_ref().map_or(Value::Null, |s| s.as_str().into());
+    let boolean = |value: Option<bool>| value.map_or(Value::Null, Value::Bool);
+    let pixels = |value: Option<Twips>| value.map_or(Value::Null, |t| t.to_pixels().into());
+    let values = vec![
+        string(&format.font),
+        pixels(format.size),
+        format.color.as_ref().map_or(Value::Null, color_to_avm),
+        boolean(format.bold),
+        boolean(format.italic),
+        boolean(format.underline),
+        string(&format.url),
+        string(&format.target),
+        format.align.map_or(Value::Null, align_to_avm),
+        pixels(format.left_margin),
+        pixels(format.right_margin),
+        pixels(format.indent),
+        pixels(format.leading),
+        pixels(format.block_indent),
+        boolean(format.bullet),
+        format.tab_stops.as_ref().map_or(Value::Null, |tab_stops| {
+            let tab_stops = tab_stops.iter().map(|t| t.to_pixels().into()).collect();
+            Object::array(gc_context, Some(array_proto), tab_stops).into()
+        }),
+    ];
+
+    let object = Object::object(gc_context, Some(avm.prototypes().text_format));
+    {
+        let mut object = object.write(gc_context);
+        for (name, value) in PROPERTY_NAMES.iter().zip(values) {
+            object.force_set(name, value, 0);
+        }
+    }
+    object
+}
+
+/// Reads the properties of a `TextFormat` object, or of any object with the same
+/// properties. Null, undefined and invalid properties give unset fields.
+pub fn text_format_from_avm<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+) -> Result<TextFormat, Error> {
+    let swf_version = avm.swf_version;
+    let boolean = |value: Option<Value<'gc>>| value.map(|value| value.as_bool(swf_version));
+    let pixels = |number: Option<f64>| number.and_then(twips_from_pixels);
+    Ok(TextFormat {
+        font: get_string(avm, context, object, "font")?,
+        size: pixels(get_number(avm, context, object, "size")?),
+        color: get_number(avm, context, object, "color")?.and_then(color_from_avm),
+        bold: boolean(get_property(avm, context, object, "bold")?),
+        italic: boolean(get_property(avm, context, object, "italic")?),
+        underline: boolean(get_property(avm, context, object, "underline")?),
+        url: get_string(avm, context, object, "url")?,
+        target: get_string(avm, context, object, "target")?,
+        align: get_string(avm, context, object, "align")?.and_then(|align| align_from_avm(&align)),
+        left_margin: pixels(get_number(avm, context, object, "leftMargin")?),
+        right_margin: pixels(get_number(avm, context, object, "rightMargin")?),
+        indent: pixels(get_number(avm, context, object, "indent")?),
+        leading: pixels(get_number(avm, context, object, "leading")?),
+        block_indent: pixels(get_number(avm, context, object, "blockIndent")?),
+        bullet: boolean(get_property(avm, context, object, "bullet")?),
+        tab_stops: match get_property(avm, context, object, "tabStops")? {
+            Some(Value::Object(tab_stops)) if tab_stops.read().is_array() => {
+                let elements = tab_stops.read().array_elements();
+                let mut tab_stops = Vec::with_capacity(elements.len());
+                for element in elements {
+                    let number = element.coerce_to_number(avm, context)?;
+                    tab_stops.extend(twips_from_pixels(number));
+                }
+                Some(tab_stops)
+            }
+            _ => None,
+        },
+    })
+}
+
+/// Reads a property of a text format object. Null and undefined give `None`.
+fn get_property<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+    name: &str,
+) -> Result<Option<Value<'gc>>, Error> {
+    match Object::get(object, name, avm, context)? {
+        Value::Undefined | Value::Null => Ok(None),
+        value => Ok(Some(value)),
+    }
+}
+
+fn get_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+    name: &str,
+) -> Result<Option<String>, Error> {
+    match get_property(avm, context, object, name)? {
+        Some(value) => Ok(Some(value.coerce_to_string(avm, context)?)),
+        None => Ok(None),
+    }
+}
+
+fn get_number<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    object: GcCell<'gc, Object<'gc>>,
+    name: &str,
+) -> Result<Option<f64>, Error> {
+    match get_property(avm, context, object, name)? {
+        Some(value) => Ok(Some(value.coerce_to_number(avm, context)?)),
+        None => Ok(None),
+    }
+}
+
+/// Converts a number of pixels. Returns `None` for invalid numbers.
+fn twips_from_pixels(pixels: f64) -> Option<Twips> {
+    if pixels.is_finite() {
+        Some(Twips::from_pixels(pixels))
+    } else {
+        None
+    }
+}
+
+fn align_to_avm<'gc>(align: TextAlign) -> Value<'gc> {
+    match align {
+        TextAlign::Left => "left",
+        TextAlign::Center => "center",
+        TextAlign::Right => "right",
+        TextAlign::Justify => "justify",
+    }
+    .into()
+}
+
+fn align_from_avm(align: &str) -> Option<TextAlign> {
+    match align {
+        "left" => Some(TextAlign::Left),
+        "center" => Some(TextAlign::Center),
+        "right" => Some(TextAlign::Right),
+        "justify" => Some(TextAlign::Justify),
+        _ => None,
+    }
+}
@@ -0,0 +1,77 @@
+//! `setInterval`, `setTimeout` and `clearInterval`
+
+use crate::avm1::globals::arg;
+use crate::avm1::timer::TimerCallback;
+use crate::avm1::{ActionContext, Avm1, Error, Value};
+
+pub fn set_interval<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_timer(avm, context, args, false)
+}
+
+pub fn set_timeout<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_timer(avm, context, args, true)
+}
+
+/// Removes a timer created by either `setInterval` or `setTimeout`.
+pub fn clear_interval<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let id = arg(args, 0).coerce_to_number(avm, context)?;
+    if !id.is_nan() && !avm.timers.remove(id as i32) {
+        log::info!("clearInterval: No timer with ID {}", id);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Registers a timer from either `(function, interval, ...args)`
+/// or `(object, methodName, interval, ...args)`, and returns its ID.
+fn create_timer<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut ActionContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    is_timeout: bool,
+) -> Result<Value<'gc>, Error> {
+    let (callback, args) = match arg(args, 0) {
+        Value::Object(object) if object.read().as_executable().is_some() => {
+            (TimerCallback::Function(Value::Object(object)), &args[1..])
+        }
+        Value::Object(object) if args.len() >= 2 => {
+            let method_name = args[1].clone().coerce_to_string(avm, context)?;
+            let callback = TimerCallback::Method {
+                this: object,
+                method_name,
+            };
+            (callback, &args[2..])
+        }
+        _ => {
+            log::warn!("setInterval: Expected a function or an object and method name");
+            return Ok(Value::Undefined);
+        }
+    };
+    let interval = arg(args, 0).coerce_to_number(avm, context)?;
+    // Negative and NaN intervals fire on every tick.
+    let interval = if interval > 0.0 { interval as u64 } else { 0 };
+    let timer_args = args.get(1..).unwrap_or_default().to_vec();
+    let id = avm.timers.add(
+        callback,
+        timer_args,
+        context.active_clip,
+        context.global_time,
+        interval,
+        is_timeout,
+    );
+    Ok(f64::from(id).into())
+}
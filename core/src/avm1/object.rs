@@ -0,0 +1,458 @@
+use crate::avm1::display_properties;
+use crate::avm1::function::Executable;
+use crate::avm1::{ActionContext, Avm1, Error, Value};
+use crate::prelude::*;
+use gc_arena::{GcCell, MutationContext};
+use indexmap::IndexMap;
+
+pub const TYPE_OF_OBJECT: &str = "object";
+pub const TYPE_OF_FUNCTION: &str = "function";
+
+/// The property is skipped by `for..in` enumeration.
+pub const DONT_ENUM: u8 = 1 << 0;
+/// The property cannot be removed with `delete`.
+pub const DONT_DELETE: u8 = 1 << 1;
+/// Writes to the property are silently ignored.
+pub const READ_ONLY: u8 = 1 << 2;
+
+/// The largest `length` of an array. Higher indices are stored as ordinary properties,
+/// so that a single assignment can't make the array methods visit billions of elements.
+const MAX_ARRAY_LENGTH: usize = 1 << 20;
+
+#[derive(Clone)]
+pub struct Property<'gc> {
+    value: Value<'gc>,
+    attributes: u8,
+}
+
+impl<'gc> Property<'gc> {
+    pub fn is_enumerable(&self) -> bool {
+        self.attributes & DONT_ENUM == 0
+    }
+
+    pub fn can_delete(&self) -> bool {
+        self.attributes & DONT_DELETE == 0
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Property<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.value.trace(cc);
+    }
+}
+
+/// An ActionScript object.
+///
+/// Objects are a bag of named properties, with any missing properties
+/// looked up through the `__proto__` chain.
+#[derive(Clone)]
+pub struct Object<'gc> {
+    prototype: Option<GcCell<'gc, Object<'gc>>>,
+    /// Properties in the order they were first defined, which `for..in` follows.
+    values: IndexMap<String, Property<'gc>>,
+    function: Option<Executable<'gc>>,
+    type_of: &'static str,
+
+    /// The interface constructors declared with `implements` on this prototype.
+    interfaces: Vec<GcCell<'gc, Object<'gc>>>,
+
+    /// For `super` objects, the `this` that calls made through `super` are bound to.
+    super_this: Option<Value<'gc>>,
+
+    /// For `String`, `Number` and `Boolean` objects, the primitive value that they wrap.
+    primitive: Option<Value<'gc>>,
+
+    /// Arrays keep their `length` property in sync with their elements.
+    is_array: bool,
+
+    /// For the objects of movie clips, the display object that this object controls.
+    display_node: Option<DisplayNode<'gc>>,
+}
+
+impl<'gc> Object<'gc> {
+    pub fn bare_object() -> Self {
+        Self {
+            prototype: None,
+            values: IndexMap::new(),
+            function: None,
+            type_of: TYPE_OF_OBJECT,
+            interfaces: vec![],
+            super_this: None,
+            primitive: None,
+            is_array: false,
+            display_node: None,
+        }
+    }
+
+    /// Allocates a new object with the given prototype.
+    pub fn object(
+        gc_context: MutationContext<'gc, '_>,
+        prototype: Option<GcCell<'gc, Object<'gc>>>,
+    ) -> GcCell<'gc, Object<'gc>> {
+        let mut object = Self::bare_object();
+        object.prototype = prototype;
+        GcCell::allocate(gc_context, object)
+    }
+
+    /// Allocates a new array with the given elements.
+    pub fn array(
+        gc_context: MutationContext<'gc, '_>,
+        prototype: Option<GcCell<'gc, Object<'gc>>>,
+        elements: Vec<Value<'gc>>,
+    ) -> GcCell<'gc, Object<'gc>> {
+        let mut object = Self::bare_object();
+        object.prototype = prototype;
+        object.is_array = true;
+        object.set_array_elements(elements);
+        GcCell::allocate(gc_context, object)
+    }
+
+    /// Allocates the object that scripts use to control a display object.
+    pub fn display_object(
+        gc_context: MutationContext<'gc, '_>,
+        prototype: Option<GcCell<'gc, Object<'gc>>>,
+        display_node: DisplayNode<'gc>,
+    ) -> GcCell<'gc, Object<'gc>> {
+        let mut object = Self::bare_object();
+        object.prototype = prototype;
+        object.display_node = Some(display_node);
+        GcCell::allocate(gc_context, object)
+    }
+
+    /// Allocates a new function object that runs `function` when called.
+    pub fn function(
+        gc_context: MutationContext<'gc, '_>,
+        function: Executable<'gc>,
+        prototype: Option<GcCell<'gc, Object<'gc>>>,
+    ) -> GcCell<'gc, Object<'gc>> {
+        let mut object = Self::bare_object();
+        object.prototype = prototype;
+        object.function = Some(function);
+        object.type_of = TYPE_OF_FUNCTION;
+        GcCell::allocate(gc_context, object)
+    }
+
+    /// Allocates the `super` object seen by a method that was found on `base_proto`.
+    ///
+    /// Members of `super` are looked up on the prototype above `base_proto`, and calling
+    /// `super` runs the superclass constructor. Either way, `this` is kept bound.
+    pub fn super_object(
+        gc_context: MutationContext<'gc, '_>,
+        this: Value<'gc>,
+        base_proto: GcCell<'gc, Object<'gc>>,
+    ) -> GcCell<'gc, Object<'gc>> {
+        let base_proto = base_proto.read();
+        let mut object = Self::bare_object();
+        object.prototype = base_proto.prototype;
+        if let Some(constructor) = base_proto.get_local("__constructor__") {
+            object.force_set("__constructor__", constructor.clone(), DONT_ENUM);
+        }
+        object.super_this = Some(this);
+        GcCell::allocate(gc_context, object)
+    }
+
+    /// Returns the object in the prototype chain of `object` that defines `name`.
+    pub fn property_owner(
+        object: GcCell<'gc, Object<'gc>>,
+        name: &str,
+    ) -> Option<GcCell<'gc, Object<'gc>>> {
+        if object.read().has_own_property(name) {
+            Some(object)
+        } else {
+            let prototype = object.read().prototype;
+            prototype.and_then(|prototype| Self::property_owner(prototype, name))
+        }
+    }
+
+    /// Returns the code to run if this object is called as a function.
+    pub fn as_executable(&self) -> Option<Executable<'gc>> {
+        self.function.clone()
+    }
+
+    pub fn prototype(&self) -> Option<GcCell<'gc, Object<'gc>>> {
+        self.prototype
+    }
+
+    pub fn set_prototype(&mut self, prototype: Option<GcCell<'gc, Object<'gc>>>) {
+        self.prototype = prototype;
+    }
+
+    pub fn type_of(&self) -> &'static str {
+        self.type_of
+    }
+
+    pub fn interfaces(&self) -> &[GcCell<'gc, Object<'gc>>] {
+        &self.interfaces
+    }
+
+    pub fn set_interfaces(&mut self, interfaces: Vec<GcCell<'gc, Object<'gc>>>) {
+        self.interfaces = interfaces;
+    }
+
+    /// Returns the value of `this` that a `super` object is bound to,
+    /// or `None` if this is not a `super` object.
+    pub fn super_this(&self) -> Option<&Value<'gc>> {
+        self.super_this.as_ref()
+    }
+
+    /// Returns the primitive value wrapped by a `String`, `Number` or `Boolean` object.
+    pub fn primitive(&self) -> Option<&Value<'gc>> {
+        self.primitive.as_ref()
+    }
+
+    pub fn set_primitive(&mut self, value: Value<'gc>) {
+        self.primitive = Some(value);
+    }
+
+    /// Returns the display object controlled by this object, if any.
+    pub fn display_node(&self) -> Option<DisplayNode<'gc>> {
+        self.display_node
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.is_array
+    }
+
+    /// Returns the value of the `length` property of this array.
+    pub fn array_length(&self) -> usize {
+        match self.get_local("length") {
+            Some(Value::Number(length)) if *length > 0.0 => *length as usize,
+            _ => 0,
+        }
+    }
+
+    /// Returns the elements of this array, ignoring any elements inherited from prototypes.
+    pub fn array_elements(&self) -> Vec<Value<'gc>> {
+        (0..self.array_length())
+            .map(|i| {
+                self.get_local(&i.to_string())
+                    .cloned()
+                    .unwrap_or(Value::Undefined)
+            })
+            .collect()
+    }
+
+    /// Replaces the contents of this array with `elements`.
+    pub fn set_array_elements(&mut self, elements: Vec<Value<'gc>>) {
+        self.set_array_length(0);
+        let length = elements.len();
+        for (i, element) in elements.into_iter().enumerate() {
+            self.force_set(&i.to_string(), element, 0);
+        }
+        self.force_set("length", length as f64, DONT_ENUM);
+    }
+
+    /// Sets the `length` of this array, removing any elements past the new end.
+    pub fn set_array_length(&mut self, length: usize) {
+        let length = length.min(MAX_ARRAY_LENGTH);
+        if length < self.array_length() {
+            self.values
+                .retain(|name, _| name.parse::<usize>().map_or(true, |index| index < length));
+        }
+        self.force_set("length", length as f64, DONT_ENUM);
+    }
+
+    /// Retrieves a property of `this`, walking the prototype chain if necessary.
+    ///
+    /// No borrow of the object is held while the properties of movie clips are read,
+    /// so that they can run scripts.
+    pub fn get(
+        this: GcCell<'gc, Object<'gc>>,
+        name: &str,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if name == "__proto__" {
+            return Ok(this
+                .read()
+                .prototype
+                .map_or(Value::Undefined, Value::Object));
+        }
+
+        let mut object = Some(this);
+        while let Some(current) = object {
+            let display_node = current.read().display_node;
+            if let Some(node) = display_node {
+                if let Some(value) = display_properties::get(avm, context, node, name) {
+                    return Ok(value);
+                }
+            }
+
+            let current = current.read();
+            if let Some(value) = current.get_local(name) {
+                return Ok(value.clone());
+            }
+
+            // Named children of a movie clip are accessible as its members.
+            if let Some(child) = current.child_by_name(name) {
+                return Ok(child.read().object());
+            }
+
+            object = current.prototype;
+        }
+
+        Ok(Value::Undefined)
+    }
+
+    /// Sets a property on `this`.
+    /// New properties are always created on the object itself, never on its prototypes.
+    ///
+    /// No borrow of the object is held while the properties of movie clips are set,
+    /// or while the text fields bound to a variable are updated.
+    pub fn set(
+        this: GcCell<'gc, Object<'gc>>,
+        name: &str,
+        value: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        if name == "__proto__" {
+            this.write(context.gc_context).prototype = value.as_object().ok();
+            return Ok(());
+        }
+
+        let (display_node, is_array) = {
+            let object = this.read();
+            (object.display_node, object.is_array)
+        };
+        if let Some(node) = display_node {
+            if display_properties::set(avm, context, node, name, value.clone())? {
+                return Ok(());
+            }
+        }
+        let value = if is_array && name == "length" {
+            value.coerce_to_number(avm, context)?.into()
+        } else {
+            value
+        };
+
+        this.write(context.gc_context)
+            .set_stored(name, value.clone());
+
+        // Text fields bound to this variable show its new value.
+        if let Some(node) = display_node {
+            avm.update_bound_text_fields(context, node, name, &value);
+        }
+        Ok(())
+    }
+
+    /// Sets a stored property on this object, unless it is read-only.
+    fn set_stored(&mut self, name: &str, value: Value<'gc>) {
+        if self.is_array {
+            if name == "length" {
+                let length = value.into_number();
+                if length >= 0.0 {
+                    self.set_array_length(length as usize);
+                }
+                return;
+            } else if let Ok(index) = name.parse::<usize>() {
+                if index >= self.array_length() && index < MAX_ARRAY_LENGTH {
+                    self.force_set("length", (index + 1) as f64, DONT_ENUM);
+                }
+            }
+        }
+
+        match self.values.get_mut(name) {
+            Some(property) => {
+                if property.attributes & READ_ONLY == 0 {
+                    property.value = value;
+                }
+            }
+            None => self.force_set(name, value, 0),
+        }
+    }
+
+    /// Defines a stored property, replacing any existing property regardless of its attributes.
+    pub fn force_set(&mut self, name: &str, value: impl Into<Value<'gc>>, attributes: u8) {
+        self.values.insert(
+            name.to_string(),
+            Property {
+                value: value.into(),
+                attributes,
+            },
+        );
+    }
+
+    /// Returns the value of a property on this object only, ignoring prototypes.
+    pub fn get_local(&self, name: &str) -> Option<&Value<'gc>> {
+        self.values.get(name).map(|property| &property.value)
+    }
+
+    pub fn has_property(&self, name: &str) -> bool {
+        if self.has_own_property(name) {
+            true
+        } else if self.display_node.is_some()
+            && (display_properties::is_display_property(name) || self.child_by_name(name).is_some())
+        {
+            true
+        } else if let Some(prototype) = self.prototype {
+            prototype.read().has_property(name)
+        } else {
+            false
+        }
+    }
+
+    pub fn has_own_property(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Returns the child of the movie clip controlled by this object with the given instance name.
+    fn child_by_name(&self, name: &str) -> Option<DisplayNode<'gc>> {
+        let node = self.display_node?;
+        let display_object = node.read();
+        display_object
+            .as_movie_clip()
+            .and_then(|clip| clip.get_child_by_name(name).copied())
+    }
+
+    /// Removes a property from this object.
+    /// Returns `false` if the property does not exist or cannot be deleted.
+    pub fn delete(&mut self, name: &str) -> bool {
+        if let Some(property) = self.values.get(name) {
+            if property.can_delete() {
+                self.values.shift_remove(name);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the names of all enumerable properties of this object and its prototypes,
+    /// in `for..in` order: own properties from the most recently defined, then those of
+    /// the prototype that aren't shadowed.
+    pub fn get_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .values
+            .iter()
+            .rev()
+            .filter(|(_, property)| property.is_enumerable())
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        if let Some(prototype) = self.prototype {
+            for name in prototype.read().get_keys() {
+                if !self.values.contains_key(&name) && !keys.contains(&name) {
+                    keys.push(name);
+                }
+            }
+        }
+
+        keys
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Object<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.prototype.trace(cc);
+        self.function.trace(cc);
+        self.interfaces.trace(cc);
+        self.super_this.trace(cc);
+        self.primitive.trace(cc);
+        self.display_node.trace(cc);
+        for property in self.values.values() {
+            property.trace(cc);
+        }
+    }
+}
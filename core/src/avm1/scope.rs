@@ -0,0 +1,103 @@
+use crate::avm1::Object;
+use gc_arena::{GcCell, MutationContext};
+
+/// A single link in a chain of variable scopes.
+///
+/// Each function call creates a new scope for its local variables, whose parent is the
+/// scope that the function was defined in. A `with` block adds a scope for its object
+/// in front of the scope it appears in.
+#[derive(Clone)]
+pub struct Scope<'gc> {
+    parent: Option<GcCell<'gc, Scope<'gc>>>,
+    locals: GcCell<'gc, Object<'gc>>,
+    class: ScopeClass,
+}
+
+/// The kind of block that created a scope.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ScopeClass {
+    /// The local variables of a function call.
+    Local,
+
+    /// The object of a `with` block.
+    With,
+}
+
+impl<'gc> Scope<'gc> {
+    /// Allocates a new, empty local scope nested inside `parent`.
+    pub fn new_local_scope(
+        gc_context: MutationContext<'gc, '_>,
+        parent: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> GcCell<'gc, Scope<'gc>> {
+        GcCell::allocate(
+            gc_context,
+            Scope {
+                parent,
+                locals: Object::object(gc_context, None),
+                class: ScopeClass::Local,
+            },
+        )
+    }
+
+    /// Allocates a scope for the object of a `with` block, nested inside `parent`.
+    pub fn new_with_scope(
+        gc_context: MutationContext<'gc, '_>,
+        parent: Option<GcCell<'gc, Scope<'gc>>>,
+        object: GcCell<'gc, Object<'gc>>,
+    ) -> GcCell<'gc, Scope<'gc>> {
+        GcCell::allocate(
+            gc_context,
+            Scope {
+                parent,
+                locals: object,
+                class: ScopeClass::With,
+            },
+        )
+    }
+
+    pub fn parent(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.parent
+    }
+
+    /// The object that holds the variables of this scope.
+    pub fn locals(&self) -> GcCell<'gc, Object<'gc>> {
+        self.locals
+    }
+
+    pub fn class(&self) -> ScopeClass {
+        self.class
+    }
+
+    /// Returns the innermost function scope of the chain starting at `scope`,
+    /// skipping any `with` scopes. Variables declared with `var` belong to this scope.
+    pub fn local_scope(scope: GcCell<'gc, Scope<'gc>>) -> Option<GcCell<'gc, Scope<'gc>>> {
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            let current_scope = current.read();
+            if current_scope.class == ScopeClass::Local {
+                return Some(current);
+            }
+            scope = current_scope.parent;
+        }
+        None
+    }
+
+    /// Returns the object of the innermost scope in this chain that defines `name`.
+    pub fn find(&self, name: &str) -> Option<GcCell<'gc, Object<'gc>>> {
+        if self.locals.read().has_property(name) {
+            Some(self.locals)
+        } else if let Some(parent) = self.parent {
+            parent.read().find(name)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Scope<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.parent.trace(cc);
+        self.locals.trace(cc);
+    }
+}
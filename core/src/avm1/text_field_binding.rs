@@ -0,0 +1,100 @@
+//! The variables shown by text fields, as named by the `variable_name` of `DefineEditText`.
+
+use crate::prelude::*;
+
+/// A text field that shows the value of a variable, and sets the variable when its
+/// text is changed.
+#[derive(Clone)]
+pub struct TextFieldBinding<'gc> {
+    pub text_field: DisplayNode<'gc>,
+
+    /// The display object whose object holds the variable.
+    pub target: DisplayNode<'gc>,
+
+    /// The name of the variable on `target`.
+    pub variable_name: String,
+}
+
+unsafe impl<'gc> gc_arena::Collect for TextFieldBinding<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.text_field.trace(cc);
+        self.target.trace(cc);
+    }
+}
+
+/// The text fields bound to variables, and the text fields still waiting to be bound.
+pub struct TextFieldBindings<'gc> {
+    bindings: Vec<TextFieldBinding<'gc>>,
+
+    /// Text fields whose variable could not be found yet, usually because they were just
+    /// placed, or because the clip holding the variable does not exist yet.
+    unbound: Vec<DisplayNode<'gc>>,
+}
+
+unsafe impl<'gc> gc_arena::Collect for TextFieldBindings<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.bindings.trace(cc);
+        self.unbound.trace(cc);
+    }
+}
+
+impl<'gc> TextFieldBindings<'gc> {
+    pub fn new() -> Self {
+        Self {
+            bindings: vec![],
+            unbound: vec![],
+        }
+    }
+
+    /// Queues `text_field` to be bound to its variable.
+    pub fn add_unbound(&mut self, text_field: DisplayNode<'gc>) {
+        self.unbound.push(text_field);
+    }
+
+    /// Removes and returns the text fields waiting to be bound.
+    pub fn take_unbound(&mut self) -> Vec<DisplayNode<'gc>> {
+        std::mem::replace(&mut self.unbound, vec![])
+    }
+
+    pub fn bind(&mut self, binding: TextFieldBinding<'gc>) {
+        self.bindings.push(binding);
+    }
+
+    /// Removes the binding of `text_field`, if any, and stops waiting to bind it.
+    pub fn unbind(&mut self, text_field: DisplayNode<'gc>) {
+        let is_other = |other: &DisplayNode<'gc>| other.as_ptr() != text_field.as_ptr();
+        self.bindings
+            .retain(|binding| is_other(&binding.text_field));
+        self.unbound.retain(is_other);
+    }
+
+    /// Drops the bindings of text fields that have been removed from the display list.
+    pub fn remove_orphans(&mut self) {
+        self.bindings
+            .retain(|binding| binding.text_field.read().parent().is_some());
+    }
+
+    /// Returns the binding of `text_field`.
+    pub fn binding_of(&self, text_field: DisplayNode<'gc>) -> Option<&TextFieldBinding<'gc>> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.text_field.as_ptr() == text_field.as_ptr())
+    }
+
+    /// Returns the text fields bound to the variable `variable_name` of `target`.
+    pub fn text_fields_of(
+        &self,
+        target: DisplayNode<'gc>,
+        variable_name: &str,
+    ) -> Vec<DisplayNode<'gc>> {
+        self.bindings
+            .iter()
+            .filter(|binding| {
+                binding.target.as_ptr() == target.as_ptr() && binding.variable_name == variable_name
+            })
+            .map(|binding| binding.text_field)
+            .collect()
+    }
+}
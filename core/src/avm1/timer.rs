@@ -0,0 +1,243 @@
+//! Timers created by `setInterval` and `setTimeout`.
+
+use crate::avm1::{Object, Value};
+use crate::prelude::*;
+use gc_arena::GcCell;
+use std::collections::BTreeMap;
+
+/// What a timer calls when it fires.
+#[derive(Clone)]
+pub enum TimerCallback<'gc> {
+    /// A function, called with no `this`.
+    Function(Value<'gc>),
+
+    /// A method looked up by name on `this` every time the timer fires.
+    Method {
+        this: GcCell<'gc, Object<'gc>>,
+        method_name: String,
+    },
+}
+
+unsafe impl<'gc> gc_arena::Collect for TimerCallback<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        match self {
+            TimerCallback::Function(function) => function.trace(cc),
+            TimerCallback::Method { this, .. } => this.trace(cc),
+        }
+    }
+}
+
+struct Timer<'gc> {
+    callback: TimerCallback<'gc>,
+    args: Vec<Value<'gc>>,
+
+    /// The clip whose script created the timer. The callback runs with it as the active clip.
+    target: DisplayNode<'gc>,
+
+    /// The delay between calls, in milliseconds.
+    interval: u64,
+
+    /// The player time at which the timer next fires, in milliseconds.
+    next_time: u64,
+
+    /// Whether the timer was created by `setTimeout`, and is removed after firing once.
+    is_timeout: bool,
+}
+
+/// The timers that are currently active, keyed by the ID handed back to the script.
+pub struct Timers<'gc> {
+    timers: BTreeMap<i32, Timer<'gc>>,
+    next_id: i32,
+}
+
+unsafe impl<'gc> gc_arena::Collect for Timers<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for timer in self.timers.values() {
+            timer.callback.trace(cc);
+            timer.args.trace(cc);
+            timer.target.trace(cc);
+        }
+    }
+}
+
+impl<'gc> Timers<'gc> {
+    pub fn new() -> Self {
+        Self {
+            timers: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Adds a timer that first fires `interval` milliseconds after `time`, and returns its ID.
+    pub fn add(
+        &mut self,
+        callback: TimerCallback<'gc>,
+        args: Vec<Value<'gc>>,
+        target: DisplayNode<'gc>,
+        time: u64,
+        interval: u64,
+        is_timeout: bool,
+    ) -> i32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.timers.insert(
+            id,
+            Timer {
+                callback,
+                args,
+                target,
+                interval,
+                next_time: time.saturating_add(interval),
+                is_timeout,
+            },
+        );
+        id
+    }
+
+    /// Removes a timer. Returns `false` if there is no timer with this ID.
+    pub fn remove(&mut self, id: i32) -> bool {
+        self.timers.remove(&id).is_some()
+    }
+
+    /// Returns the IDs of the timers due to fire at `time`, in the order they should fire.
+    pub fn due_timers(&self, time: u64) -> Vec<i32> {
+        let mut due: Vec<_> = self
+            .timers
+            .iter()
+            .filter(|(_, timer)| timer.next_time <= time)
+            .map(|(id, timer)| (timer.next_time, *id))
+            .collect();
+        due.sort();
+        due.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Fires the timer `id` at `time`, scheduling its next call or removing it if it was a timeout.
+    /// Returns the call to make, or `None` if the timer has since been removed.
+    ///
+    /// An interval that has fallen behind fires only once, and is rescheduled from `time`.
+    pub fn fire(
+        &mut self,
+        id: i32,
+        time: u64,
+    ) -> Option<(TimerCallback<'gc>, Vec<Value<'gc>>, DisplayNode<'gc>)> {
+        let timer = self.timers.get_mut(&id)?;
+        let call = (timer.callback.clone(), timer.args.clone(), timer.target);
+        if timer.is_timeout {
+            self.timers.remove(&id);
+        } else {
+            timer.next_time = timer.next_time.saturating_add(timer.interval);
+            if timer.next_time <= time {
+                timer.next_time = time.saturating_add(timer.interval);
+            }
+        }
+        Some(call)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movie_clip::MovieClip;
+    use gc_arena::{make_arena, ArenaParameters, Collect};
+
+    #[derive(Collect)]
+    #[collect(empty_drop)]
+    struct TestRoot<'gc> {
+        clip: DisplayNode<'gc>,
+    }
+
+    make_arena!(TestArena, TestRoot);
+
+    /// Runs `test` with an empty set of timers and a clip to target.
+    fn with_timers(test: impl for<'gc> FnOnce(&mut Timers<'gc>, DisplayNode<'gc>)) {
+        let mut arena = TestArena::new(ArenaParameters::default(), |gc_context| TestRoot {
+            clip: GcCell::allocate(gc_context, Box::new(MovieClip::new(gc_context))),
+        });
+        arena.mutate(|_gc_context, root| test(&mut Timers::new(), root.clip));
+    }
+
+    fn callback<'gc>() -> TimerCallback<'gc> {
+        TimerCallback::Function(Value::Undefined)
+    }
+
+    #[test]
+    fn interval_fires_repeatedly() {
+        with_timers(|timers, clip| {
+            let id = timers.add(callback(), vec![], clip, 0, 100, false);
+            assert!(timers.due_timers(99).is_empty());
+            assert_eq!(timers.due_timers(100), vec![id]);
+            assert!(timers.fire(id, 100).is_some());
+            assert!(timers.due_timers(199).is_empty());
+            assert_eq!(timers.due_timers(200), vec![id]);
+        });
+    }
+
+    #[test]
+    fn interval_that_fell_behind_fires_once() {
+        with_timers(|timers, clip| {
+            let id = timers.add(callback(), vec![], clip, 0, 100, false);
+            // Three calls are overdue, but only one is made.
+            assert_eq!(timers.due_timers(350), vec![id]);
+            assert!(timers.fire(id, 350).is_some());
+            assert!(timers.due_timers(350).is_empty());
+            // The next call is rescheduled from the time the timer fired.
+            assert!(timers.due_timers(449).is_empty());
+            assert_eq!(timers.due_timers(450), vec![id]);
+        });
+    }
+
+    #[test]
+    fn timeout_is_removed_after_firing() {
+        with_timers(|timers, clip| {
+            let id = timers.add(callback(), vec![], clip, 0, 50, true);
+            assert_eq!(timers.due_timers(50), vec![id]);
+            assert!(timers.fire(id, 50).is_some());
+            assert!(timers.due_timers(1000).is_empty());
+            assert!(timers.fire(id, 1000).is_none());
+            assert!(!timers.remove(id));
+        });
+    }
+
+    #[test]
+    fn removed_timer_does_not_fire() {
+        with_timers(|timers, clip| {
+            let id = timers.add(callback(), vec![], clip, 0, 100, false);
+            assert!(timers.remove(id));
+            assert!(timers.due_timers(100).is_empty());
+            assert!(timers.fire(id, 100).is_none());
+        });
+    }
+
+    #[test]
+    fn zero_interval_fires_once_per_update() {
+        with_timers(|timers, clip| {
+            let id = timers.add(callback(), vec![], clip, 10, 0, false);
+            assert_eq!(timers.due_timers(10), vec![id]);
+            assert!(timers.fire(id, 10).is_some());
+            // The timer is due again, but each update only asks for the due timers once.
+            assert_eq!(timers.due_timers(10), vec![id]);
+            assert_eq!(timers.due_timers(11), vec![id]);
+        });
+    }
+
+    #[test]
+    fn due_timers_fire_in_schedule_order() {
+        with_timers(|timers, clip| {
+            let first = timers.add(callback(), vec![], clip, 0, 100, false);
+            let second = timers.add(callback(), vec![], clip, 50, 50, true);
+            let earliest = timers.add(callback(), vec![], clip, 70, 20, false);
+            // Timers due at the same time fire in the order they were created.
+            assert_eq!(timers.due_timers(100), vec![earliest, first, second]);
+        });
+    }
+
+    #[test]
+    fn huge_interval_never_fires() {
+        with_timers(|timers, clip| {
+            timers.add(callback(), vec![], clip, 1000, std::u64::MAX, false);
+            assert!(timers.due_timers(std::u64::MAX - 1).is_empty());
+        });
+    }
+}
@@ -0,0 +1,561 @@
+use crate::avm1::object::Object;
+use crate::avm1::{ActionContext, Avm1, Error};
+use gc_arena::GcCell;
+
+#[derive(Clone)]
+#[allow(dead_code)]
+pub enum Value<'gc> {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Object(GcCell<'gc, Object<'gc>>),
+}
+
+impl<'gc> From<String> for Value<'gc> {
+    fn from(string: String) -> Self {
+        Value::String(string)
+    }
+}
+
+impl<'gc> From<&str> for Value<'gc> {
+    fn from(string: &str) -> Self {
+        Value::String(string.to_owned())
+    }
+}
+
+impl<'gc> From<bool> for Value<'gc> {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<'gc> From<f64> for Value<'gc> {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl<'gc> From<GcCell<'gc, Object<'gc>>> for Value<'gc> {
+    fn from(object: GcCell<'gc, Object<'gc>>) -> Self {
+        Value::Object(object)
+    }
+}
+
+impl std::fmt::Debug for Value<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Undefined => f.write_str("Undefined"),
+            Value::Null => f.write_str("Null"),
+            Value::Bool(value) => f.debug_tuple("Bool").field(value).finish(),
+            Value::Number(value) => f.debug_tuple("Number").field(value).finish(),
+            Value::String(value) => f.debug_tuple("String").field(value).finish(),
+            Value::Object(object) => f
+                .debug_tuple("Object")
+                .field(&GcCell::as_ptr(*object))
+                .finish(),
+        }
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Value<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        if let Value::Object(object) = self {
+            object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> Value<'gc> {
+    pub fn into_number_v1(self) -> f64 {
+        match self {
+            Value::Bool(true) => 1.0,
+            Value::Number(v) => v,
+            Value::String(v) => v.parse().unwrap_or(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Converts a primitive value to a number, following the rules of SWF 7 and later.
+    /// Objects are not converted through `valueOf`; use `coerce_to_number` for that.
+    pub fn into_number(self) -> f64 {
+        // ECMA-262 2nd edtion s. 9.3 ToNumber
+        use std::f64::NAN;
+        match self {
+            Value::Undefined => NAN,
+            Value::Null => NAN,
+            Value::Bool(false) => 0.0,
+            Value::Bool(true) => 1.0,
+            Value::Number(v) => v,
+            Value::String(v) => string_to_number(&v, 7),
+            Value::Object(_object) => NAN,
+        }
+    }
+
+    /// Converts this value to a number, calling `valueOf` on objects.
+    ///
+    /// SWF 6 and earlier convert `undefined` and `null` to 0 rather than NaN.
+    pub fn coerce_to_number(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<f64, Error> {
+        let swf_version = avm.swf_version;
+        let value = match self {
+            Value::Undefined | Value::Null if swf_version < 7 => 0.0,
+            Value::String(v) => string_to_number(&v, swf_version),
+            Value::Object(_) => match self.to_primitive(avm, context, PrimitiveHint::Number)? {
+                Value::Object(_) => std::f64::NAN,
+                primitive => primitive.coerce_to_number(avm, context)?,
+            },
+            value => value.into_number(),
+        };
+        Ok(value)
+    }
+
+    pub fn from_bool_v1(value: bool, swf_version: u8) -> Value<'gc> {
+        // SWF version 4 did not have true bools and will push bools as 0 or 1.
+        // e.g. SWF19 p. 72:
+        // "If the numbers are equal, true is pushed to the stack for SWF 5 and later. For SWF 4, 1 is pushed to the stack."
+        if swf_version >= 5 {
+            Value::Bool(value)
+        } else {
+            Value::Number(if value { 1.0 } else { 0.0 })
+        }
+    }
+
+    /// Converts a primitive value to a string.
+    /// Objects are not converted through `toString`; use `coerce_to_string` for that.
+    pub fn into_string(self) -> String {
+        match self {
+            Value::Undefined => "undefined".to_string(),
+            Value::Null => "null".to_string(),
+            Value::Bool(v) => v.to_string(),
+            Value::Number(v) => number_to_string(v),
+            Value::String(v) => v,
+            Value::Object(_) => "[object Object]".to_string(),
+        }
+    }
+
+    /// Converts this value to a string, calling `toString` on objects.
+    ///
+    /// SWF 6 and earlier convert `undefined` to an empty string.
+    pub fn coerce_to_string(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<String, Error> {
+        let string = match self {
+            Value::Undefined if avm.swf_version < 7 => String::new(),
+            Value::Object(_) => match self.to_primitive(avm, context, PrimitiveHint::String)? {
+                Value::Object(_) => "[object Object]".to_string(),
+                primitive => primitive.coerce_to_string(avm, context)?,
+            },
+            value => value.into_string(),
+        };
+        Ok(string)
+    }
+
+    /// Converts this value to a boolean.
+    ///
+    /// SWF 7 and later treat any non-empty string as true, while earlier versions
+    /// convert strings to numbers first, so that `"0"` and `"abc"` are false.
+    pub fn as_bool(&self, swf_version: u8) -> bool {
+        match self {
+            Value::Bool(v) => *v,
+            Value::Number(v) => *v != 0.0 && !v.is_nan(),
+            Value::String(v) if swf_version >= 7 => !v.is_empty(),
+            Value::String(v) => {
+                let v = string_to_number(v, swf_version);
+                v != 0.0 && !v.is_nan()
+            }
+            Value::Object(_) => true,
+            Value::Undefined | Value::Null => false,
+        }
+    }
+
+    /// Converts an object to a primitive value by calling its `valueOf` and `toString`
+    /// methods, in the order given by `hint`. Primitive values are returned as they are.
+    /// If neither method returns a primitive, the object itself is returned.
+    pub fn to_primitive(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+        hint: PrimitiveHint,
+    ) -> Result<Value<'gc>, Error> {
+        let object = match self {
+            Value::Object(object) => object,
+            value => return Ok(value),
+        };
+        let methods = match hint {
+            PrimitiveHint::Number => ["valueOf", "toString"],
+            PrimitiveHint::String => ["toString", "valueOf"],
+        };
+        for method_name in &methods {
+            let method = Object::get(object, method_name, avm, context)?;
+            if let Value::Object(_) = method {
+                match avm.call_function(context, method, self.clone(), &[])? {
+                    Value::Object(_) => (),
+                    primitive => return Ok(primitive),
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// The `==` operator (ECMA-262 s. 11.9.3).
+    /// Objects are equal only to themselves, and are otherwise compared as primitives.
+    pub fn abstract_eq(
+        self,
+        other: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error> {
+        let result = match (self, other) {
+            (Value::Undefined, Value::Undefined)
+            | (Value::Null, Value::Null)
+            | (Value::Undefined, Value::Null)
+            | (Value::Null, Value::Undefined) => true,
+            (Value::Undefined, _) | (Value::Null, _) | (_, Value::Undefined) | (_, Value::Null) => {
+                false
+            }
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a.as_ptr() == b.as_ptr(),
+            (Value::Number(a), Value::String(b)) | (Value::String(b), Value::Number(a)) => {
+                a == string_to_number(&b, avm.swf_version)
+            }
+            (Value::Bool(a), b) | (b, Value::Bool(a)) => {
+                let a = Value::Number(if a { 1.0 } else { 0.0 });
+                return a.abstract_eq(b, avm, context);
+            }
+            (Value::Object(a), b) | (b, Value::Object(a)) => {
+                match Value::Object(a).to_primitive(avm, context, PrimitiveHint::Number)? {
+                    Value::Object(_) => false,
+                    a => return a.abstract_eq(b, avm, context),
+                }
+            }
+        };
+        Ok(result)
+    }
+
+    /// The `===` operator (ECMA-262 s. 11.9.6), which never converts either value.
+    pub fn strict_eq(&self, other: &Value<'gc>) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) | (Value::Null, Value::Null) => true,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a.as_ptr() == b.as_ptr(),
+            _ => false,
+        }
+    }
+
+    /// The `<` operator (ECMA-262 s. 11.8.5).
+    /// Returns `undefined` if either value converts to NaN.
+    pub fn abstract_lt(
+        self,
+        other: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut ActionContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let a = self.to_primitive(avm, context, PrimitiveHint::Number)?;
+        let b = other.to_primitive(avm, context, PrimitiveHint::Number)?;
+        if let (Value::String(a), Value::String(b)) = (&a, &b) {
+            // Strings are compared by UTF-16 code units.
+            return Ok(a.encode_utf16().lt(b.encode_utf16()).into());
+        }
+        let a = a.coerce_to_number(avm, context)?;
+        let b = b.coerce_to_number(avm, context)?;
+        if a.is_nan() || b.is_nan() {
+            Ok(Value::Undefined)
+        } else {
+            Ok((a < b).into())
+        }
+    }
+
+    /// Returns the string that the `typeof` operator produces for this value.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Undefined => "undefined",
+            Value::Null => "null",
+            Value::Number(_) => "number",
+            Value::Bool(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Object(object) => object.read().type_of(),
+        }
+    }
+
+    pub fn as_i32(&self) -> Result<i32, Error> {
+        self.as_f64().map(|n| n as i32)
+    }
+
+    pub fn as_u32(&self) -> Result<u32, Error> {
+        self.as_f64().map(|n| n as u32)
+    }
+
+    pub fn as_i64(&self) -> Result<i64, Error> {
+        self.as_f64().map(|n| n as i64)
+    }
+
+    pub fn as_f64(&self) -> Result<f64, Error> {
+        match *self {
+            Value::Number(v) => Ok(v),
+            _ => Err("Expected Number".into()),
+        }
+    }
+
+    pub fn as_string(&self) -> Result<&String, Error> {
+        match self {
+            Value::String(s) => Ok(s),
+            _ => Err("Expected String".into()),
+        }
+    }
+
+    pub fn as_object(&self) -> Result<GcCell<'gc, Object<'gc>>, Error> {
+        if let Value::Object(object) = self {
+            Ok(*object)
+        } else {
+            Err("Expected Object".into())
+        }
+    }
+}
+
+/// The type that an object should preferably be converted to by `Value::to_primitive`.
+#[derive(Clone, Copy)]
+pub enum PrimitiveHint {
+    Number,
+    String,
+}
+
+/// Formats a number in the same way as the Flash Player.
+///
+/// Numbers are rounded to 15 significant digits, and use exponential notation such as
+/// `1e+21` when the exponent is 15 or more, or less than -5.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    } else if n.is_infinite() {
+        return if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    } else if n == 0.0 {
+        return "0".to_string();
+    }
+
+    // Rust rounds the mantissa correctly, so only the digits need to be rearranged.
+    let formatted = format!("{:.14e}", n.abs());
+    let mut parts = formatted.split('e');
+    let mantissa = parts.next().unwrap_or("0");
+    let exponent: i32 = parts.next().and_then(|e| e.parse().ok()).unwrap_or(0);
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let digits = digits.trim_end_matches('0');
+
+    let mut string = if n < 0.0 {
+        "-".to_string()
+    } else {
+        String::new()
+    };
+    if exponent < -5 || exponent >= 15 {
+        string.push_str(&digits[..1]);
+        if digits.len() > 1 {
+            string.push('.');
+            string.push_str(&digits[1..]);
+        }
+        string.push('e');
+        string.push(if exponent < 0 { '-' } else { '+' });
+        string.push_str(&exponent.abs().to_string());
+    } else if exponent < 0 {
+        string.push_str("0.");
+        for _ in 0..(-exponent - 1) {
+            string.push('0');
+        }
+        string.push_str(digits);
+    } else {
+        let int_len = exponent as usize + 1;
+        if digits.len() > int_len {
+            string.push_str(&digits[..int_len]);
+            string.push('.');
+            string.push_str(&digits[int_len..]);
+        } else {
+            string.push_str(digits);
+            for _ in digits.len()..int_len {
+                string.push('0');
+            }
+        }
+    }
+    string
+}
+
+/// Parses a string as a number, as `ToNumber` does.
+///
+/// Surrounding whitespace is ignored. From SWF 6 on, hexadecimal strings such as `0xFF`
+/// and octal strings such as `0777` are parsed as signed 32-bit integers.
+/// Empty strings are 0 before SWF 7 and NaN afterwards.
+pub fn string_to_number(s: &str, swf_version: u8) -> f64 {
+    let s = s.trim_matches(|c| c == ' ' || ('\t'..='\r').contains(&c));
+    if s.is_empty() {
+        return if swf_version >= 7 { std::f64::NAN } else { 0.0 };
+    }
+
+    if swf_version >= 6 {
+        if let Some(n) = parse_prefixed_integer(s) {
+            return n;
+        }
+    }
+
+    match s {
+        "Infinity" | "+Infinity" => return std::f64::INFINITY,
+        "-Infinity" => return std::f64::NEG_INFINITY,
+        _ => (),
+    }
+
+    // Rust also accepts words such as "inf" and "NaN", which ActionScript does not.
+    let is_numeric = s
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-');
+    if is_numeric {
+        s.parse().unwrap_or(std::f64::NAN)
+    } else {
+        std::f64::NAN
+    }
+}
+
+/// Parses a hexadecimal string such as `-0x1F`, or an octal string such as `017`,
+/// wrapping it around to a signed 32-bit integer.
+/// Returns `None` for any other string, including decimal strings such as `019`.
+fn parse_prefixed_integer(s: &str) -> Option<f64> {
+    let (is_negative, s) = if s.starts_with('-') {
+        (true, &s[1..])
+    } else if s.starts_with('+') {
+        (false, &s[1..])
+    } else {
+        (false, s)
+    };
+    let (radix, digits) = if s.starts_with("0x") || s.starts_with("0X") {
+        (16, &s[2..])
+    } else if s.len() > 1 && s.starts_with('0') {
+        (8, &s[1..])
+    } else {
+        return None;
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return if radix == 16 {
+            Some(std::f64::NAN)
+        } else {
+            None
+        };
+    }
+    // Digits beyond 32 bits are dropped, as they are when the value wraps around.
+    let n = digits.chars().fold(0u32, |n, c| {
+        n.wrapping_mul(radix)
+            .wrapping_add(c.to_digit(radix).unwrap_or(0))
+    }) as i32;
+    Some(f64::from(if is_negative { n.wrapping_neg() } else { n }))
+}
+
+/// Converts a number to a 32-bit integer, wrapping around as `ToInt32` does.
+pub fn number_to_i32(n: f64) -> i32 {
+    number_to_u32(n) as i32
+}
+
+/// Converts a number to an unsigned 32-bit integer, wrapping around as `ToUint32` does.
+pub fn number_to_u32(n: f64) -> u32 {
+    if n.is_finite() {
+        n.trunc().rem_euclid(4_294_967_296.0) as u32
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_to_string_notation() {
+        assert_eq!(number_to_string(0.0), "0");
+        assert_eq!(number_to_string(-0.0), "0");
+        assert_eq!(number_to_string(123.456), "123.456");
+        assert_eq!(number_to_string(-1.5), "-1.5");
+        assert_eq!(number_to_string(0.1 + 0.2), "0.3");
+        assert_eq!(number_to_string(1.0 / 3.0), "0.333333333333333");
+
+        // Exponential notation is used from 1e+15 on, and below 1e-5.
+        assert_eq!(number_to_string(1e14), "100000000000000");
+        assert_eq!(number_to_string(999_999_999_999_999.0), "999999999999999");
+        assert_eq!(number_to_string(1e15), "1e+15");
+        assert_eq!(number_to_string(9_999_999_999_999_999.0), "1e+16");
+        assert_eq!(number_to_string(1e21), "1e+21");
+        assert_eq!(number_to_string(-1.25e20), "-1.25e+20");
+        assert_eq!(number_to_string(0.00001), "0.00001");
+        assert_eq!(number_to_string(0.000_012_5), "0.0000125");
+        assert_eq!(number_to_string(0.000_001), "1e-6");
+        assert_eq!(number_to_string(-1.5e-7), "-1.5e-7");
+        assert_eq!(number_to_string(std::f64::MAX), "1.79769313486232e+308");
+    }
+
+    #[test]
+    fn number_to_string_special_values() {
+        assert_eq!(number_to_string(std::f64::NAN), "NaN");
+        assert_eq!(number_to_string(std::f64::INFINITY), "Infinity");
+        assert_eq!(number_to_string(std::f64::NEG_INFINITY), "-Infinity");
+    }
+
+    #[test]
+    fn string_to_number_decimal() {
+        assert_eq!(string_to_number("12", 7), 12.0);
+        assert_eq!(string_to_number("-12.5", 7), -12.5);
+        assert_eq!(string_to_number(".5", 7), 0.5);
+        assert_eq!(string_to_number("1e3", 7), 1000.0);
+        assert_eq!(string_to_number("1E-3", 7), 0.001);
+        assert_eq!(string_to_number("019", 7), 19.0);
+        assert!(string_to_number("12abc", 7).is_nan());
+        assert!(string_to_number("abc", 7).is_nan());
+    }
+
+    #[test]
+    fn string_to_number_whitespace() {
+        assert_eq!(string_to_number("  12  ", 7), 12.0);
+        assert_eq!(string_to_number("\t\r\n5", 7), 5.0);
+        assert!(string_to_number("1 2", 7).is_nan());
+        assert!(string_to_number(" ", 7).is_nan());
+    }
+
+    #[test]
+    fn string_to_number_special_values() {
+        assert_eq!(string_to_number("Infinity", 7), std::f64::INFINITY);
+        assert_eq!(string_to_number("+Infinity", 7), std::f64::INFINITY);
+        assert_eq!(string_to_number("-Infinity", 7), std::f64::NEG_INFINITY);
+        assert!(string_to_number("inf", 7).is_nan());
+        assert!(string_to_number("NaN", 7).is_nan());
+        assert_eq!(string_to_number("-0", 7), 0.0);
+        assert!(string_to_number("-0", 7).is_sign_negative());
+    }
+
+    #[test]
+    fn string_to_number_hex_and_octal() {
+        assert_eq!(string_to_number("0x1F", 7), 31.0);
+        assert_eq!(string_to_number("0XFF", 6), 255.0);
+        assert_eq!(string_to_number("-0x10", 7), -16.0);
+        assert_eq!(string_to_number("0xFFFFFFFF", 7), -1.0);
+        assert!(string_to_number("0x", 7).is_nan());
+        assert!(string_to_number("0xG", 7).is_nan());
+        assert_eq!(string_to_number("017", 7), 15.0);
+        assert_eq!(string_to_number("-017", 6), -15.0);
+        assert_eq!(string_to_number(" 010 ", 7), 8.0);
+    }
+
+    #[test]
+    fn string_to_number_swf_versions() {
+        // Empty strings are 0 before SWF 7.
+        assert_eq!(string_to_number("", 6), 0.0);
+        assert!(string_to_number("", 7).is_nan());
+
+        // Hexadecimal and octal strings are decimal, or NaN, before SWF 6.
+        assert!(string_to_number("0x1F", 5).is_nan());
+        assert_eq!(string_to_number("017", 5), 17.0);
+        assert_eq!(string_to_number("017", 6), 15.0);
+    }
+}
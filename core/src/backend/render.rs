@@ -24,6 +24,25 @@ pub trait RenderBackend {
     fn begin_frame(&mut self);
     fn clear(&mut self, color: Color);
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
+
+    /// Starts a new mask. The shapes rendered after this call are not drawn,
+    /// but instead form the mask.
+    fn push_mask(&mut self);
+
+    /// Finishes the mask started by `push_mask`. The shapes rendered after this call
+    /// are clipped to the mask, and to any masks beneath it.
+    fn activate_mask(&mut self);
+
+    /// Removes the topmost mask.
+    fn pop_mask(&mut self);
+
+    /// Starts a new offscreen layer. The shapes rendered after this call are drawn into the layer.
+    fn push_layer(&mut self);
+
+    /// Removes the topmost layer, applying `filters` to it in order,
+    /// and compositing it onto the layer beneath it with `blend_mode`.
+    fn pop_layer(&mut self, blend_mode: BlendMode, filters: &[swf::Filter]);
+
     fn end_frame(&mut self);
     fn draw_pause_overlay(&mut self);
     fn draw_letterbox(&mut self, letterbox: Letterbox);
@@ -32,6 +51,59 @@ pub trait RenderBackend {
 #[derive(Copy, Clone, Debug)]
 pub struct ShapeHandle(pub usize);
 
+/// How the colors of a display object are combined with the colors beneath it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Layer,
+    Multiply,
+    Screen,
+    Lighten,
+    Darken,
+    Difference,
+    Add,
+    Subtract,
+    Invert,
+    Alpha,
+    Erase,
+    Overlay,
+    HardLight,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        BlendMode::Normal
+    }
+}
+
+impl From<swf::BlendMode> for BlendMode {
+    fn from(blend_mode: swf::BlendMode) -> Self {
+        match blend_mode {
+            swf::BlendMode::Normal => BlendMode::Normal,
+            swf::BlendMode::Layer => BlendMode::Layer,
+            swf::BlendMode::Multiply => BlendMode::Multiply,
+            swf::BlendMode::Screen => BlendMode::Screen,
+            swf::BlendMode::Lighten => BlendMode::Lighten,
+            swf::BlendMode::Darken => BlendMode::Darken,
+            swf::BlendMode::Difference => BlendMode::Difference,
+            swf::BlendMode::Add => BlendMode::Add,
+            swf::BlendMode::Subtract => BlendMode::Subtract,
+            swf::BlendMode::Invert => BlendMode::Invert,
+            swf::BlendMode::Alpha => BlendMode::Alpha,
+            swf::BlendMode::Erase => BlendMode::Erase,
+            swf::BlendMode::Overlay => BlendMode::Overlay,
+            swf::BlendMode::HardLight => BlendMode::HardLight,
+        }
+    }
+}
+
+unsafe impl gc_arena::Collect for BlendMode {
+    #[inline]
+    fn needs_trace() -> bool {
+        false
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct BitmapHandle(pub usize);
 
@@ -39,10 +111,9 @@ pub struct BitmapHandle(pub usize);
 pub enum Letterbox {
     None,
     Letterbox(f32),
-    Pillarbox(f32)
+    Pillarbox(f32),
 }
 
-
 pub struct NullRenderer;
 
 impl RenderBackend for NullRenderer {
@@ -79,6 +150,11 @@ impl RenderBackend for NullRenderer {
     fn end_frame(&mut self) {}
     fn clear(&mut self, _color: Color) {}
     fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn push_mask(&mut self) {}
+    fn activate_mask(&mut self) {}
+    fn pop_mask(&mut self) {}
+    fn push_layer(&mut self) {}
+    fn pop_layer(&mut self, _blend_mode: BlendMode, _filters: &[swf::Filter]) {}
     fn draw_pause_overlay(&mut self) {}
     fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
 }
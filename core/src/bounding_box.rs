@@ -30,6 +30,22 @@ impl BoundingBox {
         }
     }
 
+    /// Grows this bounding box to also contain `other`.
+    pub fn union(&mut self, other: &BoundingBox) {
+        use std::cmp::{max, min};
+        if !other.valid {
+            return;
+        }
+        if self.valid {
+            self.x_min = min(self.x_min, other.x_min);
+            self.y_min = min(self.y_min, other.y_min);
+            self.x_max = max(self.x_max, other.x_max);
+            self.y_max = max(self.y_max, other.y_max);
+        } else {
+            *self = other.clone();
+        }
+    }
+
     pub fn intersects(&self, other: &BoundingBox) -> bool {
         if !self.valid || !other.valid {
             return false;
@@ -1,5 +1,5 @@
-use crate::display_object::{DisplayObject, DisplayObjectBase};
-use crate::events::ButtonEvent;
+use crate::display_object::{render_with_effects, DisplayObject, DisplayObjectBase};
+use crate::events::{ButtonEvent, ButtonKeyCode};
 use crate::player::{RenderContext, UpdateContext};
 use crate::prelude::*;
 use std::collections::BTreeMap;
@@ -9,6 +9,7 @@ pub struct Button<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: gc_arena::Gc<'gc, ButtonStatic>,
     state: ButtonState,
+    mouse_state: ButtonMouseState,
     children: [BTreeMap<Depth, DisplayNode<'gc>>; 4],
     tracking: ButtonTracking,
 }
@@ -23,6 +24,7 @@ impl<'gc> Button<'gc> {
         button: &swf::Button,
         library: &crate::library::Library<'gc>,
         gc_context: gc_arena::MutationContext<'gc, '_>,
+        prototypes: &crate::avm1::SystemPrototypes<'gc>,
     ) -> Self {
         use swf::ButtonState;
         let mut children = [
@@ -33,7 +35,7 @@ impl<'gc> Button<'gc> {
         ];
         for record in &button.records {
             let child = library
-                .instantiate_display_object(record.id, gc_context)
+                .instantiate_display_object(record.id, gc_context, prototypes)
                 .unwrap();
             child
                 .write(gc_context)
@@ -41,6 +43,10 @@ impl<'gc> Button<'gc> {
             child
                 .write(gc_context)
                 .set_color_transform(&record.color_transform.clone().into());
+            child
+                .write(gc_context)
+                .set_blend_mode(record.blend_mode.into());
+            child.write(gc_context).set_filters(record.filters.clone());
             for state in &record.states {
                 let i = match state {
                     ButtonState::Up => UP_STATE,
@@ -79,6 +85,7 @@ impl<'gc> Button<'gc> {
             static_data: gc_arena::Gc::allocate(gc_context, static_data),
             children,
             state: self::ButtonState::Up,
+            mouse_state: ButtonMouseState::Idle,
             tracking: if button.is_track_as_menu {
                 ButtonTracking::Menu
             } else {
@@ -113,52 +120,51 @@ impl<'gc> Button<'gc> {
         self.children[i].values_mut()
     }
 
+    /// Whether the button is tracked as a menu item, in which case it responds to the mouse
+    /// being dragged over it even if the mouse was pressed on another button.
+    pub fn is_track_as_menu(&self) -> bool {
+        self.tracking == ButtonTracking::Menu
+    }
+
     pub fn handle_button_event(
         &mut self,
         context: &mut crate::player::UpdateContext<'_, 'gc, '_>,
         event: ButtonEvent,
     ) {
-        let new_state = match event {
-            ButtonEvent::RollOut => ButtonState::Up,
-            ButtonEvent::RollOver => ButtonState::Over,
-            ButtonEvent::Press => ButtonState::Down,
-            ButtonEvent::Release => ButtonState::Over,
-            ButtonEvent::KeyPress(key) => {
-                self.run_actions(context, swf::ButtonActionCondition::KeyPress, Some(key));
-                self.state
-            }
-        };
-
-        match (self.state, new_state) {
-            (ButtonState::Up, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::IdleToOverUp, None);
-            }
-            (ButtonState::Over, ButtonState::Up) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToIdle, None);
-            }
-            (ButtonState::Over, ButtonState::Down) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToOverDown, None);
-            }
-            (ButtonState::Down, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverDownToOverUp, None);
-            }
-            _ => (),
+        use ButtonMouseState::*;
+        if let ButtonEvent::KeyPress(key) = event {
+            self.run_actions(context, swf::ButtonActionCondition::KeyPress, Some(key));
+            return;
         }
+        let (new_mouse_state, condition) =
+            match self.mouse_state.transition(event, self.is_track_as_menu()) {
+                Some(transition) => transition,
+                None => return,
+            };
 
-        self.state = new_state;
+        self.mouse_state = new_mouse_state;
+        self.state = match new_mouse_state {
+            Idle => ButtonState::Up,
+            OverUp | OutDown => ButtonState::Over,
+            OverDown => ButtonState::Down,
+        };
+        self.run_actions(context, condition, None);
     }
 
     fn run_actions(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         condition: swf::ButtonActionCondition,
-        key_code: Option<u8>,
+        key_code: Option<ButtonKeyCode>,
     ) {
         if let Some(parent) = self.parent() {
             for action in &self.static_data.actions {
                 if action.condition == condition && action.key_code == key_code {
                     // Note that AVM1 buttons run actions relative to their parent, not themselves.
-                    context.actions.push((parent, action.action_data.clone()));
+                    context.actions.push((
+                        parent,
+                        crate::player::ActionType::Normal(action.action_data.clone()),
+                    ));
                 }
             }
         }
@@ -194,11 +200,30 @@ impl<'gc> DisplayObject<'gc> for Button<'gc> {
         }
     }
 
+    fn propagate_key_press(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        key_code: ButtonKeyCode,
+    ) {
+        self.handle_button_event(context, ButtonEvent::KeyPress(key_code));
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+        for child in self.children_in_state(self.state) {
+            bounds.union(&child.read().bounds_in_parent());
+        }
+        bounds
+    }
+
     fn render(&self, context: &mut RenderContext<'_, 'gc>) {
         context.transform_stack.push(self.transform());
 
         for child in self.children_in_state(self.state) {
-            child.read().render(context);
+            let child = child.read();
+            if child.visible() {
+                render_with_effects(context, &**child);
+            }
         }
         context.transform_stack.pop();
     }
@@ -211,7 +236,7 @@ impl<'gc> DisplayObject<'gc> for Button<'gc> {
             self.state
         };
         for child in self.children_in_state(hit_state).rev() {
-            if child.read().world_bounds().contains(point) {
+            if child.read().hit_test(point) {
                 return true;
             }
         }
@@ -262,11 +287,61 @@ enum ButtonState {
     Hit,
 }
 
+/// The state of the mouse relative to a button, as named by the conditions of button actions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ButtonMouseState {
+    /// The mouse is outside the button, and is either up or was pressed elsewhere.
+    Idle,
+
+    /// The mouse is over the button, and is up.
+    OverUp,
+
+    /// The mouse is over the button, and was pressed on it.
+    /// Menu buttons also enter this state when the mouse is dragged onto them.
+    OverDown,
+
+    /// The mouse was pressed on the button, and then dragged outside of it.
+    OutDown,
+}
+
+impl ButtonMouseState {
+    /// Returns the state that a mouse event moves the button to, with the condition of the
+    /// actions to run. Returns `None` if the event doesn't apply in this state, such as a
+    /// `ReleaseOutside` for a menu button that already went idle when the mouse left it.
+    fn transition(
+        self,
+        event: ButtonEvent,
+        is_menu: bool,
+    ) -> Option<(ButtonMouseState, swf::ButtonActionCondition)> {
+        use swf::ButtonActionCondition;
+        use ButtonMouseState::*;
+        let transition = match (self, event) {
+            (Idle, ButtonEvent::RollOver) => (OverUp, ButtonActionCondition::IdleToOverUp),
+            (OverUp, ButtonEvent::RollOut) => (Idle, ButtonActionCondition::OverUpToIdle),
+            (OverUp, ButtonEvent::Press) => (OverDown, ButtonActionCondition::OverUpToOverDown),
+            (OverDown, ButtonEvent::Release) => (OverUp, ButtonActionCondition::OverDownToOverUp),
+            (OverDown, ButtonEvent::DragOut) if is_menu => {
+                (Idle, ButtonActionCondition::OverDownToIdle)
+            }
+            (OverDown, ButtonEvent::DragOut) => (OutDown, ButtonActionCondition::OverDownToOutDown),
+            (OutDown, ButtonEvent::DragOver) => {
+                (OverDown, ButtonActionCondition::OutDownToOverDown)
+            }
+            (Idle, ButtonEvent::DragOver) if is_menu => {
+                (OverDown, ButtonActionCondition::IdleToOverDown)
+            }
+            (OutDown, ButtonEvent::ReleaseOutside) => (Idle, ButtonActionCondition::OutDownToIdle),
+            _ => return None,
+        };
+        Some(transition)
+    }
+}
+
 #[derive(Clone)]
 struct ButtonAction {
     action_data: crate::tag_utils::SwfSlice,
     condition: swf::ButtonActionCondition,
-    key_code: Option<u8>,
+    key_code: Option<ButtonKeyCode>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -289,3 +364,109 @@ unsafe impl<'gc> gc_arena::Collect for ButtonStatic {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ButtonMouseState::*;
+    use super::*;
+    use swf::ButtonActionCondition;
+
+    fn push(
+        state: ButtonMouseState,
+        event: ButtonEvent,
+    ) -> Option<(ButtonMouseState, ButtonActionCondition)> {
+        state.transition(event, false)
+    }
+
+    fn menu(
+        state: ButtonMouseState,
+        event: ButtonEvent,
+    ) -> Option<(ButtonMouseState, ButtonActionCondition)> {
+        state.transition(event, true)
+    }
+
+    #[test]
+    fn push_button_click() {
+        assert_eq!(
+            push(Idle, ButtonEvent::RollOver),
+            Some((OverUp, ButtonActionCondition::IdleToOverUp))
+        );
+        assert_eq!(
+            push(OverUp, ButtonEvent::Press),
+            Some((OverDown, ButtonActionCondition::OverUpToOverDown))
+        );
+        assert_eq!(
+            push(OverDown, ButtonEvent::Release),
+            Some((OverUp, ButtonActionCondition::OverDownToOverUp))
+        );
+        assert_eq!(
+            push(OverUp, ButtonEvent::RollOut),
+            Some((Idle, ButtonActionCondition::OverUpToIdle))
+        );
+    }
+
+    #[test]
+    fn push_button_drag() {
+        assert_eq!(
+            push(OverDown, ButtonEvent::DragOut),
+            Some((OutDown, ButtonActionCondition::OverDownToOutDown))
+        );
+        assert_eq!(
+            push(OutDown, ButtonEvent::DragOver),
+            Some((OverDown, ButtonActionCondition::OutDownToOverDown))
+        );
+        assert_eq!(
+            push(OutDown, ButtonEvent::ReleaseOutside),
+            Some((Idle, ButtonActionCondition::OutDownToIdle))
+        );
+        // Push buttons only track the mouse when they were pressed.
+        assert_eq!(push(Idle, ButtonEvent::DragOver), None);
+    }
+
+    #[test]
+    fn menu_button_drag() {
+        assert_eq!(
+            menu(OverDown, ButtonEvent::DragOut),
+            Some((Idle, ButtonActionCondition::OverDownToIdle))
+        );
+        assert_eq!(
+            menu(Idle, ButtonEvent::DragOver),
+            Some((OverDown, ButtonActionCondition::IdleToOverDown))
+        );
+        assert_eq!(
+            menu(OverDown, ButtonEvent::Release),
+            Some((OverUp, ButtonActionCondition::OverDownToOverUp))
+        );
+        // The button went idle when the mouse left it, so there is nothing to release.
+        assert_eq!(menu(Idle, ButtonEvent::ReleaseOutside), None);
+    }
+
+    #[test]
+    fn events_that_do_not_apply() {
+        for &state in &[Idle, OverUp, OverDown, OutDown] {
+            for &is_menu in &[false, true] {
+                let events = vec![
+                    (ButtonEvent::RollOver, state == Idle),
+                    (ButtonEvent::RollOut, state == OverUp),
+                    (ButtonEvent::Press, state == OverUp),
+                    (ButtonEvent::Release, state == OverDown),
+                    (ButtonEvent::ReleaseOutside, state == OutDown),
+                    (ButtonEvent::DragOut, state == OverDown),
+                    (
+                        ButtonEvent::DragOver,
+                        state == OutDown || (is_menu && state == Idle),
+                    ),
+                ];
+                for (event, applies) in events {
+                    let description = format!("{:?} in {:?}, menu: {}", event, state, is_menu);
+                    assert_eq!(
+                        state.transition(event, is_menu).is_some(),
+                        applies,
+                        "{}",
+                        description
+                    );
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub enum Character<'gc> {
     MovieClip(Box<crate::movie_clip::MovieClip<'gc>>),
     Bitmap(crate::backend::render::BitmapHandle),
     Button(Box<crate::button::Button<'gc>>),
+    EditText(Box<crate::edit_text::EditText<'gc>>),
     Font(Box<crate::font::Font>),
     MorphShape(Box<crate::morph_shape::MorphShape<'gc>>),
     Text(Box<crate::text::Text<'gc>>),
@@ -17,6 +18,7 @@ unsafe impl<'gc> gc_arena::Collect for Character<'gc> {
             Character::MovieClip(c) => c.trace(cc),
             Character::Bitmap(c) => c.trace(cc),
             Character::Button(c) => c.trace(cc),
+            Character::EditText(c) => c.trace(cc),
             Character::Font(c) => c.trace(cc),
             Character::MorphShape(c) => c.trace(cc),
             Character::Text(c) => c.trace(cc),
@@ -1,16 +1,28 @@
+use crate::avm1;
+use crate::backend::render::BlendMode;
 use crate::player::{RenderContext, UpdateContext};
 use crate::prelude::*;
 use crate::transform::Transform;
-use gc_arena::{Collect, GcCell};
+use gc_arena::{Collect, GcCell, MutationContext};
 
-#[derive(Clone, Collect)]
-#[collect(empty_drop)]
+#[derive(Clone)]
 pub struct DisplayObjectBase<'gc> {
     parent: Option<DisplayNode<'gc>>,
     depth: Depth,
     transform: Transform,
     name: String,
     clip_depth: Depth,
+    visible: bool,
+    blend_mode: BlendMode,
+    is_bitmap_cached: bool,
+    filters: Vec<swf::Filter>,
+}
+
+unsafe impl<'gc> Collect for DisplayObjectBase<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.parent.trace(cc);
+    }
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -21,6 +33,10 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             transform: Default::default(),
             name: Default::default(),
             clip_depth: Default::default(),
+            visible: true,
+            blend_mode: Default::default(),
+            is_bitmap_cached: false,
+            filters: vec![],
         }
     }
 }
@@ -63,6 +79,30 @@ impl<'gc> DisplayObject<'gc> for DisplayObjectBase<'gc> {
     fn set_parent(&mut self, parent: Option<DisplayNode<'gc>>) {
         self.parent = parent;
     }
+    fn visible(&self) -> bool {
+        self.visible
+    }
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+    fn is_bitmap_cached(&self) -> bool {
+        self.is_bitmap_cached
+    }
+    fn set_is_bitmap_cached(&mut self, is_bitmap_cached: bool) {
+        self.is_bitmap_cached = is_bitmap_cached;
+    }
+    fn filters(&self) -> &[swf::Filter] {
+        &self.filters
+    }
+    fn set_filters(&mut self, filters: Vec<swf::Filter>) {
+        self.filters = filters;
+    }
     fn box_clone(&self) -> Box<dyn DisplayObject<'gc>> {
         Box::new(self.clone())
     }
@@ -73,8 +113,10 @@ pub trait DisplayObject<'gc>: 'gc + Collect {
         BoundingBox::default()
     }
 
+    /// The bounds of this display object in stage coordinates.
     fn world_bounds(&self) -> BoundingBox {
-        BoundingBox::default()
+        // TODO: Use dirty flags and cache this.
+        self.local_bounds().transform(&self.world_matrix())
     }
 
     fn transform(&self) -> &Transform;
@@ -90,11 +132,150 @@ pub trait DisplayObject<'gc>: 'gc + Collect {
     fn parent(&self) -> Option<DisplayNode<'gc>>;
     fn set_parent(&mut self, parent: Option<DisplayNode<'gc>>);
 
+    /// Whether this display object is rendered and can be picked by the mouse.
+    fn visible(&self) -> bool;
+    fn set_visible(&mut self, visible: bool);
+
+    /// How this display object is composited onto the content beneath it.
+    fn blend_mode(&self) -> BlendMode;
+    fn set_blend_mode(&mut self, blend_mode: BlendMode);
+
+    /// Whether this display object is rendered as a whole into a bitmap,
+    /// as set by `cacheAsBitmap`.
+    fn is_bitmap_cached(&self) -> bool;
+    fn set_is_bitmap_cached(&mut self, is_bitmap_cached: bool);
+
+    /// The bitmap filters applied to the rendered image of this display object, in order.
+    fn filters(&self) -> &[swf::Filter];
+    fn set_filters(&mut self, filters: Vec<swf::Filter>);
+
+    fn x(&self) -> f32 {
+        self.matrix().tx / Twips::TWIPS_PER_PIXEL as f32
+    }
+    fn set_x(&mut self, val: f32) {
+        self.matrix_mut().tx = val * Twips::TWIPS_PER_PIXEL as f32;
+    }
+    fn y(&self) -> f32 {
+        self.matrix().ty / Twips::TWIPS_PER_PIXEL as f32
+    }
+    fn set_y(&mut self, val: f32) {
+        self.matrix_mut().ty = val * Twips::TWIPS_PER_PIXEL as f32;
+    }
+    /// The horizontal scale, in percent. Unrotated objects keep the sign of their scale.
+    fn x_scale(&self) -> f32 {
+        let matrix = self.matrix();
+        if matrix.b == 0.0 {
+            matrix.a * 100.0
+        } else {
+            f32::hypot(matrix.a, matrix.b) * 100.0
+        }
+    }
+    fn set_x_scale(&mut self, val: f32) {
+        let matrix = self.matrix_mut();
+        let scale = f32::hypot(matrix.a, matrix.b);
+        if matrix.b == 0.0 || scale == 0.0 {
+            matrix.a = val / 100.0;
+        } else {
+            matrix.a *= val / 100.0 / scale;
+            matrix.b *= val / 100.0 / scale;
+        }
+    }
+    /// The vertical scale, in percent. Unrotated objects keep the sign of their scale.
+    fn y_scale(&self) -> f32 {
+        let matrix = self.matrix();
+        if matrix.c == 0.0 {
+            matrix.d * 100.0
+        } else {
+            f32::hypot(matrix.c, matrix.d) * 100.0
+        }
+    }
+    fn set_y_scale(&mut self, val: f32) {
+        let matrix = self.matrix_mut();
+        let scale = f32::hypot(matrix.c, matrix.d);
+        if matrix.c == 0.0 || scale == 0.0 {
+            matrix.d = val / 100.0;
+        } else {
+            matrix.c *= val / 100.0 / scale;
+            matrix.d *= val / 100.0 / scale;
+        }
+    }
+    fn rotation(&self) -> f32 {
+        // TODO: Cache the user-friendly transform values like rotation.
+        let matrix = self.matrix();
+        f32::atan2(matrix.b, matrix.a).to_degrees()
+    }
+    fn set_rotation(&mut self, degrees: f32) {
+        // TODO: Use cached user-friendly transform values.
+        let angle = degrees.to_radians();
+        let cos = f32::cos(angle);
+        let sin = f32::sin(angle);
+        let scale_x = self.x_scale() / 100.0;
+        let scale_y = self.y_scale() / 100.0;
+
+        let matrix = self.matrix_mut();
+        *matrix = Matrix {
+            a: scale_x * cos,
+            b: scale_x * sin,
+            c: -scale_y * sin,
+            d: scale_y * cos,
+            tx: matrix.tx,
+            ty: matrix.ty,
+        };
+    }
+
+    /// The bounds of this display object in the coordinate space of its parent.
+    fn bounds_in_parent(&self) -> BoundingBox {
+        self.local_bounds().transform(self.matrix())
+    }
+
+    /// The matrix that transforms the local coordinates of this display object
+    /// into stage coordinates.
+    fn world_matrix(&self) -> Matrix {
+        let parent_matrix = self.parent().map(world_matrix).unwrap_or_default();
+        parent_matrix * *self.matrix()
+    }
+
+    /// Transforms `point` from stage coordinates into the local coordinates of this display object.
+    fn global_to_local(&self, point: (Twips, Twips)) -> (Twips, Twips) {
+        let mut matrix = self.world_matrix();
+        matrix.invert();
+        matrix * point
+    }
+
     fn preload(&mut self, _context: &mut UpdateContext<'_, 'gc, '_>) {}
     fn run_frame(&mut self, _context: &mut UpdateContext<'_, 'gc, '_>) {}
     fn run_post_frame(&mut self, _context: &mut UpdateContext<'_, 'gc, '_>) {}
     fn render(&self, _context: &mut RenderContext<'_, 'gc>) {}
 
+    /// Runs the handlers for a broadcast event, such as `onClipEvent(mouseDown)`,
+    /// on this display object and all of its children.
+    fn propagate_clip_event(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _event: swf::ClipEvent,
+    ) {
+    }
+
+    /// Runs the `keyPress` handlers of buttons and clips for `key_code`,
+    /// on this display object and all of its children.
+    fn propagate_key_press(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _key_code: crate::events::ButtonKeyCode,
+    ) {
+    }
+
+    /// Scrolls the topmost text field under `point` by `delta` lines of the mouse wheel.
+    /// Returns `true` if a text field was under `point`.
+    fn propagate_mouse_wheel(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _point: (Twips, Twips),
+        _delta: f64,
+    ) -> bool {
+        false
+    }
+
     fn as_button(&self) -> Option<&crate::button::Button<'gc>> {
         None
     }
@@ -107,6 +288,12 @@ pub trait DisplayObject<'gc>: 'gc + Collect {
     fn as_movie_clip_mut(&mut self) -> Option<&mut crate::movie_clip::MovieClip<'gc>> {
         None
     }
+    fn as_edit_text(&self) -> Option<&crate::edit_text::EditText<'gc>> {
+        None
+    }
+    fn as_edit_text_mut(&mut self) -> Option<&mut crate::edit_text::EditText<'gc>> {
+        None
+    }
     fn as_morph_shape(&self) -> Option<&crate::morph_shape::MorphShape<'gc>> {
         None
     }
@@ -115,6 +302,23 @@ pub trait DisplayObject<'gc>: 'gc + Collect {
     }
     fn box_clone(&self) -> Box<dyn DisplayObject<'gc>>;
 
+    /// Returns the AVM1 object that scripts use to access this display object,
+    /// or `undefined` if this display object is not scriptable.
+    fn object(&self) -> avm1::Value<'gc> {
+        avm1::Value::Undefined
+    }
+
+    /// Called once a new instance has been allocated, so that it can create its AVM1 object.
+    /// `display_object` is the node that contains this display object.
+    fn post_instantiation(
+        &mut self,
+        _gc_context: MutationContext<'gc, '_>,
+        _display_object: DisplayNode<'gc>,
+        _prototypes: &avm1::SystemPrototypes<'gc>,
+    ) {
+    }
+
+    /// Whether `point`, in stage coordinates, lies on the shapes of this display object.
     fn hit_test(&self, _: (Twips, Twips)) -> bool {
         false
     }
@@ -126,6 +330,17 @@ pub trait DisplayObject<'gc>: 'gc + Collect {
     ) -> Option<DisplayNode<'gc>> {
         None
     }
+
+    /// Returns the innermost movie clip with content at `point`, such as the `_droptarget`
+    /// of a dragged clip. The clip `ignore` and its children are skipped.
+    fn pick_clip_at(
+        &self,
+        _self_node: DisplayNode<'gc>,
+        _point: (Twips, Twips),
+        _ignore: Option<DisplayNode<'gc>>,
+    ) -> Option<DisplayNode<'gc>> {
+        None
+    }
 }
 
 impl<'gc> Clone for Box<dyn DisplayObject<'gc>> {
@@ -151,7 +366,10 @@ macro_rules! impl_display_object {
         fn color_transform(&self) -> &crate::color_transform::ColorTransform {
             self.$field.color_transform()
         }
-        fn set_color_transform(&mut self, color_transform: &crate::color_transform::ColorTransform) {
+        fn set_color_transform(
+            &mut self,
+            color_transform: &crate::color_transform::ColorTransform,
+        ) {
             self.$field.set_color_transform(color_transform)
         }
         fn name(&self) -> &str {
@@ -172,6 +390,30 @@ macro_rules! impl_display_object {
         fn set_parent(&mut self, parent: Option<crate::display_object::DisplayNode<'gc>>) {
             self.$field.set_parent(parent)
         }
+        fn visible(&self) -> bool {
+            self.$field.visible()
+        }
+        fn set_visible(&mut self, visible: bool) {
+            self.$field.set_visible(visible)
+        }
+        fn blend_mode(&self) -> crate::backend::render::BlendMode {
+            self.$field.blend_mode()
+        }
+        fn set_blend_mode(&mut self, blend_mode: crate::backend::render::BlendMode) {
+            self.$field.set_blend_mode(blend_mode)
+        }
+        fn is_bitmap_cached(&self) -> bool {
+            self.$field.is_bitmap_cached()
+        }
+        fn set_is_bitmap_cached(&mut self, is_bitmap_cached: bool) {
+            self.$field.set_is_bitmap_cached(is_bitmap_cached)
+        }
+        fn filters(&self) -> &[swf::Filter] {
+            self.$field.filters()
+        }
+        fn set_filters(&mut self, filters: Vec<swf::Filter>) {
+            self.$field.set_filters(filters)
+        }
         fn box_clone(&self) -> Box<dyn crate::display_object::DisplayObject<'gc>> {
             Box::new(self.clone())
         }
@@ -182,3 +424,26 @@ macro_rules! impl_display_object {
 /// TODO(Herschel): The extra Box here is necessary to hold the trait object inside a GC pointer,
 /// but this is an extra allocation... Can we avoid this, maybe with a DST?
 pub type DisplayNode<'gc> = GcCell<'gc, Box<dyn DisplayObject<'gc>>>;
+
+/// Returns the matrix that transforms the local coordinates of `node` into stage coordinates.
+pub fn world_matrix(node: DisplayNode<'_>) -> Matrix {
+    node.read().world_matrix()
+}
+
+/// Renders `display_object` with its filters, compositing it onto the content beneath it
+/// with its blend mode. Display objects with filters or a blend mode, or that are cached
+/// as a bitmap, are rendered as a whole into a layer first.
+pub fn render_with_effects<'gc>(
+    context: &mut RenderContext<'_, 'gc>,
+    display_object: &(dyn DisplayObject<'gc> + 'gc),
+) {
+    let blend_mode = display_object.blend_mode();
+    let filters = display_object.filters();
+    if blend_mode == BlendMode::Normal && filters.is_empty() && !display_object.is_bitmap_cached() {
+        display_object.render(context);
+        return;
+    }
+    context.renderer.push_layer();
+    display_object.render(context);
+    context.renderer.pop_layer(blend_mode, filters);
+}
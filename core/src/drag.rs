@@ -0,0 +1,218 @@
+//! Dragging movie clips with the mouse, as started by `startDrag`.
+
+use crate::display_object::world_matrix;
+use crate::prelude::*;
+use gc_arena::MutationContext;
+
+/// A display object that follows the mouse.
+#[derive(Clone)]
+pub struct DragObject<'gc> {
+    display_object: DisplayNode<'gc>,
+
+    /// The offset from the mouse to the origin of the display object, in stage coordinates.
+    offset: (Twips, Twips),
+
+    /// The rectangle that the origin of the display object is kept within,
+    /// in the coordinate space of its parent.
+    constraint: Option<BoundingBox>,
+}
+
+unsafe impl<'gc> gc_arena::Collect for DragObject<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.display_object.trace(cc);
+    }
+}
+
+impl<'gc> DragObject<'gc> {
+    /// Starts dragging `display_object` with the mouse at `mouse_position`.
+    ///
+    /// With `lock_center`, the origin of the display object snaps to the mouse.
+    /// Otherwise, the display object keeps its current offset from the mouse.
+    pub fn new(
+        display_object: DisplayNode<'gc>,
+        mouse_position: (Twips, Twips),
+        lock_center: bool,
+        constraint: Option<BoundingBox>,
+    ) -> Self {
+        let offset = if lock_center {
+            (Twips::new(0), Twips::new(0))
+        } else {
+            let matrix = world_matrix(display_object);
+            (
+                Twips::new(matrix.tx as i32) - mouse_position.0,
+                Twips::new(matrix.ty as i32) - mouse_position.1,
+            )
+        };
+        let constraint = constraint.map(|rect| BoundingBox {
+            x_min: rect.x_min.min(rect.x_max),
+            y_min: rect.y_min.min(rect.y_max),
+            x_max: rect.x_min.max(rect.x_max),
+            y_max: rect.y_min.max(rect.y_max),
+            valid: true,
+        });
+        Self {
+            display_object,
+            offset,
+            constraint,
+        }
+    }
+
+    /// Moves the display object to follow the mouse, and updates its `_droptarget`
+    /// to the clip under the mouse within `root`.
+    pub fn update(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        root: DisplayNode<'gc>,
+        mouse_position: (Twips, Twips),
+    ) {
+        let drop_target = root
+            .read()
+            .pick_clip_at(root, mouse_position, Some(self.display_object));
+
+        let mut parent_matrix = self
+            .display_object
+            .read()
+            .parent()
+            .map(world_matrix)
+            .unwrap_or_default();
+        parent_matrix.invert();
+        let (mut x, mut y) = parent_matrix
+            * (
+                mouse_position.0 + self.offset.0,
+                mouse_position.1 + self.offset.1,
+            );
+        if let Some(constraint) = &self.constraint {
+            x = x.max(constraint.x_min).min(constraint.x_max);
+            y = y.max(constraint.y_min).min(constraint.y_max);
+        }
+
+        let mut display_object = self.display_object.write(gc_context);
+        display_object.set_x(x.to_pixels() as f32);
+        display_object.set_y(y.to_pixels() as f32);
+        if let Some(clip) = display_object.as_movie_clip_mut() {
+            clip.set_drop_target(drop_target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movie_clip::MovieClip;
+    use gc_arena::{make_arena, ArenaParameters, Collect, GcCell};
+
+    #[derive(Collect)]
+    #[collect(empty_drop)]
+    struct TestRoot<'gc> {
+        root: DisplayNode<'gc>,
+        clip: DisplayNode<'gc>,
+    }
+
+    make_arena!(TestArena, TestRoot);
+
+    fn pixels(x: f64, y: f64) -> (Twips, Twips) {
+        (Twips::from_pixels(x), Twips::from_pixels(y))
+    }
+
+    fn bounds(x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> BoundingBox {
+        BoundingBox {
+            x_min: Twips::from_pixels(x_min),
+            y_min: Twips::from_pixels(y_min),
+            x_max: Twips::from_pixels(x_max),
+            y_max: Twips::from_pixels(y_max),
+            valid: true,
+        }
+    }
+
+    /// Runs `test` with a clip at (5, 5) inside a parent at (100, 50), on the stage.
+    fn with_clip(
+        test: impl for<'gc> FnOnce(MutationContext<'gc, '_>, DisplayNode<'gc>, DisplayNode<'gc>),
+    ) {
+        let mut arena = TestArena::new(ArenaParameters::default(), |gc_context| {
+            let new_clip = || -> DisplayNode<'_> {
+                GcCell::allocate(gc_context, Box::new(MovieClip::new(gc_context)))
+            };
+            let (root, parent, clip) = (new_clip(), new_clip(), new_clip());
+            for (node, child, x, y) in &[(root, parent, 100.0, 50.0), (parent, clip, 5.0, 5.0)] {
+                child.write(gc_context).set_x(*x);
+                child.write(gc_context).set_y(*y);
+                if let Some(movie_clip) = node.write(gc_context).as_movie_clip_mut() {
+                    movie_clip.add_child_from_avm(gc_context, *node, *child, "clip", 1);
+                }
+            }
+            TestRoot { root, clip }
+        });
+        arena.mutate(|gc_context, root| test(gc_context, root.root, root.clip));
+    }
+
+    fn position(clip: DisplayNode<'_>) -> (f32, f32) {
+        let clip = clip.read();
+        (clip.x(), clip.y())
+    }
+
+    #[test]
+    fn lock_center_moves_origin_to_mouse() {
+        with_clip(|gc_context, root, clip| {
+            let drag = DragObject::new(clip, pixels(0.0, 0.0), true, None);
+            drag.update(gc_context, root, pixels(150.0, 80.0));
+            // The position is in the coordinates of the parent.
+            assert_eq!(position(clip), (50.0, 30.0));
+        });
+    }
+
+    #[test]
+    fn drag_keeps_offset_from_mouse() {
+        with_clip(|gc_context, root, clip| {
+            let drag = DragObject::new(clip, pixels(110.0, 60.0), false, None);
+            drag.update(gc_context, root, pixels(130.0, 70.0));
+            assert_eq!(position(clip), (25.0, 15.0));
+        });
+    }
+
+    #[test]
+    fn constraint_clamps_position() {
+        with_clip(|gc_context, root, clip| {
+            let drag = DragObject::new(
+                clip,
+                pixels(0.0, 0.0),
+                true,
+                Some(bounds(0.0, 0.0, 20.0, 10.0)),
+            );
+            drag.update(gc_context, root, pixels(500.0, 500.0));
+            assert_eq!(position(clip), (20.0, 10.0));
+            drag.update(gc_context, root, pixels(0.0, 0.0));
+            assert_eq!(position(clip), (0.0, 0.0));
+            drag.update(gc_context, root, pixels(110.0, 500.0));
+            assert_eq!(position(clip), (10.0, 10.0));
+        });
+    }
+
+    #[test]
+    fn constraint_with_swapped_corners() {
+        with_clip(|gc_context, root, clip| {
+            let drag = DragObject::new(
+                clip,
+                pixels(0.0, 0.0),
+                true,
+                Some(bounds(20.0, 10.0, 0.0, 0.0)),
+            );
+            drag.update(gc_context, root, pixels(500.0, 0.0));
+            assert_eq!(position(clip), (20.0, 0.0));
+        });
+    }
+
+    #[test]
+    fn empty_constraint_pins_position() {
+        with_clip(|gc_context, root, clip| {
+            let drag = DragObject::new(
+                clip,
+                pixels(0.0, 0.0),
+                true,
+                Some(bounds(7.0, 3.0, 7.0, 3.0)),
+            );
+            drag.update(gc_context, root, pixels(150.0, 80.0));
+            assert_eq!(position(clip), (7.0, 3.0));
+        });
+    }
+}
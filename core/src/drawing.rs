@@ -0,0 +1,237 @@
+//! Shapes drawn at runtime by scripts, such as with `MovieClip.lineTo`.
+
+use crate::backend::render::ShapeHandle;
+use crate::player::RenderContext;
+use crate::prelude::*;
+use crate::shape_utils::shape_hit_test;
+use std::cell::Cell;
+use swf::{FillStyle, LineStyle, ShapeRecord, ShapeStyles, StyleChangeData};
+
+/// The vector drawing of a movie clip, built up one edge at a time.
+///
+/// The drawing is kept as SWF shape records, so it is rendered the same way as shapes
+/// defined in the movie. It is registered with the renderer again whenever it changes.
+#[derive(Clone, Default)]
+pub struct Drawing {
+    fill_styles: Vec<FillStyle>,
+    line_styles: Vec<LineStyle>,
+    records: Vec<ShapeRecord>,
+    bounds: BoundingBox,
+
+    /// The index of the current fill style, starting from 1.
+    fill_style: Option<u32>,
+
+    /// The index of the current line style, starting from 1.
+    line_style: Option<u32>,
+
+    /// The line width, in twips, used to grow the bounds of stroked edges.
+    line_width: Twips,
+
+    cursor: (Twips, Twips),
+
+    /// Where the current fill started. The fill is closed back to this point.
+    fill_start: (Twips, Twips),
+
+    render_handle: Cell<Option<ShapeHandle>>,
+}
+
+impl Drawing {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    /// Removes everything drawn so far, along with the current fill and line styles.
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+
+    /// Starts filling the following edges with `fill_style`, or stops filling if `None`.
+    /// Any fill in progress is closed first.
+    pub fn begin_fill(&mut self, fill_style: Option<FillStyle>) {
+        self.end_fill();
+        if let Some(fill_style) = fill_style {
+            self.fill_styles.push(fill_style);
+            let index = self.fill_styles.len() as u32;
+            self.fill_style = Some(index);
+            self.fill_start = self.cursor;
+            self.change_style(StyleChangeData {
+                move_to: Some(self.cursor),
+                fill_style_0: None,
+                fill_style_1: Some(index),
+                line_style: None,
+                new_styles: None,
+            });
+        }
+    }
+
+    /// Closes the current fill, drawing a straight edge back to where it started.
+    pub fn end_fill(&mut self) {
+        if self.fill_style.is_some() {
+            self.close_fill();
+            self.fill_style = None;
+            self.change_style(StyleChangeData {
+                move_to: None,
+                fill_style_0: None,
+                fill_style_1: Some(0),
+                line_style: None,
+                new_styles: None,
+            });
+        }
+    }
+
+    /// Strokes the following edges with `line_style`, or stops stroking if `None`.
+    pub fn set_line_style(&mut self, line_style: Option<LineStyle>) {
+        let index = if let Some(line_style) = line_style {
+            self.line_width = line_style.width;
+            self.line_styles.push(line_style);
+            let index = self.line_styles.len() as u32;
+            self.line_style = Some(index);
+            index
+        } else {
+            self.line_style = None;
+            0
+        };
+        self.change_style(StyleChangeData {
+            move_to: None,
+            fill_style_0: None,
+            fill_style_1: None,
+            line_style: Some(index),
+            new_styles: None,
+        });
+    }
+
+    pub fn move_to(&mut self, point: (Twips, Twips)) {
+        self.close_fill();
+        self.cursor = point;
+        self.fill_start = point;
+        self.change_style(StyleChangeData {
+            move_to: Some(point),
+            fill_style_0: None,
+            fill_style_1: None,
+            line_style: None,
+            new_styles: None,
+        });
+    }
+
+    pub fn line_to(&mut self, point: (Twips, Twips)) {
+        self.records.push(ShapeRecord::StraightEdge {
+            delta_x: point.0 - self.cursor.0,
+            delta_y: point.1 - self.cursor.1,
+        });
+        self.extend_bounds(self.cursor);
+        self.extend_bounds(point);
+        self.cursor = point;
+        self.render_handle.set(None);
+    }
+
+    pub fn curve_to(&mut self, control: (Twips, Twips), anchor: (Twips, Twips)) {
+        self.records.push(ShapeRecord::CurvedEdge {
+            control_delta_x: control.0 - self.cursor.0,
+            control_delta_y: control.1 - self.cursor.1,
+            anchor_delta_x: anchor.0 - control.0,
+            anchor_delta_y: anchor.1 - control.1,
+        });
+        // The curve always lies within the triangle of its control points.
+        self.extend_bounds(self.cursor);
+        self.extend_bounds(control);
+        self.extend_bounds(anchor);
+        self.cursor = anchor;
+        self.render_handle.set(None);
+    }
+
+    pub fn render(&self, context: &mut RenderContext) {
+        if self.records.is_empty() {
+            return;
+        }
+        let handle = match self.render_handle.get() {
+            Some(handle) => handle,
+            None => {
+                let handle = context.renderer.register_shape(&self.to_swf_shape());
+                self.render_handle.set(Some(handle));
+                handle
+            }
+        };
+        context
+            .renderer
+            .render_shape(handle, context.transform_stack.transform());
+    }
+
+    /// Whether `point`, in the coordinate space of the drawing, lies on a fill or line.
+    pub fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        !self.records.is_empty()
+            && self.bounds.contains(point)
+            && shape_hit_test(&self.to_swf_shape(), point)
+    }
+
+    /// Draws a straight edge back to the start of the current fill, so that it is closed.
+    /// The closing edge is not stroked.
+    fn close_fill(&mut self) {
+        if self.fill_style.is_none() || self.cursor == self.fill_start {
+            return;
+        }
+        if self.line_style.is_some() {
+            self.change_line_style(0);
+        }
+        self.line_to(self.fill_start);
+        if let Some(line_style) = self.line_style {
+            self.change_line_style(line_style);
+        }
+    }
+
+    fn change_line_style(&mut self, index: u32) {
+        self.change_style(StyleChangeData {
+            move_to: None,
+            fill_style_0: None,
+            fill_style_1: None,
+            line_style: Some(index),
+            new_styles: None,
+        });
+    }
+
+    fn change_style(&mut self, style_change: StyleChangeData) {
+        self.records.push(ShapeRecord::StyleChange(style_change));
+        self.render_handle.set(None);
+    }
+
+    fn extend_bounds(&mut self, (x, y): (Twips, Twips)) {
+        let half_width = if self.line_style.is_some() {
+            self.line_width / 2
+        } else {
+            Twips::new(0)
+        };
+        self.bounds.union(&BoundingBox {
+            x_min: x - half_width,
+            y_min: y - half_width,
+            x_max: x + half_width,
+            y_max: y + half_width,
+            valid: true,
+        });
+    }
+
+    fn to_swf_shape(&self) -> swf::Shape {
+        let bounds = swf::Rectangle {
+            x_min: self.bounds.x_min,
+            x_max: self.bounds.x_max,
+            y_min: self.bounds.y_min,
+            y_max: self.bounds.y_max,
+        };
+        swf::Shape {
+            version: 4,
+            id: 0,
+            shape_bounds: bounds.clone(),
+            edge_bounds: bounds,
+            has_fill_winding_rule: false,
+            has_non_scaling_strokes: false,
+            has_scaling_strokes: true,
+            styles: ShapeStyles {
+                fill_styles: self.fill_styles.clone(),
+                line_styles: self.line_styles.clone(),
+            },
+            shape: self.records.clone(),
+        }
+    }
+}
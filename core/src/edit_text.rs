@@ -0,0 +1,2294 @@
+//! Dynamic and input text fields, defined by `DefineEditText` tags.
+
+use crate::avm1;
+use crate::display_object::{render_with_effects, DisplayObject, DisplayObjectBase};
+use crate::drawing::Drawing;
+use crate::events::{key_code, KeyCode};
+use crate::font::Font;
+use crate::html::{parse_html, to_html, HtmlImage};
+use crate::library::Library;
+use crate::player::{RenderContext, UpdateContext};
+use crate::prelude::*;
+use crate::text_format::{FormatSpans, TextFormat};
+use crate::transform::Transform;
+use gc_arena::{GcCell, MutationContext};
+use std::rc::Rc;
+use swf::{FillStyle, LineStyle, TextAlign};
+
+/// The gap between the edges of a text field and its text, in twips.
+const GUTTER: i32 = 40;
+
+/// The font size of text fields that don't give one, in twips.
+const DEFAULT_FONT_SIZE: u16 = 240;
+
+/// How long the caret is shown, and then hidden, as it blinks, in milliseconds.
+const CARET_BLINK_INTERVAL: u64 = 500;
+
+/// The distance between the tab stops of paragraphs that don't set their own, in twips.
+const DEFAULT_TAB_STOP: i32 = 720;
+
+/// The space taken by the bullet of a list item, left of the text of the item, in twips.
+const BULLET_INDENT: i32 = 360;
+
+/// A text field whose text can change at runtime, through scripts, through the variable
+/// that it is bound to, or, for input text fields, through the keyboard.
+#[derive(Clone)]
+pub struct EditText<'gc> {
+    base: DisplayObjectBase<'gc>,
+    static_data: Rc<swf::EditText>,
+
+    /// The text shown by the text field, without any HTML markup.
+    text: String,
+
+    /// The formats of the characters of `text`.
+    spans: FormatSpans,
+
+    /// The format given to new text, whether it is set by a script or typed by the user.
+    /// All of its fields are set.
+    text_format: TextFormat,
+
+    /// Whether `htmlText` and the variable of the text field are read and written as HTML.
+    is_html: bool,
+
+    /// The images placed in the text with `<img>`.
+    images: Vec<TextImage<'gc>>,
+
+    /// The path of the variable that this text field is bound to, such as `score`
+    /// or `_root.hud:score`. Empty if the text field is not bound to a variable.
+    variable_name: String,
+
+    /// The font of the text field, used for text whose own font isn't embedded.
+    font_id: Option<CharacterId>,
+
+    bounds: BoundingBox,
+    is_multiline: bool,
+    is_word_wrap: bool,
+    has_border: bool,
+    has_background: bool,
+    border_color: Color,
+    background_color: Color,
+
+    /// Whether the user can type into the text field, as with the `input` type.
+    is_editable: bool,
+
+    /// Whether the user can select the text with the mouse and the keyboard.
+    is_selectable: bool,
+
+    /// Whether the text is hidden behind asterisks.
+    is_password: bool,
+
+    /// The greatest number of UTF-16 code units that the user can enter, or `None` for no limit.
+    max_chars: Option<usize>,
+
+    /// The characters that the user can enter, or `None` to allow any.
+    restrict: Option<CharacterSet>,
+
+    /// The selected text while the text field has the focus, or `None` while it doesn't.
+    selection: Option<TextSelection>,
+
+    /// The time at which the caret started blinking, or `None` to restart it
+    /// at the next frame, after the selection was changed.
+    caret_time: Option<u64>,
+    is_caret_visible: bool,
+
+    /// The first visible line, starting from 1.
+    scroll: usize,
+
+    lines: Vec<LayoutLine>,
+
+    /// The styles that the glyphs of `lines` are drawn with.
+    styles: Vec<GlyphStyle>,
+
+    /// The border and background, redrawn whenever they or the bounds change.
+    border_drawing: Drawing,
+
+    /// A rectangle covering the bounds, used as a mask to clip the text.
+    mask_drawing: Drawing,
+
+    /// A square of one pixel, scaled to draw the caret, the selection highlight
+    /// and underlines.
+    highlight_drawing: Drawing,
+
+    object: Option<GcCell<'gc, avm1::Object<'gc>>>,
+}
+
+/// An image placed in the text of a text field with `<img>`.
+#[derive(Clone)]
+struct TextImage<'gc> {
+    html: HtmlImage,
+
+    /// The display object showing the symbol exported as `html.src`.
+    node: Option<DisplayNode<'gc>>,
+
+    /// Whether the display object has been created, or found to be missing. Images are
+    /// created at the next frame after the text is set, as that needs the AVM.
+    is_placed: bool,
+
+    /// The size of the image, without the space around it.
+    width: Twips,
+    height: Twips,
+}
+
+impl<'gc> TextImage<'gc> {
+    fn new(html: HtmlImage) -> Self {
+        Self {
+            width: html.width.unwrap_or_default(),
+            height: html.height.unwrap_or_default(),
+            html,
+            node: None,
+            is_placed: false,
+        }
+    }
+}
+
+/// The selected text of a focused text field, from the character index `anchor`, where
+/// the selection was started, to the character index `caret`.
+/// When nothing is selected, the two are the same, and the caret is shown there instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextSelection {
+    anchor: usize,
+    caret: usize,
+}
+
+impl TextSelection {
+    pub fn new(anchor: usize, caret: usize) -> Self {
+        Self { anchor, caret }
+    }
+
+    /// An empty selection, with the caret before the character at `index`.
+    pub fn at(index: usize) -> Self {
+        Self::new(index, index)
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn start(&self) -> usize {
+        self.anchor.min(self.caret)
+    }
+
+    pub fn end(&self) -> usize {
+        self.anchor.max(self.caret)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.caret
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        index >= self.start() && index < self.end()
+    }
+
+    fn clamp(self, len: usize) -> Self {
+        Self::new(self.anchor.min(len), self.caret.min(len))
+    }
+}
+
+/// How a text field changed after a key press or a typed character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EditResult {
+    Unchanged,
+    SelectionChanged,
+    TextChanged,
+}
+
+/// The characters allowed by the `restrict` property of a text field, such as `A-Z0-9`.
+///
+/// `^` switches between allowing and excluding the characters that follow it, so `^0-9`
+/// allows anything but digits, and `\` escapes the next character.
+#[derive(Clone, Debug)]
+struct CharacterSet {
+    source: String,
+
+    /// Ranges of characters that are allowed or excluded. Later ranges take precedence.
+    ranges: Vec<(char, char, bool)>,
+
+    /// Whether characters outside of all ranges are allowed, which they are when
+    /// the set starts by excluding characters.
+    allows_others: bool,
+}
+
+impl CharacterSet {
+    fn parse(source: &str) -> Self {
+        let mut ranges = vec![];
+        let mut is_allowed = true;
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            let start = match c {
+                '^' => {
+                    is_allowed = !is_allowed;
+                    continue;
+                }
+                '\\' => match chars.next() {
+                    Some(c) => c,
+                    None => break,
+                },
+                c => c,
+            };
+            let mut end = start;
+            if chars.peek() == Some(&'-') {
+                chars.next();
+                end = match chars.next() {
+                    Some('\\') => chars.next().unwrap_or('\\'),
+                    Some(c) => c,
+                    // A trailing `-` is a character of its own.
+                    None => {
+                        ranges.push(('-', '-', is_allowed));
+                        start
+                    }
+                };
+            }
+            ranges.push((start, end, is_allowed));
+        }
+        Self {
+            source: source.to_string(),
+            ranges,
+            allows_others: source.starts_with('^'),
+        }
+    }
+
+    fn allows(&self, c: char) -> bool {
+        self.ranges
+            .iter()
+            .rev()
+            .find(|&&(start, end, _)| c >= start && c <= end)
+            .map_or(self.allows_others, |&(_, _, is_allowed)| is_allowed)
+    }
+
+    /// Returns the character to enter for `c`, which is `c` in the other case
+    /// if only that case is allowed, or `None` if neither is.
+    fn filter(&self, c: char) -> Option<char> {
+        if self.allows(c) {
+            return Some(c);
+        }
+        let other_case: Vec<char> = if c.is_lowercase() {
+            c.to_uppercase().collect()
+        } else {
+            c.to_lowercase().collect()
+        };
+        match other_case[..] {
+            [other_case] if self.allows(other_case) => Some(other_case),
+            _ => None,
+        }
+    }
+}
+
+/// A line of text, as laid out to fit the width of the text field.
+#[derive(Clone, Debug)]
+struct LayoutLine {
+    glyphs: Vec<LayoutGlyph>,
+
+    /// The index of the first character of the line in the text.
+    start: usize,
+
+    /// The index of the character after the line in the text, not counting the line break.
+    end: usize,
+
+    /// The offset of the line from the left of the text area, set by the margins,
+    /// the indents and the alignment.
+    x: Twips,
+
+    /// The offset of the top of the line from the top of the first line.
+    y: Twips,
+
+    /// The width of the line, not counting any trailing spaces.
+    width: Twips,
+
+    /// The distances from the baseline to the top and the bottom of the line,
+    /// which fit the largest font on the line.
+    ascent: Twips,
+    descent: Twips,
+
+    /// The extra space between this line and the next.
+    leading: Twips,
+
+    /// Whether this is the last line of a paragraph, rather than a line broken by word wrap.
+    ends_paragraph: bool,
+
+    /// The alignment of the paragraph of the line.
+    align: TextAlign,
+
+    /// The space kept free on the left and the right of the line, for the margins,
+    /// the indents and any bullet.
+    left: Twips,
+    right: Twips,
+
+    /// The bullet of the first line of a list item, positioned from the left of the
+    /// text area.
+    bullet: Option<LayoutGlyph>,
+
+    /// The index of the image that this line shows instead of text.
+    image: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct LayoutGlyph {
+    c: char,
+
+    /// The index of the character in the text.
+    text_index: usize,
+
+    /// The index of the glyph in the font, or `None` if the font has no glyph for `c`.
+    index: Option<usize>,
+
+    /// The position of the glyph from the start of the line.
+    x: Twips,
+    advance: Twips,
+
+    /// The index of the style of the glyph in the styles of the layout.
+    style: usize,
+}
+
+/// How the glyphs of a run of text with the same format are drawn.
+#[derive(Clone, Debug)]
+struct GlyphStyle {
+    font_id: Option<CharacterId>,
+
+    /// The factor that scales glyphs from EM units to twips.
+    scale: f32,
+
+    ascent: Twips,
+    descent: Twips,
+    color: Color,
+    is_underlined: bool,
+
+    /// The link of the text and its target window, or empty strings if it isn't a link.
+    url: String,
+    target: String,
+}
+
+impl GlyphStyle {
+    fn new(format: &TextFormat, font_id: Option<CharacterId>, metrics: &FontMetrics) -> Self {
+        Self {
+            font_id,
+            scale: metrics.scale(),
+            ascent: metrics.ascent(),
+            descent: metrics.descent(),
+            color: format.color.clone().unwrap_or_else(black),
+            is_underlined: format.underline == Some(true),
+            url: format.url.clone().unwrap_or_default(),
+            target: format.target.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The measurements of a font at a font size.
+struct FontMetrics<'a> {
+    font: Option<&'a Font>,
+    size: f32,
+}
+
+impl<'a> FontMetrics<'a> {
+    /// The factor that scales glyphs from EM units to twips.
+    fn scale(&self) -> f32 {
+        match self.font {
+            Some(font) => self.size / font.scale(),
+            None => self.size / 1024.0,
+        }
+    }
+
+    /// Returns the glyph index and the advance of the character `c`.
+    ///
+    /// Without the glyphs of the font, the text is measured with rough proportions
+    /// so that layout, scrolling and auto-sizing still behave sensibly.
+    fn glyph(&self, c: char) -> (Option<usize>, Twips) {
+        if let Some(font) = self.font {
+            if let Some(index) = font.glyph_index_for_char(c) {
+                let advance = f32::from(font.glyph_advance(index)) * self.scale();
+                return (Some(index), Twips::new(advance.round() as i32));
+            }
+        }
+        let advance = if c == ' ' {
+            self.size * 0.25
+        } else {
+            self.size * 0.5
+        };
+        (None, Twips::new(advance.round() as i32))
+    }
+
+    fn kerning(&self, left: char, right: char) -> Twips {
+        match self.font {
+            Some(font) => {
+                let offset = f32::from(font.kerning_offset(left, right)) * self.scale();
+                Twips::new(offset.round() as i32)
+            }
+            None => Twips::new(0),
+        }
+    }
+
+    fn ascent(&self) -> Twips {
+        match self.font {
+            Some(font) => Twips::new((f32::from(font.ascent()) * self.scale()).round() as i32),
+            None => Twips::new((self.size * 0.8).round() as i32),
+        }
+    }
+
+    fn descent(&self) -> Twips {
+        match self.font {
+            Some(font) => Twips::new((f32::from(font.descent()) * self.scale()).round() as i32),
+            None => Twips::new((self.size * 0.2).round() as i32),
+        }
+    }
+}
+
+impl<'gc> EditText<'gc> {
+    pub fn from_swf_tag(tag: &swf::EditText, library: &Library<'gc>) -> Self {
+        let font_id = find_font(tag, library);
+        if font_id.is_none() {
+            log::warn!(
+                "Text field {} uses a device font, which can't be drawn yet",
+                tag.id
+            );
+        }
+        let text_format = initial_text_format(tag, library);
+        let mut edit_text = Self {
+            base: Default::default(),
+            static_data: Rc::new(tag.clone()),
+            text: String::new(),
+            spans: FormatSpans::new(0, text_format.clone()),
+            text_format,
+            is_html: tag.is_html,
+            images: vec![],
+            variable_name: tag.variable_name.clone(),
+            font_id,
+            bounds: tag.bounds.clone().into(),
+            is_multiline: tag.is_multiline,
+            is_word_wrap: tag.is_word_wrap,
+            has_border: tag.has_border,
+            has_background: tag.has_border,
+            border_color: black(),
+            background_color: Color {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            },
+            is_editable: !tag.is_read_only,
+            is_selectable: tag.is_selectable,
+            is_password: tag.is_password,
+            max_chars: tag
+                .max_length
+                .filter(|&max_length| max_length > 0)
+                .map(usize::from),
+            restrict: None,
+            selection: None,
+            caret_time: None,
+            is_caret_visible: false,
+            scroll: 1,
+            lines: vec![],
+            styles: vec![],
+            border_drawing: Drawing::new(),
+            mask_drawing: Drawing::new(),
+            highlight_drawing: pixel_drawing(),
+            object: None,
+        };
+        let initial_text = tag.initial_text.clone().unwrap_or_default();
+        if tag.is_html {
+            edit_text.set_html_text(initial_text, library);
+        } else {
+            edit_text.set_text(initial_text, library);
+        }
+        edit_text
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text of the text field. The new text gets the new text format.
+    pub fn set_text(&mut self, text: String, library: &Library<'gc>) {
+        self.spans = FormatSpans::new(text.chars().count(), self.text_format.clone());
+        self.text = text;
+        self.images.clear();
+        self.relayout(library);
+    }
+
+    /// Replaces the text of the text field with the text of an HTML document,
+    /// formatted by its markup.
+    pub fn set_html_text(&mut self, html_text: String, library: &Library<'gc>) {
+        let html = parse_html(&html_text, &self.text_format);
+        self.text = html.text;
+        self.spans = html.spans;
+        self.images = html.images.into_iter().map(TextImage::new).collect();
+        self.relayout(library);
+    }
+
+    /// The text of the text field, marked up with its formatting for HTML text fields.
+    pub fn html_text(&self) -> String {
+        if self.is_html {
+            let images: Vec<HtmlImage> =
+                self.images.iter().map(|image| image.html.clone()).collect();
+            to_html(&self.text, &self.spans, &images)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    pub fn is_html(&self) -> bool {
+        self.is_html
+    }
+
+    pub fn set_is_html(&mut self, is_html: bool) {
+        self.is_html = is_html;
+    }
+
+    pub fn variable_name(&self) -> &str {
+        &self.variable_name
+    }
+
+    pub fn set_variable_name(&mut self, variable_name: String) {
+        self.variable_name = variable_name;
+    }
+
+    /// The value that this text field gives its variable: the HTML source for HTML text
+    /// fields, and the plain text otherwise.
+    pub fn variable_value(&self) -> String {
+        if self.is_html {
+            self.html_text()
+        } else {
+            self.text.clone()
+        }
+    }
+
+    /// Shows the new value of the variable that this text field is bound to.
+    pub fn set_variable_value(&mut self, value: String, library: &Library<'gc>) {
+        if self.is_html {
+            self.set_html_text(value, library);
+        } else {
+            self.set_text(value, library);
+        }
+    }
+
+    /// The color of new text.
+    pub fn text_color(&self) -> Color {
+        self.text_format.color.clone().unwrap_or_else(black)
+    }
+
+    /// Colors all of the text, and any new text, in `color`.
+    pub fn set_text_color(&mut self, color: Color, library: &Library<'gc>) {
+        let format = TextFormat {
+            color: Some(color),
+            ..Default::default()
+        };
+        self.text_format.merge(&format);
+        self.set_text_format(0, self.text.chars().count(), &format, library);
+    }
+
+    /// The format shared by the characters from `start` up to `end`. Fields that differ
+    /// between the characters are `None`.
+    pub fn text_format(&self, start: usize, end: usize) -> TextFormat {
+        let end = end.min(self.text.chars().count());
+        self.spans.format_of(start.min(end), end)
+    }
+
+    /// Applies the set fields of `format` to the characters from `start` up to `end`.
+    pub fn set_text_format(
+        &mut self,
+        start: usize,
+        end: usize,
+        format: &TextFormat,
+        library: &Library<'gc>,
+    ) {
+        let end = end.min(self.text.chars().count());
+        self.spans.set_format(start.min(end), end, format);
+        self.relayout(library);
+    }
+
+    /// The format given to new text, whether it is set by a script or typed by the user.
+    pub fn new_text_format(&self) -> TextFormat {
+        self.text_format.clone()
+    }
+
+    /// Applies the set fields of `format` to the format of new text.
+    pub fn set_new_text_format(&mut self, format: &TextFormat) {
+        self.text_format.merge(format);
+    }
+
+    /// The link under `point`, in global coordinates, as its URL and its target window.
+    pub fn link_at_point(&self, point: (Twips, Twips)) -> Option<(String, String)> {
+        if self.lines.is_empty() || !self.hit_test(point) {
+            return None;
+        }
+        let (x, y) = self.global_to_local(point);
+        let line = &self.lines[self.line_at_y(y)];
+        let glyph = line.glyph_at_x(x - self.bounds.x_min - Twips::new(GUTTER))?;
+        let style = &self.styles[glyph.style];
+        if style.url.is_empty() {
+            None
+        } else {
+            Some((style.url.clone(), style.target.clone()))
+        }
+    }
+    pub fn is_multiline(&self) -> bool {
+        self.is_multiline
+    }
+
+    pub fn set_is_multiline(&mut self, is_multiline: bool, library: &Library<'gc>) {
+        self.is_multiline = is_multiline;
+        self.relayout(library);
+    }
+
+    pub fn is_word_wrap(&self) -> bool {
+        self.is_word_wrap
+    }
+
+    pub fn set_is_word_wrap(&mut self, is_word_wrap: bool, library: &Library<'gc>) {
+        self.is_word_wrap = is_word_wrap;
+        self.relayout(library);
+    }
+
+    pub fn has_border(&self) -> bool {
+        self.has_border
+    }
+
+    pub fn set_has_border(&mut self, has_border: bool) {
+        self.has_border = has_border;
+        self.redraw_border();
+    }
+
+    pub fn has_background(&self) -> bool {
+        self.has_background
+    }
+
+    pub fn set_has_background(&mut self, has_background: bool) {
+        self.has_background = has_background;
+        self.redraw_border();
+    }
+
+    pub fn border_color(&self) -> Color {
+        self.border_color.clone()
+    }
+
+    pub fn set_border_color(&mut self, color: Color) {
+        self.border_color = color;
+        self.redraw_border();
+    }
+
+    pub fn background_color(&self) -> Color {
+        self.background_color.clone()
+    }
+
+    pub fn set_background_color(&mut self, color: Color) {
+        self.background_color = color;
+        self.redraw_border();
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.is_editable
+    }
+
+    pub fn set_is_editable(&mut self, is_editable: bool) {
+        self.is_editable = is_editable;
+    }
+
+    pub fn is_selectable(&self) -> bool {
+        self.is_selectable
+    }
+
+    pub fn set_is_selectable(&mut self, is_selectable: bool) {
+        self.is_selectable = is_selectable;
+    }
+
+    /// Whether the text field takes the focus when it is clicked.
+    pub fn is_focusable(&self) -> bool {
+        self.is_editable || self.is_selectable
+    }
+
+    pub fn is_password(&self) -> bool {
+        self.is_password
+    }
+
+    pub fn set_is_password(&mut self, is_password: bool, library: &Library<'gc>) {
+        self.is_password = is_password;
+        self.relayout(library);
+    }
+
+    pub fn max_chars(&self) -> Option<usize> {
+        self.max_chars
+    }
+
+    pub fn set_max_chars(&mut self, max_chars: Option<usize>) {
+        self.max_chars = max_chars;
+    }
+
+    pub fn restrict(&self) -> Option<&str> {
+        self.restrict
+            .as_ref()
+            .map(|restrict| restrict.source.as_str())
+    }
+
+    pub fn set_restrict(&mut self, restrict: Option<&str>) {
+        self.restrict = restrict.map(CharacterSet::parse);
+    }
+
+    pub fn selection(&self) -> Option<TextSelection> {
+        self.selection
+    }
+
+    /// The selection with its indices in UTF-16 code units, as scripts see them.
+    pub fn utf16_selection(&self) -> Option<TextSelection> {
+        self.selection.map(|selection| {
+            TextSelection::new(
+                utf16_index(&self.text, selection.anchor),
+                utf16_index(&self.text, selection.caret),
+            )
+        })
+    }
+
+    /// Selects text given by indices in UTF-16 code units, as scripts give them.
+    pub fn set_utf16_selection(&mut self, selection: TextSelection) {
+        let selection = TextSelection::new(
+            char_index(&self.text, selection.anchor),
+            char_index(&self.text, selection.caret),
+        );
+        self.set_selection(Some(selection));
+    }
+
+    /// Selects text in the text field, or hides the selection and the caret
+    /// when the text field loses the focus.
+    pub fn set_selection(&mut self, selection: Option<TextSelection>) {
+        let len = self.text.chars().count();
+        self.selection = selection.map(|selection| selection.clamp(len));
+        self.caret_time = None;
+        self.is_caret_visible = true;
+    }
+
+    /// Places the caret at the character nearest to `point`, in global coordinates.
+    /// If `extend` is set, as when the mouse is dragged, the selection is extended
+    /// to the point instead.
+    pub fn select_at_point(&mut self, point: (Twips, Twips), extend: bool) {
+        let index = self.index_at_point(point);
+        let selection = match self.selection {
+            Some(selection) if extend && self.is_selectable => {
+                TextSelection::new(selection.anchor, index)
+            }
+            _ => TextSelection::at(index),
+        };
+        if self.selection != Some(selection) {
+            self.set_selection(Some(selection));
+            self.scroll_to_caret();
+        }
+    }
+
+    /// Edits the text or moves the caret for a key pressed while the text field has the focus.
+    /// The arrow keys extend the selection while shift is held.
+    pub fn key_down(
+        &mut self,
+        key_code: KeyCode,
+        is_shift_down: bool,
+        library: &Library<'gc>,
+    ) -> EditResult {
+        let selection = match self.selection {
+            Some(selection) => selection,
+            None => return EditResult::Unchanged,
+        };
+        let caret = selection.caret;
+        let len = self.text.chars().count();
+        let collapses = !selection.is_empty() && !is_shift_down;
+        let new_caret = match key_code {
+            key_code::BACKSPACE | key_code::DELETE if self.is_editable => {
+                // Without a selection, the character before or after the caret is deleted.
+                if selection.is_empty() {
+                    let deleted = if key_code == key_code::BACKSPACE {
+                        TextSelection::new(caret.saturating_sub(1), caret)
+                    } else {
+                        TextSelection::new(caret, (caret + 1).min(len))
+                    };
+                    self.selection = Some(deleted);
+                }
+                return self.replace_selection("", library);
+            }
+            key_code::ENTER if self.is_editable && self.is_multiline => {
+                return self.replace_selection("\r", library);
+            }
+            key_code::LEFT if collapses => selection.start(),
+            key_code::LEFT => caret.saturating_sub(1),
+            key_code::RIGHT if collapses => selection.end(),
+            key_code::RIGHT => (caret + 1).min(len),
+            key_code::HOME => self.lines[self.line_at_index(caret)].start,
+            key_code::END => self.lines[self.line_at_index(caret)].last_caret_index(),
+            key_code::UP | key_code::DOWN => {
+                let line = self.line_at_index(caret);
+                let target_line = if key_code == key_code::UP {
+                    line.checked_sub(1)
+                } else {
+                    Some(line + 1).filter(|&line| line < self.lines.len())
+                };
+                match target_line {
+                    Some(target_line) => {
+                        let x = self.lines[line].x_at_index(caret);
+                        self.lines[target_line].index_at_x(x)
+                    }
+                    None if key_code == key_code::UP => 0,
+                    None => len,
+                }
+            }
+            _ => return EditResult::Unchanged,
+        };
+        let new_selection = if is_shift_down && self.is_selectable {
+            TextSelection::new(selection.anchor, new_caret)
+        } else {
+            TextSelection::at(new_caret)
+        };
+        if new_selection == selection {
+            return EditResult::Unchanged;
+        }
+        self.set_selection(Some(new_selection));
+        self.scroll_to_caret();
+        EditResult::SelectionChanged
+    }
+
+    /// Types the character `c` over the selected text of an input text field.
+    pub fn text_input(&mut self, c: char, library: &Library<'gc>) -> EditResult {
+        if !self.is_editable || c.is_control() {
+            return EditResult::Unchanged;
+        }
+        self.replace_selection(c.encode_utf8(&mut [0; 4]), library)
+    }
+
+    /// Replaces the selected text with `text`, leaving the caret after it.
+    /// Characters that `restrict` doesn't allow, and any beyond `max_chars`, are dropped.
+    /// The new text gets the new text format.
+    fn replace_selection(&mut self, text: &str, library: &Library<'gc>) -> EditResult {
+        let selection = match self.selection {
+            Some(selection) => selection,
+            None => return EditResult::Unchanged,
+        };
+        let (start, end) = (selection.start(), selection.end());
+        let start_byte = byte_index(&self.text, start);
+        let end_byte = byte_index(&self.text, end);
+        // Like `length`, `maxChars` counts UTF-16 code units.
+        let remaining_units = self.max_chars.map_or(usize::max_value(), |max_chars| {
+            let selected_units = self.text[start_byte..end_byte].encode_utf16().count();
+            let kept_units = self.text.encode_utf16().count() - selected_units;
+            max_chars.saturating_sub(kept_units)
+        });
+        let mut inserted_units = 0;
+        let inserted: String = text
+            .chars()
+            .filter_map(|c| self.filter_char(c))
+            .take_while(|c| {
+                inserted_units += c.len_utf16();
+                inserted_units <= remaining_units
+            })
+            .collect();
+        // Typing a character that isn't allowed leaves the selected text in place.
+        if inserted.is_empty() && (!text.is_empty() || start == end) {
+            return EditResult::Unchanged;
+        }
+
+        let mut new_text = String::with_capacity(self.text.len() + inserted.len());
+        new_text.push_str(&self.text[..start_byte]);
+        new_text.push_str(&inserted);
+        new_text.push_str(&self.text[end_byte..]);
+        self.text = new_text;
+
+        let inserted_len = inserted.chars().count();
+        self.spans
+            .replace(start, end, inserted_len, self.text_format.clone());
+        for image in &mut self.images {
+            let text_index = &mut image.html.text_index;
+            if *text_index >= end {
+                *text_index = *text_index - (end - start) + inserted_len;
+            } else if *text_index > start {
+                *text_index = start;
+            }
+        }
+        self.relayout(library);
+        self.set_selection(Some(TextSelection::at(start + inserted_len)));
+        self.scroll_to_caret();
+        EditResult::TextChanged
+    }
+
+    /// Returns the character to enter for `c`, or `None` if the text field doesn't allow it.
+    fn filter_char(&self, c: char) -> Option<char> {
+        if c == '\r' || c == '\n' {
+            return if self.is_multiline { Some('\r') } else { None };
+        }
+        match &self.restrict {
+            Some(restrict) => restrict.filter(c),
+            None => Some(c),
+        }
+    }
+
+    /// The number of the line holding the caret position `index`, starting from 0.
+    fn line_at_index(&self, index: usize) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.start <= index)
+            .unwrap_or(0)
+    }
+
+    /// The number of the line at `y`, in local coordinates, starting from 0.
+    fn line_at_y(&self, y: Twips) -> usize {
+        let y = (y - self.bounds.y_min - Twips::new(GUTTER)).max(Twips::new(0)) + self.scroll_y();
+        self.lines.iter().rposition(|line| line.y <= y).unwrap_or(0)
+    }
+
+    /// The caret position nearest to `point`, in global coordinates.
+    fn index_at_point(&self, point: (Twips, Twips)) -> usize {
+        if self.lines.is_empty() {
+            return 0;
+        }
+        let (x, y) = self.global_to_local(point);
+        let line = self.line_at_y(y);
+        self.lines[line].index_at_x(x - self.bounds.x_min - Twips::new(GUTTER))
+    }
+
+    /// Scrolls the line holding the caret into view.
+    fn scroll_to_caret(&mut self) {
+        if let Some(selection) = self.selection {
+            let line = self.line_at_index(selection.caret) + 1;
+            if line < self.scroll {
+                self.scroll = line;
+            } else if line > self.bottom_scroll() {
+                // Scroll just far enough for the bottom of the caret line to show.
+                let bottom = self.lines[line - 1].bottom();
+                let height = self.text_area_height();
+                let first_line = self
+                    .lines
+                    .iter()
+                    .position(|line| bottom - line.y <= height)
+                    .unwrap_or(line - 1)
+                    .min(line - 1);
+                self.scroll = first_line + 1;
+            }
+        }
+    }
+
+    /// The offset of the first visible line from the top of the first line.
+    fn scroll_y(&self) -> Twips {
+        self.lines
+            .get(self.scroll - 1)
+            .map_or_else(|| Twips::new(0), |line| line.y)
+    }
+
+    /// The lines that fit in the text field from `scroll` on, with their numbers
+    /// starting from 0, and the positions of their tops.
+    fn visible_lines(&self) -> impl Iterator<Item = (usize, &LayoutLine, Twips)> {
+        let top = self.bounds.y_min + Twips::new(GUTTER) - self.scroll_y();
+        let bottom = self.bounds.y_max;
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll - 1)
+            .map(move |(i, line)| (i, line, top + line.y))
+            .take_while(move |&(_, _, line_top)| line_top <= bottom)
+    }
+
+    /// Draws `glyph` in `color`, with its origin at `(x, y)`.
+    fn render_glyph(
+        &self,
+        context: &mut RenderContext<'_, 'gc>,
+        glyph: &LayoutGlyph,
+        (x, y): (Twips, Twips),
+        color: &Color,
+    ) {
+        let style = &self.styles[glyph.style];
+        let shape = style
+            .font_id
+            .and_then(|id| context.library.get_font(id))
+            .and_then(|font| glyph.index.and_then(|index| font.get_glyph(index)));
+        if let Some(shape) = shape {
+            let mut transform: Transform = Default::default();
+            transform.matrix.a = style.scale;
+            transform.matrix.d = style.scale;
+            transform.matrix.tx = x.get() as f32;
+            transform.matrix.ty = y.get() as f32;
+            set_color(&mut transform, color);
+            context.transform_stack.push(&transform);
+            context
+                .renderer
+                .render_shape(shape, context.transform_stack.transform());
+            context.transform_stack.pop();
+        }
+    }
+
+    /// Fills a rectangle with `color` by scaling the one pixel square of `highlight_drawing`.
+    fn render_rectangle(
+        &self,
+        context: &mut RenderContext<'_, 'gc>,
+        (x, y): (Twips, Twips),
+        (width, height): (Twips, Twips),
+        color: &Color,
+    ) {
+        let mut transform: Transform = Default::default();
+        transform.matrix.a = width.get() as f32 / 20.0;
+        transform.matrix.d = height.get() as f32 / 20.0;
+        transform.matrix.tx = x.get() as f32;
+        transform.matrix.ty = y.get() as f32;
+        set_color(&mut transform, color);
+        context.transform_stack.push(&transform);
+        self.highlight_drawing.render(context);
+        context.transform_stack.pop();
+    }
+
+    /// The first visible line, starting from 1.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Scrolls the text so that `scroll` is the first visible line.
+    pub fn set_scroll(&mut self, scroll: usize) {
+        self.scroll = scroll.max(1).min(self.max_scroll());
+    }
+
+    /// The greatest value of `scroll`, at which the last line is at the bottom of the field.
+    pub fn max_scroll(&self) -> usize {
+        let bottom = self.text_height();
+        let height = self.text_area_height();
+        let first_line = self
+            .lines
+            .iter()
+            .position(|line| bottom - line.y <= height)
+            .unwrap_or_else(|| self.lines.len().saturating_sub(1));
+        first_line + 1
+    }
+
+    /// The last visible line, starting from 1.
+    pub fn bottom_scroll(&self) -> usize {
+        (self.scroll + self.fitting_line_count(self.scroll - 1)).saturating_sub(1)
+    }
+
+    /// The width of the widest line of text.
+    pub fn text_width(&self) -> Twips {
+        self.lines
+            .iter()
+            .map(|line| line.width)
+            .max()
+            .unwrap_or_else(|| Twips::new(0))
+    }
+
+    /// The height of all lines of text.
+    pub fn text_height(&self) -> Twips {
+        self.lines
+            .last()
+            .map_or_else(|| Twips::new(0), LayoutLine::bottom)
+    }
+
+    /// The height of the area that the text is shown in, inside the gutter.
+    fn text_area_height(&self) -> Twips {
+        self.bounds.y_max - self.bounds.y_min - Twips::new(GUTTER * 2)
+    }
+
+    /// The number of lines from the line `first_line` on that fit in the height
+    /// of the field. At least one line is always visible.
+    fn fitting_line_count(&self, first_line: usize) -> usize {
+        let top = match self.lines.get(first_line) {
+            Some(line) => line.y,
+            None => return 0,
+        };
+        let height = self.text_area_height();
+        self.lines[first_line..]
+            .iter()
+            .take_while(|line| line.bottom() - top <= height)
+            .count()
+            .max(1)
+    }
+
+    /// Lays out the text again, after the text, the formats, the font or the bounds changed.
+    fn relayout(&mut self, library: &Library<'gc>) {
+        let wrap_width = if self.is_word_wrap {
+            Some(self.bounds.x_max - self.bounds.x_min - Twips::new(GUTTER * 2))
+        } else {
+            None
+        };
+        let text = if self.is_password {
+            self.text
+                .chars()
+                .map(|c| if c == '\r' || c == '\n' { c } else { '*' })
+                .collect()
+        } else {
+            self.text.clone()
+        };
+        let layout = layout_text(
+            &text,
+            &self.spans,
+            &self.images,
+            library,
+            self.font_id,
+            self.is_multiline,
+            wrap_width,
+        );
+        let mut lines = layout.lines;
+
+        if self.static_data.is_auto_size {
+            self.auto_size(&lines);
+        }
+
+        let text_area_width = self.bounds.x_max - self.bounds.x_min - Twips::new(GUTTER * 2);
+        for line in &mut lines {
+            let align = line.align;
+            align_line(line, &align, text_area_width - line.left - line.right);
+            line.x += line.left;
+        }
+        self.lines = lines;
+        self.styles = layout.styles;
+        self.scroll = self.scroll.max(1).min(self.max_scroll());
+        let len = self.text.chars().count();
+        self.selection = self.selection.map(|selection| selection.clamp(len));
+        self.redraw_border();
+    }
+
+    /// Resizes the bounds to fit the text. Fields that wrap their text keep their width.
+    fn auto_size(&mut self, lines: &[LayoutLine]) {
+        if !self.is_word_wrap {
+            let text_width = lines
+                .iter()
+                .map(|line| line.left + line.width + line.right)
+                .max()
+                .unwrap_or_else(|| Twips::new(0));
+            let width = text_width + Twips::new(GUTTER * 2);
+            let old_width = self.bounds.x_max - self.bounds.x_min;
+            match self.text_format.align.unwrap_or(TextAlign::Left) {
+                TextAlign::Center => {
+                    self.bounds.x_min += (old_width - width) / 2;
+                    self.bounds.x_max = self.bounds.x_min + width;
+                }
+                TextAlign::Right => self.bounds.x_min = self.bounds.x_max - width,
+                TextAlign::Left | TextAlign::Justify => {
+                    self.bounds.x_max = self.bounds.x_min + width
+                }
+            }
+        }
+        let text_height = lines
+            .last()
+            .map_or_else(|| Twips::new(0), LayoutLine::bottom);
+        self.bounds.y_max = self.bounds.y_min + text_height + Twips::new(GUTTER * 2);
+    }
+
+    fn redraw_border(&mut self) {
+        let bounds = &self.bounds;
+        let corners = [
+            (bounds.x_min, bounds.y_min),
+            (bounds.x_max, bounds.y_min),
+            (bounds.x_max, bounds.y_max),
+            (bounds.x_min, bounds.y_max),
+        ];
+        let draw_rectangle = |drawing: &mut Drawing| {
+            drawing.move_to(corners[3]);
+            for &corner in &corners {
+                drawing.line_to(corner);
+            }
+            drawing.end_fill();
+        };
+
+        self.border_drawing.clear();
+        if self.has_border || self.has_background {
+            if self.has_border {
+                self.border_drawing.set_line_style(Some(LineStyle::new_v1(
+                    Twips::new(20),
+                    self.border_color.clone(),
+                )));
+            }
+            if self.has_background {
+                self.border_drawing
+                    .begin_fill(Some(FillStyle::Color(self.background_color.clone())));
+            }
+            draw_rectangle(&mut self.border_drawing);
+        }
+
+        self.mask_drawing.clear();
+        self.mask_drawing
+            .begin_fill(Some(FillStyle::Color(self.background_color.clone())));
+        draw_rectangle(&mut self.mask_drawing);
+    }
+}
+
+impl<'gc> DisplayObject<'gc> for EditText<'gc> {
+    impl_display_object!(base);
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    fn run_frame(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // The caret blinks while the text field has the focus.
+        if self.selection.is_some() {
+            let caret_time = *self.caret_time.get_or_insert(context.global_time);
+            let blinks = context.global_time.saturating_sub(caret_time) / CARET_BLINK_INTERVAL;
+            self.is_caret_visible = blinks % 2 == 0;
+        }
+
+        let self_node = context.active_clip;
+        for node in self.images.iter().filter_map(|image| image.node) {
+            context.active_clip = node;
+            node.write(context.gc_context).run_frame(context);
+        }
+        context.active_clip = self_node;
+
+        // Images are created from their symbols once the text is set, and the text
+        // is laid out again around their size.
+        let mut is_image_placed = false;
+        for image in self.images.iter_mut().filter(|image| !image.is_placed) {
+            image.is_placed = true;
+            let node = {
+                let library = &context.library;
+                library
+                    .character_id_by_export_name(&image.html.src)
+                    .and_then(|id| {
+                        library
+                            .instantiate_display_object(
+                                id,
+                                context.gc_context,
+                                context.avm.prototypes(),
+                            )
+                            .ok()
+                    })
+            };
+            let node = match node {
+                Some(node) => node,
+                None => {
+                    log::warn!(
+                        "Text field image {} isn't an exported symbol",
+                        image.html.src
+                    );
+                    continue;
+                }
+            };
+            {
+                let mut display_object = node.write(context.gc_context);
+                display_object.set_parent(Some(self_node));
+                display_object.set_name(&image.html.id);
+            }
+            context.active_clip = node;
+            node.write(context.gc_context).run_frame(context);
+            context.active_clip = self_node;
+
+            // The symbol is scaled to the size given by the tag, with its top left corner
+            // at the origin.
+            let mut display_object = node.write(context.gc_context);
+            let bounds = display_object.local_bounds();
+            let (width, height) = if bounds.valid {
+                (bounds.x_max - bounds.x_min, bounds.y_max - bounds.y_min)
+            } else {
+                Default::default()
+            };
+            image.width = image.html.width.unwrap_or(width);
+            image.height = image.html.height.unwrap_or(height);
+            let scale = |size: Twips, symbol_size: Twips| {
+                if symbol_size > Twips::new(0) {
+                    size.get() as f32 / symbol_size.get() as f32
+                } else {
+                    1.0
+                }
+            };
+            let matrix = display_object.matrix_mut();
+            matrix.a = scale(image.width, width);
+            matrix.d = scale(image.height, height);
+            if bounds.valid {
+                matrix.tx = -bounds.x_min.get() as f32 * matrix.a;
+                matrix.ty = -bounds.y_min.get() as f32 * matrix.d;
+            }
+            image.node = Some(node);
+            is_image_placed = true;
+        }
+        if is_image_placed {
+            self.relayout(&context.library);
+        }
+    }
+
+    fn run_post_frame(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let self_node = context.active_clip;
+        for node in self.images.iter().filter_map(|image| image.node) {
+            context.active_clip = node;
+            node.write(context.gc_context).run_post_frame(context);
+        }
+        context.active_clip = self_node;
+    }
+
+    fn render(&self, context: &mut RenderContext<'_, 'gc>) {
+        context.transform_stack.push(self.transform());
+
+        self.border_drawing.render(context);
+
+        // The text is clipped to the bounds of the field.
+        context.renderer.push_mask();
+        self.mask_drawing.render(context);
+        context.renderer.activate_mask();
+
+        let left = self.bounds.x_min + Twips::new(GUTTER);
+        let white = Color {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+
+        // Selected text is highlighted in black, and drawn in white over it.
+        let selection = self.selection.filter(|selection| !selection.is_empty());
+        if let Some(selection) = selection {
+            for (_, line, top) in self.visible_lines() {
+                if let Some((x_min, x_max)) = line.selected_range(selection) {
+                    self.render_rectangle(
+                        context,
+                        (left + line.x + x_min, top),
+                        (x_max - x_min, line.ascent + line.descent),
+                        &black(),
+                    );
+                }
+            }
+        }
+
+        for (_, line, top) in self.visible_lines() {
+            let baseline = top + line.ascent;
+            for glyph in &line.glyphs {
+                let is_selected =
+                    selection.map_or(false, |selection| selection.contains(glyph.text_index));
+                let color = if is_selected {
+                    &white
+                } else {
+                    &self.styles[glyph.style].color
+                };
+                self.render_glyph(context, glyph, (left + line.x + glyph.x, baseline), color);
+            }
+
+            for (x_min, x_max, style) in line.underlines(&self.styles) {
+                self.render_rectangle(
+                    context,
+                    (left + line.x + x_min, baseline + Twips::new(20)),
+                    (x_max - x_min, Twips::new(20)),
+                    &self.styles[style].color,
+                );
+            }
+
+            // Bullets are drawn as a small square if the font has no bullet glyph.
+            if let Some(bullet) = &line.bullet {
+                let style = &self.styles[bullet.style];
+                if bullet.index.is_some() {
+                    self.render_glyph(context, bullet, (left + bullet.x, baseline), &style.color);
+                } else {
+                    let size = (style.ascent + style.descent) / 4;
+                    self.render_rectangle(
+                        context,
+                        (left + bullet.x, baseline - style.ascent / 2 - size / 2),
+                        (size, size),
+                        &style.color,
+                    );
+                }
+            }
+
+            let image = line.image.map(|image| &self.images[image]);
+            if let Some((image, node)) = image.and_then(|image| Some((image, image.node?))) {
+                let mut transform: Transform = Default::default();
+                transform.matrix.tx = (left + line.x + image.html.hspace).get() as f32;
+                transform.matrix.ty = (top + image.html.vspace).get() as f32;
+                context.transform_stack.push(&transform);
+                render_with_effects(context, &**node.read());
+                context.transform_stack.pop();
+            }
+        }
+
+        // Input text fields show a caret when nothing is selected.
+        if let Some(selection) = self.selection {
+            if self.is_editable && selection.is_empty() && self.is_caret_visible {
+                let caret_line = self.line_at_index(selection.caret);
+                let caret = self.visible_lines().find(|&(i, _, _)| i == caret_line).map(
+                    |(_, line, top)| {
+                        let position = (left + line.x_at_index(selection.caret), top);
+                        (position, line.ascent + line.descent)
+                    },
+                );
+                if let Some((position, height)) = caret {
+                    self.render_rectangle(
+                        context,
+                        position,
+                        (Twips::new(20), height),
+                        &self.text_color(),
+                    );
+                }
+            }
+        }
+
+        context.renderer.pop_mask();
+        context.transform_stack.pop();
+    }
+
+    /// Text fields are hit anywhere within their bounds, not just on their glyphs.
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        self.bounds.contains(self.global_to_local(point))
+    }
+
+    /// Text fields that can take the focus, or that have a link under the mouse,
+    /// take the mouse from the objects below them.
+    fn mouse_pick(
+        &self,
+        self_node: DisplayNode<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<DisplayNode<'gc>> {
+        let is_picked = if self.is_focusable() {
+            self.hit_test(point)
+        } else {
+            self.link_at_point(point).is_some()
+        };
+        if is_picked {
+            Some(self_node)
+        } else {
+            None
+        }
+    }
+
+    fn propagate_mouse_wheel(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        delta: f64,
+    ) -> bool {
+        if !self.hit_test(point) {
+            return false;
+        }
+        // Scrolling up moves towards the first line.
+        let scroll = self.scroll as f64 - delta;
+        self.set_scroll(scroll.max(1.0) as usize);
+        true
+    }
+
+    fn as_edit_text(&self) -> Option<&EditText<'gc>> {
+        Some(self)
+    }
+
+    fn as_edit_text_mut(&mut self) -> Option<&mut EditText<'gc>> {
+        Some(self)
+    }
+
+    fn object(&self) -> avm1::Value<'gc> {
+        self.object
+            .map_or(avm1::Value::Undefined, avm1::Value::Object)
+    }
+
+    fn post_instantiation(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        display_object: DisplayNode<'gc>,
+        prototypes: &avm1::SystemPrototypes<'gc>,
+    ) {
+        self.object = Some(avm1::Object::display_object(
+            gc_context,
+            Some(prototypes.text_field),
+            display_object,
+        ));
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for EditText<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.base.trace(cc);
+        for image in &self.images {
+            image.node.trace(cc);
+        }
+        self.object.trace(cc);
+    }
+}
+
+/// The layout of a paragraph, given by the format of its first character.
+#[derive(Clone, Debug)]
+struct ParagraphStyle {
+    align: TextAlign,
+
+    /// The space kept free on the left of every line, for the left margin,
+    /// the block indent and any bullet.
+    left: Twips,
+    right: Twips,
+
+    /// The extra indent of the first line.
+    indent: Twips,
+    leading: Twips,
+    tab_stops: Vec<Twips>,
+    is_bullet: bool,
+}
+
+impl ParagraphStyle {
+    fn new(format: &TextFormat) -> Self {
+        let twips = |value: Option<Twips>| value.unwrap_or_default();
+        let is_bullet = format.bullet == Some(true);
+        let bullet_indent = if is_bullet {
+            Twips::new(BULLET_INDENT)
+        } else {
+            Twips::new(0)
+        };
+        Self {
+            align: format.align.unwrap_or(TextAlign::Left),
+            left: twips(format.left_margin) + twips(format.block_indent) + bullet_indent,
+            right: twips(format.right_margin),
+            indent: twips(format.indent),
+            leading: twips(format.leading),
+            tab_stops: format.tab_stops.clone().unwrap_or_default(),
+            is_bullet,
+        }
+    }
+
+    /// The position of the first tab stop after `x`.
+    fn next_tab_stop(&self, x: Twips) -> Twips {
+        match self.tab_stops.iter().find(|&&tab_stop| tab_stop > x) {
+            Some(&tab_stop) => tab_stop,
+            None => {
+                let tab_stop = Twips::new(DEFAULT_TAB_STOP);
+                tab_stop * (x.get() / DEFAULT_TAB_STOP + 1)
+            }
+        }
+    }
+}
+
+/// The lines of a text and the styles of their glyphs, as laid out by `layout_text`.
+struct Layout {
+    lines: Vec<LayoutLine>,
+    styles: Vec<GlyphStyle>,
+}
+
+/// Builds the lines of a text, one glyph at a time.
+struct LayoutBuilder {
+    lines: Vec<LayoutLine>,
+    styles: Vec<GlyphStyle>,
+
+    /// The width that lines are wrapped to, if the text is wrapped.
+    wrap_width: Option<Twips>,
+    paragraph: ParagraphStyle,
+    line: LayoutLine,
+
+    /// The position of the next glyph from the start of the line.
+    x: Twips,
+
+    /// The number of glyphs in the line up to the last space, where the line may wrap.
+    break_index: Option<usize>,
+}
+
+impl LayoutBuilder {
+    /// Starts a paragraph at the character `start`, whose first character has the format
+    /// `format`, and is drawn with `style` in the font of `metrics`.
+    fn start_paragraph(
+        &mut self,
+        start: usize,
+        format: &TextFormat,
+        style: usize,
+        metrics: &FontMetrics<'_>,
+    ) {
+        self.paragraph = ParagraphStyle::new(format);
+        self.line = LayoutLine::new(start, &self.paragraph, true);
+        if self.paragraph.is_bullet {
+            let (index, advance) = metrics.glyph('\u{2022}');
+            self.line.bullet = Some(LayoutGlyph {
+                c: '\u{2022}',
+                text_index: start,
+                index,
+                x: self.line.left - Twips::new(BULLET_INDENT),
+                advance,
+                style,
+            });
+        }
+        self.x = Twips::new(0);
+        self.break_index = None;
+    }
+
+    /// Makes room on the current line for a line of text of `style`, even if no glyph
+    /// of that style ends up on the line, as with empty lines.
+    fn fit_style(&mut self, style: usize) {
+        let style = &self.styles[style];
+        self.line.ascent = self.line.ascent.max(style.ascent);
+        self.line.descent = self.line.descent.max(style.descent);
+    }
+
+    fn push_glyph(&mut self, mut glyph: LayoutGlyph) {
+        let line_width = self
+            .wrap_width
+            .map(|wrap_width| wrap_width - self.line.left - self.line.right);
+        let is_too_wide = line_width.map_or(false, |line_width| {
+            self.x + glyph.advance > line_width && glyph.c != ' '
+        });
+        if is_too_wide && !self.line.glyphs.is_empty() {
+            // Move the word being typed onto a new line. A word that doesn't fit
+            // on a line by itself is broken at the last character that fits.
+            let split_index = match self.break_index {
+                Some(break_index) if break_index < self.line.glyphs.len() => break_index,
+                _ => self.line.glyphs.len(),
+            };
+            let glyphs = self.line.glyphs.split_off(split_index);
+            let start = glyphs
+                .first()
+                .map_or(glyph.text_index, |glyph| glyph.text_index);
+            let mut new_line = LayoutLine::new(start, &self.paragraph, false);
+            new_line.glyphs = glyphs;
+            let line_start = new_line.glyphs.first().map_or(self.x, |glyph| glyph.x);
+            for glyph in &mut new_line.glyphs {
+                glyph.x -= line_start;
+            }
+            self.x -= line_start;
+            let line = std::mem::replace(&mut self.line, new_line);
+            self.push_line(line, start, false);
+            self.break_index = None;
+        }
+
+        glyph.x = self.x;
+        self.x += glyph.advance;
+        let is_space = glyph.c == ' ';
+        self.line.glyphs.push(glyph);
+        if is_space {
+            self.break_index = Some(self.line.glyphs.len());
+        }
+    }
+
+    /// Adds a line showing the image `image_index` before the current line.
+    fn push_image(&mut self, image_index: usize, image: &TextImage<'_>, text_index: usize) {
+        if !self.line.glyphs.is_empty() {
+            let new_line = LayoutLine::new(text_index, &self.paragraph, false);
+            let line = std::mem::replace(&mut self.line, new_line);
+            self.push_line(line, text_index, false);
+            self.x = Twips::new(0);
+            self.break_index = None;
+        }
+        // TODO: Text should flow around images that are aligned left or right.
+        let mut line = LayoutLine::new(text_index, &self.paragraph, false);
+        line.image = Some(image_index);
+        line.width = image.width + image.html.hspace * 2;
+        line.ascent = image.height + image.html.vspace * 2;
+        line.leading = Twips::new(0);
+        line.bullet = None;
+        self.lines.push(line);
+        self.place_last_line();
+    }
+
+    /// Ends the current line at the character `end`.
+    fn push_line(&mut self, mut line: LayoutLine, end: usize, ends_paragraph: bool) {
+        line.finish(end, ends_paragraph, &self.styles);
+        self.lines.push(line);
+        self.place_last_line();
+    }
+
+    /// Places the last line below the line before it.
+    fn place_last_line(&mut self) {
+        let count = self.lines.len();
+        if count >= 2 {
+            let previous = &self.lines[count - 2];
+            let y = previous.bottom() + previous.leading;
+            self.lines[count - 1].y = y;
+        }
+    }
+}
+
+/// Breaks `text` into lines, at line breaks if `is_multiline` is set, and where a line
+/// would grow wider than `wrap_width`, the width of the text area.
+///
+/// Each run of text is measured in the font and the size of its format. Text whose font
+/// isn't embedded in the movie is measured in `default_font`.
+fn layout_text(
+    text: &str,
+    spans: &FormatSpans,
+    images: &[TextImage<'_>],
+    library: &Library<'_>,
+    default_font: Option<CharacterId>,
+    is_multiline: bool,
+    wrap_width: Option<Twips>,
+) -> Layout {
+    // Each span gets a style, and the metrics of its font.
+    let mut styles = vec![];
+    let mut runs = vec![];
+    let mut run_end = 0;
+    for span in spans.iter() {
+        let font_id = font_for_format(&span.format, library, default_font);
+        let metrics = FontMetrics {
+            font: font_id.and_then(|id| library.get_font(id)),
+            size: span
+                .format
+                .size
+                .map_or(f32::from(DEFAULT_FONT_SIZE), |size| size.get() as f32),
+        };
+        styles.push(GlyphStyle::new(&span.format, font_id, &metrics));
+        run_end += span.len;
+        runs.push((run_end, metrics));
+    }
+    let run_at = |index: usize| {
+        runs.iter()
+            .position(|&(run_end, _)| index < run_end)
+            .unwrap_or(runs.len() - 1)
+    };
+
+    let paragraph = ParagraphStyle::new(spans.format_at(0));
+    let mut builder = LayoutBuilder {
+        lines: vec![],
+        styles,
+        wrap_width,
+        line: LayoutLine::new(0, &paragraph, true),
+        paragraph,
+        x: Twips::new(0),
+        break_index: None,
+    };
+    let first_run = run_at(0);
+    builder.start_paragraph(0, spans.format_at(0), first_run, &runs[first_run].1);
+
+    let mut run = 0;
+    let mut next_image = 0;
+    let mut prev_char = None;
+    let mut follows_cr = false;
+    for (text_index, c) in text.chars().enumerate() {
+        // Kerning only applies within a run of the same font.
+        if text_index >= runs[run].0 && run + 1 < runs.len() {
+            run = run_at(text_index);
+            prev_char = None;
+        }
+        while next_image < images.len() && images[next_image].html.text_index <= text_index {
+            builder.push_image(next_image, &images[next_image], text_index);
+            next_image += 1;
+        }
+        let metrics = &runs[run].1;
+
+        // Single line text fields leave out line breaks.
+        if c == '\r' || c == '\n' {
+            if is_multiline && c == '\n' && follows_cr {
+                // A line feed after a carriage return belongs to the same line break.
+                builder.line.start = text_index + 1;
+                builder.line.end = text_index + 1;
+            } else if is_multiline {
+                builder.fit_style(run);
+                let new_line = LayoutLine::new(text_index + 1, &builder.paragraph, true);
+                let line = std::mem::replace(&mut builder.line, new_line);
+                builder.push_line(line, text_index, true);
+                let next_run = run_at(text_index + 1);
+                let next_format = spans.format_at(text_index + 1);
+                builder.start_paragraph(text_index + 1, next_format, next_run, &runs[next_run].1);
+            }
+            follows_cr = c == '\r';
+            prev_char = None;
+            continue;
+        }
+        follows_cr = false;
+
+        let (index, advance) = if c == '\t' {
+            (None, builder.paragraph.next_tab_stop(builder.x) - builder.x)
+        } else {
+            metrics.glyph(c)
+        };
+        if let Some(prev_char) = prev_char {
+            builder.x += metrics.kerning(prev_char, c);
+        }
+        prev_char = Some(c);
+        builder.push_glyph(LayoutGlyph {
+            c,
+            text_index,
+            index,
+            x: Twips::new(0),
+            advance,
+            style: run,
+        });
+    }
+
+    let len = text.chars().count();
+    while next_image < images.len() {
+        builder.push_image(next_image, &images[next_image], len);
+        next_image += 1;
+    }
+    // The last line takes the height of the text at the end, even if it is empty.
+    builder.fit_style(run_at(len.saturating_sub(1)));
+    let line = std::mem::replace(
+        &mut builder.line,
+        LayoutLine::new(len, &builder.paragraph, false),
+    );
+    builder.push_line(line, len, true);
+    Layout {
+        lines: builder.lines,
+        styles: builder.styles,
+    }
+}
+
+impl LayoutLine {
+    fn new(start: usize, paragraph: &ParagraphStyle, is_first_line: bool) -> Self {
+        let indent = if is_first_line {
+            paragraph.indent
+        } else {
+            Twips::new(0)
+        };
+        Self {
+            glyphs: vec![],
+            start,
+            end: start,
+            x: Twips::new(0),
+            y: Twips::new(0),
+            width: Twips::new(0),
+            ascent: Twips::new(0),
+            descent: Twips::new(0),
+            leading: paragraph.leading,
+            ends_paragraph: true,
+            align: paragraph.align,
+            left: paragraph.left + indent,
+            right: paragraph.right,
+            bullet: None,
+            image: None,
+        }
+    }
+
+    fn finish(&mut self, end: usize, ends_paragraph: bool, styles: &[GlyphStyle]) {
+        self.end = end;
+        self.ends_paragraph = ends_paragraph;
+        self.width = self
+            .glyphs
+            .iter()
+            .rev()
+            .find(|glyph| glyph.c != ' ')
+            .map_or_else(|| Twips::new(0), |glyph| glyph.x + glyph.advance);
+        for glyph in self.glyphs.iter().chain(&self.bullet) {
+            let style = &styles[glyph.style];
+            self.ascent = self.ascent.max(style.ascent);
+            self.descent = self.descent.max(style.descent);
+        }
+    }
+
+    /// The offset of the bottom of the line from the top of the first line.
+    fn bottom(&self) -> Twips {
+        self.y + self.ascent + self.descent
+    }
+
+    /// The last caret position on the line. On lines broken by word wrap, this is
+    /// before the last character, as the position after it starts the next line.
+    fn last_caret_index(&self) -> usize {
+        if self.ends_paragraph {
+            self.end
+        } else {
+            self.end.saturating_sub(1).max(self.start)
+        }
+    }
+
+    /// The position of the caret before the character at `index`, from the left of the
+    /// text area.
+    fn x_at_index(&self, index: usize) -> Twips {
+        let x = match self.glyphs.iter().find(|glyph| glyph.text_index >= index) {
+            Some(glyph) => glyph.x,
+            None => self
+                .glyphs
+                .last()
+                .map_or_else(|| Twips::new(0), |glyph| glyph.x + glyph.advance),
+        };
+        self.x + x
+    }
+
+    /// The caret position nearest to `x`, from the left of the text area.
+    fn index_at_x(&self, x: Twips) -> usize {
+        self.glyphs
+            .iter()
+            .find(|glyph| self.x + glyph.x + glyph.advance / 2 > x)
+            .map_or_else(|| self.last_caret_index(), |glyph| glyph.text_index)
+    }
+
+    /// The glyph under `x`, from the left of the text area.
+    fn glyph_at_x(&self, x: Twips) -> Option<&LayoutGlyph> {
+        self.glyphs.iter().find(|glyph| {
+            let glyph_x = self.x + glyph.x;
+            x >= glyph_x && x < glyph_x + glyph.advance
+        })
+    }
+
+    /// The left and right of the selected glyphs of the line, if any.
+    fn selected_range(&self, selection: TextSelection) -> Option<(Twips, Twips)> {
+        let mut selected = self
+            .glyphs
+            .iter()
+            .filter(|glyph| selection.contains(glyph.text_index));
+        let first = selected.next()?;
+        let last = selected.last().unwrap_or(first);
+        Some((first.x, last.x + last.advance))
+    }
+
+    /// The left and right of each run of underlined glyphs, with the style of the run.
+    fn underlines(&self, styles: &[GlyphStyle]) -> Vec<(Twips, Twips, usize)> {
+        let mut underlines: Vec<(Twips, Twips, usize)> = vec![];
+        for glyph in &self.glyphs {
+            if !styles[glyph.style].is_underlined {
+                continue;
+            }
+            match underlines.last_mut() {
+                Some((_, x_max, style)) if *style == glyph.style && *x_max >= glyph.x => {
+                    *x_max = glyph.x + glyph.advance
+                }
+                _ => underlines.push((glyph.x, glyph.x + glyph.advance, glyph.style)),
+            }
+        }
+        underlines
+    }
+}
+
+/// Positions `line` within a text area of `width`.
+/// Justified lines are spread to the full width, apart from the last line of a paragraph.
+fn align_line(line: &mut LayoutLine, align: &TextAlign, width: Twips) {
+    let extra_width = width - line.width;
+    match align {
+        TextAlign::Left => (),
+        TextAlign::Center => line.x = extra_width / 2,
+        TextAlign::Right => line.x = extra_width,
+        TextAlign::Justify => {
+            if line.ends_paragraph || extra_width <= Twips::new(0) {
+                return;
+            }
+            let end = line
+                .glyphs
+                .iter()
+                .rposition(|glyph| glyph.c != ' ')
+                .unwrap_or(0);
+            let num_spaces = line.glyphs[..end]
+                .iter()
+                .filter(|glyph| glyph.c == ' ')
+                .count() as i32;
+            if num_spaces == 0 {
+                return;
+            }
+            let mut spaces_seen = 0;
+            for glyph in &mut line.glyphs[..=end] {
+                glyph.x += extra_width * spaces_seen / num_spaces;
+                if glyph.c == ' ' {
+                    spaces_seen += 1;
+                }
+            }
+            line.width = width;
+        }
+    }
+}
+
+/// Returns the font to draw the text field with.
+///
+/// Device fonts have no glyphs of their own, so an embedded font of the same name is used
+/// in their place if the movie has one.
+fn find_font(tag: &swf::EditText, library: &Library<'_>) -> Option<CharacterId> {
+    let font_id = tag.font_id?;
+    let font = library.get_font(font_id)?;
+    if font.has_glyphs() {
+        Some(font_id)
+    } else {
+        library.font_id_by_name(font.name(), font.is_bold(), font.is_italic())
+    }
+}
+
+/// Returns the embedded font to draw text of `format` with.
+///
+/// A movie may embed several fonts of the same name with different glyphs, so
+/// `default_font` is preferred when it is the font that `format` asks for. Text in
+/// a font that isn't embedded is drawn in `default_font`.
+fn font_for_format(
+    format: &TextFormat,
+    library: &Library<'_>,
+    default_font: Option<CharacterId>,
+) -> Option<CharacterId> {
+    let name = format.font.as_ref().map_or("", String::as_str);
+    let is_bold = format.bold == Some(true);
+    let is_italic = format.italic == Some(true);
+    let has_name = |id: CharacterId| {
+        library
+            .get_font(id)
+            .map_or(false, |font| font.name() == name)
+    };
+    let has_style = |id: CharacterId| {
+        library.get_font(id).map_or(false, |font| {
+            font.is_bold() == is_bold && font.is_italic() == is_italic
+        })
+    };
+    let default_has_name = default_font.map_or(false, has_name);
+    if default_has_name && default_font.map_or(false, has_style) {
+        return default_font;
+    }
+    match library.font_id_by_name(name, is_bold, is_italic) {
+        Some(id) if has_style(id) => Some(id),
+        // Without a font of the right style, the style is left out.
+        _ if default_has_name => default_font,
+        Some(id) => Some(id),
+        None => default_font,
+    }
+}
+
+/// The format of the text of a text field before any is set, from its `DefineEditText` tag.
+fn initial_text_format(tag: &swf::EditText, library: &Library<'_>) -> TextFormat {
+    let font = tag.font_id.and_then(|id| library.get_font(id));
+    let layout = tag.layout.as_ref();
+    let twips = |value: Option<Twips>| value.unwrap_or_default();
+    TextFormat {
+        // Flash Player uses Times New Roman for text fields without a font.
+        font: Some(font.map_or("Times New Roman", Font::name).to_string()),
+        size: Some(Twips::new(i32::from(
+            tag.height.unwrap_or(DEFAULT_FONT_SIZE),
+        ))),
+        color: Some(tag.color.clone().unwrap_or_else(black)),
+        bold: Some(font.map_or(false, Font::is_bold)),
+        italic: Some(font.map_or(false, Font::is_italic)),
+        underline: Some(false),
+        url: Some(String::new()),
+        target: Some(String::new()),
+        align: Some(layout.map_or(TextAlign::Left, |layout| layout.align)),
+        left_margin: Some(twips(layout.map(|layout| layout.left_margin))),
+        right_margin: Some(twips(layout.map(|layout| layout.right_margin))),
+        indent: Some(twips(layout.map(|layout| layout.indent))),
+        block_indent: Some(Twips::new(0)),
+        leading: Some(twips(layout.map(|layout| layout.leading))),
+        bullet: Some(false),
+        tab_stops: Some(vec![]),
+    }
+}
+
+/// Returns the byte offset of the character at `index` in `text`.
+fn byte_index(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .nth(index)
+        .map_or(text.len(), |(byte_index, _)| byte_index)
+}
+
+/// Converts the index of a character in `text` to an index in UTF-16 code units.
+pub fn utf16_index(text: &str, index: usize) -> usize {
+    text.chars().take(index).map(char::len_utf16).sum()
+}
+
+/// Converts an index in UTF-16 code units to the index of a character in `text`.
+/// An index inside a surrogate pair moves past the character it splits.
+pub fn char_index(text: &str, utf16_index: usize) -> usize {
+    let mut units = 0;
+    text.chars()
+        .take_while(|c| {
+            let is_before = units < utf16_index;
+            units += c.len_utf16();
+            is_before
+        })
+        .count()
+}
+
+fn black() -> Color {
+    Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    }
+}
+
+/// A white square of one pixel.
+fn pixel_drawing() -> Drawing {
+    let mut drawing = Drawing::new();
+    let size = Twips::new(20);
+    drawing.begin_fill(Some(FillStyle::Color(Color {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    })));
+    drawing.move_to((Twips::new(0), Twips::new(0)));
+    drawing.line_to((size, Twips::new(0)));
+    drawing.line_to((size, size));
+    drawing.line_to((Twips::new(0), size));
+    drawing.line_to((Twips::new(0), Twips::new(0)));
+    drawing.end_fill();
+    drawing
+}
+
+/// Tints white shapes drawn with `transform` in `color`.
+fn set_color(transform: &mut Transform, color: &Color) {
+    transform.color_transform.r_mult = f32::from(color.r) / 255.0;
+    transform.color_transform.g_mult = f32::from(color.g) / 255.0;
+    transform.color_transform.b_mult = f32::from(color.b) / 255.0;
+    transform.color_transform.a_mult = f32::from(color.a) / 255.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out `text` in a font size of 20 twips, without an embedded font, in which
+    /// letters are 10 twips wide and spaces 5 twips.
+    fn layout(text: &str, is_multiline: bool, wrap_width: Option<i32>) -> Vec<LayoutLine> {
+        let format = TextFormat {
+            size: Some(Twips::new(20)),
+            ..Default::default()
+        };
+        let spans = FormatSpans::new(text.chars().count(), format);
+        let wrap_width = wrap_width.map(Twips::new);
+        layout_text(
+            text,
+            &spans,
+            &[],
+            &Library::new(),
+            None,
+            is_multiline,
+            wrap_width,
+        )
+        .lines
+    }
+
+    fn line_texts(lines: &[LayoutLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.glyphs.iter().map(|glyph| glyph.c).collect())
+            .collect()
+    }
+
+    #[test]
+    fn word_wrap_breaks_at_spaces() {
+        let lines = layout("aaa bbb ccc", true, Some(75));
+        assert_eq!(line_texts(&lines), vec!["aaa bbb ", "ccc"]);
+        assert_eq!(lines[0].width, Twips::new(65));
+        assert_eq!(lines[1].glyphs[0].x, Twips::new(0));
+        assert!(!lines[0].ends_paragraph);
+        assert!(lines[1].ends_paragraph);
+    }
+
+    #[test]
+    fn long_words_are_broken() {
+        let lines = layout("aaaaaaaa", true, Some(35));
+        assert_eq!(line_texts(&lines), vec!["aaa", "aaa", "aa"]);
+    }
+
+    #[test]
+    fn line_breaks_need_multiline() {
+        let lines = layout("a\rb\nc", true, None);
+        assert_eq!(line_texts(&lines), vec!["a", "b", "c"]);
+        let lines = layout("a\rb\nc", false, None);
+        assert_eq!(line_texts(&lines), vec!["abc"]);
+    }
+
+    #[test]
+    fn alignment() {
+        let mut lines = layout("aa bb", false, None);
+        align_line(&mut lines[0], &TextAlign::Right, Twips::new(100));
+        assert_eq!(lines[0].x, Twips::new(55));
+        align_line(&mut lines[0], &TextAlign::Center, Twips::new(100));
+        assert_eq!(lines[0].x, Twips::new(27));
+    }
+
+    #[test]
+    fn line_indices() {
+        let lines = layout("ab cd\r\nef", true, Some(35));
+        assert_eq!(line_texts(&lines), vec!["ab ", "cd", "ef"]);
+        let ranges: Vec<_> = lines.iter().map(|line| (line.start, line.end)).collect();
+        assert_eq!(ranges, vec![(0, 3), (3, 5), (7, 9)]);
+        assert_eq!(lines[0].last_caret_index(), 2);
+        assert_eq!(lines[1].x_at_index(4), Twips::new(10));
+        assert_eq!(lines[1].index_at_x(Twips::new(14)), 4);
+        assert_eq!(lines[1].index_at_x(Twips::new(100)), 5);
+    }
+
+    #[test]
+    fn formatted_runs() {
+        let small = TextFormat {
+            size: Some(Twips::new(20)),
+            left_margin: Some(Twips::new(100)),
+            ..Default::default()
+        };
+        let large = TextFormat {
+            size: Some(Twips::new(40)),
+            underline: Some(true),
+            ..small.clone()
+        };
+        let mut spans = FormatSpans::new(0, small.clone());
+        spans.push(2, small.clone());
+        spans.push(2, large);
+        spans.push(3, small);
+        let layout = layout_text("aabb\rcc", &spans, &[], &Library::new(), None, true, None);
+        let lines = &layout.lines;
+        assert_eq!(line_texts(lines), vec!["aabb", "cc"]);
+        assert_eq!(lines[0].width, Twips::new(60));
+        assert_eq!(lines[0].left, Twips::new(100));
+        assert_eq!(lines[0].ascent, Twips::new(32));
+        assert_eq!(lines[1].ascent, Twips::new(16));
+        assert_eq!(lines[1].y, lines[0].bottom());
+        assert_eq!(
+            lines[0].underlines(&layout.styles),
+            vec![(Twips::new(20), Twips::new(60), 1)]
+        );
+    }
+
+    #[test]
+    fn restrict() {
+        let digits = CharacterSet::parse("0-9");
+        assert!(digits.allows('5'));
+        assert!(!digits.allows('a'));
+        let no_vowels = CharacterSet::parse("^aeiou");
+        assert!(no_vowels.allows('b'));
+        assert!(!no_vowels.allows('e'));
+        let letters_but_q = CharacterSet::parse("A-Z^Q");
+        assert_eq!(letters_but_q.filter('a'), Some('A'));
+        assert_eq!(letters_but_q.filter('q'), None);
+        let escaped = CharacterSet::parse("\\-\\^");
+        assert!(escaped.allows('-'));
+        assert!(escaped.allows('^'));
+        assert!(!escaped.allows('a'));
+    }
+
+    fn input_text(max_length: Option<u16>) -> EditText<'static> {
+        let tag = swf::EditText {
+            id: 1,
+            bounds: swf::Rectangle {
+                x_min: Twips::new(0),
+                x_max: Twips::new(2000),
+                y_min: Twips::new(0),
+                y_max: Twips::new(400),
+            },
+            font_id: None,
+            font_class_name: None,
+            height: Some(20),
+            color: None,
+            max_length,
+            layout: None,
+            variable_name: String::new(),
+            initial_text: Some("abc".to_string()),
+            is_word_wrap: false,
+            is_multiline: false,
+            is_password: false,
+            is_read_only: false,
+            is_auto_size: false,
+            is_selectable: true,
+            has_border: false,
+            was_static: false,
+            is_html: false,
+            is_device_font: false,
+        };
+        EditText::from_swf_tag(&tag, &Library::new())
+    }
+
+    #[test]
+    fn keyboard_editing() {
+        let library = Library::new();
+        let mut edit_text = input_text(Some(5));
+        edit_text.set_selection(Some(TextSelection::at(3)));
+        let result = edit_text.key_down(key_code::LEFT, true, &library);
+        assert_eq!(result, EditResult::SelectionChanged);
+        assert_eq!(edit_text.selection(), Some(TextSelection::new(3, 2)));
+        edit_text.text_input('x', &library);
+        assert_eq!(edit_text.text(), "abx");
+        edit_text.key_down(key_code::HOME, false, &library);
+        edit_text.key_down(key_code::DELETE, false, &library);
+        assert_eq!(edit_text.text(), "bx");
+        assert_eq!(edit_text.selection(), Some(TextSelection::at(0)));
+        for c in "1234".chars() {
+            edit_text.text_input(c, &library);
+        }
+        assert_eq!(edit_text.text(), "123bx");
+        let result = edit_text.key_down(key_code::ENTER, false, &library);
+        assert_eq!(result, EditResult::Unchanged);
+        edit_text.key_down(key_code::BACKSPACE, false, &library);
+        assert_eq!(edit_text.text(), "12bx");
+    }
+
+    #[test]
+    fn typed_text_gets_new_text_format() {
+        let library = Library::new();
+        let mut edit_text = input_text(None);
+        edit_text.set_html_text("<b>ab</b>c".to_string(), &library);
+        assert_eq!(edit_text.text(), "abc");
+        assert_eq!(edit_text.text_format(0, 2).bold, Some(true));
+        assert_eq!(edit_text.text_format(0, 3).bold, None);
+        edit_text.set_selection(Some(TextSelection::at(1)));
+        edit_text.text_input('x', &library);
+        assert_eq!(edit_text.text(), "axbc");
+        assert_eq!(edit_text.text_format(1, 2).bold, Some(false));
+        assert_eq!(edit_text.text_format(2, 3).bold, Some(true));
+    }
+
+    #[test]
+    fn selection_and_max_chars_count_utf16_units() {
+        let library = Library::new();
+        let mut edit_text = input_text(Some(5));
+        edit_text.set_text("a\u{1F600}b".to_string(), &library);
+        edit_text.set_utf16_selection(TextSelection::new(1, 3));
+        assert_eq!(edit_text.selection(), Some(TextSelection::new(1, 2)));
+        assert_eq!(edit_text.utf16_selection(), Some(TextSelection::new(1, 3)));
+        edit_text.set_utf16_selection(TextSelection::at(2));
+        assert_eq!(edit_text.utf16_selection(), Some(TextSelection::at(3)));
+
+        // Four units are taken, so a surrogate pair no longer fits.
+        edit_text.text_input('\u{1F600}', &library);
+        assert_eq!(edit_text.text(), "a\u{1F600}b");
+        edit_text.text_input('c', &library);
+        assert_eq!(edit_text.text(), "a\u{1F600}cb");
+    }
+}
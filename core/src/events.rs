@@ -1,3 +1,11 @@
+/// An input event from the host.
+///
+/// `MouseWheel` deltas are in lines, where positive values scroll up and one notch of a typical
+/// wheel scrolls 3 lines. The right mouse button opens the context menu, and is not seen by
+/// buttons or clips.
+///
+/// `TextInput` carries a character typed on the keyboard, after the keyboard layout and
+/// any input method have been applied, and is sent after the `KeyDown` of its key.
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum PlayerEvent {
@@ -5,6 +13,14 @@ pub enum PlayerEvent {
     MouseUp { x: f64, y: f64 },
     MouseDown { x: f64, y: f64 },
     MouseLeft,
+    MouseWheel { delta: f64 },
+    RightMouseDown { x: f64, y: f64 },
+    RightMouseUp { x: f64, y: f64 },
+    MiddleMouseDown { x: f64, y: f64 },
+    MiddleMouseUp { x: f64, y: f64 },
+    KeyDown { key_code: KeyCode, ascii: u8 },
+    KeyUp { key_code: KeyCode, ascii: u8 },
+    TextInput { codepoint: char },
 }
 
 /// The events that an AVM1 button can fire.
@@ -15,15 +31,102 @@ pub enum PlayerEvent {
 ///     trace("Button clicked");
 /// }
 /// ```
+///
+/// While the mouse is held down, the pressed button sees `DragOut` and `DragOver` instead of
+/// `RollOut` and `RollOver`, and `ReleaseOutside` if the mouse is released elsewhere.
+/// Buttons tracked as menu items also see `DragOver` and `DragOut` when they were not pressed.
 #[derive(Debug)]
 pub enum ButtonEvent {
     Press,
     Release,
+    ReleaseOutside,
     RollOut,
     RollOver,
-    KeyPress(KeyCode),
+    DragOut,
+    DragOver,
+    KeyPress(ButtonKeyCode),
 }
 
-/// Flash virtual keycode.
-/// TODO: This will eventually move to a separate module.
+/// Flash virtual keycode, as returned by `Key.getCode`.
+/// Letters and digits use the ASCII codes of their uppercase characters.
 pub type KeyCode = u8;
+
+/// The virtual keycodes of keys that are not letters or digits.
+pub mod key_code {
+    use super::KeyCode;
+
+    /// The mouse buttons, as tested with `Key.isDown`.
+    pub const LEFT_MOUSE: KeyCode = 1;
+    pub const RIGHT_MOUSE: KeyCode = 2;
+    pub const MIDDLE_MOUSE: KeyCode = 4;
+
+    pub const BACKSPACE: KeyCode = 8;
+    pub const TAB: KeyCode = 9;
+    pub const ENTER: KeyCode = 13;
+    pub const SHIFT: KeyCode = 16;
+    pub const CONTROL: KeyCode = 17;
+    pub const ALT: KeyCode = 18;
+    pub const PAUSE: KeyCode = 19;
+    pub const CAPS_LOCK: KeyCode = 20;
+    pub const ESCAPE: KeyCode = 27;
+    pub const SPACE: KeyCode = 32;
+    pub const PG_UP: KeyCode = 33;
+    pub const PG_DOWN: KeyCode = 34;
+    pub const END: KeyCode = 35;
+    pub const HOME: KeyCode = 36;
+    pub const LEFT: KeyCode = 37;
+    pub const UP: KeyCode = 38;
+    pub const RIGHT: KeyCode = 39;
+    pub const DOWN: KeyCode = 40;
+    pub const INSERT: KeyCode = 45;
+    pub const DELETE: KeyCode = 46;
+    pub const NUMPAD_0: KeyCode = 96;
+    pub const NUMPAD_9: KeyCode = 105;
+    pub const NUMPAD_MULTIPLY: KeyCode = 106;
+    pub const NUMPAD_ADD: KeyCode = 107;
+    pub const NUMPAD_SUBTRACT: KeyCode = 109;
+    pub const NUMPAD_DECIMAL: KeyCode = 110;
+    pub const NUMPAD_DIVIDE: KeyCode = 111;
+    pub const F1: KeyCode = 112;
+    pub const NUM_LOCK: KeyCode = 144;
+    pub const SCROLL_LOCK: KeyCode = 145;
+    pub const SEMICOLON: KeyCode = 186;
+    pub const EQUALS: KeyCode = 187;
+    pub const COMMA: KeyCode = 188;
+    pub const MINUS: KeyCode = 189;
+    pub const PERIOD: KeyCode = 190;
+    pub const SLASH: KeyCode = 191;
+    pub const GRAVE: KeyCode = 192;
+    pub const LEFT_BRACKET: KeyCode = 219;
+    pub const BACKSLASH: KeyCode = 220;
+    pub const RIGHT_BRACKET: KeyCode = 221;
+    pub const QUOTE: KeyCode = 222;
+}
+
+/// The code of a key in a button `keyPress` condition.
+/// Printable characters use their ASCII code, while other keys have codes below 32.
+pub type ButtonKeyCode = u8;
+
+/// Returns the code that button `keyPress` conditions use for a key press,
+/// or `None` if buttons can't respond to the key.
+pub fn button_key_code(key_code: KeyCode, ascii: u8) -> Option<ButtonKeyCode> {
+    let code = match key_code {
+        key_code::LEFT => 1,
+        key_code::RIGHT => 2,
+        key_code::HOME => 3,
+        key_code::END => 4,
+        key_code::INSERT => 5,
+        key_code::DELETE => 6,
+        key_code::BACKSPACE => 8,
+        key_code::ENTER => 13,
+        key_code::UP => 14,
+        key_code::DOWN => 15,
+        key_code::PG_UP => 16,
+        key_code::PG_DOWN => 17,
+        key_code::TAB => 18,
+        key_code::ESCAPE => 19,
+        _ if ascii >= 32 && ascii < 127 => ascii,
+        _ => return None,
+    };
+    Some(code)
+}
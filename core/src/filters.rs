@@ -0,0 +1,630 @@
+//! Bitmap filters, such as blur and drop shadow.
+//!
+//! Render backends draw a display object with filters into an offscreen layer,
+//! and run the pixels of the layer through `apply_filters` before compositing it.
+
+use swf::{Color, Filter, GradientRecord};
+
+/// An image with premultiplied RGBA pixels, with components from 0 to 1.
+/// Rows are stored from top to bottom.
+pub struct FilterImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f32; 4]>,
+}
+
+impl FilterImage {
+    /// Creates an image from premultiplied RGBA8 data.
+    pub fn from_premultiplied_rgba(width: usize, height: usize, data: &[u8]) -> Self {
+        let pixels = data
+            .chunks_exact(4)
+            .map(|p| {
+                [
+                    f32::from(p[0]) / 255.0,
+                    f32::from(p[1]) / 255.0,
+                    f32::from(p[2]) / 255.0,
+                    f32::from(p[3]) / 255.0,
+                ]
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Creates an image from straight (not premultiplied) RGBA8 data, such as a canvas holds.
+    pub fn from_rgba(width: usize, height: usize, data: &[u8]) -> Self {
+        let mut image = Self::from_premultiplied_rgba(width, height, data);
+        for pixel in &mut image.pixels {
+            *pixel = premultiply(*pixel);
+        }
+        image
+    }
+
+    pub fn to_premultiplied_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| pixel.iter().map(|c| (c * 255.0).round() as u8))
+            .collect()
+    }
+
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| {
+                let pixel = unpremultiply(*pixel);
+                (0..4).map(move |i| (pixel[i] * 255.0).round() as u8)
+            })
+            .collect()
+    }
+
+    /// The alpha of each pixel.
+    fn alpha(&self) -> Vec<f32> {
+        self.pixels.iter().map(|pixel| pixel[3]).collect()
+    }
+}
+
+/// Applies `filters` to `image`, in order.
+pub fn apply_filters(image: &mut FilterImage, filters: &[Filter]) {
+    for filter in filters {
+        apply_filter(image, filter);
+    }
+}
+
+fn apply_filter(image: &mut FilterImage, filter: &Filter) {
+    match filter {
+        Filter::BlurFilter(filter) => {
+            for channel in 0..4 {
+                let mut plane: Vec<f32> = image.pixels.iter().map(|p| p[channel]).collect();
+                blur(
+                    &mut plane,
+                    image.width,
+                    image.height,
+                    filter.blur_x,
+                    filter.blur_y,
+                    filter.num_passes,
+                );
+                for (pixel, value) in image.pixels.iter_mut().zip(plane) {
+                    pixel[channel] = value;
+                }
+            }
+        }
+        Filter::DropShadowFilter(filter) => {
+            let shadow = Shadow {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                num_passes: filter.num_passes,
+                angle: filter.angle,
+                distance: filter.distance,
+                strength: filter.strength,
+                is_inner: filter.is_inner,
+            };
+            let color = premultiplied_color(&filter.color);
+            let effect = shadow
+                .coverage(image)
+                .into_iter()
+                .map(|coverage| scale(color, coverage))
+                .collect();
+            composite(image, effect, shadow.placement(), filter.is_knockout);
+        }
+        Filter::GlowFilter(filter) => {
+            let shadow = Shadow {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                num_passes: filter.num_passes,
+                angle: 0.0,
+                distance: 0.0,
+                strength: filter.strength,
+                is_inner: filter.is_inner,
+            };
+            let color = premultiplied_color(&filter.color);
+            let effect = shadow
+                .coverage(image)
+                .into_iter()
+                .map(|coverage| scale(color, coverage))
+                .collect();
+            composite(image, effect, shadow.placement(), filter.is_knockout);
+        }
+        Filter::GradientGlowFilter(filter) => {
+            let shadow = Shadow {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                num_passes: filter.num_passes,
+                angle: filter.angle,
+                distance: filter.distance,
+                strength: filter.strength,
+                is_inner: filter.is_inner,
+            };
+            let effect = shadow
+                .coverage(image)
+                .into_iter()
+                .map(|coverage| gradient_color(&filter.colors, coverage))
+                .collect();
+            let placement = if filter.is_on_top {
+                Placement::Full
+            } else {
+                shadow.placement()
+            };
+            composite(image, effect, placement, filter.is_knockout);
+        }
+        Filter::BevelFilter(filter) => {
+            let bevel = Bevel {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                num_passes: filter.num_passes,
+                angle: filter.angle,
+                distance: filter.distance,
+                strength: filter.strength,
+            };
+            let highlight = premultiplied_color(&filter.highlight_color);
+            let shadow = premultiplied_color(&filter.shadow_color);
+            let effect = bevel
+                .lighting(image)
+                .into_iter()
+                .map(|light| {
+                    if light > 0.0 {
+                        scale(highlight, light)
+                    } else {
+                        scale(shadow, -light)
+                    }
+                })
+                .collect();
+            let placement = Placement::of_bevel(filter.is_inner, filter.is_on_top);
+            composite(image, effect, placement, filter.is_knockout);
+        }
+        Filter::GradientBevelFilter(filter) => {
+            let bevel = Bevel {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                num_passes: filter.num_passes,
+                angle: filter.angle,
+                distance: filter.distance,
+                strength: filter.strength,
+            };
+            // The highlight is at the start of the gradient, and the shadow at the end.
+            let effect = bevel
+                .lighting(image)
+                .into_iter()
+                .map(|light| gradient_color(&filter.colors, (1.0 - light) / 2.0))
+                .collect();
+            let placement = Placement::of_bevel(filter.is_inner, filter.is_on_top);
+            composite(image, effect, placement, filter.is_knockout);
+        }
+        Filter::ColorMatrixFilter(filter) => {
+            let m = &filter.matrix;
+            for pixel in &mut image.pixels {
+                let [r, g, b, a] = unpremultiply(*pixel);
+                let mut out = [0.0; 4];
+                for (i, out) in out.iter_mut().enumerate() {
+                    let row = &m[i * 5..i * 5 + 5];
+                    let value = row[0] * f64::from(r)
+                        + row[1] * f64::from(g)
+                        + row[2] * f64::from(b)
+                        + row[3] * f64::from(a)
+                        + row[4] / 255.0;
+                    *out = clamp(value as f32);
+                }
+                *pixel = premultiply(out);
+            }
+        }
+        Filter::ConvolutionFilter(filter) => convolve(image, filter),
+    }
+}
+
+/// The settings shared by the drop shadow and glow filters.
+struct Shadow {
+    blur_x: f64,
+    blur_y: f64,
+    num_passes: u8,
+    angle: f64,
+    distance: f64,
+    strength: f32,
+    is_inner: bool,
+}
+
+impl Shadow {
+    /// How strongly the shadow covers each pixel, from 0 to 1.
+    ///
+    /// An inner shadow is cast by the transparent area around the image onto the image.
+    fn coverage(&self, image: &FilterImage) -> Vec<f32> {
+        let mut alpha = image.alpha();
+        let outside = if self.is_inner {
+            for a in &mut alpha {
+                *a = 1.0 - *a;
+            }
+            1.0
+        } else {
+            0.0
+        };
+        let (dx, dy) = offset(self.angle, self.distance);
+        let mut coverage = shifted(&alpha, image.width, image.height, dx, dy, outside);
+        blur(
+            &mut coverage,
+            image.width,
+            image.height,
+            self.blur_x,
+            self.blur_y,
+            self.num_passes,
+        );
+        for value in &mut coverage {
+            *value = clamp(*value * self.strength);
+        }
+        coverage
+    }
+
+    fn placement(&self) -> Placement {
+        if self.is_inner {
+            Placement::Inner
+        } else {
+            Placement::Outer
+        }
+    }
+}
+
+/// The settings shared by the bevel filters.
+struct Bevel {
+    blur_x: f64,
+    blur_y: f64,
+    num_passes: u8,
+    angle: f64,
+    distance: f64,
+    strength: f32,
+}
+
+impl Bevel {
+    /// The lighting of each pixel, from -1 for full shadow to 1 for full highlight.
+    ///
+    /// The highlight is on the edges facing away from `angle`, and the shadow on the edges facing it.
+    fn lighting(&self, image: &FilterImage) -> Vec<f32> {
+        let mut alpha = image.alpha();
+        blur(
+            &mut alpha,
+            image.width,
+            image.height,
+            self.blur_x,
+            self.blur_y,
+            self.num_passes,
+        );
+        let (dx, dy) = offset(self.angle, self.distance);
+        let ahead = shifted(&alpha, image.width, image.height, -dx, -dy, 0.0);
+        let behind = shifted(&alpha, image.width, image.height, dx, dy, 0.0);
+        ahead
+            .into_iter()
+            .zip(behind)
+            .map(|(ahead, behind)| {
+                let light = (ahead - behind) * self.strength;
+                light.max(-1.0).min(1.0)
+            })
+            .collect()
+    }
+}
+
+/// Which part of the image the effect of a filter is drawn on.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Placement {
+    /// Within the image, on top of it.
+    Inner,
+
+    /// Outside the image, beneath it.
+    Outer,
+
+    /// Everywhere, on top of the image.
+    Full,
+}
+
+impl Placement {
+    fn of_bevel(is_inner: bool, is_on_top: bool) -> Self {
+        if is_on_top {
+            Placement::Full
+        } else if is_inner {
+            Placement::Inner
+        } else {
+            Placement::Outer
+        }
+    }
+}
+
+/// Combines the premultiplied `effect` of a filter with `image`.
+/// With `is_knockout`, the image itself is removed and only the effect remains.
+fn composite(
+    image: &mut FilterImage,
+    effect: Vec<[f32; 4]>,
+    placement: Placement,
+    is_knockout: bool,
+) {
+    for (pixel, effect) in image.pixels.iter_mut().zip(effect) {
+        let source_alpha = pixel[3];
+        let effect = match placement {
+            Placement::Inner => scale(effect, source_alpha),
+            Placement::Outer if is_knockout => scale(effect, 1.0 - source_alpha),
+            _ => effect,
+        };
+        *pixel = if is_knockout {
+            effect
+        } else if placement == Placement::Outer {
+            over(*pixel, effect)
+        } else {
+            over(effect, *pixel)
+        };
+    }
+}
+
+fn convolve(image: &mut FilterImage, filter: &swf::ConvolutionFilter) {
+    let (width, height) = (image.width as isize, image.height as isize);
+    let cols = isize::from(filter.num_matrix_cols);
+    let rows = isize::from(filter.num_matrix_rows);
+    let divisor = if filter.divisor != 0.0 {
+        filter.divisor as f32
+    } else {
+        1.0
+    };
+    let bias = filter.bias as f32 / 255.0;
+    let default_color = unpremultiply(premultiplied_color(&filter.default_color));
+    let source: Vec<[f32; 4]> = image.pixels.iter().map(|p| unpremultiply(*p)).collect();
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0.0; 4];
+            for j in 0..rows {
+                for i in 0..cols {
+                    let weight = filter
+                        .matrix
+                        .get((j * cols + i) as usize)
+                        .map_or(0.0, |w| *w as f32);
+                    let (sx, sy) = (x + i - cols / 2, y + j - rows / 2);
+                    let sample = if (0..width).contains(&sx) && (0..height).contains(&sy) {
+                        source[(sy * width + sx) as usize]
+                    } else if filter.is_clamped {
+                        let sx = sx.max(0).min(width - 1);
+                        let sy = sy.max(0).min(height - 1);
+                        source[(sy * width + sx) as usize]
+                    } else {
+                        default_color
+                    };
+                    for (sum, sample) in sum.iter_mut().zip(&sample) {
+                        *sum += sample * weight;
+                    }
+                }
+            }
+            let index = (y * width + x) as usize;
+            let mut out = [0.0; 4];
+            for (out, sum) in out.iter_mut().zip(&sum) {
+                *out = clamp(sum / divisor + bias);
+            }
+            if filter.is_preserve_alpha {
+                out[3] = source[index][3];
+            }
+            image.pixels[index] = premultiply(out);
+        }
+    }
+}
+
+/// Box blurs a plane of values `num_passes` times, with boxes `blur_x` by `blur_y` pixels wide.
+/// Values beyond the edges are 0.
+fn blur(plane: &mut [f32], width: usize, height: usize, blur_x: f64, blur_y: f64, num_passes: u8) {
+    let size_x = blur_x.round().max(1.0) as usize;
+    let size_y = blur_y.round().max(1.0) as usize;
+    let mut line = vec![];
+    let mut out = vec![];
+    for _ in 0..num_passes {
+        if size_x > 1 {
+            for row in plane.chunks_exact_mut(width) {
+                line.clear();
+                line.extend_from_slice(row);
+                out.resize(width, 0.0);
+                box_blur_line(&line, &mut out, size_x);
+                row.copy_from_slice(&out);
+            }
+        }
+        if size_y > 1 {
+            for x in 0..width {
+                line.clear();
+                line.extend((0..height).map(|y| plane[y * width + x]));
+                out.resize(height, 0.0);
+                box_blur_line(&line, &mut out, size_y);
+                for (y, value) in out.iter().enumerate() {
+                    plane[y * width + x] = *value;
+                }
+            }
+        }
+    }
+}
+
+/// Averages each value of `line` with its neighbours in a window `size` values wide.
+fn box_blur_line(line: &[f32], out: &mut [f32], size: usize) {
+    let before = (size - 1) / 2;
+    let after = size - 1 - before;
+    let len = line.len();
+    let mut sum: f32 = line.iter().take(after + 1).sum();
+    for (i, out) in out.iter_mut().enumerate().take(len) {
+        *out = sum / size as f32;
+        if i + after + 1 < len {
+            sum += line[i + after + 1];
+        }
+        if i >= before {
+            sum -= line[i - before];
+        }
+    }
+}
+
+/// Moves a plane of values by a whole number of pixels, filling the uncovered area with `outside`.
+fn shifted(plane: &[f32], width: usize, height: usize, dx: f64, dy: f64, outside: f32) -> Vec<f32> {
+    let (dx, dy) = (dx.round() as isize, dy.round() as isize);
+    let (width, height) = (width as isize, height as isize);
+    let mut out = Vec::with_capacity(plane.len());
+    for y in 0..height {
+        for x in 0..width {
+            let (sx, sy) = (x - dx, y - dy);
+            if (0..width).contains(&sx) && (0..height).contains(&sy) {
+                out.push(plane[(sy * width + sx) as usize]);
+            } else {
+                out.push(outside);
+            }
+        }
+    }
+    out
+}
+
+/// The offset of a shadow cast `distance` pixels in the direction of `angle` radians.
+fn offset(angle: f64, distance: f64) -> (f64, f64) {
+    (angle.cos() * distance, angle.sin() * distance)
+}
+
+/// Returns the premultiplied color of `gradient` at `ratio`, from 0 to 1.
+fn gradient_color(gradient: &[GradientRecord], ratio: f32) -> [f32; 4] {
+    let ratio = ratio * 255.0;
+    let (first, last) = match (gradient.first(), gradient.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return [0.0; 4],
+    };
+    if ratio <= f32::from(first.ratio) {
+        return premultiplied_color(&first.color);
+    }
+    for pair in gradient.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        let (start_ratio, end_ratio) = (f32::from(start.ratio), f32::from(end.ratio));
+        if ratio <= end_ratio {
+            let t = if end_ratio > start_ratio {
+                (ratio - start_ratio) / (end_ratio - start_ratio)
+            } else {
+                1.0
+            };
+            let start = color_components(&start.color);
+            let end = color_components(&end.color);
+            let mut color = [0.0; 4];
+            for ((color, start), end) in color.iter_mut().zip(&start).zip(&end) {
+                *color = start + (end - start) * t;
+            }
+            return premultiply(color);
+        }
+    }
+    premultiplied_color(&last.color)
+}
+
+fn color_components(color: &Color) -> [f32; 4] {
+    [
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+        f32::from(color.a) / 255.0,
+    ]
+}
+
+fn premultiplied_color(color: &Color) -> [f32; 4] {
+    premultiply(color_components(color))
+}
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a > 0.0 {
+        [clamp(r / a), clamp(g / a), clamp(b / a), a]
+    } else {
+        [0.0; 4]
+    }
+}
+
+fn scale([r, g, b, a]: [f32; 4], amount: f32) -> [f32; 4] {
+    [r * amount, g * amount, b * amount, a * amount]
+}
+
+/// Draws the premultiplied color `top` over `bottom`.
+fn over(top: [f32; 4], bottom: [f32; 4]) -> [f32; 4] {
+    let remaining = 1.0 - top[3];
+    [
+        top[0] + bottom[0] * remaining,
+        top[1] + bottom[1] * remaining,
+        top[2] + bottom[2] * remaining,
+        top[3] + bottom[3] * remaining,
+    ]
+}
+
+fn clamp(value: f32) -> f32 {
+    value.max(0.0).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Makes a transparent image with an opaque white square in the middle.
+    fn build_image(size: usize, square: usize) -> FilterImage {
+        let start = (size - square) / 2;
+        let inside = start..start + square;
+        let pixels = (0..size * size)
+            .map(|i| {
+                if inside.contains(&(i % size)) && inside.contains(&(i / size)) {
+                    [1.0; 4]
+                } else {
+                    [0.0; 4]
+                }
+            })
+            .collect();
+        FilterImage {
+            width: size,
+            height: size,
+            pixels,
+        }
+    }
+
+    fn total_alpha(image: &FilterImage) -> f32 {
+        image.pixels.iter().map(|p| p[3]).sum()
+    }
+
+    #[test]
+    fn blur_spreads_without_losing_alpha() {
+        let mut image = build_image(16, 4);
+        let filter = Filter::BlurFilter(Box::new(swf::BlurFilter {
+            blur_x: 3.0,
+            blur_y: 3.0,
+            num_passes: 2,
+        }));
+        apply_filters(&mut image, &[filter]);
+        assert!((total_alpha(&image) - 16.0).abs() < 0.001);
+        assert!(image.pixels[16 * 5 + 5][3] > 0.0);
+        assert!(image.pixels[16 * 8 + 8][3] < 1.0);
+    }
+
+    #[test]
+    fn knockout_glow_removes_the_image() {
+        let mut image = build_image(16, 4);
+        let filter = Filter::GlowFilter(Box::new(swf::GlowFilter {
+            color: Color {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            blur_x: 4.0,
+            blur_y: 4.0,
+            strength: 1.0,
+            is_inner: false,
+            is_knockout: true,
+            num_passes: 1,
+        }));
+        apply_filters(&mut image, &[filter]);
+        assert_eq!(image.pixels[16 * 8 + 8], [0.0; 4]);
+        let glow = image.pixels[16 * 8 + 5];
+        assert!(glow[3] > 0.0);
+        assert_eq!(glow[1], 0.0);
+    }
+
+    #[test]
+    fn color_matrix_swaps_channels() {
+        let mut image = FilterImage::from_rgba(1, 1, &[255, 0, 0, 255]);
+        #[rustfmt::skip]
+        let matrix = [
+            0.0, 1.0, 0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        let filter = Filter::ColorMatrixFilter(Box::new(swf::ColorMatrixFilter { matrix }));
+        apply_filters(&mut image, &[filter]);
+        assert_eq!(image.to_rgba(), vec![0, 255, 0, 255]);
+    }
+}
@@ -1,22 +1,187 @@
 use crate::backend::render::{RenderBackend, ShapeHandle};
+use std::collections::HashMap;
+use std::rc::Rc;
 
 type Error = Box<dyn std::error::Error>;
 
 pub struct Font {
     glyphs: Vec<ShapeHandle>,
+
+    /// The outlines of the glyphs, kept for hit testing.
+    glyph_shapes: Rc<Vec<swf::Shape>>,
+
+    /// The name of the font, such as `Arial` or `_sans`.
+    name: String,
+
+    is_bold: bool,
+    is_italic: bool,
+
+    /// The glyph index of each character code, used to lay out the text of text fields.
+    code_point_to_glyph: HashMap<u16, usize>,
+
+    /// The distance to advance the pen after each glyph, in EM units.
+    advances: Vec<i16>,
+
+    /// The size of the EM square that the glyphs are drawn in.
+    /// `DefineFont3` glyphs have 20 times the resolution of the older font tags.
+    scale: f32,
+
+    /// The distance from the top of a line to the baseline, in EM units.
+    ascent: u16,
+
+    /// The distance from the baseline to the bottom of a line, in EM units.
+    descent: u16,
+
+    /// The extra space between lines, in EM units.
+    leading: i16,
+
+    /// The adjustment to the advance between pairs of characters.
+    kerning: HashMap<(u16, u16), i16>,
 }
 
 impl Font {
     pub fn from_swf_tag(renderer: &mut dyn RenderBackend, tag: &swf::Font) -> Result<Font, Error> {
         let mut glyphs = vec![];
-        for glyph in &tag.glyphs {
+        let mut glyph_shapes = vec![];
+        let mut code_point_to_glyph = HashMap::new();
+        let mut advances = vec![];
+        for (i, glyph) in tag.glyphs.iter().enumerate() {
             let shape_handle = renderer.register_glyph_shape(glyph);
             glyphs.push(shape_handle);
+            glyph_shapes.push(glyph_to_shape(glyph));
+            code_point_to_glyph.insert(glyph.code, i);
+            advances.push(glyph.advance.unwrap_or(0));
         }
-        Ok(Font { glyphs })
+        let scale = if tag.version >= 3 { 20480.0 } else { 1024.0 };
+        let (ascent, descent, leading, kerning) = match &tag.layout {
+            Some(layout) => (
+                layout.ascent,
+                layout.descent,
+                layout.leading,
+                layout
+                    .kerning
+                    .iter()
+                    .map(|record| {
+                        let adjustment = record.adjustment.get() as i16;
+                        ((record.left_code, record.right_code), adjustment)
+                    })
+                    .collect(),
+            ),
+            // Fonts without layout information are given the usual proportions of a font.
+            None => (
+                (scale * 0.8) as u16,
+                (scale * 0.2) as u16,
+                0,
+                HashMap::new(),
+            ),
+        };
+        Ok(Font {
+            glyphs,
+            glyph_shapes: Rc::new(glyph_shapes),
+            name: tag.name.clone(),
+            is_bold: tag.is_bold,
+            is_italic: tag.is_italic,
+            code_point_to_glyph,
+            advances,
+            scale,
+            ascent,
+            descent,
+            leading,
+            kerning,
+        })
     }
 
     pub fn get_glyph(&self, i: usize) -> Option<ShapeHandle> {
         self.glyphs.get(i).cloned()
     }
+
+    /// The outlines of all glyphs in this font, indexed the same way as `get_glyph`.
+    pub fn glyph_shapes(&self) -> Rc<Vec<swf::Shape>> {
+        Rc::clone(&self.glyph_shapes)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.is_bold
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.is_italic
+    }
+
+    /// Whether this font has any glyphs. Device fonts are defined without glyphs,
+    /// as the text is meant to be drawn with a font installed on the system.
+    pub fn has_glyphs(&self) -> bool {
+        !self.glyphs.is_empty()
+    }
+
+    /// Returns the index of the glyph for the character `c`.
+    pub fn glyph_index_for_char(&self, c: char) -> Option<usize> {
+        let code = c as u32;
+        if code > u32::from(u16::max_value()) {
+            return None;
+        }
+        self.code_point_to_glyph.get(&(code as u16)).copied()
+    }
+
+    /// Returns the advance of the glyph `i`, in EM units.
+    pub fn glyph_advance(&self, i: usize) -> i16 {
+        self.advances.get(i).copied().unwrap_or(0)
+    }
+
+    /// Returns the kerning adjustment between the characters `left` and `right`, in EM units.
+    pub fn kerning_offset(&self, left: char, right: char) -> i16 {
+        let (left, right) = (left as u32, right as u32);
+        if left > u32::from(u16::max_value()) || right > u32::from(u16::max_value()) {
+            return 0;
+        }
+        self.kerning
+            .get(&(left as u16, right as u16))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The size of the EM square, in the units of the glyph outlines.
+    /// Glyphs are scaled by `height / scale` to draw them at a font size of `height`.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn ascent(&self) -> u16 {
+        self.ascent
+    }
+
+    pub fn descent(&self) -> u16 {
+        self.descent
+    }
+
+    pub fn leading(&self) -> i16 {
+        self.leading
+    }
+}
+
+/// Converts a glyph into a shape with a single solid fill, as the renderers draw it.
+fn glyph_to_shape(glyph: &swf::Glyph) -> swf::Shape {
+    swf::Shape {
+        version: 2,
+        id: 0,
+        shape_bounds: Default::default(),
+        edge_bounds: Default::default(),
+        has_fill_winding_rule: false,
+        has_non_scaling_strokes: false,
+        has_scaling_strokes: true,
+        styles: swf::ShapeStyles {
+            fill_styles: vec![swf::FillStyle::Color(swf::Color {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            })],
+            line_styles: vec![],
+        },
+        shape: glyph.shape_records.clone(),
+    }
 }
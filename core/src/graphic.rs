@@ -2,6 +2,7 @@ use crate::backend::render::ShapeHandle;
 use crate::display_object::{DisplayObject, DisplayObjectBase};
 use crate::player::{RenderContext, UpdateContext};
 use crate::prelude::*;
+use crate::shape_utils::shape_hit_test;
 
 #[derive(Clone)]
 pub struct Graphic<'gc> {
@@ -15,6 +16,7 @@ impl<'gc> Graphic<'gc> {
             id: swf_shape.id,
             render_handle: context.renderer.register_shape(swf_shape),
             bounds: swf_shape.shape_bounds.clone().into(),
+            shape: swf_shape.clone(),
         };
         Graphic {
             base: Default::default(),
@@ -30,16 +32,9 @@ impl<'gc> DisplayObject<'gc> for Graphic<'gc> {
         self.static_data.bounds.clone()
     }
 
-    fn world_bounds(&self) -> BoundingBox {
-        // TODO: Use dirty flags and cache this.
-        let mut bounds = self.local_bounds().transform(self.matrix());
-        let mut node = self.parent();
-        while let Some(display_object) = node {
-            let display_object = display_object.read();
-            bounds = bounds.transform(display_object.matrix());
-            node = display_object.parent();
-        }
-        bounds
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        let point = self.global_to_local(point);
+        self.local_bounds().contains(point) && shape_hit_test(&self.static_data.shape, point)
     }
 
     fn run_frame(&mut self, _context: &mut UpdateContext) {
@@ -75,6 +70,9 @@ struct GraphicStatic {
     id: CharacterId,
     render_handle: ShapeHandle,
     bounds: BoundingBox,
+
+    /// The shape itself, kept for hit testing.
+    shape: swf::Shape,
 }
 
 unsafe impl<'gc> gc_arena::Collect for GraphicStatic {
@@ -0,0 +1,714 @@
+//! The subset of HTML understood by text fields, as set through `htmlText`.
+//!
+//! The supported tags are `<p align>`, `<font face size color>`, `<b>`, `<i>`, `<u>`,
+//! `<a href target>`, `<br>`, `<li>`, `<textformat>` and `<img>`. Other tags are ignored,
+//! while the text inside them is kept.
+
+use crate::prelude::*;
+use crate::text_format::{FormatSpans, TextFormat};
+use std::fmt::Write;
+use swf::TextAlign;
+
+/// The space around images that don't give their own `hspace` and `vspace`, in pixels.
+const DEFAULT_IMAGE_SPACE: f64 = 8.0;
+
+/// The text of an HTML document, with the formats of its characters.
+#[derive(Clone, Debug)]
+pub struct HtmlText {
+    /// The text, with `\r` for line breaks and the ends of paragraphs.
+    pub text: String,
+    pub spans: FormatSpans,
+    pub images: Vec<HtmlImage>,
+}
+
+/// An image embedded with `<img>`, which shows a movie clip or an image exported
+/// from the library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtmlImage {
+    /// The export name of the symbol to show.
+    pub src: String,
+
+    /// The instance name of the image, given by the `id` attribute.
+    pub id: String,
+
+    /// The size to scale the symbol to, or `None` to keep its own size.
+    pub width: Option<Twips>,
+    pub height: Option<Twips>,
+
+    /// The space left around the image, horizontally and vertically.
+    pub hspace: Twips,
+    pub vspace: Twips,
+
+    /// The index of the character that the image is placed before.
+    pub text_index: usize,
+}
+
+/// Parses an HTML document. Text outside of any formatting tags gets `default_format`.
+pub fn parse_html(html: &str, default_format: &TextFormat) -> HtmlText {
+    let mut parser = HtmlParser {
+        text: String::with_capacity(html.len()),
+        spans: FormatSpans::new(0, default_format.clone()),
+        images: vec![],
+        len: 0,
+        formats: vec![(String::new(), default_format.clone())],
+        pending_break: None,
+    };
+    let mut rest = html;
+    while let Some(start) = rest.find(|c| c == '<' || c == '&') {
+        parser.push_text(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with('<') {
+            match parse_tag(rest) {
+                Some((tag, tag_len)) => {
+                    parser.handle_tag(&tag);
+                    rest = &rest[tag_len..];
+                }
+                None => {
+                    parser.push_text("<");
+                    rest = &rest[1..];
+                }
+            }
+        } else {
+            match decode_entity(rest) {
+                Some((c, entity_len)) => {
+                    parser.push_text(c.encode_utf8(&mut [0; 4]));
+                    rest = &rest[entity_len..];
+                }
+                None => {
+                    parser.push_text("&");
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+    parser.push_text(rest);
+    // The end of the last paragraph doesn't start a new line, so `pending_break` is dropped.
+    HtmlText {
+        text: parser.text,
+        spans: parser.spans,
+        images: parser.images,
+    }
+}
+
+/// Writes `text` as HTML, in the upper case style of the HTML that Flash Player generates.
+pub fn to_html(text: &str, spans: &FormatSpans, images: &[HtmlImage]) -> String {
+    let mut html = String::with_capacity(text.len() * 2);
+    let chars: Vec<char> = text.chars().collect();
+    let mut paragraph_start = 0;
+    loop {
+        let paragraph_end = chars[paragraph_start..]
+            .iter()
+            .position(|&c| c == '\r' || c == '\n')
+            .map_or(chars.len(), |len| paragraph_start + len);
+        let paragraph_format = spans.format_at(paragraph_start);
+        write_paragraph_start(&mut html, paragraph_format);
+
+        // Each run of characters with the same format is wrapped in its own tags.
+        let mut index = paragraph_start;
+        let mut span_start = 0;
+        for span in spans.iter() {
+            let span_end = span_start + span.len;
+            let run_end = span_end.min(paragraph_end);
+            if run_end > index {
+                write_run(
+                    &mut html,
+                    &chars[index..run_end],
+                    index,
+                    &span.format,
+                    images,
+                );
+                index = run_end;
+            }
+            span_start = span_end;
+        }
+        if paragraph_start == paragraph_end {
+            // Empty paragraphs still give the font of the line.
+            write_run(&mut html, &[], index, paragraph_format, images);
+        }
+
+        write_paragraph_end(&mut html, paragraph_format);
+        if paragraph_end >= chars.len() {
+            break;
+        }
+        paragraph_start = paragraph_end + 1;
+    }
+    html
+}
+
+/// Escapes the characters of `text` that have a meaning in HTML.
+pub fn escape_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_escaped(&mut html, c);
+    }
+    html
+}
+
+fn push_escaped(html: &mut String, c: char) {
+    match c {
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        '&' => html.push_str("&amp;"),
+        '"' => html.push_str("&quot;"),
+        _ => html.push(c),
+    }
+}
+
+/// A tag, with its name and attribute names in lower case.
+struct Tag<'a> {
+    name: String,
+    is_closing: bool,
+    attributes: Vec<(String, &'a str)>,
+}
+
+impl<'a> Tag<'a> {
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(attribute_name, _)| attribute_name == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// The value of an attribute given in pixels, such as `width="100"`.
+    fn pixels(&self, name: &str) -> Option<Twips> {
+        self.attribute(name)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .map(Twips::from_pixels)
+    }
+}
+
+struct HtmlParser {
+    text: String,
+    spans: FormatSpans,
+    images: Vec<HtmlImage>,
+
+    /// The number of characters in `text`.
+    len: usize,
+
+    /// The open tags, with the format of the text inside them.
+    formats: Vec<(String, TextFormat)>,
+
+    /// The line break closing the last paragraph, which is only added once more
+    /// content follows it.
+    pending_break: Option<TextFormat>,
+}
+
+impl HtmlParser {
+    fn format(&self) -> &TextFormat {
+        &self.formats[self.formats.len() - 1].1
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.flush_break();
+        let len = text.chars().count();
+        self.text.push_str(text);
+        self.spans.push(len, self.format().clone());
+        self.len += len;
+    }
+
+    fn push_break(&mut self) {
+        self.flush_break();
+        self.text.push('\r');
+        self.spans.push(1, self.format().clone());
+        self.len += 1;
+    }
+
+    fn flush_break(&mut self) {
+        if let Some(format) = self.pending_break.take() {
+            self.text.push('\r');
+            self.spans.push(1, format);
+            self.len += 1;
+        }
+    }
+
+    /// Starts a new paragraph, unless the text is already at the start of one.
+    fn start_paragraph(&mut self) {
+        if self.len > 0 && !self.text.ends_with('\r') && self.pending_break.is_none() {
+            self.push_break();
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        self.flush_break();
+        self.pending_break = Some(self.format().clone());
+    }
+
+    fn open(&mut self, name: &str, change: TextFormat) {
+        let mut format = self.format().clone();
+        format.merge(&change);
+        self.formats.push((name.to_string(), format));
+    }
+
+    /// Closes the innermost open tag called `name`, along with any tags opened inside it.
+    fn close(&mut self, name: &str) {
+        let position = self
+            .formats
+            .iter()
+            .skip(1)
+            .rposition(|(tag_name, _)| tag_name == name);
+        if let Some(position) = position {
+            self.formats.truncate(position + 1);
+        }
+    }
+
+    fn handle_tag(&mut self, tag: &Tag<'_>) {
+        let name = tag.name.as_str();
+        if tag.is_closing {
+            match name {
+                "p" | "li" => {
+                    self.end_paragraph();
+                    self.close(name);
+                }
+                _ => self.close(name),
+            }
+            return;
+        }
+
+        match name {
+            "br" => self.push_break(),
+            "p" => {
+                self.start_paragraph();
+                let align = tag.attribute("align").and_then(align_from_html);
+                self.open(
+                    name,
+                    TextFormat {
+                        align,
+                        bullet: Some(false),
+                        ..Default::default()
+                    },
+                );
+            }
+            "li" => {
+                self.start_paragraph();
+                self.open(
+                    name,
+                    TextFormat {
+                        bullet: Some(true),
+                        ..Default::default()
+                    },
+                );
+            }
+            "font" => {
+                let size = tag.attribute("size").and_then(|size| {
+                    let size = size.trim();
+                    let pixels: f64 = size.trim_start_matches('+').parse().ok()?;
+                    // Sizes such as `+2` are relative to the size of the enclosing text.
+                    let pixels = if size.starts_with('+') || size.starts_with('-') {
+                        self.format().size.map_or(0.0, Twips::to_pixels) + pixels
+                    } else {
+                        pixels
+                    };
+                    Some(Twips::from_pixels(pixels.max(0.0)))
+                });
+                // Of a list of fonts, only the first is used.
+                let font = tag
+                    .attribute("face")
+                    .and_then(|face| face.split(',').next())
+                    .map(|face| face.trim().to_string());
+                let color = tag.attribute("color").and_then(color_from_html);
+                self.open(
+                    name,
+                    TextFormat {
+                        font,
+                        size,
+                        color,
+                        ..Default::default()
+                    },
+                );
+            }
+            "b" => self.open(
+                name,
+                TextFormat {
+                    bold: Some(true),
+                    ..Default::default()
+                },
+            ),
+            "i" => self.open(
+                name,
+                TextFormat {
+                    italic: Some(true),
+                    ..Default::default()
+                },
+            ),
+            "u" => self.open(
+                name,
+                TextFormat {
+                    underline: Some(true),
+                    ..Default::default()
+                },
+            ),
+            "a" => self.open(
+                name,
+                TextFormat {
+                    url: Some(tag.attribute("href").unwrap_or("").to_string()),
+                    target: Some(tag.attribute("target").unwrap_or("").to_string()),
+                    ..Default::default()
+                },
+            ),
+            "textformat" => {
+                let tab_stops = tag.attribute("tabstops").map(|tab_stops| {
+                    tab_stops
+                        .trim_matches(|c| c == '[' || c == ']')
+                        .split(',')
+                        .filter_map(|tab_stop| tab_stop.trim().parse::<f64>().ok())
+                        .map(Twips::from_pixels)
+                        .collect()
+                });
+                self.open(
+                    name,
+                    TextFormat {
+                        block_indent: tag.pixels("blockindent"),
+                        indent: tag.pixels("indent"),
+                        leading: tag.pixels("leading"),
+                        left_margin: tag.pixels("leftmargin"),
+                        right_margin: tag.pixels("rightmargin"),
+                        tab_stops,
+                        ..Default::default()
+                    },
+                );
+            }
+            "img" => {
+                self.flush_break();
+                let space = Twips::from_pixels(DEFAULT_IMAGE_SPACE);
+                self.images.push(HtmlImage {
+                    src: tag.attribute("src").unwrap_or("").to_string(),
+                    id: tag.attribute("id").unwrap_or("").to_string(),
+                    width: tag.pixels("width"),
+                    height: tag.pixels("height"),
+                    hspace: tag.pixels("hspace").unwrap_or(space),
+                    vspace: tag.pixels("vspace").unwrap_or(space),
+                    text_index: self.len,
+                });
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Parses the tag at the start of `html`, returning it and its length in bytes.
+/// Returns `None` if the `<` doesn't start a tag, as in `a < b`.
+fn parse_tag(html: &str) -> Option<(Tag<'_>, usize)> {
+    let end = html.find('>')?;
+    let source = &html[1..end];
+    let (is_closing, source) = if source.starts_with('/') {
+        (true, &source[1..])
+    } else {
+        (false, source)
+    };
+    if !source.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let source = source.trim_end_matches('/');
+    let name_end = source
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(source.len());
+    let name = source[..name_end].to_lowercase();
+
+    let mut attributes = vec![];
+    let mut rest = source[name_end..].trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let attribute_name = rest[..name_end].to_lowercase();
+        rest = rest[name_end..].trim_start();
+        let value = if rest.starts_with('=') {
+            rest = rest[1..].trim_start();
+            match rest.chars().next() {
+                Some(quote) if quote == '"' || quote == '\'' => {
+                    let value_end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+                    let value = &rest[1..value_end];
+                    rest = rest.get(value_end + 1..).unwrap_or("");
+                    value
+                }
+                _ => {
+                    let value_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    let value = &rest[..value_end];
+                    rest = &rest[value_end..];
+                    value
+                }
+            }
+        } else {
+            ""
+        };
+        attributes.push((attribute_name, value));
+        rest = rest.trim_start();
+    }
+
+    let tag = Tag {
+        name,
+        is_closing,
+        attributes,
+    };
+    Some((tag, end + 1))
+}
+
+/// Decodes the entity at the start of `html`, such as `&amp;` or `&#169;`,
+/// returning the character and the length of the entity in bytes.
+fn decode_entity(html: &str) -> Option<(char, usize)> {
+    let end = html.find(';').filter(|&end| end <= 10)?;
+    let name = &html[1..end];
+    let c = match name {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        _ if name.starts_with("#x") || name.starts_with("#X") => {
+            std::char::from_u32(u32::from_str_radix(&name[2..], 16).ok()?)?
+        }
+        _ if name.starts_with('#') => std::char::from_u32(name[1..].parse().ok()?)?,
+        _ => return None,
+    };
+    Some((c, end + 1))
+}
+
+fn align_from_html(align: &str) -> Option<TextAlign> {
+    match align.to_lowercase().as_str() {
+        "left" => Some(TextAlign::Left),
+        "center" => Some(TextAlign::Center),
+        "right" => Some(TextAlign::Right),
+        "justify" => Some(TextAlign::Justify),
+        _ => None,
+    }
+}
+
+fn align_to_html(align: TextAlign) -> &'static str {
+    match align {
+        TextAlign::Left => "LEFT",
+        TextAlign::Center => "CENTER",
+        TextAlign::Right => "RIGHT",
+        TextAlign::Justify => "JUSTIFY",
+    }
+}
+
+/// Parses a color such as `#ff0000`.
+fn color_from_html(color: &str) -> Option<Color> {
+    let rgb = u32::from_str_radix(color.trim().trim_start_matches('#'), 16).ok()?;
+    Some(Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: 255,
+    })
+}
+
+/// Whether the paragraph format differs from the defaults, so that it needs a `<TEXTFORMAT>`.
+fn has_text_format(format: &TextFormat) -> bool {
+    let is_set = |value: Option<Twips>| value.map_or(false, |value| value != Twips::new(0));
+    is_set(format.left_margin)
+        || is_set(format.right_margin)
+        || is_set(format.indent)
+        || is_set(format.block_indent)
+        || is_set(format.leading)
+        || format
+            .tab_stops
+            .as_ref()
+            .map_or(false, |tab_stops| !tab_stops.is_empty())
+}
+
+fn write_paragraph_start(html: &mut String, format: &TextFormat) {
+    if has_text_format(format) {
+        let pixels = |value: Option<Twips>| value.unwrap_or_default().to_pixels();
+        let tab_stops: Vec<String> = format
+            .tab_stops
+            .iter()
+            .flatten()
+            .map(|tab_stop| tab_stop.to_pixels().to_string())
+            .collect();
+        let _ = write!(
+            html,
+            "<TEXTFORMAT LEFTMARGIN=\"{}\" RIGHTMARGIN=\"{}\" INDENT=\"{}\" BLOCKINDENT=\"{}\" LEADING=\"{}\" TABSTOPS=\"{}\">",
+            pixels(format.left_margin),
+            pixels(format.right_margin),
+            pixels(format.indent),
+            pixels(format.block_indent),
+            pixels(format.leading),
+            tab_stops.join(","),
+        );
+    }
+    if format.bullet == Some(true) {
+        html.push_str("<LI>");
+    } else {
+        let align = align_to_html(format.align.unwrap_or(TextAlign::Left));
+        let _ = write!(html, "<P ALIGN=\"{}\">", align);
+    }
+}
+
+fn write_paragraph_end(html: &mut String, format: &TextFormat) {
+    if format.bullet == Some(true) {
+        html.push_str("</LI>");
+    } else {
+        html.push_str("</P>");
+    }
+    if has_text_format(format) {
+        html.push_str("</TEXTFORMAT>");
+    }
+}
+
+/// Writes the characters `chars`, starting at `start` in the text, in the tags of `format`.
+/// The images placed before these characters are written along with them.
+fn write_run(
+    html: &mut String,
+    chars: &[char],
+    start: usize,
+    format: &TextFormat,
+    images: &[HtmlImage],
+) {
+    html.push_str("<FONT");
+    if let Some(font) = &format.font {
+        let _ = write!(html, " FACE=\"{}\"", escape_html(font));
+    }
+    if let Some(size) = format.size {
+        let _ = write!(html, " SIZE=\"{}\"", size.to_pixels());
+    }
+    if let Some(color) = &format.color {
+        let _ = write!(
+            html,
+            " COLOR=\"#{:02X}{:02X}{:02X}\"",
+            color.r, color.g, color.b
+        );
+    }
+    html.push('>');
+    let url = format.url.as_ref().filter(|url| !url.is_empty());
+    if let Some(url) = url {
+        let target = format.target.as_ref().map_or("", String::as_str);
+        let _ = write!(
+            html,
+            "<A HREF=\"{}\" TARGET=\"{}\">",
+            escape_html(url),
+            escape_html(target)
+        );
+    }
+    let styles = [
+        (format.bold, "B"),
+        (format.italic, "I"),
+        (format.underline, "U"),
+    ];
+    for (is_set, tag) in &styles {
+        if *is_set == Some(true) {
+            let _ = write!(html, "<{}>", tag);
+        }
+    }
+
+    for (i, &c) in chars.iter().enumerate() {
+        for image in images.iter().filter(|image| image.text_index == start + i) {
+            write_image(html, image);
+        }
+        push_escaped(html, c);
+    }
+    if chars.is_empty() {
+        for image in images.iter().filter(|image| image.text_index == start) {
+            write_image(html, image);
+        }
+    }
+
+    for (is_set, tag) in styles.iter().rev() {
+        if *is_set == Some(true) {
+            let _ = write!(html, "</{}>", tag);
+        }
+    }
+    if url.is_some() {
+        html.push_str("</A>");
+    }
+    html.push_str("</FONT>");
+}
+
+fn write_image(html: &mut String, image: &HtmlImage) {
+    let _ = write!(html, "<IMG SRC=\"{}\"", escape_html(&image.src));
+    if let Some(width) = image.width {
+        let _ = write!(html, " WIDTH=\"{}\"", width.to_pixels());
+    }
+    if let Some(height) = image.height {
+        let _ = write!(html, " HEIGHT=\"{}\"", height.to_pixels());
+    }
+    let _ = write!(
+        html,
+        " HSPACE=\"{}\" VSPACE=\"{}\"",
+        image.hspace.to_pixels(),
+        image.vspace.to_pixels()
+    );
+    if !image.id.is_empty() {
+        let _ = write!(html, " ID=\"{}\"", escape_html(&image.id));
+    }
+    html.push('>');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_format() -> TextFormat {
+        TextFormat {
+            font: Some("Arial".to_string()),
+            size: Some(Twips::from_pixels(12.0)),
+            bold: Some(false),
+            align: Some(TextAlign::Left),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paragraphs_and_entities() {
+        let html = parse_html(
+            "<p align=\"left\"><font size=\"12\">a &amp; b</font></p><p>c<br>d</p>",
+            &default_format(),
+        );
+        assert_eq!(html.text, "a & b\rc\rd");
+        let html = parse_html("a&#65;&#x42;&unknown; < b", &default_format());
+        assert_eq!(html.text, "aAB&unknown; < b");
+        assert_eq!(escape_html("<a & b>"), "&lt;a &amp; b&gt;");
+    }
+
+    #[test]
+    fn nested_formats() {
+        let html = parse_html(
+            "<FONT FACE='Verdana' SIZE='+2' COLOR='#FF0000'>a<B>b</B></FONT>c",
+            &default_format(),
+        );
+        assert_eq!(html.text, "abc");
+        let a = html.spans.format_at(0);
+        assert_eq!(a.font.as_ref().map(String::as_str), Some("Verdana"));
+        assert_eq!(a.size, Some(Twips::from_pixels(14.0)));
+        assert_eq!(a.color.as_ref().map(|color| color.r), Some(255));
+        assert_eq!(a.bold, Some(false));
+        assert_eq!(html.spans.format_at(1).bold, Some(true));
+        assert_eq!(html.spans.format_at(2), &default_format());
+    }
+
+    #[test]
+    fn lists_links_and_images() {
+        let html = parse_html(
+            "<li>one</li><li><a href='asfunction:go,1'>two</a></li><img src='logo' width='10'>",
+            &default_format(),
+        );
+        assert_eq!(html.text, "one\rtwo\r");
+        assert_eq!(html.spans.format_at(0).bullet, Some(true));
+        assert_eq!(
+            html.spans.format_at(4).url.as_ref().map(String::as_str),
+            Some("asfunction:go,1")
+        );
+        assert_eq!(html.images.len(), 1);
+        assert_eq!(html.images[0].src, "logo");
+        assert_eq!(html.images[0].width, Some(Twips::from_pixels(10.0)));
+        assert_eq!(html.images[0].text_index, 8);
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "<P ALIGN=\"CENTER\"><FONT FACE=\"Arial\" SIZE=\"12\">a<B>b</B></FONT></P>";
+        let html = parse_html(source, &default_format());
+        let generated = to_html(&html.text, &html.spans, &html.images);
+        assert_eq!(
+            generated,
+            "<P ALIGN=\"CENTER\"><FONT FACE=\"Arial\" SIZE=\"12\">a</FONT><FONT FACE=\"Arial\" SIZE=\"12\"><B>b</B></FONT></P>"
+        );
+        let reparsed = parse_html(&generated, &default_format());
+        assert_eq!(reparsed.text, html.text);
+        assert_eq!(reparsed.spans.format_at(1), html.spans.format_at(1));
+    }
+}
@@ -6,9 +6,14 @@ mod bounding_box;
 mod button;
 mod character;
 mod color_transform;
+mod drag;
+mod drawing;
+mod edit_text;
 mod events;
+pub mod filters;
 mod font;
 mod graphic;
+mod html;
 mod library;
 pub mod matrix;
 mod morph_shape;
@@ -18,11 +23,12 @@ mod prelude;
 pub mod shape_utils;
 pub mod tag_utils;
 mod text;
+mod text_format;
 mod transform;
 
 pub mod backend;
 
-pub use events::PlayerEvent;
+pub use events::{key_code, KeyCode, PlayerEvent};
 pub use player::Player;
 pub use swf;
 pub use swf::Color;
@@ -1,14 +1,17 @@
+use crate::avm1::SystemPrototypes;
 use crate::backend::audio::SoundHandle;
 use crate::character::Character;
 use crate::display_object::DisplayObject;
 use crate::font::Font;
 use crate::prelude::*;
 use gc_arena::{GcCell, MutationContext};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use swf::CharacterId;
 
 pub struct Library<'gc> {
     characters: HashMap<CharacterId, Character<'gc>>,
+    export_characters: HashMap<String, CharacterId>,
+    init_actions_run: HashSet<CharacterId>,
     jpeg_tables: Option<Vec<u8>>,
 }
 
@@ -16,6 +19,8 @@ impl<'gc> Library<'gc> {
     pub fn new() -> Self {
         Library {
             characters: HashMap::new(),
+            export_characters: HashMap::new(),
+            init_actions_run: HashSet::new(),
             jpeg_tables: None,
         }
     }
@@ -29,6 +34,22 @@ impl<'gc> Library<'gc> {
         }
     }
 
+    /// Registers the linkage name `name` for a character, as given by an `ExportAssets` tag.
+    pub fn register_export(&mut self, id: CharacterId, name: &str) {
+        self.export_characters.insert(name.to_string(), id);
+    }
+
+    /// Returns the ID of the character exported with the linkage name `name`.
+    pub fn character_id_by_export_name(&self, name: &str) -> Option<CharacterId> {
+        self.export_characters.get(name).copied()
+    }
+
+    /// Records that the `DoInitAction` code of the sprite `id` is about to run.
+    /// Returns `false` if it has already run, since init actions only run once per movie.
+    pub fn mark_init_action_run(&mut self, id: CharacterId) -> bool {
+        self.init_actions_run.insert(id)
+    }
+
     pub fn contains_character(&self, id: CharacterId) -> bool {
         self.characters.contains_key(&id)
     }
@@ -45,6 +66,7 @@ impl<'gc> Library<'gc> {
         &self,
         id: CharacterId,
         gc_context: MutationContext<'gc, '_>,
+        prototypes: &SystemPrototypes<'gc>,
     ) -> Result<DisplayNode<'gc>, Box<dyn std::error::Error>> {
         let obj: Box<dyn DisplayObject<'gc>> = match self.characters.get(&id) {
             Some(Character::Graphic(graphic)) => graphic.clone(),
@@ -52,10 +74,15 @@ impl<'gc> Library<'gc> {
             Some(Character::MovieClip(movie_clip)) => movie_clip.clone(),
             Some(Character::Button(button)) => button.clone(),
             Some(Character::Text(text)) => text.clone(),
+            Some(Character::EditText(edit_text)) => edit_text.clone(),
             Some(_) => return Err("Not a DisplayObject".into()),
             None => return Err("Character id doesn't exist".into()),
         };
-        Ok(GcCell::allocate(gc_context, obj))
+        let display_object = GcCell::allocate(gc_context, obj);
+        display_object
+            .write(gc_context)
+            .post_instantiation(gc_context, display_object, prototypes);
+        Ok(display_object)
     }
 
     pub fn get_font(&self, id: CharacterId) -> Option<&Font> {
@@ -66,6 +93,28 @@ impl<'gc> Library<'gc> {
         }
     }
 
+    /// Returns the ID of an embedded font called `name` that has glyphs, preferring
+    /// the font with the given style. Bold and italic text is drawn with the regular
+    /// font if the movie doesn't embed a font of that style.
+    pub fn font_id_by_name(
+        &self,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<CharacterId> {
+        self.characters
+            .iter()
+            .filter_map(|(id, character)| match character {
+                Character::Font(font) if font.name() == name && font.has_glyphs() => {
+                    let is_other_style = font.is_bold() != is_bold || font.is_italic() != is_italic;
+                    Some((is_other_style, *id))
+                }
+                _ => None,
+            })
+            .min()
+            .map(|(_, id)| id)
+    }
+
     pub fn get_sound(&self, id: CharacterId) -> Option<SoundHandle> {
         if let Some(Character::Sound(sound)) = self.characters.get(&id) {
             Some(*sound)
@@ -2,6 +2,7 @@ use crate::backend::render::{RenderBackend, ShapeHandle};
 use crate::display_object::{DisplayObject, DisplayObjectBase};
 use crate::player::{RenderContext, UpdateContext};
 use crate::prelude::*;
+use crate::shape_utils::shape_hit_test;
 use std::collections::HashMap;
 use swf::Twips;
 
@@ -13,7 +14,7 @@ pub struct MorphShape<'gc> {
 
     end: swf::MorphShape,
 
-    frames: HashMap<u16, ShapeHandle>,
+    frames: HashMap<u16, Frame>,
 
     ratio: u16,
 }
@@ -217,7 +218,13 @@ impl<'gc> MorphShape<'gc> {
         };
 
         let shape_handle = renderer.register_shape(&shape);
-        self.frames.insert(ratio, shape_handle);
+        self.frames.insert(
+            ratio,
+            Frame {
+                shape_handle,
+                shape,
+            },
+        );
     }
 
     fn update_pos(x: &mut Twips, y: &mut Twips, record: &swf::ShapeRecord) {
@@ -382,6 +389,24 @@ impl<'gc> DisplayObject<'gc> for MorphShape<'gc> {
         Some(self)
     }
 
+    fn local_bounds(&self) -> BoundingBox {
+        // Interpolate the bounds of the start and end shapes.
+        let b = f32::from(self.ratio) / 65535.0;
+        let a = 1.0 - b;
+        let start = &self.start.shape_bounds;
+        let end = &self.end.shape_bounds;
+        let lerp = |start: Twips, end: Twips| {
+            Twips::new((a * start.get() as f32 + b * end.get() as f32) as i32)
+        };
+        BoundingBox {
+            x_min: lerp(start.x_min, end.x_min),
+            y_min: lerp(start.y_min, end.y_min),
+            x_max: lerp(start.x_max, end.x_max),
+            y_max: lerp(start.y_max, end.y_max),
+            valid: true,
+        }
+    }
+
     fn run_frame(&mut self, context: &mut UpdateContext) {
         if !self.frames.contains_key(&self.ratio) {
             self.register_ratio(context.renderer, self.ratio);
@@ -391,16 +416,35 @@ impl<'gc> DisplayObject<'gc> for MorphShape<'gc> {
     fn render(&self, context: &mut RenderContext) {
         context.transform_stack.push(self.transform());
 
-        if let Some(shape) = self.frames.get(&self.ratio) {
+        if let Some(frame) = self.frames.get(&self.ratio) {
             context
                 .renderer
-                .render_shape(*shape, context.transform_stack.transform());
+                .render_shape(frame.shape_handle, context.transform_stack.transform());
         } else {
             warn!("Missing ratio for morph shape");
         }
 
         context.transform_stack.pop();
     }
+
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        let point = self.global_to_local(point);
+        match self.frames.get(&self.ratio) {
+            Some(frame) => {
+                self.local_bounds().contains(point) && shape_hit_test(&frame.shape, point)
+            }
+            None => false,
+        }
+    }
+}
+
+/// A morph shape interpolated at one ratio.
+#[derive(Clone)]
+struct Frame {
+    shape_handle: ShapeHandle,
+
+    /// The interpolated shape, kept for hit testing.
+    shape: swf::Shape,
 }
 
 unsafe impl<'gc> gc_arena::Collect for MorphShape<'gc> {
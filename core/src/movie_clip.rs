@@ -2,20 +2,33 @@ use crate::avm1;
 use crate::backend::audio::AudioStreamHandle;
 use crate::character::Character;
 use crate::color_transform::ColorTransform;
-use crate::display_object::{DisplayObject, DisplayObjectBase};
+use crate::display_object::{render_with_effects, world_matrix, DisplayObject, DisplayObjectBase};
+use crate::drawing::Drawing;
+use crate::edit_text::EditText;
+use crate::events::ButtonKeyCode;
 use crate::font::Font;
 use crate::graphic::Graphic;
 use crate::matrix::Matrix;
 use crate::morph_shape::MorphShape;
-use crate::player::{RenderContext, UpdateContext};
+use crate::player::{ActionType, RenderContext, UpdateContext};
 use crate::prelude::*;
-use crate::tag_utils::{self, DecodeResult, SwfStream};
+use crate::tag_utils::{self, DecodeResult, SwfSlice, SwfStream};
 use crate::text::Text;
-use gc_arena::{Gc, MutationContext};
+use crate::transform::Transform;
+use gc_arena::{Gc, GcCell, MutationContext};
 use std::collections::{BTreeMap, HashMap};
 use swf::read::SwfRead;
+use swf::ClipEvent;
 
-type Depth = i16;
+/// Depths are wider than in SWF tags, so that scripts can create clips above the timeline.
+type Depth = i32;
+
+/// Scripts refer to depths relative to this one; depths from 0 upwards are reserved for
+/// clips created by scripts, and timeline depths appear negative to scripts.
+pub const AVM_DEPTH_BIAS: Depth = 16384;
+
+/// The largest depth, relative to `AVM_DEPTH_BIAS`, that scripts can create clips at.
+pub const AVM_MAX_DEPTH: Depth = 1_048_575;
 type FrameNumber = u16;
 
 #[derive(Clone)]
@@ -28,7 +41,19 @@ pub struct MovieClip<'gc> {
     current_frame: FrameNumber,
     audio_stream: Option<AudioStreamHandle>,
     children: BTreeMap<Depth, DisplayNode<'gc>>,
-    variables: HashMap<String, avm1::Value>,
+    object: Option<GcCell<'gc, avm1::Object<'gc>>>,
+    clip_actions: Vec<ClipAction>,
+    initialized: bool,
+    drawing: Drawing,
+
+    /// The clip under the mouse when this clip was last dragged, as reported by `_droptarget`.
+    drop_target: Option<DisplayNode<'gc>>,
+
+    /// The clip that this clip is clipped to, as set by `setMask`.
+    masker: Option<DisplayNode<'gc>>,
+
+    /// The clip that this clip is the mask of. A clip used as a mask is not drawn itself.
+    maskee: Option<DisplayNode<'gc>>,
 }
 
 impl<'gc> MovieClip<'gc> {
@@ -42,7 +67,13 @@ impl<'gc> MovieClip<'gc> {
             current_frame: 0,
             audio_stream: None,
             children: BTreeMap::new(),
-            variables: HashMap::new(),
+            object: None,
+            clip_actions: Vec::new(),
+            initialized: false,
+            drawing: Drawing::new(),
+            drop_target: None,
+            masker: None,
+            maskee: None,
         }
     }
 
@@ -72,7 +103,13 @@ impl<'gc> MovieClip<'gc> {
             current_frame: 0,
             audio_stream: None,
             children: BTreeMap::new(),
-            variables: HashMap::new(),
+            object: None,
+            clip_actions: Vec::new(),
+            initialized: false,
+            drawing: Drawing::new(),
+            drop_target: None,
+            masker: None,
+            maskee: None,
         }
     }
 
@@ -104,6 +141,8 @@ impl<'gc> MovieClip<'gc> {
     }
 
     pub fn goto_frame(&mut self, frame: FrameNumber, stop: bool) {
+        // Frames past the end go to the last frame.
+        let frame = frame.min(self.total_frames());
         if frame != self.current_frame {
             self.goto_queue.push(frame);
         }
@@ -115,38 +154,6 @@ impl<'gc> MovieClip<'gc> {
         }
     }
 
-    pub fn x(&self) -> f32 {
-        self.matrix().tx / Twips::TWIPS_PER_PIXEL as f32
-    }
-
-    pub fn set_x(&mut self, val: f32) {
-        self.matrix_mut().tx = val * Twips::TWIPS_PER_PIXEL as f32;
-    }
-
-    pub fn y(&self) -> f32 {
-        self.matrix().ty / Twips::TWIPS_PER_PIXEL as f32
-    }
-
-    pub fn set_y(&mut self, val: f32) {
-        self.matrix_mut().ty = val * Twips::TWIPS_PER_PIXEL as f32;
-    }
-
-    pub fn x_scale(&self) -> f32 {
-        self.matrix().a * 100.0
-    }
-
-    pub fn set_x_scale(&mut self, val: f32) {
-        self.matrix_mut().a = val / 100.0;
-    }
-
-    pub fn y_scale(&self) -> f32 {
-        self.matrix().d * 100.0
-    }
-
-    pub fn set_y_scale(&mut self, val: f32) {
-        self.matrix_mut().d = val / 100.0;
-    }
-
     pub fn current_frame(&self) -> FrameNumber {
         self.current_frame
     }
@@ -155,31 +162,6 @@ impl<'gc> MovieClip<'gc> {
         self.static_data.total_frames
     }
 
-    pub fn rotation(&self) -> f32 {
-        // TODO: Cache the user-friendly transform values like rotation.
-        let matrix = self.matrix();
-        f32::atan2(matrix.b, matrix.a).to_degrees()
-    }
-
-    pub fn set_rotation(&mut self, degrees: f32) {
-        // TODO: Use cached user-friendly transform values.
-        let angle = degrees.to_radians();
-        let cos = f32::cos(angle);
-        let sin = f32::sin(angle);
-        let scale_x = self.x_scale() / 100.0;
-        let scale_y = self.y_scale() / 100.0;
-
-        let matrix = self.matrix_mut();
-        *matrix = Matrix {
-            a: scale_x * cos,
-            b: scale_x * sin,
-            c: scale_y * cos,
-            d: -scale_y * sin,
-            tx: matrix.tx,
-            ty: matrix.ty,
-        };
-    }
-
     pub fn frames_loaded(&self) -> FrameNumber {
         // TODO(Herschel): root needs to progressively stream in frames.
         self.static_data.total_frames
@@ -210,7 +192,7 @@ impl<'gc> MovieClip<'gc> {
                 // Rewind
                 // Reset everything to blank, start from frame 1,
                 // and advance forward
-                self.children.clear();
+                self.remove_timeline_children(context);
                 self.tag_stream_pos = 0;
                 self.current_frame = 0;
                 while self.current_frame + 1 < frame {
@@ -225,17 +207,249 @@ impl<'gc> MovieClip<'gc> {
         self.goto_queue.clear();
     }
 
-    pub fn get_variable(&self, var_name: &str) -> avm1::Value {
-        // TODO: Value should be Copy (and contain a Cow/GcCell for big objects)
-        self.variables
-            .get(var_name)
-            .unwrap_or(&avm1::Value::Undefined)
-            .clone()
+    /// Sets the `onClipEvent` handlers of this instance, from a `PlaceObject` tag.
+    pub fn set_clip_actions(&mut self, clip_actions: &[swf::ClipAction]) {
+        self.clip_actions.clear();
+        for clip_action in clip_actions {
+            let action_data = SwfSlice {
+                data: std::sync::Arc::new(clip_action.action_data.clone()),
+                start: 0,
+                end: clip_action.action_data.len(),
+            };
+            for event in &clip_action.events {
+                self.clip_actions.push(ClipAction {
+                    event: *event,
+                    key_code: clip_action.key_code,
+                    action_data: action_data.clone(),
+                });
+            }
+        }
+    }
+
+    /// Queues the handlers of this clip for `event`: both the `onClipEvent` code placed on
+    /// the instance and the matching method of its object, such as `onEnterFrame`.
+    /// `self_node` is the node that contains this clip.
+    pub fn run_clip_event(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        self_node: DisplayNode<'gc>,
+        event: ClipEvent,
+    ) {
+        for clip_action in &self.clip_actions {
+            if clip_action.event == event {
+                context.actions.push((
+                    self_node,
+                    ActionType::Normal(clip_action.action_data.clone()),
+                ));
+            }
+        }
+
+        if let Some(method) = event_method_name(event) {
+            context
+                .actions
+                .push((self_node, ActionType::Method(method)));
+        }
+    }
+
+    /// Whether this clip handles button events such as `onPress`,
+    /// in which case the mouse picks the clip as a whole rather than its children.
+    fn has_button_handlers(&self) -> bool {
+        let is_button_event = |event| match event {
+            ClipEvent::Press
+            | ClipEvent::Release
+            | ClipEvent::ReleaseOutside
+            | ClipEvent::RollOver
+            | ClipEvent::RollOut
+            | ClipEvent::DragOver
+            | ClipEvent::DragOut => true,
+            _ => false,
+        };
+        if self
+            .clip_actions
+            .iter()
+            .any(|clip_action| is_button_event(clip_action.event))
+        {
+            return true;
+        }
+
+        if let Some(object) = self.object {
+            let object = object.read();
+            BUTTON_METHODS
+                .iter()
+                .any(|method| object.has_property(method))
+        } else {
+            false
+        }
     }
 
-    pub fn set_variable(&mut self, var_name: &str, value: avm1::Value) {
-        // TODO: Cow for String values.
-        self.variables.insert(var_name.to_owned(), value);
+    /// Adds a child created by a script, such as with `attachMovie`.
+    /// Any child already at `depth` is replaced.
+    pub fn add_child_from_avm(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        self_node: DisplayNode<'gc>,
+        child: DisplayNode<'gc>,
+        name: &str,
+        depth: Depth,
+    ) {
+        {
+            let mut child = child.write(gc_context);
+            child.set_parent(Some(self_node));
+            child.set_name(name);
+        }
+        if let Some(prev_child) = self.children.insert(depth, child) {
+            prev_child.write(gc_context).set_parent(None);
+        }
+    }
+
+    /// Removes `child` from this clip. Returns `false` if it is not a child of this clip.
+    pub fn remove_child(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        child: DisplayNode<'gc>,
+    ) -> bool {
+        if let Some(depth) = self.child_depth(child) {
+            self.children.remove(&depth);
+            child.write(gc_context).set_parent(None);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the depth of `child` in this clip.
+    pub fn child_depth(&self, child: DisplayNode<'gc>) -> Option<Depth> {
+        self.children
+            .iter()
+            .find(|(_, other)| other.as_ptr() == child.as_ptr())
+            .map(|(depth, _)| *depth)
+    }
+
+    /// Moves `child` to `depth`. Any child already at `depth` takes the old depth of `child`.
+    pub fn swap_child_to_depth(&mut self, child: DisplayNode<'gc>, depth: Depth) {
+        if let Some(prev_depth) = self.child_depth(child) {
+            if let Some(other) = self.children.remove(&depth) {
+                self.children.insert(prev_depth, other);
+            } else {
+                self.children.remove(&prev_depth);
+            }
+            self.children.insert(depth, child);
+        }
+    }
+
+    /// Returns the lowest depth, relative to `AVM_DEPTH_BIAS`, that is above every child.
+    pub fn next_highest_avm_depth(&self) -> Depth {
+        self.children
+            .keys()
+            .next_back()
+            .map_or(0, |depth| (depth - AVM_DEPTH_BIAS + 1).max(0))
+    }
+
+    /// Creates a new instance of this clip for `duplicateMovieClip`, which starts from
+    /// its first frame with the `onClipEvent` handlers and the drawing of this clip.
+    ///
+    /// The instance shares the static data of this clip rather than being instantiated
+    /// from the library, so that clips made by `createEmptyMovieClip` can be copied too.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            base: Default::default(),
+            static_data: self.static_data,
+            tag_stream_pos: 0,
+            is_playing: true,
+            goto_queue: Vec::new(),
+            current_frame: 0,
+            audio_stream: None,
+            children: BTreeMap::new(),
+            object: None,
+            clip_actions: self.clip_actions.clone(),
+            initialized: false,
+            drawing: self.drawing.clone(),
+            drop_target: None,
+            masker: None,
+            maskee: None,
+        }
+    }
+
+    pub fn drawing(&self) -> &Drawing {
+        &self.drawing
+    }
+
+    pub fn drawing_mut(&mut self) -> &mut Drawing {
+        &mut self.drawing
+    }
+
+    pub fn drop_target(&self) -> Option<DisplayNode<'gc>> {
+        self.drop_target
+    }
+
+    pub fn set_drop_target(&mut self, drop_target: Option<DisplayNode<'gc>>) {
+        self.drop_target = drop_target;
+    }
+
+    pub fn masker(&self) -> Option<DisplayNode<'gc>> {
+        self.masker
+    }
+
+    pub fn set_masker(&mut self, masker: Option<DisplayNode<'gc>>) {
+        self.masker = masker;
+    }
+
+    pub fn maskee(&self) -> Option<DisplayNode<'gc>> {
+        self.maskee
+    }
+
+    pub fn set_maskee(&mut self, maskee: Option<DisplayNode<'gc>>) {
+        self.maskee = maskee;
+    }
+
+    /// Renders the child `child`, clipped to its mask if it has one from `setMask`.
+    /// Clips that are the mask of another clip are not rendered.
+    fn render_child(&self, context: &mut RenderContext<'_, 'gc>, child: &dyn DisplayObject<'gc>) {
+        let (masker, is_mask) = match child.as_movie_clip() {
+            Some(clip) => (clip.masker(), clip.maskee().is_some()),
+            None => (None, false),
+        };
+        if is_mask {
+            return;
+        }
+        let masker = match masker {
+            Some(masker) => masker,
+            None => {
+                render_with_effects(context, child);
+                return;
+            }
+        };
+
+        // The mask may be anywhere in the display list, so it is moved from the coordinate
+        // space of its parent into the coordinate space of this clip.
+        let masker = masker.read();
+        let mut matrix = self.world_matrix();
+        matrix.invert();
+        matrix = matrix * masker.parent().map(world_matrix).unwrap_or_default();
+        context.renderer.push_mask();
+        context.transform_stack.push(&Transform {
+            matrix,
+            color_transform: Default::default(),
+        });
+        masker.render(context);
+        context.transform_stack.pop();
+        context.renderer.activate_mask();
+        render_with_effects(context, child);
+        context.renderer.pop_mask();
+    }
+
+    /// Whether `point`, in stage coordinates, lies on the drawing of this clip.
+    fn drawing_hit_test(&self, point: (Twips, Twips)) -> bool {
+        self.drawing.hit_test(self.global_to_local(point))
+    }
+
+    /// Unloads the children placed by the timeline, keeping the clips created by scripts.
+    fn remove_timeline_children(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let script_children = self.children.split_off(&AVM_DEPTH_BIAS);
+        let timeline_children = std::mem::replace(&mut self.children, script_children);
+        for (_, child) in timeline_children {
+            Self::unload_child(context, child);
+        }
     }
 
     pub fn id(&self) -> CharacterId {
@@ -271,7 +485,7 @@ impl<'gc> MovieClip<'gc> {
             self.current_frame += 1;
         } else if self.total_frames() > 1 {
             self.current_frame = 1;
-            self.children.clear();
+            self.remove_timeline_children(context);
             self.tag_stream_pos = 0;
         } else {
             // Single frame clips do not play.
@@ -284,6 +498,7 @@ impl<'gc> MovieClip<'gc> {
         use swf::TagCode;
         if only_display_actions {
             let tag_callback = |reader: &mut _, tag_code, tag_len| match tag_code {
+                TagCode::DoInitAction => self.do_init_action(context, reader, tag_len),
                 TagCode::PlaceObject => self.place_object(context, reader, tag_len, 1),
                 TagCode::PlaceObject2 => self.place_object(context, reader, tag_len, 2),
                 TagCode::PlaceObject3 => self.place_object(context, reader, tag_len, 3),
@@ -296,6 +511,7 @@ impl<'gc> MovieClip<'gc> {
         } else {
             let tag_callback = |reader: &mut _, tag_code, tag_len| match tag_code {
                 TagCode::DoAction => self.do_action(context, reader, tag_len),
+                TagCode::DoInitAction => self.do_init_action(context, reader, tag_len),
                 TagCode::PlaceObject => self.place_object(context, reader, tag_len, 1),
                 TagCode::PlaceObject2 => self.place_object(context, reader, tag_len, 2),
                 TagCode::PlaceObject3 => self.place_object(context, reader, tag_len, 3),
@@ -334,6 +550,7 @@ impl<'gc> DisplayObject<'gc> for MovieClip<'gc> {
             TagCode::DefineBitsLossless2 => self.define_bits_lossless(context, reader, 2),
             TagCode::DefineButton => self.define_button_1(context, reader),
             TagCode::DefineButton2 => self.define_button_2(context, reader),
+            TagCode::DefineEditText => self.define_edit_text(context, reader),
             TagCode::DefineFont => self.define_font_1(context, reader),
             TagCode::DefineFont2 => self.define_font_2(context, reader),
             TagCode::DefineFont3 => self.define_font_3(context, reader),
@@ -347,9 +564,12 @@ impl<'gc> DisplayObject<'gc> for MovieClip<'gc> {
             TagCode::DefineSound => self.define_sound(context, reader, tag_len),
             TagCode::DefineSprite => self.define_sprite(context, reader, tag_len),
             TagCode::DefineText => self.define_text(context, reader),
+            TagCode::ExportAssets => self.export_assets(context, reader),
             TagCode::FrameLabel => {
                 self.frame_label(context, reader, tag_len, cur_frame, &mut static_data)
             }
+            TagCode::ImportAssets => self.import_assets(context, reader, 1),
+            TagCode::ImportAssets2 => self.import_assets(context, reader, 2),
             TagCode::JpegTables => self.jpeg_tables(context, reader, tag_len),
             TagCode::PlaceObject => {
                 self.preload_place_object(context, reader, tag_len, &mut ids, 1)
@@ -385,6 +605,15 @@ impl<'gc> DisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn run_frame(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // The first frame of a clip fires `load`; later frames fire `enterFrame`.
+        let event = if self.initialized {
+            ClipEvent::EnterFrame
+        } else {
+            self.initialized = true;
+            ClipEvent::Load
+        };
+        self.run_clip_event(context, context.active_clip, event);
+
         if self.is_playing {
             self.run_frame_internal(context, false);
         }
@@ -406,23 +635,129 @@ impl<'gc> DisplayObject<'gc> for MovieClip<'gc> {
         }
     }
 
+    fn propagate_clip_event(&mut self, context: &mut UpdateContext<'_, 'gc, '_>, event: ClipEvent) {
+        self.run_clip_event(context, context.active_clip, event);
+
+        for child in self.children.values() {
+            context.active_clip = *child;
+            child
+                .write(context.gc_context)
+                .propagate_clip_event(context, event);
+        }
+    }
+
+    fn propagate_key_press(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        key_code: ButtonKeyCode,
+    ) {
+        for clip_action in &self.clip_actions {
+            if clip_action.event == ClipEvent::KeyPress && clip_action.key_code == Some(key_code) {
+                context.actions.push((
+                    context.active_clip,
+                    ActionType::Normal(clip_action.action_data.clone()),
+                ));
+            }
+        }
+
+        for child in self.children.values() {
+            context.active_clip = *child;
+            child
+                .write(context.gc_context)
+                .propagate_key_press(context, key_code);
+        }
+    }
+
+    fn propagate_mouse_wheel(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        delta: f64,
+    ) -> bool {
+        for child in self.children.values().rev() {
+            let mut display_object = child.write(context.gc_context);
+            if display_object.visible()
+                && display_object.propagate_mouse_wheel(context, point, delta)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = self.drawing.bounds().clone();
+        for child in self.children.values() {
+            bounds.union(&child.read().bounds_in_parent());
+        }
+        bounds
+    }
+
     fn render(&self, context: &mut RenderContext<'_, 'gc>) {
         context.transform_stack.push(self.transform());
 
-        for child in self.children.values() {
-            child.read().render(context);
+        // The drawing is beneath all of the children.
+        self.drawing.render(context);
+
+        // The clip depths of the mask layers in effect, from the bottom up.
+        let mut clip_depths: Vec<Depth> = vec![];
+        for (&depth, child) in &self.children {
+            // Remove the masks that end beneath this child.
+            while clip_depths
+                .last()
+                .map_or(false, |&clip_depth| depth > clip_depth)
+            {
+                clip_depths.pop();
+                context.renderer.pop_mask();
+            }
+
+            let child = child.read();
+            if child.clip_depth() > 0 {
+                // A mask layer clips the children above it, up to its clip depth.
+                // It is rendered into the mask even if it is not visible.
+                context.renderer.push_mask();
+                child.render(context);
+                context.renderer.activate_mask();
+                clip_depths.push(Depth::from(child.clip_depth()));
+            } else if child.visible() {
+                self.render_child(context, &**child);
+            }
+        }
+        for _ in clip_depths {
+            context.renderer.pop_mask();
         }
 
         context.transform_stack.pop();
     }
 
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        self.drawing_hit_test(point)
+            || self.children.values().any(|child| {
+                let child = child.read();
+                child.visible() && child.hit_test(point)
+            })
+    }
+
     fn mouse_pick(
         &self,
-        _self_node: DisplayNode<'gc>,
+        self_node: DisplayNode<'gc>,
         point: (Twips, Twips),
     ) -> Option<DisplayNode<'gc>> {
+        // Clips with button handlers are hovered as a whole.
+        if self.has_button_handlers() {
+            return if self.hit_test(point) {
+                Some(self_node)
+            } else {
+                None
+            };
+        }
+
         for child in self.children.values().rev() {
-            let result = child.read().mouse_pick(*child, point);
+            let display_object = child.read();
+            if !display_object.visible() {
+                continue;
+            }
+            let result = display_object.mouse_pick(*child, point);
             if result.is_some() {
                 return result;
             }
@@ -431,10 +766,61 @@ impl<'gc> DisplayObject<'gc> for MovieClip<'gc> {
         None
     }
 
+    fn pick_clip_at(
+        &self,
+        self_node: DisplayNode<'gc>,
+        point: (Twips, Twips),
+        ignore: Option<DisplayNode<'gc>>,
+    ) -> Option<DisplayNode<'gc>> {
+        if ignore.map(GcCell::as_ptr) == Some(GcCell::as_ptr(self_node)) {
+            return None;
+        }
+
+        for child in self.children.values().rev() {
+            let display_object = child.read();
+            if !display_object.visible() {
+                continue;
+            }
+            if display_object.as_movie_clip().is_some() {
+                let result = display_object.pick_clip_at(*child, point, ignore);
+                if result.is_some() {
+                    return result;
+                }
+            } else if display_object.hit_test(point) {
+                return Some(self_node);
+            }
+        }
+
+        if self.drawing_hit_test(point) {
+            return Some(self_node);
+        }
+
+        None
+    }
+
     fn as_movie_clip(&self) -> Option<&crate::movie_clip::MovieClip<'gc>> {
         Some(self)
     }
 
+    fn object(&self) -> avm1::Value<'gc> {
+        self.object
+            .map_or(avm1::Value::Undefined, avm1::Value::Object)
+    }
+
+    fn post_instantiation(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        display_object: DisplayNode<'gc>,
+        prototypes: &avm1::SystemPrototypes<'gc>,
+    ) {
+        // The variables of the clip are stored on its object.
+        self.object = Some(avm1::Object::display_object(
+            gc_context,
+            Some(prototypes.movie_clip),
+            display_object,
+        ));
+    }
+
     fn as_movie_clip_mut(&mut self) -> Option<&mut crate::movie_clip::MovieClip<'gc>> {
         Some(self)
     }
@@ -446,7 +832,11 @@ unsafe impl<'gc> gc_arena::Collect for MovieClip<'gc> {
         for child in self.children.values() {
             child.trace(cc);
         }
+        self.object.trace(cc);
         self.static_data.trace(cc);
+        self.drop_target.trace(cc);
+        self.masker.trace(cc);
+        self.maskee.trace(cc);
     }
 }
 
@@ -503,7 +893,7 @@ impl<'gc, 'a> MovieClip<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
-        ids: &mut fnv::FnvHashMap<Depth, CharacterId>,
+        ids: &mut fnv::FnvHashMap<swf::Depth, CharacterId>,
         version: u8,
     ) -> DecodeResult {
         use swf::PlaceObjectAction;
@@ -702,8 +1092,12 @@ impl<'gc, 'a> MovieClip<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
         let swf_button = reader.read_define_button_1()?;
-        let button =
-            crate::button::Button::from_swf_tag(&swf_button, &context.library, context.gc_context);
+        let button = crate::button::Button::from_swf_tag(
+            &swf_button,
+            &context.library,
+            context.gc_context,
+            context.avm.prototypes(),
+        );
         context
             .library
             .register_character(swf_button.id, Character::Button(Box::new(button)));
@@ -717,14 +1111,33 @@ impl<'gc, 'a> MovieClip<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
         let swf_button = reader.read_define_button_2()?;
-        let button =
-            crate::button::Button::from_swf_tag(&swf_button, &context.library, context.gc_context);
+        let button = crate::button::Button::from_swf_tag(
+            &swf_button,
+            &context.library,
+            context.gc_context,
+            context.avm.prototypes(),
+        );
         context
             .library
             .register_character(swf_button.id, Character::Button(Box::new(button)));
         Ok(())
     }
 
+    #[inline]
+    fn define_edit_text(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let edit_text = reader.read_define_edit_text()?;
+        let edit_text_object = EditText::from_swf_tag(&edit_text, &context.library);
+        context.library.register_character(
+            edit_text.id,
+            Character::EditText(Box::new(edit_text_object)),
+        );
+        Ok(())
+    }
+
     #[inline]
     fn define_font_1(
         &mut self,
@@ -843,13 +1256,28 @@ impl<'gc, 'a> MovieClip<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
         let text = reader.read_define_text()?;
-        let text_object = Text::from_swf_tag(&text);
+        let text_object = Text::from_swf_tag(&text, &context.library);
         context
             .library
             .register_character(text.id, Character::Text(Box::new(text_object)));
         Ok(())
     }
 
+    #[inline]
+    fn export_assets(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let num_exports = reader.read_u16()?;
+        for _ in 0..num_exports {
+            let id = reader.read_character_id()?;
+            let name = reader.read_c_string()?;
+            context.library.register_export(id, &name);
+        }
+        Ok(())
+    }
+
     #[inline]
     fn frame_label(
         &mut self,
@@ -870,6 +1298,33 @@ impl<'gc, 'a> MovieClip<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn import_assets(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        version: u8,
+    ) -> DecodeResult {
+        let url = reader.read_c_string()?;
+        if version >= 2 {
+            // Reserved bytes.
+            reader.read_u8()?;
+            reader.read_u8()?;
+        }
+        let num_imports = reader.read_u16()?;
+        for _ in 0..num_imports {
+            let _id = reader.read_character_id()?;
+            let name = reader.read_c_string()?;
+            // TODO: Load the exporting movie and register its characters under these IDs.
+            log::warn!(
+                "ImportAssets: Importing {} from {} is unsupported",
+                name,
+                url
+            );
+        }
+        Ok(())
+    }
+
     #[inline]
     fn jpeg_tables(
         &mut self,
@@ -893,7 +1348,7 @@ impl<'gc, 'a> MovieClip<'gc> {
         &mut self,
         _context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
-        ids: &mut fnv::FnvHashMap<Depth, CharacterId>,
+        ids: &mut fnv::FnvHashMap<swf::Depth, CharacterId>,
         version: u8,
     ) -> DecodeResult {
         let remove_object = if version == 1 {
@@ -936,7 +1391,42 @@ impl<'gc, 'a> MovieClip<'gc> {
             start,
             end,
         };
-        context.actions.push((context.active_clip, slice));
+        context
+            .actions
+            .push((context.active_clip, ActionType::Normal(slice)));
+        Ok(())
+    }
+
+    fn do_init_action(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
+    ) -> DecodeResult {
+        let id = reader.read_character_id()?;
+        if !context.library.mark_init_action_run(id) {
+            return Ok(());
+        }
+
+        let start = (self.tag_stream_start() + reader.get_ref().position()) as usize;
+        let end = start + tag_len - 2;
+        let slice = crate::tag_utils::SwfSlice {
+            data: std::sync::Arc::clone(context.swf_data),
+            start,
+            end,
+        };
+        // Init actions run before the other actions of the frame, in the order they were queued.
+        let index = context
+            .actions
+            .iter()
+            .position(|(_, action)| match action {
+                ActionType::Init(_) => false,
+                _ => true,
+            })
+            .unwrap_or_else(|| context.actions.len());
+        context
+            .actions
+            .insert(index, (context.active_clip, ActionType::Init(slice)));
         Ok(())
     }
 
@@ -953,37 +1443,43 @@ impl<'gc, 'a> MovieClip<'gc> {
             reader.read_place_object_2_or_3(version)
         }?;
         use swf::PlaceObjectAction;
+        let depth = Depth::from(place_object.depth);
         let character = match place_object.action {
             PlaceObjectAction::Place(id) => {
                 // TODO(Herschel): Behavior when character doesn't exist/isn't a DisplayObject?
-                let character = if let Ok(character) = context
-                    .library
-                    .instantiate_display_object(id, context.gc_context)
-                {
+                let character = if let Ok(character) = context.library.instantiate_display_object(
+                    id,
+                    context.gc_context,
+                    context.avm.prototypes(),
+                ) {
                     character
                 } else {
                     return Ok(());
                 };
 
-                // TODO(Herschel): Behavior when depth is occupied? (I think it replaces)
+                // Placing a character at an occupied depth replaces the previous one.
                 character
                     .write(context.gc_context)
                     .set_parent(Some(context.active_clip));
-                self.children.insert(place_object.depth, character);
-                self.children.get_mut(&place_object.depth).unwrap()
+                context.avm.add_text_field(character);
+                if let Some(prev_character) = self.children.insert(depth, character) {
+                    Self::unload_child(context, prev_character);
+                }
+                self.children.get_mut(&depth).unwrap()
             }
             PlaceObjectAction::Modify => {
-                if let Some(child) = self.children.get_mut(&place_object.depth) {
+                if let Some(child) = self.children.get_mut(&depth) {
                     child
                 } else {
                     return Ok(());
                 }
             }
             PlaceObjectAction::Replace(id) => {
-                let character = if let Ok(character) = context
-                    .library
-                    .instantiate_display_object(id, context.gc_context)
-                {
+                let character = if let Ok(character) = context.library.instantiate_display_object(
+                    id,
+                    context.gc_context,
+                    context.avm.prototypes(),
+                ) {
                     character
                 } else {
                     return Ok(());
@@ -992,8 +1488,9 @@ impl<'gc, 'a> MovieClip<'gc> {
                 character
                     .write(context.gc_context)
                     .set_parent(Some(context.active_clip));
-                let prev_character = self.children.insert(place_object.depth, character);
-                let character = self.children.get_mut(&place_object.depth).unwrap();
+                context.avm.add_text_field(character);
+                let prev_character = self.children.insert(depth, character);
+                let character = self.children.get_mut(&depth).unwrap();
                 if let Some(prev_character) = prev_character {
                     character
                         .write(context.gc_context)
@@ -1001,6 +1498,7 @@ impl<'gc, 'a> MovieClip<'gc> {
                     character
                         .write(context.gc_context)
                         .set_color_transform(prev_character.read().color_transform());
+                    Self::unload_child(context, prev_character);
                 }
                 character
             }
@@ -1029,12 +1527,42 @@ impl<'gc, 'a> MovieClip<'gc> {
             }
         }
 
+        // Only PlaceObject3 has a blend mode, bitmap caching and filters.
+        // Modifying a display object without them leaves them unchanged.
+        if version >= 3 {
+            let is_modify = place_object.action == PlaceObjectAction::Modify;
+            if !is_modify || place_object.blend_mode != swf::BlendMode::Normal {
+                character
+                    .write(context.gc_context)
+                    .set_blend_mode(place_object.blend_mode.into());
+            }
+            if !is_modify || place_object.is_bitmap_cached {
+                character
+                    .write(context.gc_context)
+                    .set_is_bitmap_cached(place_object.is_bitmap_cached);
+            }
+            if !is_modify || !place_object.filters.is_empty() {
+                character
+                    .write(context.gc_context)
+                    .set_filters(place_object.filters.clone());
+            }
+        }
+
         if let Some(clip_depth) = &place_object.clip_depth {
             character
                 .write(context.gc_context)
                 .set_clip_depth(*clip_depth);
         }
 
+        if !place_object.clip_actions.is_empty() {
+            let node = *character;
+            let mut display_object = node.write(context.gc_context);
+            if let Some(clip) = display_object.as_movie_clip_mut() {
+                clip.set_clip_actions(&place_object.clip_actions);
+                clip.run_clip_event(context, node, ClipEvent::Initialize);
+            }
+        }
+
         Ok(())
     }
 
@@ -1050,12 +1578,21 @@ impl<'gc, 'a> MovieClip<'gc> {
         } else {
             reader.read_remove_object_2()
         }?;
-        if let Some(child) = self.children.remove(&remove_object.depth) {
-            child.write(context.gc_context).set_parent(None);
+        if let Some(child) = self.children.remove(&Depth::from(remove_object.depth)) {
+            Self::unload_child(context, child);
         }
         Ok(())
     }
 
+    /// Runs the `unload` event of a child that was taken off the display list,
+    /// and detaches it from its parent.
+    fn unload_child(context: &mut UpdateContext<'_, 'gc, '_>, child: DisplayNode<'gc>) {
+        if let Some(clip) = child.read().as_movie_clip() {
+            clip.run_clip_event(context, child, ClipEvent::Unload);
+        }
+        child.write(context.gc_context).set_parent(None);
+    }
+
     #[inline]
     fn set_background_color(
         &mut self,
@@ -1131,3 +1668,52 @@ unsafe impl<'gc> gc_arena::Collect for MovieClipStatic {
         false
     }
 }
+
+/// An `onClipEvent` handler placed on a movie clip instance.
+#[derive(Clone)]
+struct ClipAction {
+    event: ClipEvent,
+
+    /// The key that triggers a `KeyPress` handler.
+    key_code: Option<ButtonKeyCode>,
+
+    action_data: SwfSlice,
+}
+
+/// The methods that make a movie clip behave like a button.
+const BUTTON_METHODS: [&str; 7] = [
+    "onDragOut",
+    "onDragOver",
+    "onPress",
+    "onRelease",
+    "onReleaseOutside",
+    "onRollOut",
+    "onRollOver",
+];
+
+/// Returns the name of the event handler method called for `event`, such as `onEnterFrame`.
+fn event_method_name(event: ClipEvent) -> Option<&'static str> {
+    match event {
+        ClipEvent::Data => Some("onData"),
+        ClipEvent::DragOut => Some("onDragOut"),
+        ClipEvent::DragOver => Some("onDragOver"),
+        ClipEvent::EnterFrame => Some("onEnterFrame"),
+        ClipEvent::Load => Some("onLoad"),
+        ClipEvent::MouseDown => Some("onMouseDown"),
+        ClipEvent::MouseMove => Some("onMouseMove"),
+        ClipEvent::MouseUp => Some("onMouseUp"),
+        ClipEvent::Press => Some("onPress"),
+        ClipEvent::Release => Some("onRelease"),
+        ClipEvent::ReleaseOutside => Some("onReleaseOutside"),
+        ClipEvent::RollOut => Some("onRollOut"),
+        ClipEvent::RollOver => Some("onRollOver"),
+        ClipEvent::Unload => Some("onUnload"),
+        // `onKeyDown` and `onKeyUp` are only called on the clip with keyboard focus,
+        // and objects added with `Key.addListener`.
+        ClipEvent::Construct
+        | ClipEvent::Initialize
+        | ClipEvent::KeyDown
+        | ClipEvent::KeyPress
+        | ClipEvent::KeyUp => None,
+    }
+}
@@ -1,6 +1,7 @@
-use crate::avm1::Avm1;
+use crate::avm1::{Avm1, Value};
 use crate::backend::{audio::AudioBackend, render::Letterbox, render::RenderBackend};
-use crate::events::{ButtonEvent, PlayerEvent};
+use crate::edit_text::EditResult;
+use crate::events::{button_key_code, key_code, ButtonEvent, PlayerEvent};
 use crate::library::Library;
 use crate::movie_clip::MovieClip;
 use crate::prelude::*;
@@ -8,6 +9,7 @@ use crate::transform::TransformStack;
 use gc_arena::{make_arena, ArenaParameters, Collect, GcCell, MutationContext};
 use log::info;
 use std::sync::Arc;
+use swf::ClipEvent;
 
 #[derive(Collect)]
 #[collect(empty_drop)]
@@ -15,6 +17,11 @@ struct GcRoot<'gc> {
     library: GcCell<'gc, Library<'gc>>,
     root: DisplayNode<'gc>,
     mouse_hover_node: GcCell<'gc, Option<DisplayNode<'gc>>>, // TODO: Remove GcCell wrapped inside GcCell.
+
+    /// The button or clip that the mouse was pressed on, which keeps receiving the mouse
+    /// until it is released.
+    mouse_pressed_node: GcCell<'gc, Option<DisplayNode<'gc>>>,
+    avm: GcCell<'gc, Avm1<'gc>>,
 }
 
 make_arena!(GcArena, GcRoot);
@@ -25,7 +32,6 @@ pub struct Player<Audio: AudioBackend, Renderer: RenderBackend> {
 
     is_playing: bool,
 
-    avm: Avm1,
     audio: Audio,
     renderer: Renderer,
     transform_stack: TransformStack,
@@ -39,6 +45,9 @@ pub struct Player<Audio: AudioBackend, Renderer: RenderBackend> {
     frame_accumulator: f64,
     global_time: u64,
 
+    /// The fraction of a millisecond that has elapsed but is not yet counted in `global_time`.
+    time_remainder: f64,
+
     viewport_width: u32,
     viewport_height: u32,
     movie_width: u32,
@@ -47,6 +56,10 @@ pub struct Player<Audio: AudioBackend, Renderer: RenderBackend> {
 
     mouse_pos: (Twips, Twips),
     is_mouse_down: bool,
+
+    /// Called with the position of the mouse in the viewport when the user right-clicks,
+    /// so that the host can show a context menu.
+    context_menu_callback: Option<Box<dyn FnMut(f64, f64)>>,
 }
 
 impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
@@ -72,7 +85,6 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
 
             is_playing: false,
 
-            avm: Avm1::new(header.version),
             renderer,
             audio,
 
@@ -86,9 +98,9 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
             view_matrix: Default::default(),
             inverse_view_matrix: Default::default(),
 
-            gc_arena: GcArena::new(ArenaParameters::default(), |gc_context| GcRoot {
-                library: GcCell::allocate(gc_context, Library::new()),
-                root: GcCell::allocate(
+            gc_arena: GcArena::new(ArenaParameters::default(), |gc_context| {
+                let avm = GcCell::allocate(gc_context, Avm1::new(gc_context, header.version));
+                let root: DisplayNode<'_> = GcCell::allocate(
                     gc_context,
                     Box::new(MovieClip::new_with_data(
                         gc_context,
@@ -97,13 +109,25 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                         swf_len,
                         header.num_frames,
                     )),
-                ),
-                mouse_hover_node: GcCell::allocate(gc_context, None),
+                );
+                root.write(gc_context).post_instantiation(
+                    gc_context,
+                    root,
+                    avm.read().prototypes(),
+                );
+                GcRoot {
+                    library: GcCell::allocate(gc_context, Library::new()),
+                    root,
+                    mouse_hover_node: GcCell::allocate(gc_context, None),
+                    mouse_pressed_node: GcCell::allocate(gc_context, None),
+                    avm,
+                }
             }),
 
             frame_rate: header.frame_rate.into(),
             frame_accumulator: 0.0,
             global_time: 0,
+            time_remainder: 0.0,
 
             movie_width,
             movie_height,
@@ -113,6 +137,7 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
 
             mouse_pos: (Twips::new(0), Twips::new(0)),
             is_mouse_down: false,
+            context_menu_callback: None,
         };
 
         player.build_matrices();
@@ -130,16 +155,21 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
 
         if self.is_playing() {
             self.frame_accumulator += dt;
-            self.global_time += dt as u64;
+            let elapsed = dt + self.time_remainder;
+            self.global_time += elapsed as u64;
+            self.time_remainder = elapsed.fract();
             let frame_time = 1000.0 / self.frame_rate;
 
+            // Timers fire even between frames, and while the root timeline is stopped.
+            let timers_fired = self.run_timers();
+
             let needs_render = self.frame_accumulator >= frame_time;
             while self.frame_accumulator >= frame_time {
                 self.frame_accumulator -= frame_time;
                 self.run_frame();
             }
 
-            if needs_render {
+            if needs_render || timers_fired {
                 self.render();
             }
 
@@ -177,13 +207,30 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
         self.build_matrices();
     }
 
+    /// Sets the function called when the user right-clicks the movie, with the position
+    /// of the mouse in the viewport. Right-clicks are not otherwise seen by the movie.
+    pub fn set_context_menu_callback(&mut self, callback: Option<Box<dyn FnMut(f64, f64)>>) {
+        self.context_menu_callback = callback;
+    }
+
     pub fn handle_event(&mut self, event: PlayerEvent) {
         let mut needs_render = false;
 
+        // The context menu opens when the right mouse button is released, as on Windows.
+        if let PlayerEvent::RightMouseUp { x, y } = event {
+            if let Some(callback) = &mut self.context_menu_callback {
+                callback(x, y);
+            }
+        }
+
         // Update mouse position from mouse events.
         if let PlayerEvent::MouseMove { x, y }
         | PlayerEvent::MouseDown { x, y }
-        | PlayerEvent::MouseUp { x, y } = event
+        | PlayerEvent::MouseUp { x, y }
+        | PlayerEvent::RightMouseDown { x, y }
+        | PlayerEvent::RightMouseUp { x, y }
+        | PlayerEvent::MiddleMouseDown { x, y }
+        | PlayerEvent::MiddleMouseUp { x, y } = event
         {
             self.mouse_pos =
                 self.inverse_view_matrix * (Twips::from_pixels(x), Twips::from_pixels(y));
@@ -199,8 +246,8 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
             background_color,
             renderer,
             audio,
-            avm,
             is_mouse_down,
+            mouse_position,
         ) = (
             self.global_time,
             &mut self.swf_data,
@@ -208,8 +255,8 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
             &mut self.background_color,
             &mut self.renderer,
             &mut self.audio,
-            &mut self.avm,
             &mut self.is_mouse_down,
+            self.mouse_pos,
         );
 
         self.gc_arena.mutate(|gc_context, gc_root| {
@@ -219,36 +266,280 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                 swf_version,
                 library: gc_root.library.write(gc_context),
                 background_color,
-                avm,
+                avm: gc_root.avm.write(gc_context),
                 renderer,
                 audio,
                 actions: vec![],
                 gc_context,
                 active_clip: gc_root.root,
+                mouse_position,
             };
 
-            if let Some(node) = &*gc_root.mouse_hover_node.read() {
-                if let Some(button) = node.write(gc_context).as_button_mut() {
-                    match event {
-                        PlayerEvent::MouseDown { .. } => {
-                            *is_mouse_down = true;
-                            needs_render = true;
-                            update_context.active_clip = *node;
-                            button.handle_button_event(&mut update_context, ButtonEvent::Press);
-                        }
+            // Update the state of the `Key` object, and notify its listeners.
+            match event {
+                PlayerEvent::KeyDown { key_code, ascii } => {
+                    update_context.avm.key_down(key_code, ascii);
+                    update_context.actions.push((
+                        gc_root.root,
+                        ActionType::NotifyListeners {
+                            broadcaster: "Key",
+                            method: "onKeyDown",
+                            args: vec![],
+                        },
+                    ));
+                }
+                PlayerEvent::KeyUp { key_code, ascii } => {
+                    update_context.avm.key_up(key_code, ascii);
+                    update_context.actions.push((
+                        gc_root.root,
+                        ActionType::NotifyListeners {
+                            broadcaster: "Key",
+                            method: "onKeyUp",
+                            args: vec![],
+                        },
+                    ));
+                }
+                _ => (),
+            }
+
+            // Move the dragged clip with the mouse, and notify the listeners of the `Mouse` object.
+            let mouse_method = match event {
+                PlayerEvent::MouseMove { .. } => Some("onMouseMove"),
+                PlayerEvent::MouseDown { .. } => Some("onMouseDown"),
+                PlayerEvent::MouseUp { .. } => Some("onMouseUp"),
+                _ => None,
+            };
+            if let Some(method) = mouse_method {
+                if update_context
+                    .avm
+                    .update_drag(gc_context, gc_root.root, mouse_position)
+                {
+                    needs_render = true;
+                }
+                update_context.actions.push((
+                    gc_root.root,
+                    ActionType::NotifyListeners {
+                        broadcaster: "Mouse",
+                        method,
+                        args: vec![],
+                    },
+                ));
+            }
+
+            // `Key.isDown` reports the state of the mouse buttons.
+            let mouse_button = match event {
+                PlayerEvent::MouseDown { .. } => Some((key_code::LEFT_MOUSE, true)),
+                PlayerEvent::MouseUp { .. } => Some((key_code::LEFT_MOUSE, false)),
+                PlayerEvent::RightMouseDown { .. } => Some((key_code::RIGHT_MOUSE, true)),
+                PlayerEvent::RightMouseUp { .. } => Some((key_code::RIGHT_MOUSE, false)),
+                PlayerEvent::MiddleMouseDown { .. } => Some((key_code::MIDDLE_MOUSE, true)),
+                PlayerEvent::MiddleMouseUp { .. } => Some((key_code::MIDDLE_MOUSE, false)),
+                _ => None,
+            };
+            if let Some((key_code, is_down)) = mouse_button {
+                update_context.avm.set_mouse_button(key_code, is_down);
+            }
 
-                        PlayerEvent::MouseUp { .. } => {
-                            *is_mouse_down = false;
+            // The mouse wheel scrolls the text field under the mouse,
+            // and is reported to `Mouse` listeners with the clip under the mouse.
+            if let PlayerEvent::MouseWheel { delta } = event {
+                update_context.active_clip = gc_root.root;
+                if gc_root.root.write(gc_context).propagate_mouse_wheel(
+                    &mut update_context,
+                    mouse_position,
+                    delta,
+                ) {
+                    needs_render = true;
+                }
+                let scroll_target = gc_root
+                    .root
+                    .read()
+                    .pick_clip_at(gc_root.root, mouse_position, None)
+                    .map_or(Value::Undefined, |clip| clip.read().object());
+                update_context.actions.push((
+                    gc_root.root,
+                    ActionType::NotifyListeners {
+                        broadcaster: "Mouse",
+                        method: "onMouseWheel",
+                        args: vec![delta.into(), scroll_target],
+                    },
+                ));
+            }
+
+            // Broadcast mouse and key events to the `onClipEvent` handlers of every clip.
+            let clip_event = match event {
+                PlayerEvent::MouseMove { .. } => Some(ClipEvent::MouseMove),
+                PlayerEvent::MouseDown { .. } => Some(ClipEvent::MouseDown),
+                PlayerEvent::MouseUp { .. } => Some(ClipEvent::MouseUp),
+                PlayerEvent::KeyDown { .. } => Some(ClipEvent::KeyDown),
+                PlayerEvent::KeyUp { .. } => Some(ClipEvent::KeyUp),
+                _ => None,
+            };
+            if let Some(clip_event) = clip_event {
+                update_context.active_clip = gc_root.root;
+                gc_root
+                    .root
+                    .write(gc_context)
+                    .propagate_clip_event(&mut update_context, clip_event);
+            }
+
+            // Key presses trigger the `on(keyPress)` handlers of every button and clip.
+            if let PlayerEvent::KeyDown { key_code, ascii } = event {
+                if let Some(key_code) = button_key_code(key_code, ascii) {
+                    update_context.active_clip = gc_root.root;
+                    gc_root
+                        .root
+                        .write(gc_context)
+                        .propagate_key_press(&mut update_context, key_code);
+                }
+            }
+
+            let hover_node = *gc_root.mouse_hover_node.read();
+            match event {
+                PlayerEvent::MouseDown { .. } => {
+                    *is_mouse_down = true;
+                    *gc_root.mouse_pressed_node.write(gc_context) = hover_node;
+                    if let Some(node) = hover_node {
+                        needs_render = true;
+                        Self::run_button_event(&mut update_context, node, ButtonEvent::Press);
+                    }
+                }
+
+                PlayerEvent::MouseUp { .. } => {
+                    *is_mouse_down = false;
+                    let pressed_node = gc_root.mouse_pressed_node.write(gc_context).take();
+                    let is_pressed_node =
+                        hover_node.map(GcCell::as_ptr) == pressed_node.map(GcCell::as_ptr);
+                    if let Some(node) = hover_node {
+                        // Buttons that the mouse was dragged onto while it was down
+                        // only see it now, unless they are menu items.
+                        let event = if tracks_mouse_while_down(node, pressed_node) {
+                            ButtonEvent::Release
+                        } else {
+                            ButtonEvent::RollOver
+                        };
+                        needs_render = true;
+                        Self::run_button_event(&mut update_context, node, event);
+                    }
+                    if let Some(node) = pressed_node {
+                        if !is_pressed_node {
                             needs_render = true;
-                            update_context.active_clip = *node;
-                            button.handle_button_event(&mut update_context, ButtonEvent::Release);
+                            Self::run_button_event(
+                                &mut update_context,
+                                node,
+                                ButtonEvent::ReleaseOutside,
+                            );
                         }
+                    }
+
+                    // Clicking a link in a text field follows it.
+                    let link = hover_node.filter(|_| is_pressed_node).and_then(|node| {
+                        let link = node
+                            .read()
+                            .as_edit_text()
+                            .and_then(|edit_text| edit_text.link_at_point(mouse_position));
+                        link.map(|link| (node, link))
+                    });
+                    if let Some((node, (url, target))) = link {
+                        needs_render = true;
+                        let mut action_context = crate::avm1::ActionContext {
+                            gc_context,
+                            global_time,
+                            root: gc_root.root,
+                            start_clip: gc_root.root,
+                            active_clip: gc_root.root,
+                            audio: update_context.audio,
+                            library: &mut update_context.library,
+                            mouse_position,
+                        };
+                        update_context
+                            .avm
+                            .follow_link(&mut action_context, node, &url, &target);
+                    }
+                }
 
-                        _ => (),
+                _ => (),
+            }
+
+            // Clicking a text field gives it the keyboard focus and places the caret there,
+            // and clicking anywhere else takes the focus away.
+            let pressed_node = *gc_root.mouse_pressed_node.read();
+            if let PlayerEvent::MouseDown { .. } = event {
+                let text_field = hover_node.filter(|node| {
+                    node.read()
+                        .as_edit_text()
+                        .map_or(false, |edit_text| edit_text.is_focusable())
+                });
+                if update_context.avm.focus().is_some() || text_field.is_some() {
+                    needs_render = true;
+                }
+                {
+                    let mut action_context = crate::avm1::ActionContext {
+                        gc_context,
+                        global_time,
+                        root: gc_root.root,
+                        start_clip: gc_root.root,
+                        active_clip: gc_root.root,
+                        audio: update_context.audio,
+                        library: &mut update_context.library,
+                        mouse_position,
+                    };
+                    update_context
+                        .avm
+                        .set_focus(&mut action_context, text_field);
+                }
+                if let Some(node) = text_field {
+                    if let Some(edit_text) = node.write(gc_context).as_edit_text_mut() {
+                        edit_text.select_at_point(mouse_position, false);
+                    }
+                }
+            } else if let (PlayerEvent::MouseMove { .. }, Some(node)) = (&event, pressed_node) {
+                // Dragging the mouse from the caret selects text.
+                let is_focus =
+                    update_context.avm.focus().map(GcCell::as_ptr) == Some(node.as_ptr());
+                if *is_mouse_down && is_focus {
+                    if let Some(edit_text) = node.write(gc_context).as_edit_text_mut() {
+                        edit_text.select_at_point(mouse_position, true);
+                        needs_render = true;
                     }
                 }
             }
 
+            // Typing edits the focused text field.
+            if let Some(focus) = update_context.avm.focus() {
+                let is_shift_down = update_context.avm.is_key_down(key_code::SHIFT);
+                let result = match focus.write(gc_context).as_edit_text_mut() {
+                    Some(edit_text) => match event {
+                        PlayerEvent::KeyDown { key_code, .. } => {
+                            edit_text.key_down(key_code, is_shift_down, &update_context.library)
+                        }
+                        PlayerEvent::TextInput { codepoint } => {
+                            edit_text.text_input(codepoint, &update_context.library)
+                        }
+                        _ => EditResult::Unchanged,
+                    },
+                    None => EditResult::Unchanged,
+                };
+                if result != EditResult::Unchanged {
+                    needs_render = true;
+                }
+                if result == EditResult::TextChanged {
+                    let mut action_context = crate::avm1::ActionContext {
+                        gc_context,
+                        global_time,
+                        root: gc_root.root,
+                        start_clip: gc_root.root,
+                        active_clip: gc_root.root,
+                        audio: update_context.audio,
+                        library: &mut update_context.library,
+                        mouse_position,
+                    };
+                    update_context
+                        .avm
+                        .text_field_changed(&mut action_context, focus);
+                }
+            }
+
             Self::run_actions(&mut update_context, gc_root.root);
         });
 
@@ -258,29 +549,33 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
         }
     }
 
+    /// Updates the button or clip under the mouse, and sends it and the previous one
+    /// the roll and drag events.
+    ///
+    /// While the mouse is down, only the node that it was pressed on and menu buttons
+    /// see it move over and out of them.
     fn update_roll_over(&mut self) -> bool {
-        // TODO: While the mouse is down, maintain the hovered node.
-        if self.is_mouse_down {
-            return false;
-        }
-
-        let (global_time, swf_data, swf_version, background_color, renderer, audio, avm) = (
+        let is_mouse_down = self.is_mouse_down;
+        let (global_time, swf_data, swf_version, background_color, renderer, audio) = (
             self.global_time,
             &mut self.swf_data,
             self.swf_version,
             &mut self.background_color,
             &mut self.renderer,
             &mut self.audio,
-            &mut self.avm,
         );
 
         let mouse_pos = &self.mouse_pos;
         // Check hovered object.
         self.gc_arena.mutate(|gc_context, gc_root| {
-            let new_hover_node = gc_root
-                .root
-                .read()
-                .mouse_pick(gc_root.root, (mouse_pos.0, mouse_pos.1));
+            let new_hover_node = {
+                let root = gc_root.root.read();
+                if root.visible() {
+                    root.mouse_pick(gc_root.root, (mouse_pos.0, mouse_pos.1))
+                } else {
+                    None
+                }
+            };
             let mut cur_hover_node = gc_root.mouse_hover_node.write(gc_context);
             if cur_hover_node.map(GcCell::as_ptr) != new_hover_node.map(GcCell::as_ptr) {
                 let mut update_context = UpdateContext {
@@ -289,27 +584,32 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                     swf_version,
                     library: gc_root.library.write(gc_context),
                     background_color,
-                    avm,
+                    avm: gc_root.avm.write(gc_context),
                     renderer,
                     audio,
                     actions: vec![],
                     gc_context,
                     active_clip: gc_root.root,
+                    mouse_position: *mouse_pos,
                 };
 
+                let pressed_node = *gc_root.mouse_pressed_node.read();
+
                 // RollOut of previous node.
-                if let Some(node) = &*cur_hover_node {
-                    if let Some(button) = node.write(gc_context).as_button_mut() {
-                        update_context.active_clip = *node;
-                        button.handle_button_event(&mut update_context, ButtonEvent::RollOut);
+                if let Some(node) = *cur_hover_node {
+                    if !is_mouse_down {
+                        Self::run_button_event(&mut update_context, node, ButtonEvent::RollOut);
+                    } else if tracks_mouse_while_down(node, pressed_node) {
+                        Self::run_button_event(&mut update_context, node, ButtonEvent::DragOut);
                     }
                 }
 
                 // RollOver on new node.
                 if let Some(node) = new_hover_node {
-                    if let Some(button) = node.write(gc_context).as_button_mut() {
-                        update_context.active_clip = node;
-                        button.handle_button_event(&mut update_context, ButtonEvent::RollOver);
+                    if !is_mouse_down {
+                        Self::run_button_event(&mut update_context, node, ButtonEvent::RollOver);
+                    } else if tracks_mouse_while_down(node, pressed_node) {
+                        Self::run_button_event(&mut update_context, node, ButtonEvent::DragOver);
                     }
                 }
 
@@ -324,14 +624,14 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
     }
 
     fn preload(&mut self) {
-        let (global_time, swf_data, swf_version, background_color, renderer, audio, avm) = (
+        let (global_time, swf_data, swf_version, background_color, renderer, audio, mouse_position) = (
             self.global_time,
             &mut self.swf_data,
             self.swf_version,
             &mut self.background_color,
             &mut self.renderer,
             &mut self.audio,
-            &mut self.avm,
+            self.mouse_pos,
         );
 
         self.gc_arena.mutate(|gc_context, gc_root| {
@@ -341,12 +641,13 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                 swf_version,
                 library: gc_root.library.write(gc_context),
                 background_color,
-                avm,
+                avm: gc_root.avm.write(gc_context),
                 renderer,
                 audio,
                 actions: vec![],
                 gc_context,
                 active_clip: gc_root.root,
+                mouse_position,
             };
 
             gc_root.root.write(gc_context).preload(&mut update_context);
@@ -354,14 +655,14 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
     }
 
     pub fn run_frame(&mut self) {
-        let (global_time, swf_data, swf_version, background_color, renderer, audio, avm) = (
+        let (global_time, swf_data, swf_version, background_color, renderer, audio, mouse_position) = (
             self.global_time,
             &mut self.swf_data,
             self.swf_version,
             &mut self.background_color,
             &mut self.renderer,
             &mut self.audio,
-            &mut self.avm,
+            self.mouse_pos,
         );
 
         self.gc_arena.mutate(|gc_context, gc_root| {
@@ -371,12 +672,13 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                 swf_version,
                 library: gc_root.library.write(gc_context),
                 background_color,
-                avm,
+                avm: gc_root.avm.write(gc_context),
                 renderer,
                 audio,
                 actions: vec![],
                 gc_context,
                 active_clip: gc_root.root,
+                mouse_position,
             };
 
             gc_root
@@ -385,6 +687,11 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                 .run_frame(&mut update_context);
 
             Self::run_actions(&mut update_context, gc_root.root);
+
+            // Keep the dragged clip under the mouse, even if the frame moved it.
+            update_context
+                .avm
+                .update_drag(gc_context, gc_root.root, mouse_position);
         });
 
         // Update mouse state (check for new hovered button, etc.)
@@ -394,6 +701,66 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
         self.gc_arena.collect_debt();
     }
 
+    /// Calls the `setInterval` and `setTimeout` timers that are due.
+    /// Returns `true` if any timer fired.
+    fn run_timers(&mut self) -> bool {
+        let (global_time, swf_data, swf_version, background_color, renderer, audio, mouse_position) = (
+            self.global_time,
+            &mut self.swf_data,
+            self.swf_version,
+            &mut self.background_color,
+            &mut self.renderer,
+            &mut self.audio,
+            self.mouse_pos,
+        );
+
+        let fired = self.gc_arena.mutate(|gc_context, gc_root| {
+            let mut update_context = UpdateContext {
+                global_time,
+                swf_data,
+                swf_version,
+                library: gc_root.library.write(gc_context),
+                background_color,
+                avm: gc_root.avm.write(gc_context),
+                renderer,
+                audio,
+                actions: vec![],
+                gc_context,
+                active_clip: gc_root.root,
+                mouse_position,
+            };
+
+            let fired = {
+                let mut action_context = crate::avm1::ActionContext {
+                    gc_context,
+                    global_time,
+                    root: gc_root.root,
+                    start_clip: gc_root.root,
+                    active_clip: gc_root.root,
+                    audio: update_context.audio,
+                    library: &mut update_context.library,
+                    mouse_position,
+                };
+                update_context.avm.run_timers(&mut action_context)
+            };
+
+            if fired {
+                // Run the gotos queued by the callbacks, and any actions on the new frames.
+                gc_root
+                    .root
+                    .write(gc_context)
+                    .run_post_frame(&mut update_context);
+                Self::run_actions(&mut update_context, gc_root.root);
+            }
+            fired
+        });
+
+        if fired {
+            self.gc_arena.collect_debt();
+        }
+        fired
+    }
+
     pub fn render(&mut self) {
         let view_bounds = BoundingBox {
             x_min: Twips::new(0),
@@ -420,7 +787,10 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                 transform_stack,
                 view_bounds,
             };
-            gc_root.root.read().render(&mut render_context);
+            let root = gc_root.root.read();
+            if root.visible() {
+                root.render(&mut render_context);
+            }
         });
         transform_stack.pop();
 
@@ -432,6 +802,26 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
         self.renderer.end_frame();
     }
 
+    /// Sets the URL that the movie was loaded from, which scripts can read through `_url`.
+    pub fn set_movie_url(&mut self, url: &str) {
+        self.gc_arena.mutate(|gc_context, gc_root| {
+            gc_root.avm.write(gc_context).set_movie_url(url);
+        });
+    }
+
+    /// Returns whether scripts want the mouse cursor shown, as set by `Mouse.show` and `Mouse.hide`.
+    pub fn mouse_cursor_visible(&mut self) -> bool {
+        self.gc_arena
+            .mutate(|_gc_context, gc_root| gc_root.avm.read().mouse_visible())
+    }
+
+    /// Returns the messages of the script exceptions that were thrown and never caught
+    /// since the last call, so that the host can report them.
+    pub fn take_uncaught_errors(&mut self) -> Vec<String> {
+        self.gc_arena
+            .mutate(|gc_context, gc_root| gc_root.avm.write(gc_context).take_uncaught_errors())
+    }
+
     pub fn renderer(&self) -> &Renderer {
         &self.renderer
     }
@@ -440,13 +830,40 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
         &mut self.renderer
     }
 
+    /// Sends a mouse event to a button, or to a clip that acts as a button
+    /// through handlers such as `onPress`.
+    fn run_button_event<'gc>(
+        update_context: &mut UpdateContext<'_, 'gc, '_>,
+        node: DisplayNode<'gc>,
+        event: ButtonEvent,
+    ) {
+        let mut display_object = node.write(update_context.gc_context);
+        if let Some(button) = display_object.as_button_mut() {
+            update_context.active_clip = node;
+            button.handle_button_event(update_context, event);
+        } else if let Some(clip) = display_object.as_movie_clip() {
+            let clip_event = match event {
+                ButtonEvent::Press => ClipEvent::Press,
+                ButtonEvent::Release => ClipEvent::Release,
+                ButtonEvent::ReleaseOutside => ClipEvent::ReleaseOutside,
+                ButtonEvent::RollOut => ClipEvent::RollOut,
+                ButtonEvent::RollOver => ClipEvent::RollOver,
+                ButtonEvent::DragOut => ClipEvent::DragOut,
+                ButtonEvent::DragOver => ClipEvent::DragOver,
+                ButtonEvent::KeyPress(_) => return,
+            };
+            clip.run_clip_event(update_context, node, clip_event);
+        }
+    }
+
     fn run_actions<'gc>(update_context: &mut UpdateContext<'_, 'gc, '_>, root: DisplayNode<'gc>) {
         // TODO: Loop here because goto-ing a frame can queue up for actions.
         // I think this will eventually be cleaned up;
         // Need to figure out the proper order of operations between ticking a clip
         // and running the actions.
         let mut actions = std::mem::replace(&mut update_context.actions, vec![]);
-        while !actions.is_empty() {
+        loop {
+            let has_actions = !actions.is_empty();
             {
                 let mut action_context = crate::avm1::ActionContext {
                     gc_context: update_context.gc_context,
@@ -455,16 +872,52 @@ impl<Audio: AudioBackend, Renderer: RenderBackend> Player<Audio, Renderer> {
                     start_clip: root,
                     active_clip: root,
                     audio: update_context.audio,
+                    library: &mut update_context.library,
+                    mouse_position: update_context.mouse_position,
                 };
+                // Text fields placed since the last actions ran show their variables
+                // before any script can change them.
+                update_context.avm.bind_text_fields(&mut action_context);
                 for (active_clip, action) in actions {
                     action_context.start_clip = active_clip;
                     action_context.active_clip = active_clip;
-                    let _ = update_context
-                        .avm
-                        .do_action(&mut action_context, action.as_ref());
+                    let _ = match action {
+                        ActionType::Normal(code) | ActionType::Init(code) => {
+                            update_context.avm.do_action(&mut action_context, code)
+                        }
+                        ActionType::Method(name) => update_context
+                            .avm
+                            .call_event_handler(&mut action_context, name),
+                        ActionType::NotifyListeners {
+                            broadcaster,
+                            method,
+                            args,
+                        } => update_context.avm.notify_system_listeners(
+                            &mut action_context,
+                            broadcaster,
+                            method,
+                            &args,
+                        ),
+                    };
+                }
+            }
+
+            // Clips created by the actions run their first frame right away, which queues
+            // up their `load` events and frame actions to run next.
+            let new_clips = update_context.avm.take_new_clips();
+            for clip in new_clips {
+                // Clips removed before their first frame never run it.
+                if clip.read().parent().is_some() {
+                    update_context.active_clip = clip;
+                    clip.write(update_context.gc_context)
+                        .run_frame(update_context);
                 }
             }
 
+            if !has_actions {
+                break;
+            }
+
             // Run goto queues.
             update_context.active_clip = root;
             root.write(update_context.gc_context)
@@ -519,11 +972,32 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     pub library: std::cell::RefMut<'a, Library<'gc>>,
     pub gc_context: MutationContext<'gc, 'gc_context>,
     pub background_color: &'a mut Color,
-    pub avm: &'a mut Avm1,
+    pub avm: std::cell::RefMut<'a, Avm1<'gc>>,
     pub renderer: &'a mut dyn RenderBackend,
     pub audio: &'a mut dyn AudioBackend,
-    pub actions: Vec<(DisplayNode<'gc>, crate::tag_utils::SwfSlice)>,
+    pub actions: Vec<(DisplayNode<'gc>, ActionType<'gc>)>,
     pub active_clip: DisplayNode<'gc>,
+    pub mouse_position: (Twips, Twips),
+}
+
+/// Code queued to run on a display object once the current update has finished.
+#[derive(Debug, Clone)]
+pub enum ActionType<'gc> {
+    /// A block of AVM1 bytecode, such as a `DoAction` tag or an event handler.
+    Normal(crate::tag_utils::SwfSlice),
+
+    /// The bytecode of a `DoInitAction` tag, which runs ahead of the other queued actions.
+    Init(crate::tag_utils::SwfSlice),
+
+    /// A call to an event handler method of the display object, such as `onEnterFrame`.
+    Method(&'static str),
+
+    /// A call to the `method` of every listener of a built-in object, such as `Key`.
+    NotifyListeners {
+        broadcaster: &'static str,
+        method: &'static str,
+        args: Vec<Value<'gc>>,
+    },
 }
 
 pub struct RenderContext<'a, 'gc> {
@@ -532,3 +1006,19 @@ pub struct RenderContext<'a, 'gc> {
     pub transform_stack: &'a mut TransformStack,
     pub view_bounds: BoundingBox,
 }
+
+/// Whether `node` sees the mouse move over and out of it while the mouse is down,
+/// because it is either the node that the mouse was pressed on or a menu button.
+fn tracks_mouse_while_down<'gc>(
+    node: DisplayNode<'gc>,
+    pressed_node: Option<DisplayNode<'gc>>,
+) -> bool {
+    pressed_node.map(GcCell::as_ptr) == Some(GcCell::as_ptr(node)) || is_track_as_menu(node)
+}
+
+/// Whether `node` is a button tracked as a menu item.
+fn is_track_as_menu(node: DisplayNode<'_>) -> bool {
+    node.read()
+        .as_button()
+        .map_or(false, |button| button.is_track_as_menu())
+}
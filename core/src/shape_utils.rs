@@ -532,6 +532,122 @@ impl<'a> ShapeConverter<'a> {
     }
 }
 
+/// The number of line segments that a curve is split into for hit testing.
+const CURVE_HIT_TEST_STEPS: u32 = 16;
+
+/// The smallest half-width of a stroke for hit testing, in twips, so that hairlines can be hit.
+const MIN_STROKE_HALF_WIDTH: f64 = 10.0;
+
+/// Returns whether `point` lies on a fill or stroke of `shape`, in the coordinate space of the shape.
+/// Fills use the non-zero winding rule if the shape has `has_fill_winding_rule` set,
+/// and the even-odd rule otherwise.
+pub fn shape_hit_test(shape: &swf::Shape, point: (Twips, Twips)) -> bool {
+    let paths = swf_shape_to_paths(shape);
+    draw_paths_hit_test(&paths, point, shape.has_fill_winding_rule)
+}
+
+/// Returns whether `point` lies on any of `paths`.
+pub fn draw_paths_hit_test(paths: &[DrawPath], point: (Twips, Twips), non_zero: bool) -> bool {
+    let point = (f64::from(point.0.get()), f64::from(point.1.get()));
+    paths.iter().any(|path| match path {
+        DrawPath::Fill { commands, .. } => {
+            let winding = fill_winding_number(commands, point);
+            if non_zero {
+                winding != 0
+            } else {
+                winding % 2 != 0
+            }
+        }
+        DrawPath::Stroke {
+            style, commands, ..
+        } => {
+            let half_width = (f64::from(style.width.get()) / 2.0).max(MIN_STROKE_HALF_WIDTH);
+            let mut hit = false;
+            for_each_line_segment(commands, false, |start, end| {
+                hit |= distance_to_segment(point, start, end) <= half_width;
+            });
+            hit
+        }
+    })
+}
+
+/// Counts how many times the outline of a fill winds around `point`.
+/// Each subpath is closed back to its start.
+fn fill_winding_number(commands: &[DrawCommand], (x, y): (f64, f64)) -> i32 {
+    let mut winding = 0;
+    for_each_line_segment(commands, true, |start, end| {
+        let side = (end.0 - start.0) * (y - start.1) - (x - start.0) * (end.1 - start.1);
+        if start.1 <= y && end.1 > y && side > 0.0 {
+            winding += 1;
+        } else if end.1 <= y && start.1 > y && side < 0.0 {
+            winding -= 1;
+        }
+    });
+    winding
+}
+
+/// Calls `f` with the start and end of every line segment in `commands`, in twips.
+/// Curves are split into straight lines. With `close`, each subpath ends with a line back
+/// to its first point.
+fn for_each_line_segment(
+    commands: &[DrawCommand],
+    close: bool,
+    mut f: impl FnMut((f64, f64), (f64, f64)),
+) {
+    let twips = |x: Twips, y: Twips| (f64::from(x.get()), f64::from(y.get()));
+    let mut subpath_start = (0.0, 0.0);
+    let mut cursor = (0.0, 0.0);
+    for command in commands {
+        match *command {
+            DrawCommand::MoveTo { x, y } => {
+                if close && cursor != subpath_start {
+                    f(cursor, subpath_start);
+                }
+                subpath_start = twips(x, y);
+                cursor = subpath_start;
+            }
+            DrawCommand::LineTo { x, y } => {
+                let end = twips(x, y);
+                f(cursor, end);
+                cursor = end;
+            }
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                let control = twips(x1, y1);
+                let anchor = twips(x2, y2);
+                let start = cursor;
+                for step in 1..=CURVE_HIT_TEST_STEPS {
+                    let t = f64::from(step) / f64::from(CURVE_HIT_TEST_STEPS);
+                    let u = 1.0 - t;
+                    let end = (
+                        u * u * start.0 + 2.0 * u * t * control.0 + t * t * anchor.0,
+                        u * u * start.1 + 2.0 * u * t * control.1 + t * t * anchor.1,
+                    );
+                    f(cursor, end);
+                    cursor = end;
+                }
+            }
+        }
+    }
+    if close && cursor != subpath_start {
+        f(cursor, subpath_start);
+    }
+}
+
+/// Returns the distance from `point` to the nearest point on the line segment from `start` to `end`.
+fn distance_to_segment(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+    let (nearest_x, nearest_y) = (start.0 + t * dx, start.1 + t * dy);
+    ((point.0 - nearest_x).powi(2) + (point.1 - nearest_y).powi(2)).sqrt()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -684,4 +800,39 @@ mod tests {
         }];
         assert_eq!(commands, expected);
     }
+
+    /// Points inside and outside of a solid square.
+    #[test]
+    fn hit_test_square() {
+        let shape = build_shape(vec![
+            ShapeRecord::StyleChange(swf::StyleChangeData {
+                move_to: Some((Twips::from_pixels(100.0), Twips::from_pixels(100.0))),
+                fill_style_0: None,
+                fill_style_1: Some(1),
+                line_style: None,
+                new_styles: None,
+            }),
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::from_pixels(100.0),
+                delta_y: Twips::from_pixels(0.0),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::from_pixels(0.0),
+                delta_y: Twips::from_pixels(100.0),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::from_pixels(-100.0),
+                delta_y: Twips::from_pixels(0.0),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::from_pixels(0.0),
+                delta_y: Twips::from_pixels(-100.0),
+            },
+        ]);
+        let point = |x, y| (Twips::from_pixels(x), Twips::from_pixels(y));
+        assert!(shape_hit_test(&shape, point(150.0, 150.0)));
+        assert!(shape_hit_test(&shape, point(101.0, 199.0)));
+        assert!(!shape_hit_test(&shape, point(50.0, 150.0)));
+        assert!(!shape_hit_test(&shape, point(150.0, 201.0)));
+    }
 }
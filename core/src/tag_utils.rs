@@ -10,6 +10,23 @@ pub struct SwfSlice {
     pub end: usize,
 }
 
+impl SwfSlice {
+    /// Creates a slice of `len` bytes starting at `start`, relative to the start of this slice.
+    /// Returns `None` if the range is out of bounds.
+    pub fn to_subslice(&self, start: usize, len: usize) -> Option<SwfSlice> {
+        let end = start.checked_add(len)?;
+        if end <= self.end - self.start {
+            Some(SwfSlice {
+                data: std::sync::Arc::clone(&self.data),
+                start: self.start + start,
+                end: self.start + end,
+            })
+        } else {
+            None
+        }
+    }
+}
+
 impl AsRef<[u8]> for SwfSlice {
     fn as_ref(&self) -> &[u8] {
         &self.data[self.start..self.end]
@@ -1,18 +1,39 @@
 use crate::display_object::{DisplayObject, DisplayObjectBase};
+use crate::library::Library;
 use crate::player::{RenderContext, UpdateContext};
+use crate::prelude::*;
+use crate::shape_utils::shape_hit_test;
 use crate::transform::Transform;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct Text<'gc> {
     base: DisplayObjectBase<'gc>,
+    bounds: BoundingBox,
     text_blocks: Vec<swf::TextRecord>,
+
+    /// The glyph outlines of the fonts used by this text, kept for hit testing.
+    glyph_shapes: HashMap<CharacterId, Rc<Vec<swf::Shape>>>,
 }
 
 impl<'gc> Text<'gc> {
-    pub fn from_swf_tag(tag: &swf::Text) -> Self {
+    pub fn from_swf_tag(tag: &swf::Text, library: &Library<'gc>) -> Self {
+        let glyph_shapes = tag
+            .records
+            .iter()
+            .filter_map(|block| block.font_id)
+            .filter_map(|font_id| {
+                library
+                    .get_font(font_id)
+                    .map(|font| (font_id, font.glyph_shapes()))
+            })
+            .collect();
         Self {
             base: Default::default(),
+            bounds: tag.bounds.clone().into(),
             text_blocks: tag.records.clone(),
+            glyph_shapes,
         }
     }
 }
@@ -20,6 +41,10 @@ impl<'gc> Text<'gc> {
 impl<'gc> DisplayObject<'gc> for Text<'gc> {
     impl_display_object!(base);
 
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
     fn run_frame(&mut self, _context: &mut UpdateContext) {
         // Noop
     }
@@ -68,6 +93,45 @@ impl<'gc> DisplayObject<'gc> for Text<'gc> {
         }
         context.transform_stack.pop();
     }
+
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        let (point_x, point_y) = self.global_to_local(point);
+        if !self.bounds.contains((point_x, point_y)) {
+            return false;
+        }
+
+        // Walk the glyphs in the same way as `render`.
+        let mut x = Default::default();
+        let mut y = Default::default();
+        let mut font_id = 0;
+        let mut height = 0;
+        for block in &self.text_blocks {
+            x = block.x_offset.unwrap_or(x);
+            y = block.y_offset.unwrap_or(y);
+            font_id = block.font_id.unwrap_or(font_id);
+            height = block.height.unwrap_or(height);
+            let scale = f32::from(height) / 1024.0;
+            if scale == 0.0 {
+                continue;
+            }
+            let mut glyph_x = x.get() as f32;
+            if let Some(glyph_shapes) = self.glyph_shapes.get(&font_id) {
+                for c in &block.glyphs {
+                    if let Some(glyph_shape) = glyph_shapes.get(c.index as usize) {
+                        let glyph_point = (
+                            Twips::new(((point_x.get() as f32 - glyph_x) / scale) as i32),
+                            Twips::new(((point_y.get() - y.get()) as f32 / scale) as i32),
+                        );
+                        if shape_hit_test(glyph_shape, glyph_point) {
+                            return true;
+                        }
+                        glyph_x += c.advance as f32;
+                    }
+                }
+            }
+        }
+        false
+    }
 }
 
 unsafe impl<'gc> gc_arena::Collect for Text<'gc> {
@@ -0,0 +1,342 @@
+//! The formatting of the text in text fields, as given by HTML text and `TextFormat` objects.
+
+use crate::prelude::*;
+use swf::TextAlign;
+
+/// The character and paragraph formatting of text.
+///
+/// Every field is optional. Applying a format to text only changes the fields that are set,
+/// and the format of a range of text leaves out the fields that vary within the range.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextFormat {
+    pub font: Option<String>,
+    pub size: Option<Twips>,
+    pub color: Option<Color>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+
+    /// The target of a link, or an empty string for text that isn't a link.
+    pub url: Option<String>,
+    pub target: Option<String>,
+
+    pub align: Option<TextAlign>,
+    pub left_margin: Option<Twips>,
+    pub right_margin: Option<Twips>,
+
+    /// The indent of the first line of a paragraph, from the left margin.
+    pub indent: Option<Twips>,
+
+    /// The indent of all lines of a paragraph, from the left margin.
+    pub block_indent: Option<Twips>,
+
+    /// The extra space between the lines of a paragraph.
+    pub leading: Option<Twips>,
+    pub bullet: Option<bool>,
+    pub tab_stops: Option<Vec<Twips>>,
+}
+
+impl TextFormat {
+    /// Sets the fields of this format that are set in `other`.
+    pub fn merge(&mut self, other: &TextFormat) {
+        fn merge_field<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if other.is_some() {
+                *field = other.clone();
+            }
+        }
+        merge_field(&mut self.font, &other.font);
+        merge_field(&mut self.size, &other.size);
+        merge_field(&mut self.color, &other.color);
+        merge_field(&mut self.bold, &other.bold);
+        merge_field(&mut self.italic, &other.italic);
+        merge_field(&mut self.underline, &other.underline);
+        merge_field(&mut self.url, &other.url);
+        merge_field(&mut self.target, &other.target);
+        merge_field(&mut self.align, &other.align);
+        merge_field(&mut self.left_margin, &other.left_margin);
+        merge_field(&mut self.right_margin, &other.right_margin);
+        merge_field(&mut self.indent, &other.indent);
+        merge_field(&mut self.block_indent, &other.block_indent);
+        merge_field(&mut self.leading, &other.leading);
+        merge_field(&mut self.bullet, &other.bullet);
+        merge_field(&mut self.tab_stops, &other.tab_stops);
+    }
+
+    /// Clears the fields of this format that differ in `other`.
+    pub fn intersect(&mut self, other: &TextFormat) {
+        fn intersect_field<T: PartialEq>(field: &mut Option<T>, other: &Option<T>) {
+            if field != other {
+                *field = None;
+            }
+        }
+        intersect_field(&mut self.font, &other.font);
+        intersect_field(&mut self.size, &other.size);
+        intersect_field(&mut self.color, &other.color);
+        intersect_field(&mut self.bold, &other.bold);
+        intersect_field(&mut self.italic, &other.italic);
+        intersect_field(&mut self.underline, &other.underline);
+        intersect_field(&mut self.url, &other.url);
+        intersect_field(&mut self.target, &other.target);
+        intersect_field(&mut self.align, &other.align);
+        intersect_field(&mut self.left_margin, &other.left_margin);
+        intersect_field(&mut self.right_margin, &other.right_margin);
+        intersect_field(&mut self.indent, &other.indent);
+        intersect_field(&mut self.block_indent, &other.block_indent);
+        intersect_field(&mut self.leading, &other.leading);
+        intersect_field(&mut self.bullet, &other.bullet);
+        intersect_field(&mut self.tab_stops, &other.tab_stops);
+    }
+
+    /// The character formatting of this format, without the paragraph formatting.
+    pub fn character_format(&self) -> TextFormat {
+        TextFormat {
+            font: self.font.clone(),
+            size: self.size,
+            color: self.color.clone(),
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            url: self.url.clone(),
+            target: self.target.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// The paragraph formatting of this format, without the character formatting.
+    pub fn paragraph_format(&self) -> TextFormat {
+        TextFormat {
+            align: self.align,
+            left_margin: self.left_margin,
+            right_margin: self.right_margin,
+            indent: self.indent,
+            block_indent: self.block_indent,
+            leading: self.leading,
+            bullet: self.bullet,
+            tab_stops: self.tab_stops.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A run of characters with the same format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSpan {
+    /// The number of characters in the run.
+    pub len: usize,
+    pub format: TextFormat,
+}
+
+/// The formats of the characters of a text, as runs of characters with the same format.
+///
+/// There is always at least one span, so that an empty text still has the format that
+/// typed characters get.
+#[derive(Clone, Debug)]
+pub struct FormatSpans {
+    spans: Vec<TextSpan>,
+}
+
+impl FormatSpans {
+    /// Formats `len` characters with `format`.
+    pub fn new(len: usize, format: TextFormat) -> Self {
+        Self {
+            spans: vec![TextSpan { len, format }],
+        }
+    }
+
+    /// The number of characters that the spans cover.
+    pub fn len(&self) -> usize {
+        self.spans.iter().map(|span| span.len).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TextSpan> {
+        self.spans.iter()
+    }
+
+    /// Appends `len` characters with `format`.
+    pub fn push(&mut self, len: usize, format: TextFormat) {
+        if len == 0 {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.format == format => last.len += len,
+            // The span of an empty text only holds its format.
+            Some(last) if last.len == 0 => *last = TextSpan { len, format },
+            _ => self.spans.push(TextSpan { len, format }),
+        }
+    }
+
+    /// The format of the character at `index`. The end of the text has the format
+    /// of the last character.
+    pub fn format_at(&self, index: usize) -> &TextFormat {
+        let mut start = 0;
+        for span in &self.spans {
+            if index < start + span.len {
+                return &span.format;
+            }
+            start += span.len;
+        }
+        &self.spans[self.spans.len() - 1].format
+    }
+
+    /// The format shared by all characters from `start` up to `end`.
+    pub fn format_of(&self, start: usize, end: usize) -> TextFormat {
+        if start >= end {
+            return self.format_at(start).clone();
+        }
+        let mut format: Option<TextFormat> = None;
+        let mut span_start = 0;
+        for span in &self.spans {
+            let span_end = span_start + span.len;
+            if span_start < end && span_end > start {
+                match &mut format {
+                    Some(format) => format.intersect(&span.format),
+                    None => format = Some(span.format.clone()),
+                }
+            }
+            span_start = span_end;
+        }
+        format.unwrap_or_else(|| self.format_at(start).clone())
+    }
+
+    /// Applies `format` to the characters from `start` up to `end`.
+    pub fn set_format(&mut self, start: usize, end: usize, format: &TextFormat) {
+        let end = end.min(self.len());
+        if start >= end {
+            return;
+        }
+        self.split_at(start);
+        self.split_at(end);
+        let mut span_start = 0;
+        for span in &mut self.spans {
+            if span_start >= start && span_start < end {
+                span.format.merge(format);
+            }
+            span_start += span.len;
+        }
+        self.join_spans();
+    }
+
+    /// Replaces the characters from `start` up to `end` with `len` characters with `format`.
+    pub fn replace(&mut self, start: usize, end: usize, len: usize, format: TextFormat) {
+        let end = end.min(self.len());
+        let start = start.min(end);
+        self.split_at(start);
+        self.split_at(end);
+        let mut spans = vec![];
+        let mut span_start = 0;
+        let inserted = TextSpan { len, format };
+        let mut is_inserted = false;
+        for span in self.spans.drain(..) {
+            if span_start == start && !is_inserted {
+                spans.push(inserted.clone());
+                is_inserted = true;
+            }
+            let span_len = span.len;
+            if span_start < start || span_start >= end {
+                spans.push(span);
+            }
+            span_start += span_len;
+        }
+        if !is_inserted {
+            spans.push(inserted);
+        }
+        self.spans = spans;
+        self.join_spans();
+    }
+
+    /// Splits the span containing `index`, so that a span starts at `index`.
+    fn split_at(&mut self, index: usize) {
+        let mut start = 0;
+        for i in 0..self.spans.len() {
+            let len = self.spans[i].len;
+            if index > start && index < start + len {
+                let format = self.spans[i].format.clone();
+                self.spans[i].len = index - start;
+                self.spans.insert(
+                    i + 1,
+                    TextSpan {
+                        len: start + len - index,
+                        format,
+                    },
+                );
+                return;
+            }
+            start += len;
+        }
+    }
+
+    /// Joins neighbouring spans with the same format, and drops empty spans.
+    fn join_spans(&mut self) {
+        let mut spans: Vec<TextSpan> = Vec::with_capacity(self.spans.len());
+        for span in self.spans.drain(..) {
+            if let Some(last) = spans.last_mut() {
+                if last.format == span.format {
+                    last.len += span.len;
+                    continue;
+                } else if span.len == 0 {
+                    continue;
+                } else if last.len == 0 {
+                    *last = span;
+                    continue;
+                }
+            }
+            spans.push(span);
+        }
+        self.spans = spans;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold() -> TextFormat {
+        TextFormat {
+            bold: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn lengths(spans: &FormatSpans) -> Vec<usize> {
+        spans.iter().map(|span| span.len).collect()
+    }
+
+    #[test]
+    fn set_format_splits_spans() {
+        let mut spans = FormatSpans::new(10, TextFormat::default());
+        spans.set_format(2, 5, &bold());
+        assert_eq!(lengths(&spans), vec![2, 3, 5]);
+        assert_eq!(spans.format_at(2).bold, Some(true));
+        assert_eq!(spans.format_at(5).bold, None);
+        spans.set_format(0, 2, &bold());
+        assert_eq!(lengths(&spans), vec![5, 5]);
+    }
+
+    #[test]
+    fn format_of_range_keeps_shared_fields() {
+        let mut spans = FormatSpans::new(4, TextFormat::default());
+        spans.set_format(
+            0,
+            4,
+            &TextFormat {
+                size: Some(Twips::new(240)),
+                ..Default::default()
+            },
+        );
+        spans.set_format(2, 4, &bold());
+        let format = spans.format_of(0, 4);
+        assert_eq!(format.size, Some(Twips::new(240)));
+        assert_eq!(format.bold, None);
+        assert_eq!(spans.format_of(2, 4).bold, Some(true));
+    }
+
+    #[test]
+    fn replace_inserts_and_removes() {
+        let mut spans = FormatSpans::new(4, TextFormat::default());
+        spans.replace(1, 3, 5, bold());
+        assert_eq!(lengths(&spans), vec![1, 5, 1]);
+        spans.replace(0, 7, 0, TextFormat::default());
+        assert_eq!(spans.len(), 0);
+        assert_eq!(lengths(&spans), vec![0]);
+    }
+}
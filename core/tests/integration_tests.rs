@@ -32,6 +32,18 @@ macro_rules! swf_tests {
 swf_tests! {
     (single_frame, "avm1/single_frame", 2),
     (looping, "avm1/looping", 6),
+    (function_calls, "avm1/function_calls", 1),
+    (classes, "avm1/classes", 1),
+    (builtins, "avm1/builtins", 1),
+    (try_catch, "avm1/try_catch", 2),
+    (scope_chain, "avm1/scope_chain", 1),
+    (type_coercion, "avm1/type_coercion", 1),
+    (type_coercion_swf6, "avm1/type_coercion_swf6", 1),
+    (goto_out_of_range, "avm1/goto_out_of_range", 4),
+    (enumeration_order, "avm1/enumeration_order", 1),
+    (duplicate_movie_clip, "avm1/duplicate_movie_clip", 1),
+    (display_properties, "avm1/display_properties", 1),
+    (init_action_attach_movie, "avm1/init_action_attach_movie", 4),
 }
 
 /// Loads an SWF and runs it through the Ruffle core for a number of frames.
@@ -0,0 +1,75 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8.
+a = [3, 1, 10, 2];
+a.sort();
+trace(a.join());
+a.sort(Array.NUMERIC);
+trace(a.join());
+a.sort(Array.NUMERIC | Array.DESCENDING);
+trace(a.join());
+a.sort();
+a.sort(Array.NUMERIC);
+trace(a.join());
+trace(a.join("-"));
+trace(a.push(5));
+
+b = [1, 2, 3, 4, 5];
+trace(b.splice(1, 2).join());
+trace(b.join());
+
+c = ["b", "c", "a"];
+trace(c.sort(Array.RETURNINDEXEDARRAY).join());
+trace(c.join());
+trace([1, 2, 1].sort(Array.UNIQUESORT));
+
+s = "hello world";
+trace(s.charAt(4));
+trace(s.indexOf("o"));
+trace(s.lastIndexOf("o"));
+trace(s.split(" ").join());
+trace(s.toUpperCase());
+trace(s.substr(6, 3));
+trace(s.substring(6));
+trace(s.slice(-5));
+trace(s.charCodeAt(0));
+trace(s.length);
+trace(String.fromCharCode(72, 105));
+
+trace(Math.max(3, 7));
+trace(Math.floor(-1.5));
+trace(Math.abs(-4));
+trace(Math.pow(2, 10));
+trace(Math.sqrt(16));
+trace(Math.round(-2.5));
+trace(Math.PI);
+
+trace(Number("12") + 1);
+trace((255).toString(16));
+trace(Boolean(1));
+
+o = new Object();
+o.x = 1;
+trace(o.hasOwnProperty("x"));
+trace(o.hasOwnProperty("toString"));
+
+function sum(a, b) {
+    return a + b;
+}
+trace(sum.apply(null, [2, 3]));
+trace(sum.call(null, 4, 6));
+
+d = [3, 1, 10, 2];
+d.sort(function(x, y) {
+    return x - y;
+});
+trace(d.join());
+
+trace(a);
+trace([1, [2, 3]]);
+
+e = new Array(3);
+trace(e.length);
+trace(e.hasOwnProperty("0"));
+big = new Array(1e12);
+big[4000000000] = "far";
+trace(big[4000000000]);
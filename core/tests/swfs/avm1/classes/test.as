@@ -0,0 +1,41 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. `Dog` and `Dog.prototype.speak` are DefineFunction2s
+// that preload `this` and `super` into registers, as the AS2 compiler emits them.
+function Animal(name) {
+    this.name = name;
+    return;
+}
+Animal.prototype.speak = function() {
+    return this.name + " makes a sound";
+};
+
+function Dog(name) {
+    super(name);
+    return;
+}
+Dog extends Animal;
+Dog.prototype.speak = function() {
+    return super.speak() + " and barks";
+};
+
+d = new Dog("Rex");
+trace(d.name);
+trace(d.speak());
+trace(d instanceof Dog);
+trace(d instanceof Animal);
+trace(d instanceof Object);
+
+a = new Animal("Cat");
+trace(a.speak());
+trace(a instanceof Dog);
+trace(Dog(a));
+trace(Animal(d).name);
+
+function IFace() {
+}
+Dog implements IFace;
+trace(d instanceof IFace);
+
+o = {Make: Animal};
+b = new o.Make("Bee");
+trace(b.speak());
@@ -0,0 +1,115 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. The timeline places an empty
+// symbol at depth 1 with the instance name `clip` before these actions run.
+
+trace(getProperty(clip, _x));
+setProperty(clip, _x, 10);
+trace(getProperty(clip, _x));
+setProperty(clip, _x, "abc");
+trace(getProperty(clip, _x));
+setProperty(clip, _x, undefined);
+trace(getProperty(clip, _x));
+
+trace(getProperty(clip, _y));
+setProperty(clip, _y, 20.5);
+trace(getProperty(clip, _y));
+
+trace(getProperty(clip, _rotation));
+setProperty(clip, _rotation, 90);
+trace(getProperty(clip, _rotation));
+setProperty(clip, _rotation, 0);
+trace(getProperty(clip, _rotation));
+
+trace(getProperty(clip, _xscale));
+setProperty(clip, _xscale, 50);
+trace(getProperty(clip, _xscale));
+setProperty(clip, _xscale, "abc");
+trace(getProperty(clip, _xscale));
+
+trace(getProperty(clip, _yscale));
+setProperty(clip, _yscale, 200);
+trace(getProperty(clip, _yscale));
+
+trace(getProperty(clip, _currentframe));
+setProperty(clip, _currentframe, 5);
+trace(getProperty(clip, _currentframe));
+
+trace(getProperty(clip, _totalframes));
+setProperty(clip, _totalframes, 5);
+trace(getProperty(clip, _totalframes));
+
+trace(getProperty(clip, _framesloaded));
+setProperty(clip, _framesloaded, 5);
+trace(getProperty(clip, _framesloaded));
+
+trace(getProperty(clip, _alpha));
+setProperty(clip, _alpha, 50);
+trace(getProperty(clip, _alpha));
+setProperty(clip, _alpha, "abc");
+trace(getProperty(clip, _alpha));
+
+trace(getProperty(clip, _visible));
+setProperty(clip, _visible, false);
+trace(getProperty(clip, _visible));
+setProperty(clip, _visible, true);
+trace(getProperty(clip, _visible));
+
+// The clip has no content, so its size can't be changed.
+
+trace(getProperty(clip, _width));
+setProperty(clip, _width, 100);
+trace(getProperty(clip, _width));
+
+trace(getProperty(clip, _height));
+setProperty(clip, _height, 100);
+trace(getProperty(clip, _height));
+trace(getProperty(clip, _xscale));
+trace(getProperty(clip, _yscale));
+
+trace(getProperty(clip, _target));
+setProperty(clip, _target, "/other");
+trace(getProperty(clip, _target));
+
+trace(getProperty(clip, _droptarget));
+setProperty(clip, _droptarget, "/other");
+trace(getProperty(clip, _droptarget));
+
+setProperty(clip, _name, "renamed");
+trace(getProperty(renamed, _name));
+trace(getProperty(renamed, _target));
+setProperty(renamed, _name, "clip");
+trace(getProperty(clip, _name));
+
+trace(typeof getProperty(clip, _url));
+
+// These properties are global, and are the same for every clip.
+
+trace(getProperty(clip, _quality));
+setProperty(clip, _quality, "low");
+trace(getProperty(clip, _quality));
+setProperty(clip, _quality, "invalid");
+trace(getProperty(clip, _quality));
+setProperty(clip, _quality, "best");
+trace(getProperty(clip, _quality));
+
+trace(getProperty(clip, _highquality));
+setProperty(clip, _highquality, 0);
+trace(getProperty(clip, _highquality));
+setProperty(clip, _highquality, 1);
+trace(getProperty(clip, _highquality));
+setProperty(clip, _highquality, "abc");
+trace(getProperty(clip, _highquality));
+trace(getProperty("", _quality));
+
+trace(getProperty(clip, _focusrect));
+setProperty(clip, _focusrect, false);
+trace(getProperty(clip, _focusrect));
+
+trace(getProperty(clip, _soundbuftime));
+setProperty(clip, _soundbuftime, 10);
+trace(getProperty(clip, _soundbuftime));
+setProperty(clip, _soundbuftime, "abc");
+trace(getProperty(clip, _soundbuftime));
+
+trace(typeof getProperty(clip, _xmouse));
+trace(typeof getProperty(clip, _ymouse));
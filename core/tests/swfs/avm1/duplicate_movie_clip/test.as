@@ -0,0 +1,34 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. The timeline places an empty
+// symbol at depth 1 with the instance name `timeline_clip` before these actions run.
+timeline_clip.v = "timeline";
+trace(timeline_clip.getDepth());
+
+// Copies start with none of the variables of the original.
+timeline_clip.duplicateMovieClip("copy", 5);
+trace(copy.getDepth());
+trace(copy._name);
+trace(copy.v);
+trace(timeline_clip.v);
+
+duplicateMovieClip("timeline_clip", "copy2", 7);
+trace(copy2.getDepth());
+
+// Clips placed by the timeline can't be removed.
+timeline_clip.removeMovieClip();
+trace(timeline_clip._name);
+removeMovieClip("timeline_clip");
+trace(timeline_clip._name);
+
+copy.removeMovieClip();
+trace(typeof copy);
+removeMovieClip("copy2");
+trace(typeof copy2);
+
+e = this.createEmptyMovieClip("empty", 10);
+trace(e.getDepth());
+trace(e._name);
+trace(e == empty);
+trace(this.getNextHighestDepth());
+e.removeMovieClip();
+trace(typeof empty);
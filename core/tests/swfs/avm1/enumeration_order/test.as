@@ -0,0 +1,35 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. Properties enumerate from the
+// most recently defined, then those of the prototype.
+o = {a: 1, b: 2};
+o.c = 3;
+for (k in o) {
+    trace(k);
+}
+
+// Redefining a deleted property moves it to the end; assigning to one keeps its place.
+delete o.b;
+o.b = 4;
+o.a = 5;
+for (k in o) {
+    trace(k);
+}
+
+function P() {
+}
+P.prototype.p = 1;
+P.prototype.q = 2;
+x = new P();
+x.own = 3;
+for (k in x) {
+    trace(k);
+}
+x.p = 4;
+for (k in x) {
+    trace(k);
+}
+
+arr = ["x", "y", "z"];
+for (k in arr) {
+    trace(k);
+}
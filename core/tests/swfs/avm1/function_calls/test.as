@@ -0,0 +1,53 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. `mul` is a DefineFunction2 that keeps its arguments in registers.
+function add(a, b) {
+    return a + b;
+}
+trace(add(2, 3));
+
+function fact(n) {
+    if (n < 2) {
+        return 1;
+    }
+    return n * fact(n - 1);
+}
+trace(fact(5));
+
+function count() {
+    return arguments.length;
+}
+trace(count(1, 2, 3));
+
+x = "global";
+function shadow() {
+    var x = "local";
+    return x;
+}
+trace(shadow());
+trace(x);
+
+function mul(a, b) {
+    return a * b;
+}
+trace(mul(4, 5));
+
+function nothing() {
+}
+trace(nothing());
+
+function outer() {
+    var v = "captured";
+    var inner = function() {
+        return v;
+    };
+    return inner;
+}
+f = outer();
+trace(f());
+
+o = {n: 7, getN: function() {
+    return this.n;
+}};
+trace(o.getN());
+
+trace(add(1));
@@ -0,0 +1,20 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. Frames past the end of the
+// timeline go to the last frame.
+
+// Frame 1
+trace("frame 1");
+gotoAndStop(100);
+
+// Frame 2
+trace("frame 2");
+
+// Frame 3
+trace("frame 3, _currentframe " + _currentframe);
+if (!looped) {
+    looped = true;
+    f = 70000;
+    gotoAndPlay(f);
+} else {
+    this.gotoAndStop(70000);
+}
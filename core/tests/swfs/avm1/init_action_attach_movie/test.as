@@ -0,0 +1,28 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. Frame 1 defines the
+// empty sprite 1, exports it as "Symbol" and gives it a DoInitAction tag, before these actions.
+
+// Frame 1
+
+// #initclip in the symbol exported as "Symbol":
+// trace("init Symbol");
+// initialized = true;
+
+trace("frame 1, initialized: " + initialized);
+if (!looped) {
+    a = this.attachMovie("Symbol", "attached", 3, {v: 5});
+    trace(a._name);
+    trace(attached.getDepth());
+    trace(attached.v);
+    trace(a == attached);
+    trace(this.attachMovie("Missing", "m", 4));
+    trace(typeof m);
+}
+
+// Frame 2
+trace("frame 2");
+// The timeline loops once, passing the DoInitAction tag again.
+if (looped) {
+    this.stop();
+}
+looped = true;
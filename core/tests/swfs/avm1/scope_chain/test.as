@@ -0,0 +1,35 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8.
+o = {a: 1};
+with (o) {
+    trace(a);
+    a = 2;
+    b = 3;
+}
+trace(o.a);
+trace(o.b);
+trace(b);
+
+_global.g = "glob";
+trace(g);
+function f() {
+    var g = "local";
+    return g;
+}
+trace(f());
+
+x = 5;
+trace(eval("/:x"));
+trace(eval("_level0:x"));
+trace(eval("_root.x"));
+
+_root.createEmptyMovieClip("mc", 1);
+tellTarget ("mc") {
+    v = "inside";
+    trace(_name);
+    trace(_parent.x);
+    trace(eval("../:x"));
+}
+trace(mc.v);
+trace(eval("/mc:v"));
+trace(eval("mc.v"));
@@ -0,0 +1,66 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8. `g` is a DefineFunction2 that catches into register 1.
+
+// Frame 1
+try {
+    throw "oops";
+} catch (e) {
+    trace("caught " + e);
+} finally {
+    trace("finally");
+}
+
+function thrower() {
+    throw new Error("bad");
+}
+try {
+    thrower();
+    trace("not reached");
+} catch (e) {
+    trace(e.message);
+}
+
+function f() {
+    try {
+        return "try";
+    } finally {
+        trace("cleanup");
+    }
+}
+trace(f());
+
+try {
+    try {
+        throw 1;
+    } finally {
+        trace("inner finally");
+    }
+} catch (e) {
+    trace("outer caught " + e);
+}
+
+function g() {
+    try {
+        throw "reg";
+    } catch (r) {
+        return r;
+    }
+}
+trace(g());
+
+function h() {
+    try {
+        return "a";
+    } finally {
+        return "b";
+    }
+}
+trace(h());
+
+// An uncaught exception abandons the rest of the frame's actions.
+trace("before");
+throw "uncaught";
+trace("after");
+
+// Frame 2
+trace("next frame");
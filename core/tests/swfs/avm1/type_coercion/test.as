@@ -0,0 +1,45 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 8.
+o = {valueOf: function() {
+    return 5;
+}};
+p = {toString: function() {
+    return "P";
+}};
+
+trace(0.1 + 0.2);
+trace(1 / 3);
+trace(1e21);
+trace(1e15);
+trace(123456789012345);
+trace(0.000001);
+trace(0.00001);
+trace(0 * -1);
+trace(-1 / 0);
+trace(0 / 0);
+
+trace(Number("0x1F"));
+trace(Number("017"));
+trace(Number(" 12 "));
+trace(Number("Infinity"));
+trace(Number("12abc"));
+trace(Number(""));
+
+trace(Boolean("false"));
+trace(Boolean("0"));
+trace(Boolean(""));
+
+trace("" + undefined);
+trace(undefined + 1);
+trace(null + 1);
+trace("a" + 1.5);
+
+trace(o + 1);
+trace(o * 2);
+trace(o == 5);
+trace("x" + p);
+
+trace(null == undefined);
+trace("5" == 5);
+trace("10" < "9");
+trace("10" < 9);
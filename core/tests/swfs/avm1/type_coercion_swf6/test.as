@@ -0,0 +1,23 @@
+// test.swf was assembled by hand, so there is no test.fla. It holds the bytecode that
+// this ActionScript compiles to for SWF 6, where undefined, null and empty strings convert to 0,
+// and strings convert to booleans through numbers.
+o = {valueOf: function() {
+    return 5;
+}};
+p = {toString: function() {
+    return "P";
+}};
+
+trace(Number("0x1F"));
+trace(Number("017"));
+trace(Number(""));
+
+trace(Boolean("false"));
+trace(Boolean("1"));
+
+trace("" + undefined);
+trace(undefined + 1);
+trace(null + 1);
+
+trace(o + 1);
+trace("x" + p);
@@ -0,0 +1,242 @@
+//! Translation of keyboard input into Flash keycodes, and of mouse wheel input into lines.
+
+use glutin::VirtualKeyCode;
+use ruffle_core::{key_code, KeyCode};
+
+/// The number of lines that Flash scrolls for one notch of the mouse wheel.
+pub const LINES_PER_NOTCH: f64 = 3.0;
+
+/// The height of a line, for wheels and touchpads that report scrolling in pixels.
+pub const PIXELS_PER_LINE: f64 = 100.0 / LINES_PER_NOTCH;
+
+/// Returns the Flash keycode of a key, or `None` if Flash has no code for it.
+pub fn flash_key_code(key: VirtualKeyCode) -> Option<KeyCode> {
+    let code = match key {
+        VirtualKeyCode::A => b'A',
+        VirtualKeyCode::B => b'B',
+        VirtualKeyCode::C => b'C',
+        VirtualKeyCode::D => b'D',
+        VirtualKeyCode::E => b'E',
+        VirtualKeyCode::F => b'F',
+        VirtualKeyCode::G => b'G',
+        VirtualKeyCode::H => b'H',
+        VirtualKeyCode::I => b'I',
+        VirtualKeyCode::J => b'J',
+        VirtualKeyCode::K => b'K',
+        VirtualKeyCode::L => b'L',
+        VirtualKeyCode::M => b'M',
+        VirtualKeyCode::N => b'N',
+        VirtualKeyCode::O => b'O',
+        VirtualKeyCode::P => b'P',
+        VirtualKeyCode::Q => b'Q',
+        VirtualKeyCode::R => b'R',
+        VirtualKeyCode::S => b'S',
+        VirtualKeyCode::T => b'T',
+        VirtualKeyCode::U => b'U',
+        VirtualKeyCode::V => b'V',
+        VirtualKeyCode::W => b'W',
+        VirtualKeyCode::X => b'X',
+        VirtualKeyCode::Y => b'Y',
+        VirtualKeyCode::Z => b'Z',
+        VirtualKeyCode::Key0 => b'0',
+        VirtualKeyCode::Key1 => b'1',
+        VirtualKeyCode::Key2 => b'2',
+        VirtualKeyCode::Key3 => b'3',
+        VirtualKeyCode::Key4 => b'4',
+        VirtualKeyCode::Key5 => b'5',
+        VirtualKeyCode::Key6 => b'6',
+        VirtualKeyCode::Key7 => b'7',
+        VirtualKeyCode::Key8 => b'8',
+        VirtualKeyCode::Key9 => b'9',
+        VirtualKeyCode::Numpad0 => key_code::NUMPAD_0 + 0,
+        VirtualKeyCode::Numpad1 => key_code::NUMPAD_0 + 1,
+        VirtualKeyCode::Numpad2 => key_code::NUMPAD_0 + 2,
+        VirtualKeyCode::Numpad3 => key_code::NUMPAD_0 + 3,
+        VirtualKeyCode::Numpad4 => key_code::NUMPAD_0 + 4,
+        VirtualKeyCode::Numpad5 => key_code::NUMPAD_0 + 5,
+        VirtualKeyCode::Numpad6 => key_code::NUMPAD_0 + 6,
+        VirtualKeyCode::Numpad7 => key_code::NUMPAD_0 + 7,
+        VirtualKeyCode::Numpad8 => key_code::NUMPAD_0 + 8,
+        VirtualKeyCode::Numpad9 => key_code::NUMPAD_0 + 9,
+        VirtualKeyCode::F1 => key_code::F1 + 0,
+        VirtualKeyCode::F2 => key_code::F1 + 1,
+        VirtualKeyCode::F3 => key_code::F1 + 2,
+        VirtualKeyCode::F4 => key_code::F1 + 3,
+        VirtualKeyCode::F5 => key_code::F1 + 4,
+        VirtualKeyCode::F6 => key_code::F1 + 5,
+        VirtualKeyCode::F7 => key_code::F1 + 6,
+        VirtualKeyCode::F8 => key_code::F1 + 7,
+        VirtualKeyCode::F9 => key_code::F1 + 8,
+        VirtualKeyCode::F10 => key_code::F1 + 9,
+        VirtualKeyCode::F11 => key_code::F1 + 10,
+        VirtualKeyCode::F12 => key_code::F1 + 11,
+        VirtualKeyCode::F13 => key_code::F1 + 12,
+        VirtualKeyCode::F14 => key_code::F1 + 13,
+        VirtualKeyCode::F15 => key_code::F1 + 14,
+        VirtualKeyCode::Back => key_code::BACKSPACE,
+        VirtualKeyCode::Tab => key_code::TAB,
+        VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => key_code::ENTER,
+        VirtualKeyCode::LShift | VirtualKeyCode::RShift => key_code::SHIFT,
+        VirtualKeyCode::LControl | VirtualKeyCode::RControl => key_code::CONTROL,
+        VirtualKeyCode::LAlt | VirtualKeyCode::RAlt => key_code::ALT,
+        VirtualKeyCode::Pause => key_code::PAUSE,
+        VirtualKeyCode::Capital => key_code::CAPS_LOCK,
+        VirtualKeyCode::Escape => key_code::ESCAPE,
+        VirtualKeyCode::Space => key_code::SPACE,
+        VirtualKeyCode::PageUp => key_code::PG_UP,
+        VirtualKeyCode::PageDown => key_code::PG_DOWN,
+        VirtualKeyCode::End => key_code::END,
+        VirtualKeyCode::Home => key_code::HOME,
+        VirtualKeyCode::Left => key_code::LEFT,
+        VirtualKeyCode::Up => key_code::UP,
+        VirtualKeyCode::Right => key_code::RIGHT,
+        VirtualKeyCode::Down => key_code::DOWN,
+        VirtualKeyCode::Insert => key_code::INSERT,
+        VirtualKeyCode::Delete => key_code::DELETE,
+        VirtualKeyCode::Multiply => key_code::NUMPAD_MULTIPLY,
+        VirtualKeyCode::Add => key_code::NUMPAD_ADD,
+        VirtualKeyCode::Subtract => key_code::NUMPAD_SUBTRACT,
+        VirtualKeyCode::Decimal => key_code::NUMPAD_DECIMAL,
+        VirtualKeyCode::Divide => key_code::NUMPAD_DIVIDE,
+        VirtualKeyCode::Numlock => key_code::NUM_LOCK,
+        VirtualKeyCode::Scroll => key_code::SCROLL_LOCK,
+        VirtualKeyCode::Semicolon => key_code::SEMICOLON,
+        VirtualKeyCode::Equals => key_code::EQUALS,
+        VirtualKeyCode::Comma => key_code::COMMA,
+        VirtualKeyCode::Minus => key_code::MINUS,
+        VirtualKeyCode::Period => key_code::PERIOD,
+        VirtualKeyCode::Slash => key_code::SLASH,
+        VirtualKeyCode::Grave => key_code::GRAVE,
+        VirtualKeyCode::LBracket => key_code::LEFT_BRACKET,
+        VirtualKeyCode::Backslash => key_code::BACKSLASH,
+        VirtualKeyCode::RBracket => key_code::RIGHT_BRACKET,
+        VirtualKeyCode::Apostrophe => key_code::QUOTE,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Returns the ASCII code that a key types on a US keyboard, or 0 if it types nothing.
+pub fn ascii(code: KeyCode, shift: bool) -> u8 {
+    const SHIFTED_DIGITS: &[u8; 10] = b")!@#$%^&*(";
+    match code {
+        b'A'..=b'Z' if shift => code,
+        b'A'..=b'Z' => code.to_ascii_lowercase(),
+        b'0'..=b'9' if shift => SHIFTED_DIGITS[usize::from(code - b'0')],
+        b'0'..=b'9' => code,
+        key_code::BACKSPACE => 8,
+        key_code::TAB => 9,
+        key_code::ENTER => 13,
+        key_code::ESCAPE => 27,
+        key_code::SPACE => b' ',
+        key_code::DELETE => 127,
+        key_code::NUMPAD_0..=key_code::NUMPAD_9 => b'0' + (code - key_code::NUMPAD_0),
+        key_code::NUMPAD_MULTIPLY => b'*',
+        key_code::NUMPAD_ADD => b'+',
+        key_code::NUMPAD_SUBTRACT => b'-',
+        key_code::NUMPAD_DECIMAL => b'.',
+        key_code::NUMPAD_DIVIDE => b'/',
+        _ => {
+            let (unshifted, shifted) = match code {
+                key_code::SEMICOLON => (b';', b':'),
+                key_code::EQUALS => (b'=', b'+'),
+                key_code::COMMA => (b',', b'<'),
+                key_code::MINUS => (b'-', b'_'),
+                key_code::PERIOD => (b'.', b'>'),
+                key_code::SLASH => (b'/', b'?'),
+                key_code::GRAVE => (b'`', b'~'),
+                key_code::LEFT_BRACKET => (b'[', b'{'),
+                key_code::BACKSLASH => (b'\\', b'|'),
+                key_code::RIGHT_BRACKET => (b']', b'}'),
+                key_code::QUOTE => (b'\'', b'"'),
+                _ => return 0,
+            };
+            if shift {
+                shifted
+            } else {
+                unshifted
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_and_digits_use_ascii_codes() {
+        assert_eq!(flash_key_code(VirtualKeyCode::A), Some(b'A'));
+        assert_eq!(flash_key_code(VirtualKeyCode::Z), Some(b'Z'));
+        assert_eq!(flash_key_code(VirtualKeyCode::Key0), Some(b'0'));
+        assert_eq!(flash_key_code(VirtualKeyCode::Key9), Some(b'9'));
+    }
+
+    #[test]
+    fn ranges_of_keys() {
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::Numpad0),
+            Some(key_code::NUMPAD_0)
+        );
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::Numpad9),
+            Some(key_code::NUMPAD_9)
+        );
+        assert_eq!(flash_key_code(VirtualKeyCode::F1), Some(key_code::F1));
+        assert_eq!(flash_key_code(VirtualKeyCode::F12), Some(key_code::F1 + 11));
+    }
+
+    #[test]
+    fn keys_on_both_sides_share_codes() {
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::LShift),
+            Some(key_code::SHIFT)
+        );
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::RShift),
+            Some(key_code::SHIFT)
+        );
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::LControl),
+            Some(key_code::CONTROL)
+        );
+        assert_eq!(flash_key_code(VirtualKeyCode::RAlt), Some(key_code::ALT));
+        assert_eq!(
+            flash_key_code(VirtualKeyCode::NumpadEnter),
+            Some(key_code::ENTER)
+        );
+    }
+
+    #[test]
+    fn keys_without_flash_codes() {
+        assert_eq!(flash_key_code(VirtualKeyCode::LWin), None);
+        assert_eq!(flash_key_code(VirtualKeyCode::Apps), None);
+    }
+
+    #[test]
+    fn ascii_of_letters_and_digits() {
+        assert_eq!(ascii(b'A', false), b'a');
+        assert_eq!(ascii(b'A', true), b'A');
+        assert_eq!(ascii(b'1', false), b'1');
+        assert_eq!(ascii(b'1', true), b'!');
+        assert_eq!(ascii(b'0', true), b')');
+        assert_eq!(ascii(key_code::NUMPAD_0 + 5, false), b'5');
+    }
+
+    #[test]
+    fn ascii_of_punctuation() {
+        assert_eq!(ascii(key_code::SEMICOLON, false), b';');
+        assert_eq!(ascii(key_code::SEMICOLON, true), b':');
+        assert_eq!(ascii(key_code::QUOTE, true), b'"');
+        assert_eq!(ascii(key_code::NUMPAD_ADD, true), b'+');
+    }
+
+    #[test]
+    fn ascii_of_control_keys() {
+        assert_eq!(ascii(key_code::ENTER, false), 13);
+        assert_eq!(ascii(key_code::BACKSPACE, false), 8);
+        assert_eq!(ascii(key_code::DELETE, false), 127);
+        assert_eq!(ascii(key_code::LEFT, false), 0);
+        assert_eq!(ascii(key_code::SHIFT, true), 0);
+    }
+}
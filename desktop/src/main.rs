@@ -1,10 +1,12 @@
 mod audio;
+mod input;
 mod render;
 
 use crate::render::GliumRenderBackend;
 use glutin::{
     dpi::{LogicalSize, PhysicalPosition},
-    ContextBuilder, ElementState, EventsLoop, MouseButton, WindowBuilder, WindowEvent,
+    ContextBuilder, ElementState, EventsLoop, KeyboardInput, MouseButton, MouseScrollDelta,
+    WindowBuilder, WindowEvent,
 };
 use ruffle_core::{backend::render::RenderBackend, Player};
 use std::path::PathBuf;
@@ -32,7 +34,7 @@ fn main() {
 }
 
 fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    let swf_data = std::fs::read(input_path)?;
+    let swf_data = std::fs::read(&input_path)?;
 
     let mut events_loop = EventsLoop::new();
     let window_builder = WindowBuilder::new().with_title("Ruffle");
@@ -40,11 +42,14 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         .with_vsync(true)
         .with_multisampling(4)
         .with_srgb(true)
+        .with_stencil_buffer(8)
         .build_windowed(window_builder, &events_loop)?;
     let audio = audio::RodioAudioBackend::new()?;
     let renderer = GliumRenderBackend::new(windowed_context)?;
     let display = renderer.display().clone();
     let mut player = Player::new(renderer, audio, swf_data)?;
+    let movie_path = input_path.canonicalize().unwrap_or(input_path);
+    player.set_movie_url(&format!("file://{}", movie_path.display()));
     player.set_is_playing(true); // Desktop player will auto-play.
 
     let logical_size: LogicalSize = (player.movie_width(), player.movie_height()).into();
@@ -57,6 +62,7 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     display.gl_window().set_inner_size(logical_size);
 
     let mut mouse_pos = PhysicalPosition::new(0.0, 0.0);
+    let mut cursor_visible = true;
     let mut time = Instant::now();
     loop {
         // Poll UI events
@@ -84,24 +90,67 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                         };
                         player.handle_event(event);
                     }
-                    WindowEvent::MouseInput {
-                        button: MouseButton::Left,
-                        state: pressed,
-                        ..
-                    } => {
-                        let event = if pressed == ElementState::Pressed {
-                            ruffle_core::PlayerEvent::MouseDown {
-                                x: mouse_pos.x,
-                                y: mouse_pos.y,
+                    WindowEvent::MouseInput { button, state, .. } => {
+                        let (x, y) = (mouse_pos.x, mouse_pos.y);
+                        let is_down = state == ElementState::Pressed;
+                        let event = match (button, is_down) {
+                            (MouseButton::Left, true) => {
+                                ruffle_core::PlayerEvent::MouseDown { x, y }
+                            }
+                            (MouseButton::Left, false) => {
+                                ruffle_core::PlayerEvent::MouseUp { x, y }
+                            }
+                            (MouseButton::Right, true) => {
+                                ruffle_core::PlayerEvent::RightMouseDown { x, y }
+                            }
+                            (MouseButton::Right, false) => {
+                                ruffle_core::PlayerEvent::RightMouseUp { x, y }
                             }
-                        } else {
-                            ruffle_core::PlayerEvent::MouseUp {
-                                x: mouse_pos.x,
-                                y: mouse_pos.y,
+                            (MouseButton::Middle, true) => {
+                                ruffle_core::PlayerEvent::MiddleMouseDown { x, y }
                             }
+                            (MouseButton::Middle, false) => {
+                                ruffle_core::PlayerEvent::MiddleMouseUp { x, y }
+                            }
+                            (MouseButton::Other(_), _) => return,
                         };
                         player.handle_event(event);
                     }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let delta = match delta {
+                            MouseScrollDelta::LineDelta(_, lines) => {
+                                f64::from(lines) * input::LINES_PER_NOTCH
+                            }
+                            MouseScrollDelta::PixelDelta(position) => {
+                                position.y / input::PIXELS_PER_LINE
+                            }
+                        };
+                        player.handle_event(ruffle_core::PlayerEvent::MouseWheel { delta });
+                    }
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state,
+                                virtual_keycode: Some(key),
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    } => {
+                        if let Some(key_code) = input::flash_key_code(key) {
+                            let ascii = input::ascii(key_code, modifiers.shift);
+                            let event = if state == ElementState::Pressed {
+                                ruffle_core::PlayerEvent::KeyDown { key_code, ascii }
+                            } else {
+                                ruffle_core::PlayerEvent::KeyUp { key_code, ascii }
+                            };
+                            player.handle_event(event);
+                        }
+                    }
+                    // Characters typed on the keyboard, after the keyboard layout is applied.
+                    WindowEvent::ReceivedCharacter(codepoint) if !codepoint.is_control() => {
+                        player.handle_event(ruffle_core::PlayerEvent::TextInput { codepoint })
+                    }
                     WindowEvent::CursorLeft { .. } => {
                         player.handle_event(ruffle_core::PlayerEvent::MouseLeft)
                     }
@@ -120,6 +169,16 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         time = new_time;
 
         player.tick(dt as f64);
+        for error in player.take_uncaught_errors() {
+            eprintln!("Uncaught ActionScript error: {}", error);
+        }
+
+        // Scripts can hide the cursor with `Mouse.hide`.
+        let new_cursor_visible = player.mouse_cursor_visible();
+        if new_cursor_visible != cursor_visible {
+            display.gl_window().hide_cursor(!new_cursor_visible);
+            cursor_visible = new_cursor_visible;
+        }
 
         std::thread::sleep(Duration::from_millis(1000 / 60));
     }
@@ -1,6 +1,11 @@
 #![allow(clippy::invalid_ref)]
 
-use glium::uniforms::{UniformValue, Uniforms};
+use glium::framebuffer::{SimpleFrameBuffer, StencilRenderBuffer};
+use glium::index::PrimitiveType;
+use glium::texture::{
+    MipmapsOption, RawImage2d, StencilFormat, Texture2d, UncompressedFloatFormat,
+};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, UniformValue, Uniforms};
 use glium::{draw_parameters::DrawParameters, implement_vertex, uniform, Display, Frame, Surface};
 use glutin::WindowedContext;
 use lyon::tessellation::geometry_builder::{BuffersBuilder, VertexBuffers};
@@ -8,7 +13,8 @@ use lyon::{
     path::PathEvent, tessellation, tessellation::FillTessellator, tessellation::StrokeTessellator,
 };
 use ruffle_core::backend::render::swf::{self, FillStyle};
-use ruffle_core::backend::render::{BitmapHandle, Color, Letterbox, RenderBackend, ShapeHandle, Transform};
+use ruffle_core::backend::render::{BitmapHandle, BlendMode, Color, Letterbox, RenderBackend, ShapeHandle, Transform};
+use ruffle_core::filters::{apply_filters, FilterImage};
 use ruffle_core::shape_utils::{DrawCommand, DrawPath};
 use swf::Twips;
 
@@ -18,11 +24,44 @@ pub struct GliumRenderBackend {
     shader_program: glium::Program,
     gradient_shader_program: glium::Program,
     bitmap_shader_program: glium::Program,
+    composite_shader_program: glium::Program,
     meshes: Vec<Mesh>,
     textures: Vec<(swf::CharacterId, Texture)>,
     viewport_width: f32,
     viewport_height: f32,
     view_matrix: [[f32; 4]; 4],
+
+    /// The number of masks on the stencil buffer.
+    num_masks: u32,
+    mask_state: MaskState,
+
+    /// The shapes drawn into each mask on the stack. They are drawn again when the mask is popped
+    /// to remove it from the stencil buffer.
+    mask_shapes: Vec<Vec<(ShapeHandle, Transform)>>,
+
+    /// The offscreen layers that display objects with a blend mode are drawn into.
+    /// Shapes are drawn into the topmost layer, or into the frame if there are none.
+    layers: Vec<Layer>,
+
+    /// A quad covering the whole viewport, used to composite layers.
+    quad_vertex_buffer: glium::VertexBuffer<Vertex>,
+    quad_index_buffer: glium::IndexBuffer<u32>,
+}
+
+/// How shapes are drawn with respect to the masks on the stencil buffer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum MaskState {
+    /// Shapes are drawn normally.
+    NoMask,
+
+    /// Shapes are drawn into the stencil buffer to build the topmost mask.
+    DrawMaskStencil,
+
+    /// Shapes are drawn where all of the masks on the stencil buffer cover.
+    DrawMaskedContent,
+
+    /// Shapes are drawn into the stencil buffer to remove the topmost mask.
+    ClearMaskStencil,
 }
 
 impl GliumRenderBackend {
@@ -74,17 +113,62 @@ impl GliumRenderBackend {
             },
         )?;
 
+        let composite_shader_program = glium::Program::new(
+            &display,
+            ProgramCreationInput::SourceCode {
+                vertex_shader: COMPOSITE_VERTEX_SHADER,
+                fragment_shader: COMPOSITE_FRAGMENT_SHADER,
+                geometry_shader: None,
+                tessellation_control_shader: None,
+                tessellation_evaluation_shader: None,
+                transform_feedback_varyings: None,
+                outputs_srgb: true,
+                uses_point_size: false,
+            },
+        )?;
+
+        let quad_vertex_buffer = glium::VertexBuffer::new(
+            &display,
+            &[
+                Vertex {
+                    position: [-1.0, -1.0],
+                    color: [1.0; 4],
+                },
+                Vertex {
+                    position: [1.0, -1.0],
+                    color: [1.0; 4],
+                },
+                Vertex {
+                    position: [1.0, 1.0],
+                    color: [1.0; 4],
+                },
+                Vertex {
+                    position: [-1.0, 1.0],
+                    color: [1.0; 4],
+                },
+            ],
+        )?;
+        let quad_index_buffer =
+            glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList, &[0, 1, 2, 0, 2, 3])?;
+
         let mut renderer = GliumRenderBackend {
             display,
             shader_program,
             gradient_shader_program,
             bitmap_shader_program,
+            composite_shader_program,
             target: None,
             meshes: vec![],
             textures: vec![],
             viewport_width: 500.0,
             viewport_height: 500.0,
             view_matrix: [[0.0; 4]; 4],
+            num_masks: 0,
+            mask_state: MaskState::NoMask,
+            mask_shapes: vec![],
+            layers: vec![],
+            quad_vertex_buffer,
+            quad_index_buffer,
         };
         renderer.build_matrices();
         Ok(renderer)
@@ -426,6 +510,237 @@ impl GliumRenderBackend {
         handle
     }
 
+    /// Draws the mesh of `shape` onto `surface`.
+    fn draw_mesh<S: Surface>(
+        &self,
+        surface: &mut S,
+        shape: ShapeHandle,
+        transform: &Transform,
+        draw_parameters: &DrawParameters,
+    ) {
+        let mesh = &self.meshes[shape.0];
+
+        let world_matrix = [
+            [transform.matrix.a, transform.matrix.b, 0.0, 0.0],
+            [transform.matrix.c, transform.matrix.d, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                transform.matrix.tx / 20.0,
+                transform.matrix.ty / 20.0,
+                0.0,
+                1.0,
+            ],
+        ];
+
+        let mult_color = [
+            transform.color_transform.r_mult,
+            transform.color_transform.g_mult,
+            transform.color_transform.b_mult,
+            transform.color_transform.a_mult,
+        ];
+
+        let add_color = [
+            transform.color_transform.r_add,
+            transform.color_transform.g_add,
+            transform.color_transform.b_add,
+            transform.color_transform.a_add,
+        ];
+
+        for draw in &mesh.draws {
+            match &draw.draw_type {
+                DrawType::Color => {
+                    surface
+                        .draw(
+                            &draw.vertex_buffer,
+                            &draw.index_buffer,
+                            &self.shader_program,
+                            &uniform! { view_matrix: self.view_matrix, world_matrix: world_matrix, mult_color: mult_color, add_color: add_color },
+                            draw_parameters
+                        )
+                        .unwrap();
+                }
+                DrawType::Gradient(gradient_uniforms) => {
+                    let uniforms = GradientUniformsFull {
+                        view_matrix: self.view_matrix,
+                        world_matrix,
+                        mult_color,
+                        add_color,
+                        gradient: gradient_uniforms.clone(),
+                    };
+
+                    surface
+                        .draw(
+                            &draw.vertex_buffer,
+                            &draw.index_buffer,
+                            &self.gradient_shader_program,
+                            &uniforms,
+                            draw_parameters,
+                        )
+                        .unwrap();
+                }
+                DrawType::Bitmap(bitmap_uniforms) => {
+                    let texture = &self
+                        .textures
+                        .iter()
+                        .find(|(id, _tex)| *id == bitmap_uniforms.id)
+                        .unwrap()
+                        .1;
+
+                    let uniforms = BitmapUniformsFull {
+                        view_matrix: self.view_matrix,
+                        world_matrix,
+                        mult_color,
+                        add_color,
+                        matrix: bitmap_uniforms.matrix,
+                        texture: &texture.texture,
+                    };
+
+                    surface
+                        .draw(
+                            &draw.vertex_buffer,
+                            &draw.index_buffer,
+                            &self.bitmap_shader_program,
+                            &uniforms,
+                            draw_parameters,
+                        )
+                        .unwrap();
+                }
+            }
+        }
+    }
+
+    /// The draw parameters for the current mask state.
+    ///
+    /// The stencil value of a pixel is the number of masks that cover it. Each mask is drawn
+    /// only where the masks beneath it cover, so content is drawn only where the value equals
+    /// the number of masks.
+    fn draw_parameters(&self) -> DrawParameters<'static> {
+        use glium::draw_parameters::{Stencil, StencilOperation, StencilTest};
+        let (test_value, operation, color_mask) = match self.mask_state {
+            MaskState::NoMask => {
+                return DrawParameters {
+                    blend: SHAPE_BLEND,
+                    ..Default::default()
+                };
+            }
+            MaskState::DrawMaskStencil => (self.num_masks - 1, StencilOperation::Increment, false),
+            MaskState::DrawMaskedContent => (self.num_masks, StencilOperation::Keep, true),
+            MaskState::ClearMaskStencil => (self.num_masks, StencilOperation::Decrement, false),
+        };
+        let test = StencilTest::IfEqual { mask: 0xff };
+        DrawParameters {
+            blend: SHAPE_BLEND,
+            color_mask: (color_mask, color_mask, color_mask, color_mask),
+            stencil: Stencil {
+                test_clockwise: test,
+                reference_value_clockwise: test_value as i32,
+                depth_pass_operation_clockwise: operation,
+                test_counter_clockwise: test,
+                reference_value_counter_clockwise: test_value as i32,
+                depth_pass_operation_counter_clockwise: operation,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Draws the color of `layer` onto `surface` with `blend_mode`.
+    ///
+    /// Layers hold premultiplied colors. Blend modes that fixed-function blending can express
+    /// are done that way; the rest are computed in the shader from a copy of the backdrop.
+    fn composite_layer<S: Surface>(
+        &self,
+        surface: &mut S,
+        layer: &Layer,
+        blend_mode: BlendMode,
+        has_backdrop_alpha: bool,
+    ) {
+        use glium::{BlendingFunction, LinearBlendingFactor::*};
+        let add = |source, destination| BlendingFunction::Addition {
+            source,
+            destination,
+        };
+        let over = add(One, OneMinusSourceAlpha);
+        let (shader_blend_mode, color, alpha) = match blend_mode {
+            BlendMode::Normal | BlendMode::Layer => (BLEND_COPY, over, over),
+            BlendMode::Multiply => (BLEND_COPY, add(DestinationColor, OneMinusSourceAlpha), over),
+            BlendMode::Screen => (BLEND_COPY, add(One, OneMinusSourceColor), over),
+            BlendMode::Add => (BLEND_COPY, add(One, One), add(One, One)),
+            BlendMode::Subtract => (
+                BLEND_COPY,
+                BlendingFunction::ReverseSubtraction {
+                    source: One,
+                    destination: One,
+                },
+                add(Zero, One),
+            ),
+            BlendMode::Invert => (
+                BLEND_INVERT,
+                add(OneMinusDestinationColor, OneMinusSourceAlpha),
+                add(Zero, One),
+            ),
+            // Alpha and erase only affect the alpha of a layer beneath.
+            BlendMode::Alpha | BlendMode::Erase if !has_backdrop_alpha => return,
+            BlendMode::Alpha => (BLEND_COPY, add(Zero, SourceAlpha), add(Zero, SourceAlpha)),
+            BlendMode::Erase => (
+                BLEND_COPY,
+                add(Zero, OneMinusSourceAlpha),
+                add(Zero, OneMinusSourceAlpha),
+            ),
+            BlendMode::Difference => (BLEND_DIFFERENCE, over, over),
+            BlendMode::Darken => (BLEND_DARKEN, over, over),
+            BlendMode::Lighten => (BLEND_LIGHTEN, over, over),
+            BlendMode::Overlay => (BLEND_OVERLAY, over, over),
+            BlendMode::HardLight => (BLEND_HARD_LIGHT, over, over),
+        };
+
+        let (width, height) = layer.color.dimensions();
+        let backdrop = if shader_blend_mode >= BLEND_DIFFERENCE {
+            let backdrop = Texture2d::empty(&self.display, width, height).unwrap();
+            surface.blit_whole_color_to(
+                &backdrop.as_surface(),
+                &glium::BlitTarget {
+                    left: 0,
+                    bottom: 0,
+                    width: width as i32,
+                    height: height as i32,
+                },
+                MagnifySamplerFilter::Nearest,
+            );
+            Some(backdrop)
+        } else {
+            None
+        };
+
+        let sampled = |texture: &'_ Texture2d| {
+            texture
+                .sampled()
+                .minify_filter(MinifySamplerFilter::Nearest)
+                .magnify_filter(MagnifySamplerFilter::Nearest)
+        };
+        let draw_parameters = DrawParameters {
+            blend: glium::Blend {
+                color,
+                alpha,
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+            ..self.draw_parameters()
+        };
+        surface
+            .draw(
+                &self.quad_vertex_buffer,
+                &self.quad_index_buffer,
+                &self.composite_shader_program,
+                &uniform! {
+                    u_layer: sampled(&layer.color),
+                    u_backdrop: sampled(backdrop.as_ref().unwrap_or(&layer.color)),
+                    u_blend_mode: shader_blend_mode,
+                },
+                &draw_parameters,
+            )
+            .unwrap();
+    }
+
     fn build_matrices(&mut self) {
         self.view_matrix = [
             [1.0 / (self.viewport_width as f32 / 2.0), 0.0, 0.0, 0.0],
@@ -564,7 +879,12 @@ impl RenderBackend for GliumRenderBackend {
 
     fn begin_frame(&mut self) {
         assert!(self.target.is_none());
-        self.target = Some(self.display.draw());
+        let mut target = self.display.draw();
+        target.clear_stencil(0);
+        self.target = Some(target);
+        self.num_masks = 0;
+        self.mask_state = MaskState::NoMask;
+        self.mask_shapes.clear();
     }
 
     fn end_frame(&mut self) {
@@ -585,101 +905,100 @@ impl RenderBackend for GliumRenderBackend {
     }
 
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
-        let target = self.target.as_mut().unwrap();
+        if self.mask_state == MaskState::DrawMaskStencil {
+            if let Some(mask_shapes) = self.mask_shapes.last_mut() {
+                mask_shapes.push((shape, transform.clone()));
+            }
+        }
+        let draw_parameters = self.draw_parameters();
 
-        let mesh = &self.meshes[shape.0];
+        if let Some(layer) = self.layers.last() {
+            let mut surface = layer.framebuffer(&self.display);
+            self.draw_mesh(&mut surface, shape, transform, &draw_parameters);
+        } else {
+            let mut target = self.target.take().unwrap();
+            self.draw_mesh(&mut target, shape, transform, &draw_parameters);
+            self.target = Some(target);
+        }
+    }
 
-        let world_matrix = [
-            [transform.matrix.a, transform.matrix.b, 0.0, 0.0],
-            [transform.matrix.c, transform.matrix.d, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [
-                transform.matrix.tx / 20.0,
-                transform.matrix.ty / 20.0,
-                0.0,
-                1.0,
-            ],
-        ];
+    fn push_mask(&mut self) {
+        self.num_masks += 1;
+        self.mask_state = MaskState::DrawMaskStencil;
+        self.mask_shapes.push(vec![]);
+    }
 
-        let mult_color = [
-            transform.color_transform.r_mult,
-            transform.color_transform.g_mult,
-            transform.color_transform.b_mult,
-            transform.color_transform.a_mult,
-        ];
+    fn activate_mask(&mut self) {
+        self.mask_state = MaskState::DrawMaskedContent;
+    }
 
-        let add_color = [
-            transform.color_transform.r_add,
-            transform.color_transform.g_add,
-            transform.color_transform.b_add,
-            transform.color_transform.a_add,
-        ];
+    fn pop_mask(&mut self) {
+        if self.num_masks == 0 {
+            return;
+        }
+        self.mask_state = MaskState::ClearMaskStencil;
+        let mask_shapes = self.mask_shapes.pop().unwrap_or_default();
+        for (shape, transform) in &mask_shapes {
+            self.render_shape(*shape, transform);
+        }
+        self.num_masks -= 1;
+        self.mask_state = if self.num_masks == 0 {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
 
-        let draw_parameters = DrawParameters {
-            blend: glium::Blend::alpha_blending(),
-            ..Default::default()
+    fn push_layer(&mut self) {
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let color = Texture2d::empty_with_format(
+            &self.display,
+            UncompressedFloatFormat::U8U8U8U8,
+            MipmapsOption::NoMipmap,
+            width,
+            height,
+        )
+        .unwrap();
+        let stencil =
+            StencilRenderBuffer::new(&self.display, StencilFormat::I8, width, height).unwrap();
+
+        // The masks of the outer layer don't apply inside this one until it is composited.
+        let layer = Layer {
+            color,
+            stencil,
+            num_masks: self.num_masks,
+            mask_state: self.mask_state,
+            mask_shapes: std::mem::replace(&mut self.mask_shapes, vec![]),
         };
+        self.num_masks = 0;
+        self.mask_state = MaskState::NoMask;
 
-        for draw in &mesh.draws {
-            match &draw.draw_type {
-                DrawType::Color => {
-                    target
-                        .draw(
-                            &draw.vertex_buffer,
-                            &draw.index_buffer,
-                            &self.shader_program,
-                            &uniform! { view_matrix: self.view_matrix, world_matrix: world_matrix, mult_color: mult_color, add_color: add_color },
-                            &draw_parameters
-                        )
-                        .unwrap();
-                }
-                DrawType::Gradient(gradient_uniforms) => {
-                    let uniforms = GradientUniformsFull {
-                        view_matrix: self.view_matrix,
-                        world_matrix,
-                        mult_color,
-                        add_color,
-                        gradient: gradient_uniforms.clone(),
-                    };
+        let mut surface = layer.framebuffer(&self.display);
+        surface.clear_color_and_stencil((0.0, 0.0, 0.0, 0.0), 0);
+        drop(surface);
+        self.layers.push(layer);
+    }
 
-                    target
-                        .draw(
-                            &draw.vertex_buffer,
-                            &draw.index_buffer,
-                            &self.gradient_shader_program,
-                            &uniforms,
-                            &draw_parameters,
-                        )
-                        .unwrap();
-                }
-                DrawType::Bitmap(bitmap_uniforms) => {
-                    let texture = &self
-                        .textures
-                        .iter()
-                        .find(|(id, _tex)| *id == bitmap_uniforms.id)
-                        .unwrap()
-                        .1;
+    fn pop_layer(&mut self, blend_mode: BlendMode, filters: &[swf::Filter]) {
+        let mut layer = match self.layers.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+        self.num_masks = layer.num_masks;
+        self.mask_state = layer.mask_state;
+        self.mask_shapes = std::mem::replace(&mut layer.mask_shapes, vec![]);
 
-                    let uniforms = BitmapUniformsFull {
-                        view_matrix: self.view_matrix,
-                        world_matrix,
-                        mult_color,
-                        add_color,
-                        matrix: bitmap_uniforms.matrix,
-                        texture: &texture.texture,
-                    };
+        if !filters.is_empty() {
+            layer.apply_filters(filters);
+        }
 
-                    target
-                        .draw(
-                            &draw.vertex_buffer,
-                            &draw.index_buffer,
-                            &self.bitmap_shader_program,
-                            &uniforms,
-                            &draw_parameters,
-                        )
-                        .unwrap();
-                }
-            }
+        if let Some(parent) = self.layers.last() {
+            let mut surface = parent.framebuffer(&self.display);
+            self.composite_layer(&mut surface, &layer, blend_mode, true);
+        } else {
+            let mut target = self.target.take().unwrap();
+            self.composite_layer(&mut target, &layer, blend_mode, false);
+            self.target = Some(target);
         }
     }
 
@@ -746,6 +1065,44 @@ impl RenderBackend for GliumRenderBackend {
     }
 }
 
+/// An offscreen surface that a display object with a blend mode is drawn into,
+/// along with the mask state of the surface beneath it.
+struct Layer {
+    color: Texture2d,
+    stencil: StencilRenderBuffer,
+    num_masks: u32,
+    mask_state: MaskState,
+    mask_shapes: Vec<Vec<(ShapeHandle, Transform)>>,
+}
+
+impl Layer {
+    fn framebuffer<'a>(&'a self, display: &Display) -> SimpleFrameBuffer<'a> {
+        SimpleFrameBuffer::with_stencil_buffer(display, &self.color, &self.stencil).unwrap()
+    }
+
+    /// Runs the pixels of this layer through `filters`.
+    fn apply_filters(&self, filters: &[swf::Filter]) {
+        let (width, height) = self.color.dimensions();
+        let pixels: RawImage2d<u8> = self.color.read();
+        let mut image = FilterImage::from_premultiplied_rgba(
+            width as usize,
+            height as usize,
+            &flip_rows(&pixels.data, width),
+        );
+        apply_filters(&mut image, filters);
+        let pixels = flip_rows(&image.to_premultiplied_rgba(), width);
+        self.color.write(
+            glium::Rect {
+                left: 0,
+                bottom: 0,
+                width,
+                height,
+            },
+            RawImage2d::from_raw_rgba(pixels, (width, height)),
+        );
+    }
+}
+
 struct Texture {
     width: u32,
     height: u32,
@@ -846,6 +1203,29 @@ impl<'a> Uniforms for BitmapUniformsFull<'a> {
     }
 }
 
+/// Blends colors onto the premultiplied colors of a layer. The alpha is accumulated with
+/// `One` rather than `SourceAlpha`, so that a layer's alpha is its coverage.
+const SHAPE_BLEND: glium::Blend = glium::Blend {
+    color: glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::SourceAlpha,
+        destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+    },
+    alpha: glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+    },
+    constant_value: (0.0, 0.0, 0.0, 0.0),
+};
+
+// The values of `u_blend_mode` in `COMPOSITE_FRAGMENT_SHADER`.
+const BLEND_COPY: i32 = 0;
+const BLEND_INVERT: i32 = 1;
+const BLEND_DIFFERENCE: i32 = 2;
+const BLEND_DARKEN: i32 = 3;
+const BLEND_LIGHTEN: i32 = 4;
+const BLEND_OVERLAY: i32 = 5;
+const BLEND_HARD_LIGHT: i32 = 6;
+
 const VERTEX_SHADER: &str = r#"
     #version 140
 
@@ -873,6 +1253,65 @@ const FRAGMENT_SHADER: &str = r#"
     }
 "#;
 
+const COMPOSITE_VERTEX_SHADER: &str = r#"
+    #version 140
+
+    in vec2 position;
+    in vec4 color;
+    out vec2 frag_uv;
+
+    void main() {
+        frag_uv = position * 0.5 + 0.5;
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+"#;
+
+const COMPOSITE_FRAGMENT_SHADER: &str = r#"
+    #version 140
+
+    uniform sampler2D u_layer;
+    uniform sampler2D u_backdrop;
+    uniform int u_blend_mode;
+
+    in vec2 frag_uv;
+    out vec4 out_color;
+
+    vec3 hard_light(vec3 backdrop, vec3 source) {
+        vec3 multiply = backdrop * 2.0 * source;
+        vec3 screen = 1.0 - (1.0 - backdrop) * (2.0 - 2.0 * source);
+        return mix(multiply, screen, step(0.5, source));
+    }
+
+    void main() {
+        vec4 src = texture(u_layer, frag_uv);
+        if (u_blend_mode == 0) {
+            out_color = src;
+            return;
+        }
+        if (u_blend_mode == 1) {
+            out_color = vec4(src.a);
+            return;
+        }
+
+        vec4 dst = texture(u_backdrop, frag_uv);
+        vec3 cs = src.a > 0.0 ? src.rgb / src.a : vec3(0.0);
+        vec3 cb = dst.a > 0.0 ? dst.rgb / dst.a : vec3(0.0);
+        vec3 blended;
+        if (u_blend_mode == 2) {
+            blended = abs(cb - cs);
+        } else if (u_blend_mode == 3) {
+            blended = min(cb, cs);
+        } else if (u_blend_mode == 4) {
+            blended = max(cb, cs);
+        } else if (u_blend_mode == 5) {
+            blended = hard_light(cs, cb);
+        } else {
+            blended = hard_light(cb, cs);
+        }
+        out_color = vec4(blended * src.a, src.a);
+    }
+"#;
+
 const TEXTURE_VERTEX_SHADER: &str = r#"
     #version 140
 
@@ -994,6 +1433,16 @@ enum DrawType {
     Bitmap(BitmapUniforms),
 }
 
+/// Reverses the order of the rows of RGBA8 pixels, as OpenGL stores them from the bottom up.
+fn flip_rows(pixels: &[u8], width: u32) -> Vec<u8> {
+    pixels
+        .chunks_exact(width as usize * 4)
+        .rev()
+        .flatten()
+        .copied()
+        .collect()
+}
+
 fn point(x: Twips, y: Twips) -> lyon::math::Point {
     lyon::math::Point::new(x.to_pixels() as f32, y.to_pixels() as f32)
 }
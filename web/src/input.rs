@@ -0,0 +1,115 @@
+//! Translation of browser keyboard events into Flash keycodes, and of wheel events into lines.
+
+use ruffle_core::{key_code, KeyCode};
+use web_sys::{KeyboardEvent, WheelEvent};
+
+/// The number of lines that Flash scrolls for one notch of the mouse wheel.
+const LINES_PER_NOTCH: f64 = 3.0;
+
+/// The distance that browsers scroll for one notch of the mouse wheel, when they report pixels.
+const PIXELS_PER_NOTCH: f64 = 100.0;
+
+/// Returns the Flash keycode of the key in a keyboard event, or `None` if Flash has no code for it.
+pub fn flash_key_code(event: &KeyboardEvent) -> Option<KeyCode> {
+    key_code_from_browser(event.key_code())
+}
+
+/// Translates a browser virtual keycode into a Flash keycode.
+///
+/// Browsers report the same virtual keycodes as Flash, except for a few keys in Firefox.
+fn key_code_from_browser(code: u32) -> Option<KeyCode> {
+    let code = match code {
+        59 => key_code::SEMICOLON,
+        61 => key_code::EQUALS,
+        173 => key_code::MINUS,
+        code if code > 0 && code < 256 => code as KeyCode,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Returns the ASCII code typed by a keyboard event, or 0 if it types no ASCII character.
+pub fn ascii(event: &KeyboardEvent) -> u8 {
+    ascii_from_key(&event.key())
+}
+
+/// Returns the ASCII code typed by a key, given its `KeyboardEvent.key` value.
+fn ascii_from_key(key: &str) -> u8 {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii() => c as u8,
+        _ => match key {
+            "Backspace" => 8,
+            "Tab" => 9,
+            "Enter" => 13,
+            "Escape" => 27,
+            "Delete" => 127,
+            _ => 0,
+        },
+    }
+}
+
+/// Returns the character typed by a keyboard event, or `None` if the key types no character,
+/// as for arrow keys and shortcuts. AltGr is reported as Ctrl+Alt on some systems, so
+/// characters typed with it are kept.
+pub fn text_input(event: &KeyboardEvent) -> Option<char> {
+    if (event.ctrl_key() && !event.alt_key()) || event.meta_key() {
+        return None;
+    }
+    let key = event.key();
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Some(c),
+        _ => None,
+    }
+}
+
+/// Returns the number of lines that a wheel event scrolls, where positive values scroll up.
+pub fn wheel_lines(event: &WheelEvent) -> f64 {
+    let delta = -event.delta_y();
+    match event.delta_mode() {
+        WheelEvent::DOM_DELTA_PIXEL => delta * LINES_PER_NOTCH / PIXELS_PER_NOTCH,
+        WheelEvent::DOM_DELTA_LINE => delta,
+        // Scroll by a notch for each page.
+        _ => delta * LINES_PER_NOTCH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browser_key_codes() {
+        assert_eq!(key_code_from_browser(65), Some(b'A'));
+        assert_eq!(key_code_from_browser(48), Some(b'0'));
+        assert_eq!(key_code_from_browser(13), Some(key_code::ENTER));
+        assert_eq!(key_code_from_browser(37), Some(key_code::LEFT));
+        assert_eq!(key_code_from_browser(112), Some(key_code::F1));
+        assert_eq!(key_code_from_browser(186), Some(key_code::SEMICOLON));
+        assert_eq!(key_code_from_browser(0), None);
+        assert_eq!(key_code_from_browser(256), None);
+    }
+
+    #[test]
+    fn firefox_key_codes() {
+        assert_eq!(key_code_from_browser(59), Some(key_code::SEMICOLON));
+        assert_eq!(key_code_from_browser(61), Some(key_code::EQUALS));
+        assert_eq!(key_code_from_browser(173), Some(key_code::MINUS));
+    }
+
+    #[test]
+    fn ascii_of_keys() {
+        assert_eq!(ascii_from_key("a"), b'a');
+        assert_eq!(ascii_from_key("A"), b'A');
+        assert_eq!(ascii_from_key("!"), b'!');
+        assert_eq!(ascii_from_key(" "), b' ');
+        assert_eq!(ascii_from_key("Backspace"), 8);
+        assert_eq!(ascii_from_key("Tab"), 9);
+        assert_eq!(ascii_from_key("Enter"), 13);
+        assert_eq!(ascii_from_key("Escape"), 27);
+        assert_eq!(ascii_from_key("Delete"), 127);
+        assert_eq!(ascii_from_key("ArrowLeft"), 0);
+        assert_eq!(ascii_from_key("é"), 0);
+    }
+}
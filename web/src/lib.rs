@@ -1,5 +1,6 @@
 //! Ruffle web frontend.
 mod audio;
+mod input;
 mod render;
 
 use crate::{audio::WebAudioBackend, render::WebCanvasRenderBackend};
@@ -8,7 +9,7 @@ use js_sys::Uint8Array;
 use ruffle_core::{backend::render::RenderBackend, PlayerEvent};
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
-use web_sys::{Event, EventTarget, HtmlCanvasElement, MouseEvent};
+use web_sys::{Event, EventTarget, HtmlCanvasElement, KeyboardEvent, MouseEvent, WheelEvent};
 
 thread_local! {
     /// We store the actual instances of the ruffle core in a static pool.
@@ -25,6 +26,7 @@ struct RuffleInstance {
     canvas_width: i32,
     canvas_height: i32,
     device_pixel_ratio: f64,
+    cursor_visible: bool,
     timestamp: f64,
     animation_handler: Option<AnimationHandler>, // requestAnimationFrame callback
     animation_handler_id: Option<NonZeroI32>,    // requestAnimationFrame id
@@ -33,6 +35,9 @@ struct RuffleInstance {
     mouse_move_callback: Option<Closure<dyn FnMut(MouseEvent)>>,
     mouse_down_callback: Option<Closure<dyn FnMut(MouseEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(MouseEvent)>>,
+    wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
+    key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+    key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
 }
 
 /// An opaque handle to a `RuffleInstance` inside the pool.
@@ -96,12 +101,16 @@ impl Ruffle {
             canvas_width: 0, // Intiailize canvas width and height to 0 to force an initial canvas resize.
             canvas_height: 0,
             device_pixel_ratio: window.device_pixel_ratio(),
+            cursor_visible: true,
             animation_handler: None,
             animation_handler_id: None,
             click_callback: None,
             mouse_move_callback: None,
             mouse_down_callback: None,
             mouse_up_callback: None,
+            wheel_callback: None,
+            key_down_callback: None,
+            key_up_callback: None,
             timestamp,
         };
 
@@ -153,9 +162,13 @@ impl Ruffle {
                     INSTANCES.with(move |instances| {
                         let mut instances = instances.borrow_mut();
                         if let Some(instance) = instances.get_mut(index) {
-                            let event = PlayerEvent::MouseDown {
-                                x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                                y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
+                            let x = f64::from(js_event.offset_x()) * instance.device_pixel_ratio;
+                            let y = f64::from(js_event.offset_y()) * instance.device_pixel_ratio;
+                            let event = match js_event.button() {
+                                0 => PlayerEvent::MouseDown { x, y },
+                                1 => PlayerEvent::MiddleMouseDown { x, y },
+                                2 => PlayerEvent::RightMouseDown { x, y },
+                                _ => return,
                             };
                             instance.core.handle_event(event);
                         }
@@ -179,9 +192,13 @@ impl Ruffle {
                     INSTANCES.with(move |instances| {
                         let mut instances = instances.borrow_mut();
                         if let Some(instance) = instances.get_mut(index) {
-                            let event = PlayerEvent::MouseUp {
-                                x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                                y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
+                            let x = f64::from(js_event.offset_x()) * instance.device_pixel_ratio;
+                            let y = f64::from(js_event.offset_y()) * instance.device_pixel_ratio;
+                            let event = match js_event.button() {
+                                0 => PlayerEvent::MouseUp { x, y },
+                                1 => PlayerEvent::MiddleMouseUp { x, y },
+                                2 => PlayerEvent::RightMouseUp { x, y },
+                                _ => return,
                             };
                             instance.core.handle_event(event);
                         }
@@ -199,6 +216,98 @@ impl Ruffle {
                 instance.mouse_up_callback = Some(mouse_up_callback);
             }
 
+            // Create mouse wheel handler.
+            {
+                let wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            // Keep the page from scrolling while the mouse is over the movie.
+                            js_event.prevent_default();
+                            let event = PlayerEvent::MouseWheel {
+                                delta: input::wheel_lines(&js_event),
+                            };
+                            instance.core.handle_event(event);
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(WheelEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "wheel",
+                        wheel_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.wheel_callback = Some(wheel_callback);
+            }
+
+            // Create key down handler.
+            // The canvas must be focusable to receive keyboard events.
+            canvas.set_tab_index(0);
+            {
+                let key_down_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            if let Some(key_code) = input::flash_key_code(&js_event) {
+                                // Keep keys such as the arrows and space from scrolling the page.
+                                js_event.prevent_default();
+                                let event = PlayerEvent::KeyDown {
+                                    key_code,
+                                    ascii: input::ascii(&js_event),
+                                };
+                                instance.core.handle_event(event);
+                            }
+                            if let Some(codepoint) = input::text_input(&js_event) {
+                                instance
+                                    .core
+                                    .handle_event(PlayerEvent::TextInput { codepoint });
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(KeyboardEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "keydown",
+                        key_down_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.key_down_callback = Some(key_down_callback);
+            }
+
+            // Create key up handler.
+            {
+                let key_up_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            if let Some(key_code) = input::flash_key_code(&js_event) {
+                                let event = PlayerEvent::KeyUp {
+                                    key_code,
+                                    ascii: input::ascii(&js_event),
+                                };
+                                instance.core.handle_event(event);
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(KeyboardEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "keyup",
+                        key_up_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.key_up_callback = Some(key_up_callback);
+            }
+
             // Create click event handler.
             {
                 let click_callback = Closure::wrap(Box::new(move |_| {
@@ -240,6 +349,21 @@ impl Ruffle {
                 instance.timestamp = timestamp;
 
                 instance.core.tick(dt);
+                for error in instance.core.take_uncaught_errors() {
+                    log::error!("Uncaught ActionScript error: {}", error);
+                }
+
+                // Scripts can hide the cursor with `Mouse.hide`.
+                let cursor_visible = instance.core.mouse_cursor_visible();
+                if instance.cursor_visible != cursor_visible {
+                    instance.cursor_visible = cursor_visible;
+                    let cursor = if cursor_visible { "auto" } else { "none" };
+                    instance
+                        .canvas
+                        .style()
+                        .set_property("cursor", cursor)
+                        .unwrap();
+                }
 
                 // Check for canvas resize.
                 let canvas_width = instance.canvas.client_width();
@@ -1,9 +1,13 @@
 use ruffle_core::backend::render::{
-    swf, swf::CharacterId, BitmapHandle, Color, Letterbox, RenderBackend, ShapeHandle, Transform,
+    swf, swf::CharacterId, swf::Twips, BitmapHandle, BlendMode, Color, Letterbox, RenderBackend,
+    ShapeHandle, Transform,
 };
+use ruffle_core::filters::{apply_filters, FilterImage};
+use ruffle_core::matrix::Matrix;
+use ruffle_core::shape_utils::{swf_shape_to_paths, DrawCommand, DrawPath};
 use std::collections::HashMap;
-use wasm_bindgen::JsCast;
-use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement};
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement, ImageData};
 
 pub struct WebCanvasRenderBackend {
     canvas: HtmlCanvasElement,
@@ -14,12 +18,21 @@ pub struct WebCanvasRenderBackend {
     id_to_bitmap: HashMap<CharacterId, BitmapHandle>,
     viewport_width: u32,
     viewport_height: u32,
+
+    /// Whether shapes are added to the path of a mask instead of being drawn.
+    drawing_mask: bool,
+
+    /// The offscreen layers on the stack, each with the context that was drawn to beneath it.
+    layers: Vec<(HtmlCanvasElement, CanvasRenderingContext2d)>,
 }
 
 struct ShapeData {
     image: HtmlImageElement,
     x_min: f64,
     y_min: f64,
+
+    /// The outlines of the fills of the shape, used when the shape is part of a mask.
+    fills: Vec<Vec<DrawCommand>>,
 }
 
 #[allow(dead_code)]
@@ -116,10 +129,19 @@ impl WebCanvasRenderBackend {
             id_to_bitmap: HashMap::new(),
             viewport_width: 0,
             viewport_height: 0,
+            drawing_mask: false,
+            layers: vec![],
         };
         Ok(renderer)
     }
 
+    /// Inverts the colors of the whole canvas.
+    fn invert_canvas(&self, width: f64, height: f64) {
+        let _ = self.context.set_global_composite_operation("difference");
+        self.context.set_fill_style(&"white".into());
+        self.context.fill_rect(0.0, 0.0, width, height);
+    }
+
     /// Converts an RGBA image into a PNG encoded as a base64 data URI.
     fn rgba_to_png_data_uri(
         rgba: &[u8],
@@ -172,10 +194,19 @@ impl RenderBackend for WebCanvasRenderBackend {
 
         image.set_src(&svg_encoded);
 
+        let fills = swf_shape_to_paths(shape)
+            .into_iter()
+            .filter_map(|path| match path {
+                DrawPath::Fill { commands, .. } => Some(commands),
+                DrawPath::Stroke { .. } => None,
+            })
+            .collect();
+
         self.shapes.push(ShapeData {
             image,
             x_min: shape.shape_bounds.x_min.to_pixels(),
             y_min: shape.shape_bounds.y_min.to_pixels(),
+            fills,
         });
 
         handle
@@ -292,6 +323,7 @@ impl RenderBackend for WebCanvasRenderBackend {
     fn begin_frame(&mut self) {
         // Reset canvas transform in case it was left in a dirty state.
         self.context.reset_transform().unwrap();
+        self.drawing_mask = false;
     }
 
     fn end_frame(&mut self) {
@@ -316,6 +348,11 @@ impl RenderBackend for WebCanvasRenderBackend {
             return;
         };
 
+        if self.drawing_mask {
+            add_fills_to_path(&self.context, &shape.fills, &transform.matrix);
+            return;
+        }
+
         let matrix = transform.matrix; //self.view_matrix * transform.matrix;
 
         self.context
@@ -366,6 +403,91 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.context.set_global_alpha(1.0);
     }
 
+    fn push_mask(&mut self) {
+        // The fills of the mask are collected into one path in canvas coordinates.
+        self.context.reset_transform().unwrap();
+        self.context.begin_path();
+        self.drawing_mask = true;
+    }
+
+    fn activate_mask(&mut self) {
+        // The clip is undone by the `restore` in `pop_mask`.
+        // Clips intersect, so nested masks are clipped to the masks beneath them.
+        self.context.save();
+        self.context.clip();
+        self.drawing_mask = false;
+    }
+
+    fn pop_mask(&mut self) {
+        self.context.restore();
+    }
+
+    fn push_layer(&mut self) {
+        let (canvas, context) =
+            create_layer(self.canvas.width(), self.canvas.height()).expect("Couldn't create layer");
+        let context = std::mem::replace(&mut self.context, context);
+        self.layers.push((canvas, context));
+    }
+
+    fn pop_layer(&mut self, blend_mode: BlendMode, filters: &[swf::Filter]) {
+        let (layer, context) = match self.layers.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+        let layer_context = std::mem::replace(&mut self.context, context);
+
+        // Alpha and erase change the alpha of the layer beneath, so they have no effect on the
+        // canvas itself, which is opaque.
+        if self.layers.is_empty()
+            && (blend_mode == BlendMode::Alpha || blend_mode == BlendMode::Erase)
+        {
+            return;
+        }
+        if !filters.is_empty() {
+            filter_layer(&layer_context, filters, layer.width(), layer.height());
+        }
+        let width = f64::from(self.canvas.width());
+        let height = f64::from(self.canvas.height());
+
+        // Canvas has no composite operation for invert or subtract, so they are built from others.
+        // Invert is the difference with white wherever the layer is drawn.
+        // Subtract inverts the destination, adds the layer, and inverts the destination again.
+        let composite_operation = match blend_mode {
+            BlendMode::Normal | BlendMode::Layer => "source-over",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Lighten => "lighten",
+            BlendMode::Darken => "darken",
+            BlendMode::Difference | BlendMode::Invert => "difference",
+            BlendMode::Add | BlendMode::Subtract => "lighter",
+            BlendMode::Alpha => "destination-in",
+            BlendMode::Erase => "destination-out",
+            BlendMode::Overlay => "overlay",
+            BlendMode::HardLight => "hard-light",
+        };
+        if blend_mode == BlendMode::Invert {
+            let _ = layer_context.set_global_composite_operation("source-in");
+            layer_context.set_fill_style(&"white".into());
+            layer_context.fill_rect(0.0, 0.0, width, height);
+        }
+
+        self.context.save();
+        self.context.reset_transform().unwrap();
+        if blend_mode == BlendMode::Subtract {
+            self.invert_canvas(width, height);
+        }
+        let _ = self
+            .context
+            .set_global_composite_operation(composite_operation);
+        let _ = self
+            .context
+            .draw_image_with_html_canvas_element(&layer, 0.0, 0.0);
+        if blend_mode == BlendMode::Subtract {
+            self.invert_canvas(width, height);
+        }
+        self.context.restore();
+    }
+
     fn draw_pause_overlay(&mut self) {
         let width = f64::from(self.canvas.width());
         let height = f64::from(self.canvas.height());
@@ -410,13 +532,89 @@ impl RenderBackend for WebCanvasRenderBackend {
     }
 }
 
+/// Creates an offscreen canvas of the given size for a layer, along with its context.
+fn create_layer(
+    width: u32,
+    height: u32,
+) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), Box<dyn std::error::Error>> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas: HtmlCanvasElement = document
+        .create_element("canvas")
+        .map_err(|_| "Couldn't create canvas")?
+        .dyn_into()
+        .map_err(|_| "Expected HtmlCanvasElement")?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .map_err(|_| "Could not create context")?
+        .ok_or("Could not create context")?
+        .dyn_into()
+        .map_err(|_| "Expected CanvasRenderingContext2d")?;
+    Ok((canvas, context))
+}
+
+/// Runs the pixels of a layer through `filters`.
+fn filter_layer(
+    context: &CanvasRenderingContext2d,
+    filters: &[swf::Filter],
+    width: u32,
+    height: u32,
+) {
+    let image_data = match context.get_image_data(0.0, 0.0, f64::from(width), f64::from(height)) {
+        Ok(image_data) => image_data,
+        Err(_) => {
+            log::warn!("Couldn't read layer to apply filters");
+            return;
+        }
+    };
+    let mut image = FilterImage::from_rgba(width as usize, height as usize, &image_data.data());
+    apply_filters(&mut image, filters);
+    let mut data = image.to_rgba();
+    if let Ok(image_data) =
+        ImageData::new_with_u8_clamped_array_and_sh(Clamped(&mut data), width, height)
+    {
+        let _ = context.put_image_data(&image_data, 0.0, 0.0);
+    }
+}
+
+/// Adds the outlines of `fills`, transformed by `matrix`, to the current path of `context`.
+fn add_fills_to_path(
+    context: &CanvasRenderingContext2d,
+    fills: &[Vec<DrawCommand>],
+    matrix: &Matrix,
+) {
+    let transform = |x: Twips, y: Twips| {
+        let (x, y) = *matrix * (x, y);
+        (x.to_pixels(), y.to_pixels())
+    };
+    for commands in fills {
+        for command in commands {
+            match *command {
+                DrawCommand::MoveTo { x, y } => {
+                    let (x, y) = transform(x, y);
+                    context.move_to(x, y);
+                }
+                DrawCommand::LineTo { x, y } => {
+                    let (x, y) = transform(x, y);
+                    context.line_to(x, y);
+                }
+                DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                    let (x1, y1) = transform(x1, y1);
+                    let (x2, y2) = transform(x2, y2);
+                    context.quadratic_curve_to(x1, y1, x2, y2);
+                }
+            }
+        }
+        context.close_path();
+    }
+}
+
 fn swf_shape_to_svg(
     shape: &swf::Shape,
     bitmaps: &HashMap<CharacterId, (&str, u32, u32)>,
 ) -> String {
     use fnv::FnvHashSet;
-    use ruffle_core::matrix::Matrix;
-    use ruffle_core::shape_utils::{swf_shape_to_paths, DrawCommand, DrawPath};
     use svg::node::element::{
         path::Data, Definitions, Image, LinearGradient, Path as SvgPath, Pattern, RadialGradient,
         Stop,